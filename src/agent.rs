@@ -1,10 +1,19 @@
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
+use regex::Regex;
+
+use crate::http_backend::{ChatMessage, ChatOutcome, HttpBackendSpec, LineBuffer};
+use crate::pass_diff::{self, WorkspaceSnapshot};
+use crate::process_control::{RunControl, isolate_process_group};
+use crate::recorder::{RecordingHandle, RecordingSpec};
+use crate::replay::ReplayBackendSpec;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AgentEvent {
     Output(String),
@@ -21,12 +30,30 @@ pub struct CodexCommandConfig {
     pub skip_reader_join_after_wait: bool,
     pub model: Option<String>,
     pub model_reasoning_effort: Option<String>,
+    pub working_dir: Option<PathBuf>,
+    /// Set for a `[backend.custom]` CLI; built-in backends leave it `None`.
+    pub custom: Option<CustomBackendSpec>,
+    /// Set for a `[backend.http]` server, which is called directly instead of spawning `program`.
+    pub http: Option<HttpBackendSpec>,
+    /// Set for the `[backend.replay]` fixture, which answers prompts without running an agent.
+    pub replay: Option<ReplayBackendSpec>,
+    /// Set under `--record`; every prompt and its events are written to the session fixture.
+    pub recording: Option<RecordingSpec>,
+    pub permissions: PermissionProfile,
+    /// Flags each permission profile adds for this backend.
+    pub permission_args: PermissionArgs,
+    /// Fail a read-only run that changed files under its working directory. Off for agents
+    /// that run alongside workers, whose edits would look like violations.
+    pub guard_workspace: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BackendKind {
     Codex,
     Claude,
+    Custom,
+    Http,
+    Replay,
 }
 
 impl BackendKind {
@@ -44,19 +71,153 @@ impl BackendKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum AdapterOutputMode {
+    #[default]
     PlainText,
     JsonAssistantOnly,
 }
 
+/// How a custom backend receives the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PromptInput {
+    /// Substituted for `{prompt}` in the argument templates, or appended as the last argument.
+    #[default]
+    Argv,
+    Stdin,
+    /// Written to a temporary file whose path replaces `{prompt_file}`, or is appended.
+    File,
+}
+
+/// What an agent may change. Each backend maps a profile to its own sandbox or tool flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PermissionProfile {
+    /// May read the workspace but not change it; changes made anyway fail the run.
+    ReadOnly,
+    /// May edit files inside its working directory.
+    WorkspaceWrite,
+    /// Runs with approvals and sandboxing disabled.
+    #[default]
+    Full,
+}
+
+impl PermissionProfile {
+    /// Parses a profile name as written in config; `-` and `_` are interchangeable.
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "read-only" => Some(Self::ReadOnly),
+            "workspace-write" => Some(Self::WorkspaceWrite),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read-only",
+            Self::WorkspaceWrite => "workspace-write",
+            Self::Full => "full",
+        }
+    }
+}
+
+/// Backend flags for each permission profile, from `[backend.<name>.permissions]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionArgs {
+    pub read_only: Vec<String>,
+    pub workspace_write: Vec<String>,
+    pub full: Vec<String>,
+}
+
+impl PermissionArgs {
+    pub fn default_for_backend(backend: BackendKind) -> Self {
+        let args = |values: &[&str]| values.iter().map(ToString::to_string).collect();
+        match backend {
+            // Config overrides rather than `--sandbox`, so `exec resume` accepts them too.
+            BackendKind::Codex => Self {
+                read_only: args(&["-c", "sandbox_mode=\"read-only\""]),
+                workspace_write: args(&["-c", "sandbox_mode=\"workspace-write\""]),
+                full: args(&["--dangerously-bypass-approvals-and-sandbox"]),
+            },
+            // Claude has no OS sandbox; in print mode any tool that is not allowed is refused.
+            BackendKind::Claude => Self {
+                read_only: args(&[
+                    "--allowedTools",
+                    "Read,Glob,Grep,LS,Bash(git diff:*),Bash(git log:*),Bash(git show:*),Bash(git status:*)",
+                ]),
+                workspace_write: args(&[
+                    "--permission-mode",
+                    "acceptEdits",
+                    "--allowedTools",
+                    "Bash",
+                ]),
+                full: args(&["--dangerously-skip-permissions"]),
+            },
+            BackendKind::Custom | BackendKind::Http | BackendKind::Replay => Self::default(),
+        }
+    }
+
+    pub fn for_profile(&self, profile: PermissionProfile) -> &[String] {
+        match profile {
+            PermissionProfile::ReadOnly => &self.read_only,
+            PermissionProfile::WorkspaceWrite => &self.workspace_write,
+            PermissionProfile::Full => &self.full,
+        }
+    }
+}
+
+/// An agent CLI described entirely by config. Argument templates may use the `{model}`,
+/// `{effort}`, `{session_id}`, `{prompt}` and `{prompt_file}` placeholders.
+#[derive(Debug, Clone, Default)]
+pub struct CustomBackendSpec {
+    pub new_session_args: Vec<String>,
+    /// Arguments for continuing a saved session; when empty every prompt starts a new session.
+    pub resume_args: Vec<String>,
+    /// Appended only when a model is set.
+    pub model_args: Vec<String>,
+    /// Appended only when a reasoning effort is set.
+    pub effort_args: Vec<String>,
+    pub prompt_input: PromptInput,
+    pub output_mode: AdapterOutputMode,
+    pub assistant_text: OutputExtractor,
+    pub session_id: OutputExtractor,
+}
+
+/// Pulls a value out of one output line: the JSON path is tried first, then the regex (its
+/// first capture group, or the whole match when it has none).
+#[derive(Debug, Clone, Default)]
+pub struct OutputExtractor {
+    pub json_path: Option<Vec<String>>,
+    pub regex: Option<Regex>,
+}
+
+impl OutputExtractor {
+    pub fn is_configured(&self) -> bool {
+        self.json_path.is_some() || self.regex.is_some()
+    }
+
+    fn extract(&self, line: &str) -> Option<String> {
+        if let Some(path) = self.json_path.as_ref()
+            && let Ok(value) = serde_json::from_str::<serde_json::Value>(line)
+            && let Some(found) = json_value_at_path(&value, path)
+        {
+            return Some(found);
+        }
+        let captures = self.regex.as_ref()?.captures(line)?;
+        captures
+            .get(1)
+            .or_else(|| captures.get(0))
+            .map(|found| found.as_str().to_string())
+    }
+}
+
 impl CodexCommandConfig {
     pub fn default_for_backend(backend: BackendKind) -> Self {
         match backend {
             BackendKind::Codex => Self {
                 program: "codex".to_string(),
-                // This version intentionally runs Codex fully unsandboxed and with approvals disabled.
-                // Future versions should replace this with a safer, explicit permissions model.
+                // The bypass flag is the `full` permission profile; restricted profiles drop it
+                // from the prefix and add their own flags, see `apply_permission_args`.
                 args_prefix: vec![
                     "exec".to_string(),
                     "--dangerously-bypass-approvals-and-sandbox".to_string(),
@@ -68,6 +229,14 @@ impl CodexCommandConfig {
                 skip_reader_join_after_wait: false,
                 model: None,
                 model_reasoning_effort: None,
+                working_dir: None,
+                custom: None,
+                http: None,
+                replay: None,
+                recording: None,
+                permissions: PermissionProfile::Full,
+                permission_args: PermissionArgs::default_for_backend(BackendKind::Codex),
+                guard_workspace: true,
             },
             BackendKind::Claude => Self {
                 program: "claude".to_string(),
@@ -77,13 +246,104 @@ impl CodexCommandConfig {
                 skip_reader_join_after_wait: false,
                 model: None,
                 model_reasoning_effort: None,
+                working_dir: None,
+                custom: None,
+                http: None,
+                replay: None,
+                recording: None,
+                permissions: PermissionProfile::Full,
+                permission_args: PermissionArgs::default_for_backend(BackendKind::Claude),
+                guard_workspace: true,
+            },
+            BackendKind::Custom => Self {
+                program: String::new(),
+                args_prefix: Vec::new(),
+                output_mode: AdapterOutputMode::PlainText,
+                persistent_session: false,
+                skip_reader_join_after_wait: false,
+                model: None,
+                model_reasoning_effort: None,
+                working_dir: None,
+                custom: Some(CustomBackendSpec::default()),
+                http: None,
+                replay: None,
+                recording: None,
+                permissions: PermissionProfile::Full,
+                permission_args: PermissionArgs::default_for_backend(BackendKind::Custom),
+                guard_workspace: true,
+            },
+            BackendKind::Http => {
+                let spec = HttpBackendSpec::default();
+                Self {
+                    program: spec.base_url.clone(),
+                    args_prefix: Vec::new(),
+                    output_mode: AdapterOutputMode::PlainText,
+                    persistent_session: false,
+                    skip_reader_join_after_wait: false,
+                    model: Some(spec.model.clone()),
+                    model_reasoning_effort: None,
+                    working_dir: None,
+                    custom: None,
+                    http: Some(spec),
+                    replay: None,
+                    recording: None,
+                    permissions: PermissionProfile::Full,
+                    permission_args: PermissionArgs::default_for_backend(BackendKind::Http),
+                    guard_workspace: true,
+                }
+            }
+            BackendKind::Replay => Self {
+                program: "replay".to_string(),
+                args_prefix: Vec::new(),
+                output_mode: AdapterOutputMode::PlainText,
+                persistent_session: false,
+                skip_reader_join_after_wait: false,
+                model: None,
+                model_reasoning_effort: None,
+                working_dir: None,
+                custom: None,
+                http: None,
+                replay: Some(ReplayBackendSpec::default()),
+                recording: None,
+                permissions: PermissionProfile::Full,
+                permission_args: PermissionArgs::default_for_backend(BackendKind::Replay),
+                guard_workspace: true,
             },
         }
     }
 
     pub fn backend_kind(&self) -> BackendKind {
+        if self.custom.is_some() {
+            return BackendKind::Custom;
+        }
+        if self.http.is_some() {
+            return BackendKind::Http;
+        }
+        if self.replay.is_some() {
+            return BackendKind::Replay;
+        }
         BackendKind::from_program(&self.program)
     }
+
+    /// Whether routed model profiles apply: always for Codex, and for a custom backend that
+    /// declares model or effort flags.
+    pub fn accepts_model_profiles(&self) -> bool {
+        match self.backend_kind() {
+            BackendKind::Codex => true,
+            BackendKind::Claude | BackendKind::Http | BackendKind::Replay => false,
+            BackendKind::Custom => self
+                .custom
+                .as_ref()
+                .is_some_and(|spec| !spec.model_args.is_empty() || !spec.effort_args.is_empty()),
+        }
+    }
+
+    /// The output mode readers use; a custom backend's own setting wins over the role default.
+    pub fn effective_output_mode(&self) -> AdapterOutputMode {
+        self.custom
+            .as_ref()
+            .map_or(self.output_mode, |spec| spec.output_mode)
+    }
 }
 
 impl Default for CodexCommandConfig {
@@ -97,6 +357,9 @@ pub struct CodexAdapter {
     event_tx: Sender<AgentEvent>,
     event_rx: Receiver<AgentEvent>,
     session_id: Arc<Mutex<Option<String>>>,
+    /// Message history replayed to an HTTP backend, which keeps no server-side session.
+    conversation: Arc<Mutex<Vec<ChatMessage>>>,
+    run_control: RunControl,
 }
 
 const CODEX_GLOBAL_PROMPT_PREAMBLE: Option<&str> = Some(
@@ -148,55 +411,147 @@ impl CodexAdapter {
             event_tx,
             event_rx,
             session_id: Arc::new(Mutex::new(None)),
+            conversation: Arc::new(Mutex::new(Vec::new())),
+            run_control: RunControl::default(),
         }
     }
 
     pub fn send_prompt(&self, prompt: String) {
         let config = self.config.clone();
         let program = config.program.clone();
-        let tx = self.event_tx.clone();
+        let recording = config.recording.as_ref().map(|spec| {
+            let root = config
+                .working_dir
+                .clone()
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_default();
+            spec.begin(
+                &prompt,
+                config.model.as_deref(),
+                config.model_reasoning_effort.as_deref(),
+                &root,
+            )
+        });
+        let guard = (config.permissions == PermissionProfile::ReadOnly && config.guard_workspace)
+            .then(|| {
+                Arc::new(WorkspaceGuard {
+                    root: config
+                        .working_dir
+                        .clone()
+                        .or_else(|| std::env::current_dir().ok())
+                        .unwrap_or_default(),
+                    ignored: config.recording.as_ref().and_then(|spec| {
+                        spec.recorder.fixture_path().parent().map(Path::to_path_buf)
+                    }),
+                    baseline: Mutex::new(None),
+                })
+            });
+        let tx = RunSender {
+            tx: self.event_tx.clone(),
+            control: self.run_control.clone(),
+            generation: self.run_control.generation(),
+            recording,
+            guard,
+        };
+        if let Some(spec) = config.replay.clone() {
+            thread::spawn(move || {
+                tx.capture_workspace_baseline();
+                run_replay_prompt(&spec, &config, &tx);
+            });
+            return;
+        }
+        if let Some(spec) = config.http.clone() {
+            let conversation = self.conversation.clone();
+            thread::spawn(move || {
+                tx.capture_workspace_baseline();
+                run_http_prompt(&spec, &config, &conversation, prompt, &tx);
+            });
+            return;
+        }
         let session_id = self.session_id.clone();
         let session_id_snapshot = self.saved_session_id();
         thread::spawn(move || {
-            let prompt = apply_global_prompt_preamble(prompt, &config.program);
+            tx.capture_workspace_baseline();
             let mut command = Command::new(&config.program);
-            if config.persistent_session {
-                let known_session = session_id_snapshot;
-                if let Some(existing_session) = known_session {
-                    command
-                        .args(build_resume_prompt_args(&config, &existing_session))
-                        .arg(prompt);
+            let mut stdin_prompt = None;
+            let mut prompt_file = None;
+            if let Some(spec) = config.custom.as_ref() {
+                let known_session = session_id_snapshot.filter(|_| config.persistent_session);
+                match prepare_custom_invocation(&config, spec, known_session.as_deref(), prompt) {
+                    Ok(invocation) => {
+                        command.args(invocation.args);
+                        stdin_prompt = invocation.stdin;
+                        prompt_file = invocation.prompt_file;
+                    }
+                    Err(err) => {
+                        tx.send(AgentEvent::System(format!(
+                            "Adapter ({program}) could not write its prompt file: {err}"
+                        )));
+                        tx.send(AgentEvent::Completed {
+                            success: false,
+                            code: -1,
+                        });
+                        return;
+                    }
+                }
+            } else {
+                let prompt = apply_global_prompt_preamble(prompt, &config.program);
+                if config.persistent_session {
+                    let known_session = session_id_snapshot;
+                    if let Some(existing_session) = known_session {
+                        command
+                            .args(build_resume_prompt_args(&config, &existing_session))
+                            .arg(prompt);
+                    } else {
+                        command.args(build_new_session_args(&config)).arg(prompt);
+                    }
                 } else {
                     command.args(build_new_session_args(&config)).arg(prompt);
                 }
-            } else {
-                command.args(build_new_session_args(&config)).arg(prompt);
+            }
+            if stdin_prompt.is_some() {
+                command.stdin(Stdio::piped());
             }
             command.stdout(Stdio::piped()).stderr(Stdio::piped());
+            isolate_process_group(&mut command);
+            if let Some(working_dir) = config.working_dir.as_ref() {
+                command.current_dir(working_dir);
+            }
 
             let mut child = match command.spawn() {
                 Ok(child) => child,
                 Err(err) => {
-                    let _ = tx.send(AgentEvent::System(format!(
+                    remove_prompt_file(prompt_file.as_deref());
+                    tx.send(AgentEvent::System(format!(
                         "Adapter ({program}) failed to start: {err}"
                     )));
-                    let _ = tx.send(AgentEvent::Completed {
+                    tx.send(AgentEvent::Completed {
                         success: false,
                         code: -1,
                     });
                     return;
                 }
             };
+            tx.control.attach(tx.generation, child.id());
+            if let (Some(prompt), Some(mut stdin)) = (stdin_prompt, child.stdin.take()) {
+                // Written from its own thread so a CLI that streams output before it finishes
+                // reading stdin cannot deadlock against the readers below.
+                thread::spawn(move || {
+                    let _ = stdin.write_all(prompt.as_bytes());
+                });
+            }
 
+            let output_mode = config.effective_output_mode();
             let mut readers = Vec::new();
 
             if let Some(stdout) = child.stdout.take() {
                 readers.push(spawn_reader(
                     stdout,
                     tx.clone(),
-                    config.output_mode,
+                    output_mode,
                     Some(session_id.clone()),
                     config.backend_kind(),
+                    config.custom.clone(),
                     false,
                 ));
             }
@@ -204,16 +559,19 @@ impl CodexAdapter {
                 readers.push(spawn_reader(
                     stderr,
                     tx.clone(),
-                    config.output_mode,
+                    output_mode,
                     Some(session_id.clone()),
                     config.backend_kind(),
+                    config.custom.clone(),
                     true,
                 ));
             }
 
             let wait_result = child.wait();
+            tx.control.detach(tx.generation);
+            remove_prompt_file(prompt_file.as_deref());
             let skip_reader_join_after_wait = (config.persistent_session
-                && matches!(config.output_mode, AdapterOutputMode::PlainText))
+                && matches!(output_mode, AdapterOutputMode::PlainText))
                 || (config.persistent_session && config.skip_reader_join_after_wait);
             if skip_reader_join_after_wait {
                 // Worker-style adapters can run shell commands that spawn background descendants.
@@ -230,6 +588,19 @@ impl CodexAdapter {
         });
     }
 
+    /// Scopes replay and recording to one worker context of a top-level task; adapters with
+    /// neither are returned unchanged.
+    pub fn with_worker_context(mut self, task: u64, context_key: &str) -> Self {
+        if let Some(replay) = self.config.replay.as_mut() {
+            replay.task = Some(task);
+        }
+        if let Some(recording) = self.config.recording.as_mut() {
+            recording.task = Some(task);
+            recording.context_key = context_key.to_string();
+        }
+        self
+    }
+
     #[cfg(test)]
     pub fn program(&self) -> &str {
         &self.config.program
@@ -259,8 +630,15 @@ impl CodexAdapter {
         events
     }
 
+    pub fn kill_running(&self) -> bool {
+        self.run_control.kill()
+    }
+
     pub fn reset_session(&self) {
         self.set_saved_session_id(None);
+        if let Ok(mut conversation) = self.conversation.lock() {
+            conversation.clear();
+        }
     }
 
     pub fn saved_session_id(&self) -> Option<String> {
@@ -274,18 +652,219 @@ impl CodexAdapter {
     }
 }
 
+#[derive(Clone)]
+struct RunSender {
+    tx: Sender<AgentEvent>,
+    control: RunControl,
+    generation: u64,
+    recording: Option<RecordingHandle>,
+    guard: Option<Arc<WorkspaceGuard>>,
+}
+
+/// Checks that a read-only run left its working directory as it found it.
+struct WorkspaceGuard {
+    root: PathBuf,
+    /// The recording dir, which other adapters may write to while this run is in flight.
+    ignored: Option<PathBuf>,
+    baseline: Mutex<Option<WorkspaceSnapshot>>,
+}
+
+impl WorkspaceGuard {
+    /// Files the run changed, as `status path` lines; empty when nothing changed or the
+    /// baseline could not be taken.
+    fn changed_files(&self) -> Vec<String> {
+        let Some(baseline) = self.baseline.lock().ok().and_then(|mut lock| lock.take()) else {
+            return Vec::new();
+        };
+        let Ok(diff) = pass_diff::diff_since(&self.root, &baseline) else {
+            return Vec::new();
+        };
+        diff.files
+            .into_iter()
+            .filter(|file| {
+                self.ignored
+                    .as_ref()
+                    .is_none_or(|dir| !self.root.join(&file.path).starts_with(dir))
+            })
+            .map(|file| format!("{} {}", file.status, file.path))
+            .collect()
+    }
+}
+
+impl RunSender {
+    /// Snapshots the workspace of a read-only run; called before the agent starts.
+    fn capture_workspace_baseline(&self) {
+        let Some(guard) = self.guard.as_ref() else {
+            return;
+        };
+        match pass_diff::capture_manifest_snapshot(&guard.root) {
+            Ok(snapshot) => {
+                if let Ok(mut baseline) = guard.baseline.lock() {
+                    *baseline = Some(snapshot);
+                }
+            }
+            Err(err) => self.send(AgentEvent::System(format!(
+                "Could not snapshot {} for this read-only run; its changes will not be checked: {err}",
+                guard.root.display()
+            ))),
+        }
+    }
+
+    fn send(&self, event: AgentEvent) {
+        let event = match (event, self.guard.as_ref()) {
+            (AgentEvent::Completed { success, code }, Some(guard)) => {
+                let changed = guard.changed_files();
+                if changed.is_empty() {
+                    AgentEvent::Completed { success, code }
+                } else {
+                    self.deliver(AgentEvent::System(format!(
+                        "Read-only run changed the workspace, failing it:\n{}",
+                        changed.join("\n")
+                    )));
+                    AgentEvent::Completed {
+                        success: false,
+                        code,
+                    }
+                }
+            }
+            (event, _) => event,
+        };
+        self.deliver(event);
+    }
+
+    fn deliver(&self, event: AgentEvent) {
+        // Recorded before sending so a completion's workspace edits are captured before the
+        // workflow reacts to it.
+        if let Some(recording) = self.recording.as_ref()
+            && self.is_current()
+            && let Err(err) = recording.record(&event)
+        {
+            self.control.send_if_current(self.generation, || {
+                let _ = self.tx.send(AgentEvent::System(format!(
+                    "Recorder could not write its fixture: {err}"
+                )));
+            });
+        }
+        self.control.send_if_current(self.generation, || {
+            let _ = self.tx.send(event);
+        });
+    }
+
+    fn is_current(&self) -> bool {
+        self.control.generation() == self.generation
+    }
+}
+
+fn run_replay_prompt(spec: &ReplayBackendSpec, config: &CodexCommandConfig, tx: &RunSender) {
+    let root = config
+        .working_dir
+        .clone()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    crate::replay::play_next_step(
+        &spec.player,
+        &spec.role,
+        spec.task,
+        &root,
+        "Adapter (replay)",
+        |event| tx.send(event),
+    );
+}
+
+/// Runs one prompt against an HTTP backend. Persistent adapters send the whole conversation
+/// and remember the reply; a killed run stops streaming and leaves the history untouched.
+fn run_http_prompt(
+    spec: &HttpBackendSpec,
+    config: &CodexCommandConfig,
+    conversation: &Mutex<Vec<ChatMessage>>,
+    prompt: String,
+    tx: &RunSender,
+) {
+    let mut messages = if config.persistent_session {
+        conversation
+            .lock()
+            .map(|history| history.clone())
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    messages.push(ChatMessage::user(prompt.clone()));
+    let mut spec = spec.clone();
+    if let Some(model) = config
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        spec.model = model.to_string();
+    }
+
+    let mut lines = LineBuffer::default();
+    let result = crate::http_backend::stream_chat_completion(&spec, &messages, |delta| {
+        for line in lines.push(delta) {
+            tx.send(AgentEvent::Output(line));
+        }
+        tx.is_current()
+    });
+    match result {
+        Ok(ChatOutcome::Completed(reply)) => {
+            if let Some(rest) = lines.finish() {
+                tx.send(AgentEvent::Output(rest));
+            }
+            if config.persistent_session
+                && tx.is_current()
+                && let Ok(mut history) = conversation.lock()
+            {
+                history.push(ChatMessage::user(prompt));
+                history.push(ChatMessage::assistant(reply));
+            }
+            tx.send(AgentEvent::Completed {
+                success: true,
+                code: 0,
+            });
+        }
+        Ok(ChatOutcome::Cancelled) => {}
+        Err(message) => {
+            if let Some(rest) = lines.finish() {
+                tx.send(AgentEvent::Output(rest));
+            }
+            tx.send(AgentEvent::System(message));
+            tx.send(AgentEvent::Completed {
+                success: false,
+                code: -1,
+            });
+        }
+    }
+}
+
 fn spawn_reader<R: std::io::Read + Send + 'static>(
     reader: R,
-    tx: Sender<AgentEvent>,
+    tx: RunSender,
     output_mode: AdapterOutputMode,
     session_id: Option<Arc<Mutex<Option<String>>>>,
     backend_kind: BackendKind,
+    custom: Option<CustomBackendSpec>,
     is_stderr: bool,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
+        let session_extractor = custom
+            .as_ref()
+            .map(|spec| &spec.session_id)
+            .filter(|extractor| extractor.is_configured());
+        let text_extractor = custom
+            .as_ref()
+            .map(|spec| &spec.assistant_text)
+            .filter(|extractor| extractor.is_configured());
         for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            let found_session = match session_extractor {
+                Some(extractor) => extractor
+                    .extract(&line)
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty()),
+                None => parse_session_id_from_jsonl_line(&line, backend_kind),
+            };
             if let Some(state) = &session_id
-                && let Some(found) = parse_session_id_from_jsonl_line(&line, backend_kind)
+                && let Some(found) = found_session
                 && let Ok(mut lock) = state.lock()
                 && lock.is_none()
             {
@@ -293,20 +872,24 @@ fn spawn_reader<R: std::io::Read + Send + 'static>(
             }
             match output_mode {
                 AdapterOutputMode::PlainText => {
-                    let _ = tx.send(AgentEvent::Output(line));
+                    tx.send(AgentEvent::Output(line));
                 }
                 AdapterOutputMode::JsonAssistantOnly => {
-                    if let Some(text) = parse_agent_message_from_jsonl_line(&line) {
-                        let _ = tx.send(AgentEvent::Output(text));
+                    let assistant_text = match text_extractor {
+                        Some(extractor) => extractor.extract(&line),
+                        None => parse_agent_message_from_jsonl_line(&line),
+                    };
+                    if let Some(text) = assistant_text {
+                        tx.send(AgentEvent::Output(text));
                     } else if let Some(system_line) = parse_system_message_from_jsonl_line(&line) {
-                        let _ = tx.send(AgentEvent::System(system_line));
+                        tx.send(AgentEvent::System(system_line));
                     } else if !is_stderr && !looks_like_json_line(&line) && !line.trim().is_empty()
                     {
                         // Some adapters occasionally emit plaintext lines even in JSON mode.
                         // Surface these lines instead of dropping potentially user-facing content.
-                        let _ = tx.send(AgentEvent::Output(line));
+                        tx.send(AgentEvent::Output(line));
                     } else if is_stderr {
-                        let _ = tx.send(AgentEvent::System(line));
+                        tx.send(AgentEvent::System(line));
                     }
                 }
             }
@@ -527,7 +1110,7 @@ fn program_looks_like_claude(program: &str) -> bool {
 fn build_new_session_args(config: &CodexCommandConfig) -> Vec<String> {
     match config.backend_kind() {
         BackendKind::Codex => {
-            let mut args = config.args_prefix.clone();
+            let mut args = apply_permission_args(config, config.args_prefix.clone());
             append_codex_model_selection_args(
                 &mut args,
                 config.model.as_deref(),
@@ -536,7 +1119,7 @@ fn build_new_session_args(config: &CodexCommandConfig) -> Vec<String> {
             args
         }
         BackendKind::Claude => {
-            let mut args = config.args_prefix.clone();
+            let mut args = apply_permission_args(config, config.args_prefix.clone());
             if matches!(config.output_mode, AdapterOutputMode::JsonAssistantOnly) {
                 args.push("--output-format".to_string());
                 args.push("stream-json".to_string());
@@ -546,6 +1129,7 @@ fn build_new_session_args(config: &CodexCommandConfig) -> Vec<String> {
             args.push("-p".to_string());
             args
         }
+        BackendKind::Custom | BackendKind::Http | BackendKind::Replay => config.args_prefix.clone(),
     }
 }
 
@@ -556,7 +1140,7 @@ fn build_resume_args(config: &CodexCommandConfig) -> Vec<String> {
             if args.first().is_some_and(|arg| arg == "exec") {
                 args.remove(0);
             }
-            let mut args = sanitize_resume_args(args);
+            let mut args = apply_permission_args(config, sanitize_resume_args(args));
             append_codex_model_selection_args(
                 &mut args,
                 config.model.as_deref(),
@@ -565,7 +1149,7 @@ fn build_resume_args(config: &CodexCommandConfig) -> Vec<String> {
             args
         }
         BackendKind::Claude => {
-            let mut args = config.args_prefix.clone();
+            let mut args = apply_permission_args(config, config.args_prefix.clone());
             if matches!(config.output_mode, AdapterOutputMode::JsonAssistantOnly) {
                 args.push("--output-format".to_string());
                 args.push("stream-json".to_string());
@@ -574,6 +1158,7 @@ fn build_resume_args(config: &CodexCommandConfig) -> Vec<String> {
             append_claude_model_selection_args(&mut args, config.model.as_deref());
             args
         }
+        BackendKind::Custom | BackendKind::Http | BackendKind::Replay => config.args_prefix.clone(),
     }
 }
 
@@ -592,6 +1177,158 @@ fn build_resume_prompt_args(config: &CodexCommandConfig, existing_session: &str)
             args.push("-p".to_string());
             args
         }
+        BackendKind::Custom | BackendKind::Http | BackendKind::Replay => build_resume_args(config),
+    }
+}
+
+/// Swaps the `full` flags in `args` for the selected profile's flags. A `full` run keeps the
+/// prefix as written and only adds flags it is missing.
+fn apply_permission_args(config: &CodexCommandConfig, mut args: Vec<String>) -> Vec<String> {
+    let full = &config.permission_args.full;
+    let selected = config.permission_args.for_profile(config.permissions);
+    if config.permissions != PermissionProfile::Full {
+        args.retain(|arg| !full.contains(arg));
+    }
+    for arg in selected {
+        if config.permissions != PermissionProfile::Full || !args.contains(arg) {
+            args.push(arg.clone());
+        }
+    }
+    args
+}
+
+struct CustomInvocation {
+    args: Vec<String>,
+    stdin: Option<String>,
+    prompt_file: Option<PathBuf>,
+}
+
+static PROMPT_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn prepare_custom_invocation(
+    config: &CodexCommandConfig,
+    spec: &CustomBackendSpec,
+    existing_session: Option<&str>,
+    prompt: String,
+) -> std::io::Result<CustomInvocation> {
+    let prompt_file = match spec.prompt_input {
+        PromptInput::File => {
+            let path = std::env::temp_dir().join(format!(
+                "agentbob-prompt-{}-{}.txt",
+                std::process::id(),
+                PROMPT_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::write(&path, &prompt)?;
+            Some(path)
+        }
+        PromptInput::Argv | PromptInput::Stdin => None,
+    };
+    let args = build_custom_args(
+        config,
+        spec,
+        existing_session,
+        &prompt,
+        prompt_file.as_deref(),
+    );
+    Ok(CustomInvocation {
+        args,
+        stdin: (spec.prompt_input == PromptInput::Stdin).then_some(prompt),
+        prompt_file,
+    })
+}
+
+fn build_custom_args(
+    config: &CodexCommandConfig,
+    spec: &CustomBackendSpec,
+    existing_session: Option<&str>,
+    prompt: &str,
+    prompt_file: Option<&Path>,
+) -> Vec<String> {
+    let model = config
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let effort = config
+        .model_reasoning_effort
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let prompt_file = prompt_file
+        .map(|path| path.display().to_string())
+        .unwrap_or_default();
+    let session_args = match existing_session {
+        Some(_) if !spec.resume_args.is_empty() => &spec.resume_args,
+        _ => &spec.new_session_args,
+    };
+
+    let mut templates = apply_permission_args(config, config.args_prefix.clone());
+    templates.extend(session_args.iter().cloned());
+    if model.is_some() {
+        templates.extend(spec.model_args.iter().cloned());
+    }
+    if effort.is_some() {
+        templates.extend(spec.effort_args.iter().cloned());
+    }
+    let placeholder = match spec.prompt_input {
+        PromptInput::Argv => Some("{prompt}"),
+        PromptInput::File => Some("{prompt_file}"),
+        PromptInput::Stdin => None,
+    };
+    let append_prompt = placeholder
+        .is_some_and(|placeholder| !templates.iter().any(|arg| arg.contains(placeholder)));
+    // A stdin prompt stays off the command line even if a template names `{prompt}`.
+    let argv_prompt = if spec.prompt_input == PromptInput::Stdin {
+        ""
+    } else {
+        prompt
+    };
+
+    // `{prompt}` is expanded last so placeholder-like text inside the prompt is left alone.
+    let vars = [
+        ("{model}", model.unwrap_or_default()),
+        ("{effort}", effort.unwrap_or_default()),
+        ("{session_id}", existing_session.unwrap_or_default()),
+        ("{prompt_file}", prompt_file.as_str()),
+        ("{prompt}", argv_prompt),
+    ];
+    let mut args = templates
+        .iter()
+        .map(|template| {
+            vars.iter().fold(template.clone(), |arg, (name, value)| {
+                arg.replace(name, value)
+            })
+        })
+        .collect::<Vec<_>>();
+    if append_prompt {
+        match spec.prompt_input {
+            PromptInput::Argv => args.push(prompt.to_string()),
+            PromptInput::File => args.push(prompt_file),
+            PromptInput::Stdin => {}
+        }
+    }
+    args
+}
+
+fn remove_prompt_file(path: Option<&Path>) {
+    if let Some(path) = path {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Walks dot-separated keys (numeric segments index arrays) and renders the value as text.
+fn json_value_at_path(value: &serde_json::Value, path: &[String]) -> Option<String> {
+    let mut current = value;
+    for segment in path {
+        current = match current {
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => current.get(segment)?,
+        };
+    }
+    match current {
+        serde_json::Value::String(text) if !text.is_empty() => Some(text.clone()),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        _ => None,
     }
 }
 
@@ -621,28 +1358,28 @@ fn append_claude_model_selection_args(args: &mut Vec<String>, model: Option<&str
 }
 
 fn emit_completion_event(
-    tx: &Sender<AgentEvent>,
+    tx: &RunSender,
     program: &str,
     wait_result: std::io::Result<std::process::ExitStatus>,
 ) {
     match wait_result {
         Ok(status) => {
             let code = status.code().unwrap_or(-1);
-            let _ = tx.send(AgentEvent::Completed {
+            tx.send(AgentEvent::Completed {
                 success: status.success(),
                 code,
             });
             if !status.success() {
-                let _ = tx.send(AgentEvent::System(format!(
+                tx.send(AgentEvent::System(format!(
                     "Adapter ({program}) exited with status code {code}"
                 )));
             }
         }
         Err(err) => {
-            let _ = tx.send(AgentEvent::System(format!(
+            tx.send(AgentEvent::System(format!(
                 "Adapter ({program}) failed while waiting for process: {err}"
             )));
-            let _ = tx.send(AgentEvent::Completed {
+            tx.send(AgentEvent::Completed {
                 success: false,
                 code: -1,
             });
@@ -1,11 +1,21 @@
 use std::collections::HashMap;
 use std::io;
+use std::path::Path;
+use std::sync::Arc;
 
 use serde::Deserialize;
 
-use crate::agent::{BackendKind, CodexCommandConfig};
+use regex::Regex;
+
+use crate::agent::{
+    AdapterOutputMode, BackendKind, CodexCommandConfig, CustomBackendSpec, OutputExtractor,
+    PermissionArgs, PermissionProfile, PromptInput,
+};
 use crate::artifact_io::load_merged_metaagent_config_text;
 use crate::default_config::DEFAULT_CONFIG_TOML;
+use crate::http_backend::HttpBackendSpec;
+use crate::recorder::{RecordingSpec, SessionRecorder};
+use crate::replay::{ReplayBackendSpec, ReplayPlayer};
 
 pub const DEFAULT_PROFILE_LABEL: &str = "large-smart";
 
@@ -45,11 +55,47 @@ pub enum CodexAgentKind {
     WorkerFinalAudit,
 }
 
+impl CodexAgentKind {
+    /// Key used for this agent in `[codex.agent_profiles]` and in replay fixtures.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            Self::Master => "master",
+            Self::MasterReport => "master_report",
+            Self::ProjectInfo => "project_info",
+            Self::DocsAttach => "docs_attach",
+            Self::TaskCheck => "task_check",
+            Self::WorkerImplementor => "worker_implementor",
+            Self::WorkerAuditor => "worker_auditor",
+            Self::WorkerTestWriter => "worker_test_writer",
+            Self::WorkerFinalAudit => "worker_final_audit",
+        }
+    }
+
+    /// Permissions used when the agent's `agent_profiles` entry names none. Agents that only
+    /// report are read-only; the master, project info and docs agents write session files
+    /// outside the workspace, which a workspace-write sandbox would block.
+    pub fn default_permissions(self) -> PermissionProfile {
+        match self {
+            Self::MasterReport | Self::TaskCheck | Self::WorkerAuditor | Self::WorkerFinalAudit => {
+                PermissionProfile::ReadOnly
+            }
+            Self::WorkerImplementor | Self::WorkerTestWriter => PermissionProfile::WorkspaceWrite,
+            Self::Master | Self::ProjectInfo | Self::DocsAttach => PermissionProfile::Full,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CodexAgentModelRouting {
     profiles: HashMap<String, CodexModelProfile>,
     agent_profiles: AgentProfileAssignments,
-    base_command: CodexCommandConfig,
+    selected_backend: BackendKind,
+    codex_command: CodexCommandConfig,
+    claude_command: CodexCommandConfig,
+    custom_command: Option<CodexCommandConfig>,
+    http_command: Option<CodexCommandConfig>,
+    replay_command: Option<CodexCommandConfig>,
+    recorder: Option<SessionRecorder>,
 }
 
 impl Default for CodexAgentModelRouting {
@@ -67,11 +113,11 @@ impl CodexAgentModelRouting {
     pub fn from_toml_str(text: &str) -> io::Result<Self> {
         let default_config = parse_config(DEFAULT_CONFIG_TOML)?;
         let override_config = parse_config(text)?;
-        Ok(Self::from_merged_config(default_config, override_config))
+        Self::from_merged_config(default_config, override_config)
     }
 
     pub fn profile_for(&self, kind: CodexAgentKind) -> CodexModelProfile {
-        let label = self.agent_profiles.label_for(kind);
+        let label = &self.agent_profiles.assignment_for(kind).profile;
         self.profiles
             .get(label)
             .or_else(|| self.profiles.get(DEFAULT_PROFILE_LABEL))
@@ -79,11 +125,112 @@ impl CodexAgentModelRouting {
             .unwrap_or_else(default_large_smart_profile)
     }
 
+    /// Looks up a profile by label, e.g. one named by a task's review quorum.
+    pub fn named_profile(&self, label: &str) -> Option<CodexModelProfile> {
+        self.profiles.get(&normalize_profile_label(label)).cloned()
+    }
+
+    #[cfg(test)]
     pub fn base_command_config(&self) -> CodexCommandConfig {
-        self.base_command.clone()
+        self.backend_command_config(self.selected_backend)
+            .expect("the selected backend is always configured")
+    }
+
+    pub fn selected_backend(&self) -> BackendKind {
+        self.selected_backend
+    }
+
+    /// Backends that can be selected: the built-ins plus any configured custom CLI, HTTP server or
+    /// replay fixture.
+    pub fn available_backends(&self) -> Vec<BackendKind> {
+        let mut backends = vec![BackendKind::Codex, BackendKind::Claude];
+        if self.custom_command.is_some() {
+            backends.push(BackendKind::Custom);
+        }
+        if self.http_command.is_some() {
+            backends.push(BackendKind::Http);
+        }
+        if self.replay_command.is_some() {
+            backends.push(BackendKind::Replay);
+        }
+        backends
+    }
+
+    /// The fixture player shared by replay adapters when `replay` is the selected backend.
+    pub fn replay_player(&self) -> Option<Arc<ReplayPlayer>> {
+        if self.selected_backend != BackendKind::Replay {
+            return None;
+        }
+        self.replay_command
+            .as_ref()
+            .and_then(|config| config.replay.as_ref())
+            .map(|spec| Arc::clone(&spec.player))
+    }
+
+    pub fn recorder(&self) -> Option<&SessionRecorder> {
+        self.recorder.as_ref()
+    }
+
+    /// Records every adapter built from this routing from now on; see `--record`.
+    pub fn set_recorder(&mut self, recorder: Option<SessionRecorder>) {
+        self.recorder = recorder;
+    }
+
+    /// Backend named by the agent's `agent_profiles` entry; `None` follows `[backend].selected`.
+    pub fn backend_for(&self, kind: CodexAgentKind) -> Option<BackendKind> {
+        self.agent_profiles.assignment_for(kind).backend
+    }
+
+    pub fn permissions_for(&self, kind: CodexAgentKind) -> PermissionProfile {
+        self.agent_profiles
+            .assignment_for(kind)
+            .permissions
+            .unwrap_or_else(|| kind.default_permissions())
+    }
+
+    /// Command config for the backend `kind` runs on: its own backend when its `agent_profiles`
+    /// entry names one, otherwise `selected_backend`.
+    pub fn command_config_for(
+        &self,
+        kind: CodexAgentKind,
+        selected_backend: BackendKind,
+    ) -> io::Result<CodexCommandConfig> {
+        let mut config =
+            self.backend_command_config(self.backend_for(kind).unwrap_or(selected_backend))?;
+        if let Some(replay) = config.replay.as_mut() {
+            replay.role = kind.config_key().to_string();
+        }
+        config.permissions = self.permissions_for(kind);
+        // The master report runs while workers edit the workspace.
+        config.guard_workspace = kind != CodexAgentKind::MasterReport;
+        config.recording = self.recorder.clone().map(|recorder| RecordingSpec {
+            recorder,
+            role: kind.config_key().to_string(),
+            task: None,
+            context_key: kind.config_key().to_string(),
+        });
+        Ok(config)
     }
 
-    fn from_merged_config(base: MetaAgentConfigFile, override_cfg: MetaAgentConfigFile) -> Self {
+    /// Errors for an optional backend without its `[backend.<name>]` section rather than running
+    /// the agent on a different backend than the config asked for.
+    fn backend_command_config(&self, backend: BackendKind) -> io::Result<CodexCommandConfig> {
+        let (config, section) = match backend {
+            BackendKind::Codex => return Ok(self.codex_command.clone()),
+            BackendKind::Claude => return Ok(self.claude_command.clone()),
+            BackendKind::Custom => (&self.custom_command, "custom"),
+            BackendKind::Http => (&self.http_command, "http"),
+            BackendKind::Replay => (&self.replay_command, "replay"),
+        };
+        config
+            .clone()
+            .ok_or_else(|| unconfigured_backend_error(section))
+    }
+
+    fn from_merged_config(
+        base: MetaAgentConfigFile,
+        override_cfg: MetaAgentConfigFile,
+    ) -> io::Result<Self> {
         let merged_backend = base.backend.merged_with(override_cfg.backend);
         let merged_codex = base.codex.merged_with(override_cfg.codex);
         Self::from_merged_runtime_config(merged_backend, merged_codex)
@@ -92,7 +239,7 @@ impl CodexAgentModelRouting {
     fn from_merged_runtime_config(
         backend: BackendSelectionConfigFile,
         config: CodexModelConfigFile,
-    ) -> Self {
+    ) -> io::Result<Self> {
         let mut profiles = HashMap::new();
         for (label, profile) in config.model_profiles {
             let Some(parsed_profile) = CodexModelProfile::from_config(profile) else {
@@ -100,11 +247,20 @@ impl CodexAgentModelRouting {
             };
             profiles.insert(normalize_profile_label(&label), parsed_profile);
         }
-        Self {
+        let commands = backend.into_runtime()?;
+        let agent_profiles = config.agent_profiles.into_runtime()?;
+        agent_profiles.check_backends_configured(&commands)?;
+        Ok(Self {
             profiles,
-            agent_profiles: config.agent_profiles.into_runtime(),
-            base_command: backend.into_runtime(),
-        }
+            agent_profiles,
+            selected_backend: commands.selected,
+            codex_command: commands.codex,
+            claude_command: commands.claude,
+            custom_command: commands.custom,
+            http_command: commands.http,
+            replay_command: commands.replay,
+            recorder: None,
+        })
     }
 
     fn emergency_fallback() -> Self {
@@ -116,7 +272,13 @@ impl CodexAgentModelRouting {
         Self {
             profiles,
             agent_profiles: AgentProfileAssignments::default(),
-            base_command: CodexCommandConfig::default(),
+            selected_backend: BackendKind::Codex,
+            codex_command: CodexCommandConfig::default_for_backend(BackendKind::Codex),
+            claude_command: CodexCommandConfig::default_for_backend(BackendKind::Claude),
+            custom_command: None,
+            http_command: None,
+            replay_command: None,
+            recorder: None,
         }
     }
 }
@@ -134,6 +296,18 @@ struct BackendSelectionConfigFile {
     selected: Option<String>,
     codex: BackendCommandConfigFile,
     claude: BackendCommandConfigFile,
+    custom: CustomBackendConfigFile,
+    http: HttpBackendConfigFile,
+    replay: ReplayBackendConfigFile,
+}
+
+struct BackendCommands {
+    selected: BackendKind,
+    codex: CodexCommandConfig,
+    claude: CodexCommandConfig,
+    custom: Option<CodexCommandConfig>,
+    http: Option<CodexCommandConfig>,
+    replay: Option<CodexCommandConfig>,
 }
 
 impl BackendSelectionConfigFile {
@@ -142,34 +316,269 @@ impl BackendSelectionConfigFile {
             selected: override_cfg.selected.or(self.selected),
             codex: self.codex.merged_with(override_cfg.codex),
             claude: self.claude.merged_with(override_cfg.claude),
+            custom: self.custom.merged_with(override_cfg.custom),
+            http: self.http.merged_with(override_cfg.http),
+            replay: ReplayBackendConfigFile {
+                fixture: override_cfg.replay.fixture.or(self.replay.fixture),
+            },
         }
     }
 
-    fn into_runtime(self) -> CodexCommandConfig {
+    fn into_runtime(self) -> io::Result<BackendCommands> {
+        let custom = self.custom.into_runtime()?;
+        let http = self.http.into_runtime();
+        let replay = self.replay.into_runtime()?;
         let selected = self
             .selected
             .as_deref()
+            .and_then(parse_backend_label)
+            .filter(|backend| match backend {
+                BackendKind::Custom => custom.is_some(),
+                BackendKind::Http => http.is_some(),
+                BackendKind::Replay => replay.is_some(),
+                BackendKind::Codex | BackendKind::Claude => true,
+            })
+            .unwrap_or(BackendKind::Codex);
+        Ok(BackendCommands {
+            selected,
+            codex: self.codex.into_runtime(BackendKind::Codex),
+            claude: self.claude.into_runtime(BackendKind::Claude),
+            custom,
+            http,
+            replay,
+        })
+    }
+}
+
+/// `[backend.replay]`: a JSON fixture of scripted agent replies, for offline workflow runs.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+struct ReplayBackendConfigFile {
+    /// Relative paths resolve against the directory `bob` is started from.
+    fixture: Option<String>,
+}
+
+impl ReplayBackendConfigFile {
+    /// Returns `None` when no fixture is configured; an unreadable fixture is an error.
+    fn into_runtime(self) -> io::Result<Option<CodexCommandConfig>> {
+        let Some(fixture) = self
+            .fixture
+            .as_deref()
             .map(str::trim)
-            .unwrap_or("codex")
-            .to_ascii_lowercase();
-        let backend_kind = if selected == "claude" {
-            BackendKind::Claude
-        } else {
-            BackendKind::Codex
+            .filter(|value| !value.is_empty())
+        else {
+            return Ok(None);
         };
-        let backend_config = match backend_kind {
-            BackendKind::Codex => self.codex,
-            BackendKind::Claude => self.claude,
+        let player = ReplayPlayer::load(Path::new(fixture))?;
+        let mut config = CodexCommandConfig::default_for_backend(BackendKind::Replay);
+        config.program = fixture.to_string();
+        config.replay = Some(ReplayBackendSpec {
+            player: Arc::new(player),
+            ..ReplayBackendSpec::default()
+        });
+        Ok(Some(config))
+    }
+}
+
+/// `[backend.http]`: an OpenAI-compatible chat completions server.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+struct HttpBackendConfigFile {
+    base_url: Option<String>,
+    model: Option<String>,
+    api_key_env: Option<String>,
+    connect_timeout_secs: Option<u64>,
+    read_timeout_secs: Option<u64>,
+}
+
+impl HttpBackendConfigFile {
+    fn merged_with(self, override_cfg: Self) -> Self {
+        Self {
+            base_url: override_cfg.base_url.or(self.base_url),
+            model: override_cfg.model.or(self.model),
+            api_key_env: override_cfg.api_key_env.or(self.api_key_env),
+            connect_timeout_secs: override_cfg
+                .connect_timeout_secs
+                .or(self.connect_timeout_secs),
+            read_timeout_secs: override_cfg.read_timeout_secs.or(self.read_timeout_secs),
+        }
+    }
+
+    /// Returns `None` when no `base_url` is configured.
+    fn into_runtime(self) -> Option<CodexCommandConfig> {
+        let base_url = self
+            .base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())?;
+        let defaults = HttpBackendSpec::default();
+        let spec = HttpBackendSpec {
+            base_url: base_url.to_string(),
+            model: self
+                .model
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map_or(defaults.model, ToString::to_string),
+            api_key_env: self
+                .api_key_env
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty()),
+            connect_timeout_secs: self
+                .connect_timeout_secs
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.connect_timeout_secs),
+            read_timeout_secs: self
+                .read_timeout_secs
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.read_timeout_secs),
+        };
+        let mut config = CodexCommandConfig::default_for_backend(BackendKind::Http);
+        config.program = spec.base_url.clone();
+        config.model = Some(spec.model.clone());
+        config.http = Some(spec);
+        Some(config)
+    }
+}
+
+/// `[backend.custom]`: an agent CLI described by argument templates and output extractors.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+struct CustomBackendConfigFile {
+    program: Option<String>,
+    args_prefix: Option<Vec<String>>,
+    new_session_args: Option<Vec<String>>,
+    resume_args: Option<Vec<String>>,
+    model_args: Option<Vec<String>>,
+    effort_args: Option<Vec<String>>,
+    prompt_input: Option<String>,
+    output_mode: Option<String>,
+    assistant_text_path: Option<String>,
+    assistant_text_regex: Option<String>,
+    session_id_path: Option<String>,
+    session_id_regex: Option<String>,
+    permissions: PermissionArgsConfigFile,
+}
+
+impl CustomBackendConfigFile {
+    fn merged_with(self, override_cfg: Self) -> Self {
+        Self {
+            program: override_cfg.program.or(self.program),
+            args_prefix: override_cfg.args_prefix.or(self.args_prefix),
+            new_session_args: override_cfg.new_session_args.or(self.new_session_args),
+            resume_args: override_cfg.resume_args.or(self.resume_args),
+            model_args: override_cfg.model_args.or(self.model_args),
+            effort_args: override_cfg.effort_args.or(self.effort_args),
+            prompt_input: override_cfg.prompt_input.or(self.prompt_input),
+            output_mode: override_cfg.output_mode.or(self.output_mode),
+            assistant_text_path: override_cfg
+                .assistant_text_path
+                .or(self.assistant_text_path),
+            assistant_text_regex: override_cfg
+                .assistant_text_regex
+                .or(self.assistant_text_regex),
+            session_id_path: override_cfg.session_id_path.or(self.session_id_path),
+            session_id_regex: override_cfg.session_id_regex.or(self.session_id_regex),
+            permissions: self.permissions.merged_with(override_cfg.permissions),
+        }
+    }
+
+    /// Returns `None` when no program is configured; malformed settings are reported as errors.
+    fn into_runtime(self) -> io::Result<Option<CodexCommandConfig>> {
+        let Some(program) = self
+            .program
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        else {
+            return Ok(None);
+        };
+        let prompt_input = match self.prompt_input.as_deref().map(normalize_profile_label) {
+            None => PromptInput::Argv,
+            Some(value) => match value.as_str() {
+                "argv" => PromptInput::Argv,
+                "stdin" => PromptInput::Stdin,
+                "file" => PromptInput::File,
+                _ => {
+                    return Err(invalid_custom_backend(format!(
+                        "prompt_input must be \"argv\", \"stdin\" or \"file\", got {value:?}"
+                    )));
+                }
+            },
         };
-        backend_config.into_runtime(backend_kind)
+        let output_mode = match self.output_mode.as_deref().map(normalize_profile_label) {
+            None => AdapterOutputMode::PlainText,
+            Some(value) => match value.as_str() {
+                "plain" => AdapterOutputMode::PlainText,
+                "json" => AdapterOutputMode::JsonAssistantOnly,
+                _ => {
+                    return Err(invalid_custom_backend(format!(
+                        "output_mode must be \"plain\" or \"json\", got {value:?}"
+                    )));
+                }
+            },
+        };
+        let spec = CustomBackendSpec {
+            new_session_args: self.new_session_args.unwrap_or_default(),
+            resume_args: self.resume_args.unwrap_or_default(),
+            model_args: self.model_args.unwrap_or_default(),
+            effort_args: self.effort_args.unwrap_or_default(),
+            prompt_input,
+            output_mode,
+            assistant_text: output_extractor(
+                "assistant_text",
+                self.assistant_text_path,
+                self.assistant_text_regex,
+            )?,
+            session_id: output_extractor(
+                "session_id",
+                self.session_id_path,
+                self.session_id_regex,
+            )?,
+        };
+        let mut config = CodexCommandConfig::default_for_backend(BackendKind::Custom);
+        config.program = program.to_string();
+        config.args_prefix = self.args_prefix.unwrap_or_default();
+        config.output_mode = output_mode;
+        config.custom = Some(spec);
+        config.permission_args = self.permissions.into_runtime(config.permission_args);
+        Ok(Some(config))
     }
 }
 
+fn output_extractor(
+    name: &str,
+    path: Option<String>,
+    regex: Option<String>,
+) -> io::Result<OutputExtractor> {
+    let json_path = path
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.split('.').map(ToString::to_string).collect());
+    let regex = match regex.as_deref().filter(|value| !value.is_empty()) {
+        Some(pattern) => Some(
+            Regex::new(pattern)
+                .map_err(|err| invalid_custom_backend(format!("{name}_regex: {err}")))?,
+        ),
+        None => None,
+    };
+    Ok(OutputExtractor { json_path, regex })
+}
+
+fn invalid_custom_backend(message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("[backend.custom] {message}"),
+    )
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
 struct BackendCommandConfigFile {
     program: Option<String>,
     args_prefix: Option<Vec<String>>,
+    permissions: PermissionArgsConfigFile,
 }
 
 impl BackendCommandConfigFile {
@@ -177,6 +586,7 @@ impl BackendCommandConfigFile {
         Self {
             program: override_cfg.program.or(self.program),
             args_prefix: override_cfg.args_prefix.or(self.args_prefix),
+            permissions: self.permissions.merged_with(override_cfg.permissions),
         }
     }
 
@@ -197,10 +607,47 @@ impl BackendCommandConfigFile {
                 .filter(|arg| !arg.is_empty())
                 .collect();
         }
+        config.permission_args = self.permissions.into_runtime(config.permission_args);
         config
     }
 }
 
+/// `[backend.<name>.permissions]`: the flags each permission profile adds for that backend.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+struct PermissionArgsConfigFile {
+    read_only: Option<Vec<String>>,
+    workspace_write: Option<Vec<String>>,
+    full: Option<Vec<String>>,
+}
+
+impl PermissionArgsConfigFile {
+    fn merged_with(self, override_cfg: Self) -> Self {
+        Self {
+            read_only: override_cfg.read_only.or(self.read_only),
+            workspace_write: override_cfg.workspace_write.or(self.workspace_write),
+            full: override_cfg.full.or(self.full),
+        }
+    }
+
+    fn into_runtime(self, defaults: PermissionArgs) -> PermissionArgs {
+        let args = |values: Option<Vec<String>>, default: Vec<String>| {
+            values.map_or(default, |values| {
+                values
+                    .into_iter()
+                    .map(|arg| arg.trim().to_string())
+                    .filter(|arg| !arg.is_empty())
+                    .collect()
+            })
+        };
+        PermissionArgs {
+            read_only: args(self.read_only, defaults.read_only),
+            workspace_write: args(self.workspace_write, defaults.workspace_write),
+            full: args(self.full, defaults.full),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
 struct CodexModelConfigFile {
@@ -231,15 +678,32 @@ struct CodexModelProfileConfig {
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
 struct AgentProfileAssignmentsConfig {
-    master: Option<String>,
-    master_report: Option<String>,
-    project_info: Option<String>,
-    docs_attach: Option<String>,
-    task_check: Option<String>,
-    worker_implementor: Option<String>,
-    worker_auditor: Option<String>,
-    worker_test_writer: Option<String>,
-    worker_final_audit: Option<String>,
+    master: Option<AgentAssignmentConfig>,
+    master_report: Option<AgentAssignmentConfig>,
+    project_info: Option<AgentAssignmentConfig>,
+    docs_attach: Option<AgentAssignmentConfig>,
+    task_check: Option<AgentAssignmentConfig>,
+    worker_implementor: Option<AgentAssignmentConfig>,
+    worker_auditor: Option<AgentAssignmentConfig>,
+    worker_test_writer: Option<AgentAssignmentConfig>,
+    worker_final_audit: Option<AgentAssignmentConfig>,
+}
+
+/// An `agent_profiles` entry: either a bare profile label or a table that may also name the
+/// backend the agent runs on and its permissions, e.g.
+/// `{ profile = "large-smart", backend = "claude", permissions = "read-only" }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum AgentAssignmentConfig {
+    Profile(String),
+    Routed {
+        #[serde(default)]
+        profile: Option<String>,
+        #[serde(default)]
+        backend: Option<String>,
+        #[serde(default)]
+        permissions: Option<String>,
+    },
 }
 
 impl AgentProfileAssignmentsConfig {
@@ -257,52 +721,93 @@ impl AgentProfileAssignmentsConfig {
         }
     }
 
-    fn into_runtime(self) -> AgentProfileAssignments {
-        AgentProfileAssignments {
-            master: normalize_assignment(self.master.as_deref()),
-            master_report: normalize_assignment(self.master_report.as_deref()),
-            project_info: normalize_assignment(self.project_info.as_deref()),
-            docs_attach: normalize_assignment(self.docs_attach.as_deref()),
-            task_check: normalize_assignment(self.task_check.as_deref()),
-            worker_implementor: normalize_assignment(self.worker_implementor.as_deref()),
-            worker_auditor: normalize_assignment(self.worker_auditor.as_deref()),
-            worker_test_writer: normalize_assignment(self.worker_test_writer.as_deref()),
-            worker_final_audit: normalize_assignment(self.worker_final_audit.as_deref()),
-        }
+    fn into_runtime(self) -> io::Result<AgentProfileAssignments> {
+        Ok(AgentProfileAssignments {
+            master: normalize_assignment("master", self.master)?,
+            master_report: normalize_assignment("master_report", self.master_report)?,
+            project_info: normalize_assignment("project_info", self.project_info)?,
+            docs_attach: normalize_assignment("docs_attach", self.docs_attach)?,
+            task_check: normalize_assignment("task_check", self.task_check)?,
+            worker_implementor: normalize_assignment(
+                "worker_implementor",
+                self.worker_implementor,
+            )?,
+            worker_auditor: normalize_assignment("worker_auditor", self.worker_auditor)?,
+            worker_test_writer: normalize_assignment(
+                "worker_test_writer",
+                self.worker_test_writer,
+            )?,
+            worker_final_audit: normalize_assignment(
+                "worker_final_audit",
+                self.worker_final_audit,
+            )?,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-struct AgentProfileAssignments {
-    master: String,
-    master_report: String,
-    project_info: String,
-    docs_attach: String,
-    task_check: String,
-    worker_implementor: String,
-    worker_auditor: String,
-    worker_test_writer: String,
-    worker_final_audit: String,
-}
-
-impl Default for AgentProfileAssignments {
+struct AgentAssignment {
+    profile: String,
+    backend: Option<BackendKind>,
+    /// `None` uses the agent's default permissions.
+    permissions: Option<PermissionProfile>,
+}
+
+impl Default for AgentAssignment {
     fn default() -> Self {
         Self {
-            master: DEFAULT_PROFILE_LABEL.to_string(),
-            master_report: DEFAULT_PROFILE_LABEL.to_string(),
-            project_info: DEFAULT_PROFILE_LABEL.to_string(),
-            docs_attach: DEFAULT_PROFILE_LABEL.to_string(),
-            task_check: DEFAULT_PROFILE_LABEL.to_string(),
-            worker_implementor: DEFAULT_PROFILE_LABEL.to_string(),
-            worker_auditor: DEFAULT_PROFILE_LABEL.to_string(),
-            worker_test_writer: DEFAULT_PROFILE_LABEL.to_string(),
-            worker_final_audit: DEFAULT_PROFILE_LABEL.to_string(),
+            profile: DEFAULT_PROFILE_LABEL.to_string(),
+            backend: None,
+            permissions: None,
         }
     }
 }
 
+#[derive(Debug, Clone, Default)]
+struct AgentProfileAssignments {
+    master: AgentAssignment,
+    master_report: AgentAssignment,
+    project_info: AgentAssignment,
+    docs_attach: AgentAssignment,
+    task_check: AgentAssignment,
+    worker_implementor: AgentAssignment,
+    worker_auditor: AgentAssignment,
+    worker_test_writer: AgentAssignment,
+    worker_final_audit: AgentAssignment,
+}
+
 impl AgentProfileAssignments {
-    fn label_for(&self, kind: CodexAgentKind) -> &str {
+    fn check_backends_configured(&self, commands: &BackendCommands) -> io::Result<()> {
+        let assignments = [
+            ("master", &self.master),
+            ("master_report", &self.master_report),
+            ("project_info", &self.project_info),
+            ("docs_attach", &self.docs_attach),
+            ("task_check", &self.task_check),
+            ("worker_implementor", &self.worker_implementor),
+            ("worker_auditor", &self.worker_auditor),
+            ("worker_test_writer", &self.worker_test_writer),
+            ("worker_final_audit", &self.worker_final_audit),
+        ];
+        for (key, assignment) in assignments {
+            let (configured, section) = match assignment.backend {
+                None | Some(BackendKind::Codex | BackendKind::Claude) => continue,
+                Some(BackendKind::Custom) => (commands.custom.is_some(), "custom"),
+                Some(BackendKind::Http) => (commands.http.is_some(), "http"),
+                Some(BackendKind::Replay) => (commands.replay.is_some(), "replay"),
+            };
+            if !configured {
+                let err = unconfigured_backend_error(section);
+                return Err(io::Error::new(
+                    err.kind(),
+                    format!("[codex.agent_profiles] {key}: {err}"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn assignment_for(&self, kind: CodexAgentKind) -> &AgentAssignment {
         match kind {
             CodexAgentKind::Master => &self.master,
             CodexAgentKind::MasterReport => &self.master_report,
@@ -322,12 +827,71 @@ fn parse_config(text: &str) -> io::Result<MetaAgentConfigFile> {
         .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
 }
 
-fn normalize_assignment(raw: Option<&str>) -> String {
-    let normalized = normalize_profile_label(raw.unwrap_or(DEFAULT_PROFILE_LABEL));
-    if normalized.is_empty() {
-        return DEFAULT_PROFILE_LABEL.to_string();
+/// An unknown `permissions` or `backend` name is an error rather than a silent fallback, since
+/// falling back could grant more than the config asked for or run the agent somewhere else.
+fn normalize_assignment(
+    key: &str,
+    raw: Option<AgentAssignmentConfig>,
+) -> io::Result<AgentAssignment> {
+    let (profile, backend, permissions) = match raw {
+        None => (None, None, None),
+        Some(AgentAssignmentConfig::Profile(profile)) => (Some(profile), None, None),
+        Some(AgentAssignmentConfig::Routed {
+            profile,
+            backend,
+            permissions,
+        }) => (profile, backend, permissions),
+    };
+    let permissions = match permissions.as_deref() {
+        None => None,
+        Some(label) => Some(PermissionProfile::from_label(label).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "[codex.agent_profiles] {key}: permissions must be \"read-only\", \"workspace-write\" or \"full\", got {label:?}"
+                ),
+            )
+        })?),
+    };
+    let profile = normalize_profile_label(profile.as_deref().unwrap_or(DEFAULT_PROFILE_LABEL));
+    Ok(AgentAssignment {
+        profile: if profile.is_empty() {
+            DEFAULT_PROFILE_LABEL.to_string()
+        } else {
+            profile
+        },
+        backend: match backend.as_deref() {
+            None => None,
+            Some(label) => Some(parse_backend_label(label).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "[codex.agent_profiles] {key}: backend must be \"codex\", \"claude\", \"custom\", \"http\" or \"replay\", got {label:?}"
+                    ),
+                )
+            })?),
+        },
+        permissions,
+    })
+}
+
+fn unconfigured_backend_error(section: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("backend \"{section}\" is not configured; add a [backend.{section}] section"),
+    )
+}
+
+/// Parses a backend name as written in config; unknown names yield `None`.
+fn parse_backend_label(label: &str) -> Option<BackendKind> {
+    match label.trim().to_ascii_lowercase().as_str() {
+        "codex" => Some(BackendKind::Codex),
+        "claude" => Some(BackendKind::Claude),
+        "custom" => Some(BackendKind::Custom),
+        "http" => Some(BackendKind::Http),
+        "replay" => Some(BackendKind::Replay),
+        _ => None,
     }
-    normalized
 }
 
 fn normalize_profile_label(label: &str) -> String {
@@ -10,11 +10,17 @@ pub enum CapabilityId {
     WorkflowTaskGraphSync,
     WorkflowExecutionQueue,
     WorkflowContextProjection,
+    WorkflowCheckpointRollback,
+    WorkflowTaskControl,
+    WorkflowDryRun,
     SessionLifecycle,
     SessionPlannerStorage,
     SessionFailureStorage,
     SessionProjectContextStorage,
+    SessionExecutionControl,
+    SessionReviewDecision,
     SubagentPromptGeneration,
+    SubagentPromptTemplates,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -135,6 +141,36 @@ pub const CAPABILITY_MATRIX: &[CapabilityDefinition] = &[
         ],
         notes: "Projects normalized context and pane representations for any adapter.",
     },
+    CapabilityDefinition {
+        id: CapabilityId::WorkflowCheckpointRollback,
+        domain: CapabilityDomain::Workflow,
+        operation: CapabilityOperation::Command,
+        request_contract: "WorkflowRequest::RollbackTask",
+        response_contract: "WorkflowResponse::RolledBack",
+        code_paths: &[
+            "src/checkpoint.rs::create_checkpoint",
+            "src/checkpoint.rs::rollback_task",
+        ],
+        notes: "Restores a workspace to the git checkpoint recorded before a task's first worker pass.",
+    },
+    CapabilityDefinition {
+        id: CapabilityId::WorkflowTaskControl,
+        domain: CapabilityDomain::Workflow,
+        operation: CapabilityOperation::Command,
+        request_contract: "WorkflowRequest::{RetryTask,SkipTask}",
+        response_contract: "WorkflowResponse::TasksEdited",
+        code_paths: &["src/workflow.rs::retry_task", "src/workflow.rs::skip_task"],
+        notes: "Resets a task subtree for another run or marks it skipped, rewriting the tasks file in place.",
+    },
+    CapabilityDefinition {
+        id: CapabilityId::WorkflowDryRun,
+        domain: CapabilityDomain::Workflow,
+        operation: CapabilityOperation::Query,
+        request_contract: "WorkflowRequest::DryRun",
+        response_contract: "WorkflowResponse::DryRun",
+        code_paths: &["src/workflow/dry_run.rs::dry_run_prompts"],
+        notes: "Simulates a passing run of the task tree and returns each worker prompt with its role, task id, and model profile without invoking a backend.",
+    },
     CapabilityDefinition {
         id: CapabilityId::SessionLifecycle,
         domain: CapabilityDomain::Session,
@@ -187,6 +223,30 @@ pub const CAPABILITY_MATRIX: &[CapabilityDefinition] = &[
         ],
         notes: "Manages project-context and session-meta documents consumed across subagents.",
     },
+    CapabilityDefinition {
+        id: CapabilityId::SessionExecutionControl,
+        domain: CapabilityDomain::Session,
+        operation: CapabilityOperation::Command,
+        request_contract: "SessionRequest::ControlExecution",
+        response_contract: "SessionResponse::ExecutionControl",
+        code_paths: &[
+            "src/session_store.rs::read_execution_control",
+            "src/session_store.rs::write_execution_control",
+        ],
+        notes: "Records pause, stop, and resume requests that a running TUI applies on its next tick.",
+    },
+    CapabilityDefinition {
+        id: CapabilityId::SessionReviewDecision,
+        domain: CapabilityDomain::Session,
+        operation: CapabilityOperation::Command,
+        request_contract: "SessionRequest::SubmitReview",
+        response_contract: "SessionResponse::ReviewDecision",
+        code_paths: &[
+            "src/session_store.rs::write_review_decision",
+            "src/session_store.rs::take_review_decision",
+        ],
+        notes: "Records approve or reject decisions for a task held at a review gate; a running TUI applies them on its next tick.",
+    },
     CapabilityDefinition {
         id: CapabilityId::SubagentPromptGeneration,
         domain: CapabilityDomain::Subagent,
@@ -200,6 +260,18 @@ pub const CAPABILITY_MATRIX: &[CapabilityDefinition] = &[
         ],
         notes: "Builds deterministic prompt contracts used by worker and master adapters.",
     },
+    CapabilityDefinition {
+        id: CapabilityId::SubagentPromptTemplates,
+        domain: CapabilityDomain::Subagent,
+        operation: CapabilityOperation::Query,
+        request_contract: "SubagentRequest::{ListPromptTemplates,RenderPromptTemplate}",
+        response_contract: "SubagentResponse::{PromptTemplates,RenderedPromptTemplate}",
+        code_paths: &[
+            "src/prompt_templates.rs::PromptTemplateDirs::resolve",
+            "src/prompt_templates.rs::fill_placeholders",
+        ],
+        notes: "Lists the effective prompt templates after workspace and config-dir overrides and renders one with placeholder values for preview.",
+    },
 ];
 
 pub fn capability_definition(id: CapabilityId) -> Option<&'static CapabilityDefinition> {
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -96,7 +98,10 @@ pub enum WorkerRoleContract {
     Auditor,
     TestWriter,
     TestRunner,
+    LintRunner,
+    CoverageRunner,
     FinalAudit,
+    Custom,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -113,6 +118,8 @@ pub struct StartedWorkerJobContract {
     pub top_task_id: u64,
     #[serde(default)]
     pub parent_context_key: Option<String>,
+    #[serde(default)]
+    pub workspace_dir: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -172,6 +179,25 @@ pub enum WorkflowRequest {
         entries: Vec<String>,
     },
     RightPaneBlockView,
+    RollbackTask {
+        task_id: String,
+        workspace: String,
+        session_dir: String,
+    },
+    RetryTask {
+        tasks_file: String,
+        task_id: String,
+        guidance: Option<String>,
+    },
+    SkipTask {
+        tasks_file: String,
+        task_id: String,
+    },
+    DryRun {
+        tasks_file: String,
+        #[serde(default)]
+        output_dir: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -200,6 +226,32 @@ pub enum WorkflowResponse {
         lines: Vec<String>,
         toggles: Vec<RightPaneToggleContract>,
     },
+    RolledBack {
+        checkpoint_ref: String,
+        backup_ref: String,
+    },
+    TasksEdited {
+        messages: Vec<String>,
+        tasks: Vec<PlannerTaskEntryContract>,
+    },
+    DryRun {
+        prompts: Vec<DryRunPromptContract>,
+        #[serde(default)]
+        output_dir: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DryRunPromptContract {
+    pub sequence: usize,
+    pub role: WorkerRoleContract,
+    pub task_id: String,
+    pub top_task_id: u64,
+    pub pass: u8,
+    pub model: String,
+    #[serde(default)]
+    pub thinking_effort: Option<String>,
+    pub prompt: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -223,6 +275,27 @@ pub enum SessionRequest {
     ReadProjectInfo,
     WriteProjectInfo { markdown: String },
     ReadSessionMeta,
+    ControlExecution { control: ExecutionControlActionContract },
+    SubmitReview {
+        decision: ReviewDecisionContract,
+        task_id: Option<String>,
+        feedback: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionControlActionContract {
+    Pause,
+    Stop,
+    Resume,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDecisionContract {
+    Approve,
+    Reject,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -252,6 +325,15 @@ pub enum SessionResponse {
     SessionMeta {
         meta: SessionMetaContract,
     },
+    ExecutionControl {
+        paused: bool,
+        stop_requested: bool,
+    },
+    ReviewDecision {
+        decision: ReviewDecisionContract,
+        task_id: Option<String>,
+        feedback: Option<String>,
+    },
     Ack,
 }
 
@@ -307,6 +389,15 @@ pub enum SubagentRequest {
         project_info_file: String,
         session_meta_file: String,
     },
+    ListPromptTemplates {
+        cwd: String,
+    },
+    RenderPromptTemplate {
+        cwd: String,
+        name: String,
+        #[serde(default)]
+        values: BTreeMap<String, String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -319,6 +410,33 @@ pub enum SubagentResponse {
         text: String,
         intro_needed_after: bool,
     },
+    PromptTemplates {
+        search_dirs: Vec<String>,
+        templates: Vec<PromptTemplateContract>,
+    },
+    RenderedPromptTemplate {
+        template: PromptTemplateContract,
+        text: String,
+        #[serde(default)]
+        unfilled: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PromptTemplateContract {
+    pub name: String,
+    /// Override file the template was read from; `None` means the built-in text.
+    #[serde(default)]
+    pub source: Option<String>,
+    pub placeholders: Vec<PromptPlaceholderContract>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PromptPlaceholderContract {
+    pub name: String,
+    pub description: String,
+    /// Whether the effective template text references this placeholder.
+    pub used: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -341,6 +459,15 @@ pub struct SessionMetaContract {
     pub stack_description: String,
     #[serde(default)]
     pub test_command: Option<String>,
+    #[serde(default)]
+    pub lint_command: Option<String>,
+    #[serde(default)]
+    pub test_report_path: Option<String>,
+    #[serde(default)]
+    pub coverage_command: Option<String>,
+    /// Minimum line coverage of changed files, in whole percent.
+    #[serde(default)]
+    pub coverage_threshold: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -351,7 +478,10 @@ pub enum PlannerTaskKindContract {
     Auditor,
     TestWriter,
     TestRunner,
+    LintRunner,
     FinalAudit,
+    Review,
+    Role,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -361,6 +491,7 @@ pub enum PlannerTaskStatusContract {
     InProgress,
     NeedsChanges,
     Done,
+    Skipped,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -386,6 +517,26 @@ pub struct PlannerTaskEntryContract {
     #[serde(default)]
     pub parent_id: Option<String>,
     pub order: Option<u32>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub max_retries: Option<u8>,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub review_quorum: Option<ReviewQuorumContract>,
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReviewQuorumContract {
+    pub reviewers: u8,
+    pub required: u8,
+    #[serde(default)]
+    pub profiles: Vec<String>,
 }
 
 impl Default for PlannerTaskKindContract {
@@ -405,6 +556,8 @@ impl Default for PlannerTaskStatusContract {
 pub enum WorkflowFailureKindContract {
     Audit,
     Test,
+    Merge,
+    Timeout,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -415,4 +568,8 @@ pub struct TaskFailureContract {
     pub attempts: u8,
     pub reason: String,
     pub action_taken: String,
+    #[serde(default)]
+    pub retry_budget: Option<String>,
+    #[serde(default)]
+    pub checkpoint_refs: Vec<String>,
 }
@@ -1,16 +1,27 @@
 use std::cell::RefCell;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::agent::BackendKind;
+use crate::pass_diff::{PassBaseline, PassDiff};
+use crate::prompt_templates::{self, PromptTemplate};
 use crate::session_store::PlannerTaskFileEntry;
 use crate::subagents;
+use crate::test_report::TestRunSummary;
 use crate::text_layout::{WrappedText, wrap_word_with_positions};
-use crate::workflow::{RightPaneBlockView, StartedJob, WorkerRole, Workflow, WorkflowFailure};
+use crate::workflow::{
+    CustomRole, DryRunPrompt, JobTimeouts, PendingReview, ReadyMerge, RetryBudgets, ReviewerVote,
+    RightPaneBlockView, StartedJob, TimedOutJob, WorkerRole, Workflow, WorkflowFailure,
+};
 
 #[cfg(not(test))]
-const COMMAND_INDEX: [(&str, &str); 15] = [
+const COMMAND_INDEX: [(&str, &str); 24] = [
     ("/start", "Start execution"),
+    ("/pause", "Pause execution after running jobs finish"),
+    ("/stop", "Stop running jobs and pause execution"),
+    ("/resume-execution", "Resume paused execution"),
     ("/backend", "Choose backend"),
     ("/toggle-tests", "Toggle global tests mode"),
     ("/planner", "Show collaborative planner markdown"),
@@ -25,10 +36,19 @@ const COMMAND_INDEX: [(&str, &str); 15] = [
     ("/merge-audits", "Merge audits"),
     ("/add-final-audit", "Add final audit task"),
     ("/remove-final-audit", "Remove final audit task"),
+    ("/rollback", "Roll a task back to its pre-task checkpoint"),
+    ("/approve", "Approve a task waiting for review"),
+    ("/reject", "Reject a reviewed task with feedback"),
+    ("/retry", "Reset a task subtree and run it again"),
+    ("/skip", "Skip a task subtree so later tasks proceed"),
+    ("/dry-run", "Preview worker prompts without a backend"),
 ];
 #[cfg(test)]
-const COMMAND_INDEX: [(&str, &str); 17] = [
+const COMMAND_INDEX: [(&str, &str); 26] = [
     ("/start", "Start execution"),
+    ("/pause", "Pause execution after running jobs finish"),
+    ("/stop", "Stop running jobs and pause execution"),
+    ("/resume-execution", "Resume paused execution"),
     ("/backend", "Choose backend"),
     ("/toggle-tests", "Toggle global tests mode"),
     ("/planner", "Show collaborative planner markdown"),
@@ -45,6 +65,12 @@ const COMMAND_INDEX: [(&str, &str); 17] = [
     ("/merge-tests", "Merge tests"),
     ("/add-final-audit", "Add final audit task"),
     ("/remove-final-audit", "Remove final audit task"),
+    ("/rollback", "Roll a task back to its pre-task checkpoint"),
+    ("/approve", "Approve a task waiting for review"),
+    ("/reject", "Reject a reviewed task with feedback"),
+    ("/retry", "Reset a task subtree and run it again"),
+    ("/skip", "Skip a task subtree so later tasks proceed"),
+    ("/dry-run", "Preview worker prompts without a backend"),
 ];
 const MAX_LEFT_TOP_LINES: usize = 2000;
 
@@ -368,6 +394,7 @@ impl App {
             tasks_file,
             &self.workflow.prepare_master_prompt(message),
             self.tests_mode_enabled,
+            &self.workflow.custom_roles_prompt_block(),
         )
     }
 
@@ -413,6 +440,46 @@ impl App {
         )
     }
 
+    pub fn is_pause_command(message: &str) -> bool {
+        message.trim().eq_ignore_ascii_case("/pause")
+    }
+
+    pub fn is_stop_command(message: &str) -> bool {
+        message.trim().eq_ignore_ascii_case("/stop")
+    }
+
+    pub fn is_resume_execution_command(message: &str) -> bool {
+        message.trim().eq_ignore_ascii_case("/resume-execution")
+    }
+
+    pub fn is_approve_command(message: &str) -> bool {
+        command_query(message).is_some_and(|command| command.eq_ignore_ascii_case("/approve"))
+    }
+
+    /// The task named by `/approve <task-id>`; a bare `/approve` only applies when a single
+    /// review is waiting.
+    pub fn approve_command_target(message: &str) -> Option<&str> {
+        if !Self::is_approve_command(message) {
+            return None;
+        }
+        message.split_whitespace().nth(1)
+    }
+
+    pub fn is_reject_command(message: &str) -> bool {
+        command_query(message).is_some_and(|command| command.eq_ignore_ascii_case("/reject"))
+    }
+
+    /// Splits `/reject <task-id> <feedback>` into the task reference and the feedback.
+    pub fn reject_command_args(message: &str) -> Option<(&str, &str)> {
+        if !Self::is_reject_command(message) {
+            return None;
+        }
+        let rest = message.trim()["/reject".len()..].trim_start();
+        let (task_ref, feedback) = rest.split_once(char::is_whitespace)?;
+        let feedback = feedback.trim();
+        (!feedback.is_empty()).then_some((task_ref, feedback))
+    }
+
     pub fn is_planner_mode_command(message: &str) -> bool {
         message.trim().eq_ignore_ascii_case("/planner")
     }
@@ -472,19 +539,54 @@ impl App {
         message.trim().eq_ignore_ascii_case("/remove-final-audit")
     }
 
+    pub fn is_rollback_command(message: &str) -> bool {
+        command_query(message).is_some_and(|command| command.eq_ignore_ascii_case("/rollback"))
+    }
+
+    pub fn rollback_command_target(message: &str) -> Option<&str> {
+        if !Self::is_rollback_command(message) {
+            return None;
+        }
+        message.split_whitespace().nth(1)
+    }
+
+    pub fn is_retry_command(message: &str) -> bool {
+        command_query(message).is_some_and(|command| command.eq_ignore_ascii_case("/retry"))
+    }
+
+    /// Splits `/retry <task-id> [guidance]` into the task reference and optional guidance.
+    pub fn retry_command_args(message: &str) -> Option<(&str, Option<&str>)> {
+        if !Self::is_retry_command(message) {
+            return None;
+        }
+        let rest = message.trim()["/retry".len()..].trim_start();
+        let (task_ref, guidance) = match rest.split_once(char::is_whitespace) {
+            Some((task_ref, guidance)) => (task_ref, Some(guidance.trim())),
+            None => (rest, None),
+        };
+        if task_ref.is_empty() {
+            return None;
+        }
+        Some((task_ref, guidance.filter(|text| !text.is_empty())))
+    }
+
+    pub fn is_skip_command(message: &str) -> bool {
+        command_query(message).is_some_and(|command| command.eq_ignore_ascii_case("/skip"))
+    }
+
+    pub fn skip_command_target(message: &str) -> Option<&str> {
+        if !Self::is_skip_command(message) {
+            return None;
+        }
+        message.split_whitespace().nth(1)
+    }
+
+    pub fn is_dry_run_command(message: &str) -> bool {
+        message.trim().eq_ignore_ascii_case("/dry-run")
+    }
+
     pub fn prepare_attach_docs_prompt(&self, tasks_file: &str) -> String {
-        format!(
-            "You are a docs-research sub-agent.\n\
-             Goal: update the planner task file with implementation documentation links.\n\
-             Read and edit this JSON file directly: {tasks_file}\n\
-             Requirements:\n\
-             - For every task/subtask where kind != \"test_runner\", populate or refresh a `docs` array.\n\
-             - Each docs item must include: title, url, summary.\n\
-             - Use the latest authoritative online docs relevant to implementing that task.\n\
-             - Keep existing task structure/order/status intact; only add/update docs.\n\
-             - Leave test_runner tasks with docs as-is (do not add docs there).\n\
-             - Save tasks.json, then output a short confirmation summary."
-        )
+        prompt_templates::render(PromptTemplate::AttachDocs, &[("tasks_file", tasks_file)])
     }
 
     pub fn prepare_planner_prompt(
@@ -506,47 +608,25 @@ impl App {
         let tests_policy = if self.tests_mode_enabled {
             "- Tests mode is ON: every plan step must include self-contained sections for Implementation, Auditing, and Test Writing.\n\
              - Plan test coverage and deterministic execution strategy for each step."
-                .to_string()
         } else {
             "- Tests mode is OFF: do not include Test Writing sections.\n\
              - Do not plan or request creating/modifying test files, test-only setup work, or test execution."
-                .to_string()
         };
-        format!(
-            "You are the master Codex agent in planner mode.\n\
-             Goal: collaboratively build a codebase-aware implementation plan before task generation.\n\
-             Planner storage:\n\
-             - Read and update this markdown file directly: {planner_file}\n\
-             - Do not edit tasks.json while planner mode is active.\n\
-             Codebase context:\n\
-             - Prefer grounding plan details using local repository files and this context brief: {project_info_file}\n\
-             - Keep references concrete by naming likely files/modules when confident.\n\
-             Clarification-first behavior:\n\
-             - Do not generate or update planner markdown until you have asked follow-up questions that clarify scope, constraints, and success criteria.\n\
-             - If key details are ambiguous, ask concise follow-up questions first and wait for answers before planning.\n\
-             Tests-mode policy:\n\
-             {tests_policy}\n\
-             Plan formatting requirements:\n\
-             - Break work down into concrete numbered steps.\n\
-             {}\n\
-             - Every step must be self-contained for isolated-context execution.\n\
-             - In each step, require explicit details for: files/modules to touch, intended behavior/outcomes, constraints/non-goals, and verification approach (commands/checks).\n\
-             - In each step, include an \"Isolated-context rationale\" sentence explaining why an implementor/auditor/test-writer can execute correctly from task details + listed artifacts without hidden chat context.\n\
-             - Maintain readable markdown with sections and checklists.\n\
-             - Track open questions/risks and assumptions.\n\
-             - Keep it collaborative and iterative; update the markdown on each turn when the plan changes.\n\
-             - When the plan is ready for execution, explicitly tell the user to run `/convert` to proceed to implementation.\n\
-             Rolling task context:\n\
-             {context_text}\n\
-             User message:\n\
-             {message}\n\
-             After saving planner markdown updates, send a concise conversational summary of what changed and remind the user they can run `/convert` when ready to implement."
-            ,
-            if self.tests_mode_enabled {
-                "- For every step, include self-contained sections for Implementation, Auditing, and Test Writing."
-            } else {
-                "- For every step, include self-contained sections for Implementation and Auditing only."
-            }
+        let step_sections = if self.tests_mode_enabled {
+            "- For every step, include self-contained sections for Implementation, Auditing, and Test Writing."
+        } else {
+            "- For every step, include self-contained sections for Implementation and Auditing only."
+        };
+        prompt_templates::render(
+            PromptTemplate::Planner,
+            &[
+                ("planner_file", planner_file),
+                ("project_info_file", project_info_file),
+                ("tests_policy", tests_policy),
+                ("step_sections", step_sections),
+                ("rolling_context", &context_text),
+                ("message", message),
+            ],
         )
     }
 
@@ -566,6 +646,49 @@ impl App {
         self.workflow.execution_busy()
     }
 
+    pub fn is_execution_paused(&self) -> bool {
+        self.workflow.execution_paused()
+    }
+
+    pub fn set_execution_paused(&mut self, paused: bool) {
+        self.workflow.set_execution_paused(paused);
+    }
+
+    pub fn pause_execution(&mut self) -> Vec<String> {
+        self.workflow.pause_execution()
+    }
+
+    pub fn stop_active_jobs(&mut self) -> Vec<u64> {
+        let stopped = self.workflow.stop_active_jobs();
+        self.refresh_right_lines();
+        stopped
+    }
+
+    pub fn resume_execution(&mut self) -> Vec<String> {
+        let messages = self.workflow.resume_execution();
+        self.prune_expanded_detail_keys();
+        self.refresh_right_lines();
+        messages
+    }
+
+    pub fn drain_new_reviews(&mut self) -> Vec<PendingReview> {
+        self.workflow.drain_new_reviews()
+    }
+
+    pub fn approve_review(&mut self, task_ref: Option<&str>) -> Vec<String> {
+        let messages = self.workflow.approve_review(task_ref);
+        self.prune_expanded_detail_keys();
+        self.refresh_right_lines();
+        messages
+    }
+
+    pub fn reject_review(&mut self, task_ref: Option<&str>, feedback: &str) -> Vec<String> {
+        let messages = self.workflow.reject_review(task_ref, feedback);
+        self.prune_expanded_detail_keys();
+        self.refresh_right_lines();
+        messages
+    }
+
     pub fn start_next_worker_job(&mut self) -> Option<StartedJob> {
         let started = self.workflow.start_next_job();
         if started.is_some() {
@@ -575,20 +698,160 @@ impl App {
         started
     }
 
+    pub fn set_max_parallel_tasks(&mut self, limit: usize) {
+        self.workflow.set_max_parallel_tasks(limit);
+    }
+
+    pub fn max_parallel_tasks(&self) -> usize {
+        self.workflow.max_parallel_tasks()
+    }
+
+    pub fn set_retry_budgets(&mut self, budgets: RetryBudgets) {
+        self.workflow.set_retry_budgets(budgets);
+    }
+
+    pub fn set_job_timeouts(&mut self, timeouts: JobTimeouts) {
+        self.workflow.set_job_timeouts(timeouts);
+    }
+
+    pub fn set_custom_roles(&mut self, roles: Vec<CustomRole>) {
+        self.workflow.set_custom_roles(roles);
+    }
+
+    /// Configured `[workflow.roles]` as the planning prompts describe them.
+    pub fn custom_roles_prompt_block(&self) -> String {
+        self.workflow.custom_roles_prompt_block()
+    }
+
+    pub fn timed_out_jobs(&self, now: Instant) -> Vec<TimedOutJob> {
+        self.workflow.timed_out_jobs(now)
+    }
+
+    pub fn record_job_timeout(&mut self, timeout: &TimedOutJob) {
+        self.workflow.record_job_timeout(timeout);
+    }
+
+    pub fn set_checkpoints_enabled(&mut self, enabled: bool) {
+        self.workflow.set_checkpoints_enabled(enabled);
+    }
+
+    pub fn set_coverage_threshold(&mut self, threshold: Option<f64>) {
+        self.workflow.set_coverage_threshold(threshold);
+    }
+
+    pub fn top_task_key(&self, top_task_id: u64) -> Option<String> {
+        self.workflow.top_task_key(top_task_id)
+    }
+
+    pub fn assign_task_workspace(&mut self, top_task_id: u64, workspace_dir: PathBuf) {
+        self.workflow
+            .assign_task_workspace(top_task_id, workspace_dir);
+    }
+
+    pub fn task_workspace(&self, top_task_id: u64) -> Option<PathBuf> {
+        self.workflow.task_workspace(top_task_id)
+    }
+
+    pub fn resolve_top_task_id(&self, task_ref: &str) -> Option<u64> {
+        self.workflow.resolve_top_task_id(task_ref)
+    }
+
+    pub fn resolve_task_id(&self, task_ref: &str) -> Option<u64> {
+        self.workflow.resolve_task_id(task_ref)
+    }
+
+    pub fn pending_top_task_ids(&self) -> Vec<u64> {
+        self.workflow.pending_top_task_ids()
+    }
+
+    pub fn retry_task(&mut self, task_id: u64, guidance: Option<&str>) -> Result<String, String> {
+        let result = self.workflow.retry_task(task_id, guidance);
+        self.refresh_right_lines();
+        result
+    }
+
+    pub fn drain_reset_merges(&mut self) -> Vec<(u64, Option<PathBuf>)> {
+        self.workflow.drain_reset_merges()
+    }
+
+    pub fn skip_task(&mut self, task_id: u64) -> Result<Vec<String>, String> {
+        let result = self.workflow.skip_task(task_id);
+        self.refresh_right_lines();
+        result
+    }
+
+    pub fn dry_run_prompts(&self) -> Result<Vec<DryRunPrompt>, String> {
+        self.workflow.dry_run_prompts()
+    }
+
+    pub fn record_task_checkpoint(&mut self, top_task_id: u64, checkpoint_ref: String) {
+        self.workflow
+            .record_task_checkpoint(top_task_id, checkpoint_ref);
+    }
+
+    pub fn set_pass_baseline(&mut self, top_task_id: u64, baseline: PassBaseline) {
+        self.workflow.set_pass_baseline(top_task_id, baseline);
+    }
+
+    pub fn take_pass_baseline(&mut self, top_task_id: u64) -> Option<PassBaseline> {
+        self.workflow.take_pass_baseline(top_task_id)
+    }
+
+    pub fn record_pass_diff(&mut self, top_task_id: u64, diff: PassDiff) {
+        self.workflow.record_pass_diff(top_task_id, diff);
+    }
+
+    pub fn record_test_report(&mut self, top_task_id: u64, report: TestRunSummary) {
+        self.workflow.record_test_report(top_task_id, report);
+    }
+
+    pub fn take_ready_merges(&mut self) -> Vec<ReadyMerge> {
+        self.workflow.take_ready_merges()
+    }
+
+    pub fn mark_task_merged(&mut self, top_task_id: u64) {
+        self.workflow.mark_task_merged(top_task_id);
+    }
+
+    pub fn record_merge_failure(&mut self, top_task_id: u64, reason: String, action_taken: String) {
+        self.workflow
+            .record_merge_failure(top_task_id, reason, action_taken);
+    }
+
+    pub fn active_job_top_task_id(&self) -> Option<u64> {
+        self.workflow.active_job_meta().map(|meta| meta.top_task_id)
+    }
+
     pub fn on_worker_output(&mut self, line: String) {
-        if let Some(meta) = self.workflow.active_job_meta() {
-            let role = match meta.role {
-                WorkerRole::Implementor => "Impl",
-                WorkerRole::Auditor => "Audit",
-                WorkerRole::TestWriter => "Tests",
-                WorkerRole::TestRunner => "TestRun",
-                WorkerRole::FinalAudit => "FinalAudit",
-            };
-            self.append_left_top_line(format!("{role}#{}: {line}", meta.top_task_id));
-        } else {
-            self.append_left_top_line(format!("Worker: {line}"));
+        match self.workflow.active_job_meta() {
+            Some(meta) => self.on_worker_output_for_task(meta.top_task_id, line),
+            None => self.append_left_top_line(format!("Worker: {line}")),
         }
-        self.workflow.append_active_output(line);
+    }
+
+    pub fn on_worker_output_for_task(&mut self, top_task_id: u64, line: String) {
+        let role = self
+            .workflow
+            .active_jobs_meta()
+            .into_iter()
+            .find(|meta| meta.top_task_id == top_task_id)
+            .map(|meta| meta.role);
+        let Some(role) = role else {
+            self.append_left_top_line(format!("Worker: {line}"));
+            return;
+        };
+        let role = match role {
+            WorkerRole::Implementor => "Impl",
+            WorkerRole::Auditor => "Audit",
+            WorkerRole::TestWriter => "Tests",
+            WorkerRole::TestRunner => "TestRun",
+            WorkerRole::LintRunner => "Lint",
+            WorkerRole::CoverageRunner => "Coverage",
+            WorkerRole::FinalAudit => "FinalAudit",
+            WorkerRole::Custom => "Role",
+        };
+        self.append_left_top_line(format!("{role}#{top_task_id}: {line}"));
+        self.workflow.append_output_for_task(top_task_id, line);
     }
 
     pub fn on_worker_system_output(&mut self, line: String) {
@@ -599,8 +862,25 @@ impl App {
         self.append_left_top_line(line.into());
     }
 
+    #[cfg(test)]
     pub fn on_worker_completed(&mut self, success: bool, code: i32) -> Vec<String> {
         let messages = self.workflow.finish_active_job(success, code);
+        self.apply_worker_completion_messages(messages)
+    }
+
+    pub fn on_worker_completed_for_task(
+        &mut self,
+        top_task_id: u64,
+        success: bool,
+        code: i32,
+    ) -> Vec<String> {
+        let messages = self
+            .workflow
+            .finish_job_for_task(top_task_id, success, code);
+        self.apply_worker_completion_messages(messages)
+    }
+
+    fn apply_worker_completion_messages(&mut self, messages: Vec<String>) -> Vec<String> {
         for message in messages {
             self.push_chat_message_line(message);
         }
@@ -617,6 +897,10 @@ impl App {
         self.workflow.drain_recent_failures()
     }
 
+    pub fn drain_review_votes(&mut self) -> Vec<ReviewerVote> {
+        self.workflow.drain_review_votes()
+    }
+
     pub fn set_chat_scroll(&mut self, scroll: u16) {
         self.chat_scroll = scroll;
     }
@@ -936,6 +1220,7 @@ impl App {
 
     pub fn reset_execution_for_session_switch(&mut self) {
         self.workflow.reset_execution_runtime();
+        self.workflow.set_execution_paused(false);
         self.refresh_right_lines();
     }
 
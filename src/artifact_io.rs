@@ -49,6 +49,10 @@ pub fn home_dir() -> io::Result<PathBuf> {
         .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME is not set"))
 }
 
+pub fn workspace_app_dir(workspace: &Path) -> PathBuf {
+    workspace.join(APP_DIR_NAME)
+}
+
 pub fn runtime_storage_dir() -> io::Result<PathBuf> {
     let home = home_dir()?;
     Ok(resolve_runtime_storage_dir(&home))
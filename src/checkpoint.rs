@@ -0,0 +1,180 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use crate::worktree::{run_git, sanitize_task_key};
+
+const CHECKPOINT_REF_ROOT: &str = "refs/bob/checkpoints";
+const CHECKPOINT_INDEX_FILE: &str = "bob-checkpoint-index";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollbackOutcome {
+    pub checkpoint_ref: String,
+    pub backup_ref: String,
+}
+
+/// Checkpoint refs are scoped by session: task keys such as `task-1` repeat across sessions
+/// and plans, and a rollback must never reach a snapshot taken for unrelated work.
+pub fn checkpoint_ref_prefix(session_id: &str, task_key: &str) -> String {
+    format!(
+        "{CHECKPOINT_REF_ROOT}/{}/{}",
+        sanitize_task_key(session_id),
+        sanitize_task_key(task_key)
+    )
+}
+
+pub fn list_checkpoints(
+    workspace: &Path,
+    session_id: &str,
+    task_key: &str,
+) -> io::Result<Vec<String>> {
+    let prefix = checkpoint_ref_prefix(session_id, task_key);
+    let output = run_git(
+        workspace,
+        &[
+            "for-each-ref",
+            "--format=%(refname)",
+            "--sort=refname",
+            &prefix,
+        ],
+    )?;
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToString::to_string)
+        .collect())
+}
+
+/// Snapshots the full working tree (tracked and untracked files) onto a shadow ref without
+/// touching the workspace, the real index, or HEAD.
+pub fn create_checkpoint(
+    workspace: &Path,
+    session_id: &str,
+    task_key: &str,
+    label: &str,
+) -> io::Result<String> {
+    let tree = snapshot_tree(workspace)?;
+    let sequence = list_checkpoints(workspace, session_id, task_key)?.len() + 1;
+    let checkpoint_ref = format!(
+        "{}/{sequence:03}-{}",
+        checkpoint_ref_prefix(session_id, task_key),
+        sanitize_task_key(label)
+    );
+    let message = format!("bob checkpoint {checkpoint_ref}");
+    let commit = run_git(
+        workspace,
+        &["commit-tree", tree.trim(), "-p", "HEAD", "-m", &message],
+    )?;
+    run_git(workspace, &["update-ref", &checkpoint_ref, commit.trim()])?;
+    Ok(checkpoint_ref)
+}
+
+/// Deletes every checkpoint recorded for the task in this session, so a re-planned or retried
+/// task starts a fresh checkpoint history. Returns how many refs were removed.
+pub fn clear_checkpoints(workspace: &Path, session_id: &str, task_key: &str) -> io::Result<usize> {
+    let refs = list_checkpoints(workspace, session_id, task_key)?;
+    for checkpoint_ref in &refs {
+        run_git(workspace, &["update-ref", "-d", checkpoint_ref])?;
+    }
+    Ok(refs.len())
+}
+
+/// Restores the workspace to the first checkpoint recorded for the task. The state being
+/// replaced is saved as one more checkpoint first, so a rollback can itself be undone.
+pub fn rollback_task(
+    workspace: &Path,
+    session_id: &str,
+    task_key: &str,
+) -> io::Result<RollbackOutcome> {
+    let Some(checkpoint_ref) = list_checkpoints(workspace, session_id, task_key)?
+        .into_iter()
+        .next()
+    else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no checkpoints recorded for task {task_key} in session {session_id}"),
+        ));
+    };
+    let backup_ref = create_checkpoint(workspace, session_id, task_key, "pre-rollback")?;
+
+    let added = run_git(
+        workspace,
+        &[
+            "diff",
+            "--name-only",
+            "--no-renames",
+            "--diff-filter=A",
+            &checkpoint_ref,
+            &backup_ref,
+        ],
+    )?;
+    for path in added.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        match std::fs::remove_file(workspace.join(path)) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+    run_git(workspace, &["checkout", &checkpoint_ref, "--", "."])?;
+    run_git(workspace, &["reset", "-q"])?;
+    Ok(RollbackOutcome {
+        checkpoint_ref,
+        backup_ref,
+    })
+}
+
+/// Diffs the current working tree, untracked files included, against the task's first
+/// checkpoint, or against HEAD when the task has none yet.
+pub fn task_diff(workspace: &Path, session_id: &str, task_key: &str) -> io::Result<String> {
+    let base = list_checkpoints(workspace, session_id, task_key)?
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| "HEAD".to_string());
+    let tree = snapshot_tree(workspace)?;
+    run_git(
+        workspace,
+        &[
+            "diff",
+            "--stat",
+            "--patch",
+            "--no-color",
+            &base,
+            tree.trim(),
+        ],
+    )
+}
+
+pub fn snapshot_tree(workspace: &Path) -> io::Result<String> {
+    let git_dir = run_git(workspace, &["rev-parse", "--absolute-git-dir"])?;
+    let index_file = Path::new(git_dir.trim()).join(CHECKPOINT_INDEX_FILE);
+    let _ = std::fs::remove_file(&index_file);
+    let snapshot = (|| {
+        run_git_with_index(workspace, &index_file, &["read-tree", "HEAD"])?;
+        run_git_with_index(workspace, &index_file, &["add", "-A"])?;
+        run_git_with_index(workspace, &index_file, &["write-tree"])
+    })();
+    let _ = std::fs::remove_file(&index_file);
+    snapshot
+}
+
+fn run_git_with_index(dir: &Path, index_file: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_INDEX_FILE", index_file)
+        .output()?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    Err(io::Error::other(format!(
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr).trim()
+    )))
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/checkpoint_tests.rs"]
+mod tests;
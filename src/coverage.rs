@@ -0,0 +1,411 @@
+use std::collections::BTreeMap;
+
+use crate::pass_diff::{ChangedLines, paths_match};
+
+/// Threshold used when meta.json sets a coverage command but no `coverage_threshold`.
+pub const DEFAULT_COVERAGE_THRESHOLD: f64 = 80.0;
+
+/// Line coverage totals of one file as printed in a coverage tool's summary table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileCoverage {
+    pub path: String,
+    pub percent: f64,
+    /// Covered and total line counts, when the report prints counts rather than percentages.
+    pub lines: Option<(usize, usize)>,
+    /// Uncovered lines as the tool prints them, when it prints them.
+    pub missing: Option<String>,
+}
+
+/// Per-line hit counts of one file from an lcov or Cobertura report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineCoverage {
+    pub path: String,
+    pub hits: BTreeMap<usize, u64>,
+}
+
+/// What the coverage command printed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoverageReport {
+    Lines(Vec<LineCoverage>),
+    /// A summary table with per-file totals only; it cannot tell changed lines apart.
+    FileTotals(Vec<FileCoverage>),
+    Unrecognized,
+}
+
+/// Coverage of one changed file, counted over its changed lines the report instruments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedFileCoverage {
+    pub path: String,
+    pub covered: usize,
+    pub uncovered: Vec<usize>,
+}
+
+/// Line coverage of the non-test lines a task changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageCheck {
+    pub percent: f64,
+    pub files: Vec<ChangedFileCoverage>,
+}
+
+/// Prefers per-line data (lcov, then Cobertura XML) and falls back to recognizing the text
+/// summaries of `cargo llvm-cov`, coverage.py (`coverage report`) and nyc/istanbul.
+pub fn parse_coverage_report(lines: &[String]) -> CoverageReport {
+    let parsed = parse_lcov(lines);
+    if !parsed.is_empty() {
+        return CoverageReport::Lines(parsed);
+    }
+    let parsed = parse_cobertura(lines);
+    if !parsed.is_empty() {
+        return CoverageReport::Lines(parsed);
+    }
+    let totals = parse_file_totals(lines);
+    if totals.is_empty() {
+        CoverageReport::Unrecognized
+    } else {
+        CoverageReport::FileTotals(totals)
+    }
+}
+
+fn parse_file_totals(lines: &[String]) -> Vec<FileCoverage> {
+    let parsed = parse_llvm_cov(lines);
+    if !parsed.is_empty() {
+        return parsed;
+    }
+    let parsed = parse_coverage_py(lines);
+    if !parsed.is_empty() {
+        return parsed;
+    }
+    parse_istanbul(lines)
+}
+
+/// Intersects the task's changed lines with the lines the report instruments, leaving out
+/// test files. Returns `None` when no changed line is instrumented, e.g. when only tests,
+/// docs or blank lines changed.
+pub fn check_changed_line_coverage(
+    report: &[LineCoverage],
+    changed: &ChangedLines,
+) -> Option<CoverageCheck> {
+    let mut files = Vec::new();
+    for (path, lines) in changed {
+        if is_test_path(path) {
+            continue;
+        }
+        let Some(file) = report
+            .iter()
+            .find(|file| paths_match(file.path.trim_start_matches("./"), path))
+        else {
+            continue;
+        };
+        let instrumented: Vec<(usize, u64)> = lines
+            .iter()
+            .filter_map(|line| file.hits.get(line).map(|hits| (*line, *hits)))
+            .collect();
+        if instrumented.is_empty() {
+            continue;
+        }
+        files.push(ChangedFileCoverage {
+            path: path.clone(),
+            covered: instrumented.iter().filter(|(_, hits)| *hits > 0).count(),
+            uncovered: instrumented
+                .iter()
+                .filter(|(_, hits)| *hits == 0)
+                .map(|(line, _)| *line)
+                .collect(),
+        });
+    }
+    if files.is_empty() {
+        return None;
+    }
+    let covered: usize = files.iter().map(|file| file.covered).sum();
+    let total: usize = files
+        .iter()
+        .map(|file| file.covered + file.uncovered.len())
+        .sum();
+    Some(CoverageCheck {
+        percent: covered as f64 * 100.0 / total as f64,
+        files,
+    })
+}
+
+/// Recognizes test sources by the directory and file-name conventions of the common test
+/// runners, so tests a task adds never count toward its own coverage.
+pub fn is_test_path(path: &str) -> bool {
+    let mut components: Vec<&str> = path.split('/').collect();
+    let name = components.pop().unwrap_or_default();
+    if components
+        .iter()
+        .any(|dir| matches!(*dir, "tests" | "test" | "__tests__" | "spec"))
+    {
+        return true;
+    }
+    let stem = name.split('.').next().unwrap_or_default();
+    stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_tests")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+impl CoverageCheck {
+    /// Feedback for the test writer naming every changed line left uncovered.
+    pub fn feedback(&self, threshold: f64) -> String {
+        let mut lines = vec![
+            format!(
+                "Coverage gate failed: line coverage of the lines changed in this task is {:.1}%, below the required {threshold:.1}%.",
+                self.percent
+            ),
+            "Add meaningful tests that exercise the uncovered code paths; trivial tests that only touch lines do not count as a fix.".to_string(),
+            "Changed lines without coverage:".to_string(),
+        ];
+        for file in self.files.iter().filter(|file| !file.uncovered.is_empty()) {
+            lines.push(format!(
+                "- {}: {} of {} changed lines covered, uncovered: {}",
+                file.path,
+                file.covered,
+                file.covered + file.uncovered.len(),
+                line_ranges(&file.uncovered)
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Formats sorted line numbers as `3-5, 9`.
+fn line_ranges(lines: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &line in lines {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == line => *end = line,
+            _ => ranges.push((line, line)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Reads `SF:`/`DA:line,hits` records; hits of a file listed in several records add up.
+fn parse_lcov(lines: &[String]) -> Vec<LineCoverage> {
+    let mut files: BTreeMap<String, BTreeMap<usize, u64>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in lines {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            files.entry(path.to_string()).or_default();
+            current = Some(path.to_string());
+        } else if line == "end_of_record" {
+            current = None;
+        } else if let (Some(path), Some(record)) = (&current, line.strip_prefix("DA:")) {
+            let mut fields = record.split(',');
+            let (Some(Ok(number)), Some(Ok(hits))) = (
+                fields.next().map(str::parse::<usize>),
+                fields.next().map(str::parse::<u64>),
+            ) else {
+                continue;
+            };
+            *files
+                .entry(path.clone())
+                .or_default()
+                .entry(number)
+                .or_default() += hits;
+        }
+    }
+    into_line_coverage(files)
+}
+
+/// Reads the `<class filename=..>` / `<line number=.. hits=..>` elements of a Cobertura XML
+/// report. Lines repeated under a class's methods keep their highest count.
+fn parse_cobertura(lines: &[String]) -> Vec<LineCoverage> {
+    let text = lines.join("\n");
+    if !text.contains("<coverage") {
+        return Vec::new();
+    }
+    let mut files: BTreeMap<String, BTreeMap<usize, u64>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for element in text.split('<').skip(1) {
+        let tag = element.split('>').next().unwrap_or_default();
+        let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+        if tag.starts_with("class ") {
+            current = xml_attribute(&tag, "filename");
+        } else if tag.starts_with("/class") {
+            current = None;
+        } else if tag.starts_with("line ")
+            && let Some(path) = &current
+        {
+            let (Some(number), Some(hits)) = (
+                xml_attribute(&tag, "number").and_then(|number| number.parse::<usize>().ok()),
+                xml_attribute(&tag, "hits").and_then(|hits| hits.parse::<u64>().ok()),
+            ) else {
+                continue;
+            };
+            let count = files
+                .entry(path.clone())
+                .or_default()
+                .entry(number)
+                .or_default();
+            *count = (*count).max(hits);
+        }
+    }
+    into_line_coverage(files)
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let marker = format!(" {name}=\"");
+    let start = tag.find(&marker)? + marker.len();
+    let len = tag[start..].find('"')?;
+    Some(tag[start..start + len].to_string())
+}
+
+fn into_line_coverage(files: BTreeMap<String, BTreeMap<usize, u64>>) -> Vec<LineCoverage> {
+    files
+        .into_iter()
+        .filter(|(_, hits)| !hits.is_empty())
+        .map(|(path, hits)| LineCoverage { path, hits })
+        .collect()
+}
+
+fn parse_llvm_cov(lines: &[String]) -> Vec<FileCoverage> {
+    let Some(header_index) = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("Filename") && line.contains("Lines"))
+    else {
+        return Vec::new();
+    };
+    let columns = split_columns(&lines[header_index]);
+    let (Some(total_index), Some(missed_index)) = (
+        columns.iter().position(|column| column == "Lines"),
+        columns.iter().position(|column| column == "Missed Lines"),
+    ) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for line in &lines[header_index + 1..] {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != columns.len() || tokens[0] == "TOTAL" {
+            continue;
+        }
+        let (Ok(total), Ok(missed)) = (
+            tokens[total_index].parse::<usize>(),
+            tokens[missed_index].parse::<usize>(),
+        ) else {
+            continue;
+        };
+        files.push(counted_file(tokens[0], total, missed, None));
+    }
+    files
+}
+
+fn parse_coverage_py(lines: &[String]) -> Vec<FileCoverage> {
+    let Some(header_index) = lines.iter().position(|line| {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        tokens.first() == Some(&"Name") && tokens.contains(&"Stmts") && tokens.contains(&"Miss")
+    }) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for line in &lines[header_index + 1..] {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() < 4 || tokens[0] == "TOTAL" {
+            continue;
+        }
+        let (Ok(total), Ok(missed)) = (tokens[1].parse::<usize>(), tokens[2].parse::<usize>())
+        else {
+            continue;
+        };
+        let Some(cover_index) = tokens.iter().position(|token| token.ends_with('%')) else {
+            continue;
+        };
+        let missing = tokens[cover_index + 1..].join(" ");
+        let missing = (!missing.is_empty()).then_some(missing);
+        files.push(counted_file(tokens[0], total, missed, missing));
+    }
+    files
+}
+
+fn parse_istanbul(lines: &[String]) -> Vec<FileCoverage> {
+    let Some(header_index) = lines
+        .iter()
+        .position(|line| line.contains('|') && line.contains("% Lines"))
+    else {
+        return Vec::new();
+    };
+    let header: Vec<&str> = lines[header_index].split('|').map(str::trim).collect();
+    let Some(lines_index) = header.iter().position(|cell| *cell == "% Lines") else {
+        return Vec::new();
+    };
+    let missing_index = header.iter().position(|cell| cell.starts_with("Uncovered"));
+    // Files are listed under their directory rows, indented one level deeper.
+    let mut parents: Vec<(usize, String)> = Vec::new();
+    let mut files = Vec::new();
+    for line in &lines[header_index + 1..] {
+        let cells: Vec<&str> = line.split('|').collect();
+        if cells.len() != header.len() || cells[0].trim().is_empty() {
+            continue;
+        }
+        let name = cells[0].trim();
+        if name.starts_with('-') || name == "All files" {
+            continue;
+        }
+        let Ok(percent) = cells[lines_index].trim().parse::<f64>() else {
+            continue;
+        };
+        let indent = cells[0].len() - cells[0].trim_start().len();
+        while parents.last().is_some_and(|(depth, _)| *depth >= indent) {
+            parents.pop();
+        }
+        let path = parents
+            .iter()
+            .map(|(_, parent)| parent.as_str())
+            .chain([name])
+            .collect::<Vec<_>>()
+            .join("/");
+        parents.push((indent, name.to_string()));
+        let missing = missing_index
+            .and_then(|index| cells.get(index))
+            .map(|cell| cell.trim().to_string())
+            .filter(|cell| !cell.is_empty());
+        files.push(FileCoverage {
+            path,
+            percent,
+            lines: None,
+            missing,
+        });
+    }
+    files
+}
+
+fn counted_file(path: &str, total: usize, missed: usize, missing: Option<String>) -> FileCoverage {
+    let covered = total.saturating_sub(missed);
+    FileCoverage {
+        path: path.to_string(),
+        percent: if total == 0 {
+            100.0
+        } else {
+            covered as f64 * 100.0 / total as f64
+        },
+        lines: Some((covered, total)),
+        missing,
+    }
+}
+
+/// Splits a table header on runs of two or more spaces, keeping multi-word column names.
+fn split_columns(header: &str) -> Vec<String> {
+    header
+        .split("  ")
+        .map(str::trim)
+        .filter(|column| !column.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/coverage_tests.rs"]
+mod tests;
@@ -1,9 +1,16 @@
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use crate::agent::AgentEvent;
+use crate::process_control::{RunControl, isolate_process_group};
+use crate::recorder::{RecordingHandle, RecordingSpec, SessionRecorder};
+use crate::replay::{ReplayPlayer, play_next_step};
+use crate::test_report::{TestFramework, TestRunSummary, parse_junit_xml, parse_test_output};
 
 #[derive(Debug, Clone)]
 pub struct TestRunnerConfig {
@@ -20,11 +27,42 @@ impl Default for TestRunnerConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunStage {
+    Tests,
+    Lint,
+    Coverage,
+}
+
+impl RunStage {
+    fn label(self) -> &'static str {
+        match self {
+            RunStage::Tests => "test",
+            RunStage::Lint => "lint",
+            RunStage::Coverage => "coverage",
+        }
+    }
+
+    /// Role name for this stage in recorded and replayed fixtures.
+    fn fixture_role(self) -> &'static str {
+        match self {
+            RunStage::Tests => "test_runner",
+            RunStage::Lint => "lint_runner",
+            RunStage::Coverage => "coverage_runner",
+        }
+    }
+}
+
 pub struct TestRunnerAdapter {
     #[cfg(test)]
     config: TestRunnerConfig,
-    event_tx: Sender<AgentEvent>,
-    event_rx: Receiver<AgentEvent>,
+    event_tx: Sender<(Option<u64>, AgentEvent)>,
+    event_rx: Receiver<(Option<u64>, AgentEvent)>,
+    run_controls: Mutex<HashMap<Option<u64>, RunControl>>,
+    reports: Arc<Mutex<HashMap<Option<u64>, TestRunSummary>>>,
+    flaky_test_reruns: u8,
+    recorder: Option<SessionRecorder>,
+    replay: Option<Arc<ReplayPlayer>>,
 }
 
 impl TestRunnerAdapter {
@@ -35,6 +73,11 @@ impl TestRunnerAdapter {
             config: TestRunnerConfig::default(),
             event_tx,
             event_rx,
+            run_controls: Mutex::new(HashMap::new()),
+            reports: Arc::new(Mutex::new(HashMap::new())),
+            flaky_test_reruns: 0,
+            recorder: None,
+            replay: None,
         }
     }
 
@@ -45,29 +88,228 @@ impl TestRunnerAdapter {
             config,
             event_tx,
             event_rx,
+            run_controls: Mutex::new(HashMap::new()),
+            reports: Arc::new(Mutex::new(HashMap::new())),
+            flaky_test_reruns: 0,
+            recorder: None,
+            replay: None,
         }
     }
 
+    /// How many times failing tests are re-run before a failure counts as consistent; 0 turns
+    /// flaky-test detection off.
+    pub fn set_flaky_test_reruns(&mut self, reruns: u8) {
+        self.flaky_test_reruns = reruns;
+    }
+
+    /// Records every run into the `--record` fixture.
+    pub fn set_recorder(&mut self, recorder: Option<SessionRecorder>) {
+        self.recorder = recorder;
+    }
+
+    /// Answers runs from the replay fixture for each stage it has steps for; stages without
+    /// steps still run their real command.
+    pub fn set_replay_player(&mut self, player: Option<Arc<ReplayPlayer>>) {
+        self.replay = player;
+    }
+
     #[cfg(test)]
     pub fn run_tests(&self) {
-        Self::spawn_run(self.config.clone(), self.event_tx.clone());
+        Self::spawn_run(
+            self.config.clone(),
+            None,
+            RunStage::Tests,
+            None,
+            self.flaky_test_reruns,
+            self.tagged_sender(None),
+        );
     }
 
+    #[cfg(test)]
     pub fn run_tests_with_command(&self, command: Option<&str>) {
-        let tx = self.event_tx.clone();
+        self.spawn_command(
+            command,
+            None,
+            RunStage::Tests,
+            None,
+            self.tagged_sender(None),
+        );
+    }
+
+    #[cfg(test)]
+    pub fn run_tests_with_report(&self, command: Option<&str>, junit_report: &Path) {
+        self.spawn_command(
+            command,
+            None,
+            RunStage::Tests,
+            Some(junit_report.to_path_buf()),
+            self.tagged_sender(None),
+        );
+    }
+
+    #[cfg(test)]
+    pub fn take_report(&self) -> Option<TestRunSummary> {
+        self.reports
+            .lock()
+            .ok()
+            .and_then(|mut reports| reports.remove(&None))
+    }
+
+    #[cfg(test)]
+    pub fn run_lint_with_command(&self, command: Option<&str>) {
+        self.spawn_command(
+            command,
+            None,
+            RunStage::Lint,
+            None,
+            self.tagged_sender(None),
+        );
+    }
+
+    /// Runs the session test command. When `junit_report` is set, the JUnit XML it names
+    /// (relative to the working dir) is parsed after the run; see `take_task_report`.
+    pub fn run_tests_for_task(
+        &self,
+        top_task_id: u64,
+        command: Option<&str>,
+        working_dir: Option<&Path>,
+        junit_report: Option<&Path>,
+    ) {
+        self.spawn_command(
+            command,
+            working_dir.map(Path::to_path_buf),
+            RunStage::Tests,
+            junit_report.map(Path::to_path_buf),
+            self.tagged_sender(Some(top_task_id)),
+        );
+    }
+
+    /// Runs the session lint command; with no command configured the stage passes trivially.
+    pub fn run_lint_for_task(
+        &self,
+        top_task_id: u64,
+        command: Option<&str>,
+        working_dir: Option<&Path>,
+    ) {
+        self.spawn_command(
+            command,
+            working_dir.map(Path::to_path_buf),
+            RunStage::Lint,
+            None,
+            self.tagged_sender(Some(top_task_id)),
+        );
+    }
+
+    /// Runs the session coverage command, whose output carries the coverage summary.
+    pub fn run_coverage_for_task(
+        &self,
+        top_task_id: u64,
+        command: Option<&str>,
+        working_dir: Option<&Path>,
+    ) {
+        self.spawn_command(
+            command,
+            working_dir.map(Path::to_path_buf),
+            RunStage::Coverage,
+            None,
+            self.tagged_sender(Some(top_task_id)),
+        );
+    }
+
+    /// Takes the JUnit report parsed for the task's last test run, if one was configured and
+    /// written.
+    pub fn take_task_report(&self, top_task_id: u64) -> Option<TestRunSummary> {
+        self.reports
+            .lock()
+            .ok()
+            .and_then(|mut reports| reports.remove(&Some(top_task_id)))
+    }
+
+    pub fn kill_task_run(&self, top_task_id: u64) -> bool {
+        self.run_controls
+            .lock()
+            .ok()
+            .and_then(|controls| controls.get(&Some(top_task_id)).cloned())
+            .is_some_and(|control| control.kill())
+    }
+
+    fn tagged_sender(&self, tag: Option<u64>) -> TaggedSender {
+        let control = self
+            .run_controls
+            .lock()
+            .map(|mut controls| controls.entry(tag).or_default().clone())
+            .unwrap_or_default();
+        TaggedSender {
+            tag,
+            tx: self.event_tx.clone(),
+            reports: Arc::clone(&self.reports),
+            generation: control.generation(),
+            control,
+            recording: None,
+        }
+    }
+
+    fn spawn_command(
+        &self,
+        command: Option<&str>,
+        working_dir: Option<PathBuf>,
+        stage: RunStage,
+        junit_report: Option<PathBuf>,
+        mut tx: TaggedSender,
+    ) {
         let normalized = command.map(str::trim).filter(|value| !value.is_empty());
+        let role = stage.fixture_role();
+        if self.recorder.is_some() || self.replay.is_some() {
+            let root = working_dir
+                .clone()
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_default();
+            tx.recording = self.recorder.clone().map(|recorder| {
+                RecordingSpec {
+                    recorder,
+                    role: role.to_string(),
+                    task: tx.tag,
+                    context_key: tx
+                        .tag
+                        .map_or_else(|| role.to_string(), |tag| format!("{role}:{tag}")),
+                }
+                .begin(normalized.unwrap_or_default(), None, None, &root)
+            });
+            if let Some(player) = self.replay.clone().filter(|player| player.has_role(role)) {
+                let source = format!("Deterministic {} runner (replay)", stage.label());
+                thread::spawn(move || {
+                    play_next_step(&player, role, tx.tag, &root, &source, |event| {
+                        tx.send(event);
+                    });
+                });
+                return;
+            }
+        }
         if let Some(command_line) = normalized {
             let config = TestRunnerConfig {
                 program: "bash".to_string(),
                 args: vec!["-lc".to_string(), command_line.to_string()],
             };
-            Self::spawn_run(config, tx);
-        } else {
-            let _ = tx.send(AgentEvent::System(
-                "Deterministic test runner failed: no test command configured in meta.json."
+            let reruns = match stage {
+                RunStage::Tests => self.flaky_test_reruns,
+                RunStage::Lint | RunStage::Coverage => 0,
+            };
+            Self::spawn_run(config, working_dir, stage, junit_report, reruns, tx);
+        } else if stage == RunStage::Lint {
+            tx.send(AgentEvent::System(
+                "Deterministic lint runner skipped: no lint command configured in meta.json."
                     .to_string(),
             ));
-            let _ = tx.send(AgentEvent::Completed {
+            tx.send(AgentEvent::Completed {
+                success: true,
+                code: 0,
+            });
+        } else {
+            tx.send(AgentEvent::System(format!(
+                "Deterministic {0} runner failed: no {0} command configured in meta.json.",
+                stage.label()
+            )));
+            tx.send(AgentEvent::Completed {
                 success: false,
                 code: -2,
             });
@@ -79,7 +321,15 @@ impl TestRunnerAdapter {
         self.drain_events_limited(usize::MAX)
     }
 
+    #[cfg(test)]
     pub fn drain_events_limited(&self, max_events: usize) -> Vec<AgentEvent> {
+        self.drain_task_events_limited(max_events)
+            .into_iter()
+            .map(|(_, event)| event)
+            .collect()
+    }
+
+    pub fn drain_task_events_limited(&self, max_events: usize) -> Vec<(Option<u64>, AgentEvent)> {
         let mut events = Vec::new();
         if max_events == 0 {
             return events;
@@ -93,74 +343,384 @@ impl TestRunnerAdapter {
         events
     }
 
-    fn spawn_run(config: TestRunnerConfig, tx: Sender<AgentEvent>) {
+    fn spawn_run(
+        config: TestRunnerConfig,
+        working_dir: Option<PathBuf>,
+        stage: RunStage,
+        junit_report: Option<PathBuf>,
+        flaky_test_reruns: u8,
+        tx: TaggedSender,
+    ) {
+        let junit_report = junit_report.map(|path| match &working_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path,
+        });
+        if let Some(path) = junit_report.as_ref() {
+            // A report left over from an earlier run must not be mistaken for this one.
+            let _ = std::fs::remove_file(path);
+        }
         thread::spawn(move || {
-            let mut command = Command::new(&config.program);
-            command
-                .args(&config.args)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
-
-            let mut child = match command.spawn() {
-                Ok(child) => child,
-                Err(err) => {
-                    let _ = tx.send(AgentEvent::System(format!(
-                        "Deterministic test runner failed to start: {err}"
-                    )));
-                    let _ = tx.send(AgentEvent::Completed {
+            let outcome = match run_process(&config, working_dir.as_deref(), stage, &tx) {
+                Ok(outcome) => outcome,
+                Err(message) => {
+                    tx.send(AgentEvent::System(message));
+                    tx.send(AgentEvent::Completed {
                         success: false,
                         code: -1,
                     });
                     return;
                 }
             };
+            let mut report = read_junit_report(junit_report.as_deref());
+            let mut success = outcome.success;
+            let mut code = outcome.code;
+            if stage == RunStage::Tests
+                && !success
+                && flaky_test_reruns > 0
+                && let Some(summary) = report
+                    .clone()
+                    .or_else(|| parse_test_output(&outcome.output))
+                && !summary.failures.is_empty()
+            {
+                let summary = rerun_failures(
+                    &config,
+                    working_dir.as_deref(),
+                    junit_report.as_deref(),
+                    flaky_test_reruns,
+                    summary,
+                    &tx,
+                );
+                if summary.failures.is_empty() {
+                    success = true;
+                    code = 0;
+                    tx.send(AgentEvent::System(format!(
+                        "Deterministic test runner: every failing test passed on rerun; recorded {} flaky test(s).",
+                        summary.flaky.len()
+                    )));
+                }
+                report = Some(summary);
+            }
+            if let Some(report) = report {
+                tx.store_report(report);
+            }
+            tx.send(AgentEvent::Completed { success, code });
+            if !success {
+                tx.send(AgentEvent::System(format!(
+                    "Deterministic {} runner exited with status code {code}",
+                    stage.label()
+                )));
+            }
+        });
+    }
+}
+
+struct ProcessOutcome {
+    success: bool,
+    code: i32,
+    output: Vec<String>,
+}
 
-            let mut readers = Vec::new();
-            if let Some(stdout) = child.stdout.take() {
-                readers.push(spawn_reader(stdout, tx.clone()));
+/// Runs one command to completion, streaming its output as events and keeping a copy of it.
+fn run_process(
+    config: &TestRunnerConfig,
+    working_dir: Option<&Path>,
+    stage: RunStage,
+    tx: &TaggedSender,
+) -> Result<ProcessOutcome, String> {
+    let mut command = Command::new(&config.program);
+    command
+        .args(&config.args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    isolate_process_group(&mut command);
+    if let Some(working_dir) = working_dir {
+        command.current_dir(working_dir);
+    }
+
+    let mut child = command.spawn().map_err(|err| {
+        format!(
+            "Deterministic {} runner failed to start: {err}",
+            stage.label()
+        )
+    })?;
+    tx.control.attach(tx.generation, child.id());
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(spawn_reader(stdout, tx.clone(), Arc::clone(&output)));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(spawn_reader(stderr, tx.clone(), Arc::clone(&output)));
+    }
+
+    let wait_result = child.wait();
+    tx.control.detach(tx.generation);
+    for reader in readers {
+        let _ = reader.join();
+    }
+    let status = wait_result
+        .map_err(|err| format!("Deterministic {} runner wait failed: {err}", stage.label()))?;
+    let output = output
+        .lock()
+        .map(|mut lines| std::mem::take(&mut *lines))
+        .unwrap_or_default();
+    Ok(ProcessOutcome {
+        success: status.success(),
+        code: status.code().unwrap_or(-1),
+        output,
+    })
+}
+
+fn read_junit_report(path: Option<&Path>) -> Option<TestRunSummary> {
+    path.and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|xml| parse_junit_xml(&xml))
+}
+
+/// Re-runs the failing tests up to `reruns` times and moves every test that passes on a rerun
+/// that exits successfully from `failures` to `flaky`. A rerun is narrowed to the failing
+/// tests when the command is a single invocation of a framework that supports filtering;
+/// otherwise the whole command runs again.
+fn rerun_failures(
+    config: &TestRunnerConfig,
+    working_dir: Option<&Path>,
+    junit_report: Option<&Path>,
+    reruns: u8,
+    mut summary: TestRunSummary,
+    tx: &TaggedSender,
+) -> TestRunSummary {
+    for attempt in 1..=reruns {
+        if summary.failures.is_empty() || !tx.is_current() {
+            break;
+        }
+        let filtered = filtered_rerun_config(config, summary.framework, &summary.failing_names());
+        tx.send(AgentEvent::System(format!(
+            "Deterministic test runner: re-running {} to check {} failing test(s) for flakiness (attempt {attempt} of {reruns}).",
+            if filtered.is_some() {
+                "only the failing tests"
+            } else {
+                "the full test command"
+            },
+            summary.failures.len()
+        )));
+        if let Some(path) = junit_report {
+            let _ = std::fs::remove_file(path);
+        }
+        let outcome = match run_process(
+            filtered.as_ref().unwrap_or(config),
+            working_dir,
+            RunStage::Tests,
+            tx,
+        ) {
+            Ok(outcome) => outcome,
+            Err(message) => {
+                tx.send(AgentEvent::System(message));
+                break;
             }
-            if let Some(stderr) = child.stderr.take() {
-                readers.push(spawn_reader(stderr, tx.clone()));
+        };
+        if !outcome.success {
+            // A test only counts as flaky when a rerun passes outright; a failing exit may
+            // come from a build error or a crash that the parsed output does not show.
+            tx.send(AgentEvent::System(format!(
+                "Deterministic test runner: rerun exited with status code {}; keeping the failures.",
+                outcome.code
+            )));
+            continue;
+        }
+        let rerun = read_junit_report(junit_report).or_else(|| parse_test_output(&outcome.output));
+        // A filtered rerun only proves anything if every requested test actually ran; a
+        // filter that matched nothing also exits successfully.
+        let still_failing = match rerun {
+            Some(rerun)
+                if filtered.is_none()
+                    || rerun.counts.passed + rerun.counts.failed >= summary.failures.len() =>
+            {
+                rerun
+                    .failures
+                    .into_iter()
+                    .map(|failure| failure.name)
+                    .collect::<Vec<_>>()
+            }
+            None if filtered.is_none() => Vec::new(),
+            _ => {
+                tx.send(AgentEvent::System(
+                    "Deterministic test runner: rerun results were inconclusive; keeping the failures."
+                        .to_string(),
+                ));
+                continue;
             }
+        };
+        let (consistent, flaky): (Vec<_>, Vec<_>) = std::mem::take(&mut summary.failures)
+            .into_iter()
+            .partition(|failure| still_failing.contains(&failure.name));
+        summary.failures = consistent;
+        summary.counts.failed = summary.counts.failed.saturating_sub(flaky.len());
+        summary.counts.flaky += flaky.len();
+        summary.flaky.extend(flaky);
+    }
+    summary
+}
 
-            let wait_result = child.wait();
-            for reader in readers {
-                let _ = reader.join();
+/// Builds a command that runs only the named tests, or `None` when the framework has no
+/// filter syntax Bob knows or the command is not a single invocation of the framework's test
+/// command, since filter arguments appended to a chain would land on its last command.
+fn filtered_rerun_config(
+    config: &TestRunnerConfig,
+    framework: Option<TestFramework>,
+    names: &[&str],
+) -> Option<TestRunnerConfig> {
+    let framework = framework?;
+    let is_shell_line = config.program == "bash"
+        && config.args.len() == 2
+        && config.args.first().map(String::as_str) == Some("-lc");
+    let words = if is_shell_line {
+        if !is_single_shell_command(&config.args[1]) {
+            return None;
+        }
+        config.args[1].split_whitespace().collect::<Vec<_>>()
+    } else {
+        std::iter::once(config.program.as_str())
+            .chain(config.args.iter().map(String::as_str))
+            .collect()
+    };
+    if !is_test_invocation(&words, framework) {
+        return None;
+    }
+    let mut filter_args = Vec::new();
+    match framework {
+        TestFramework::Libtest => {
+            let has_separator = if is_shell_line {
+                config.args[1].split_whitespace().any(|word| word == "--")
+            } else {
+                config.args.iter().any(|arg| arg == "--")
+            };
+            if !has_separator {
+                filter_args.push("--".to_string());
             }
-            match wait_result {
-                Ok(status) => {
-                    let code = status.code().unwrap_or(-1);
-                    let _ = tx.send(AgentEvent::Completed {
-                        success: status.success(),
-                        code,
-                    });
-                    if !status.success() {
-                        let _ = tx.send(AgentEvent::System(format!(
-                            "Deterministic test runner exited with status code {code}"
-                        )));
-                    }
-                }
-                Err(err) => {
-                    let _ = tx.send(AgentEvent::System(format!(
-                        "Deterministic test runner wait failed: {err}"
-                    )));
-                    let _ = tx.send(AgentEvent::Completed {
-                        success: false,
-                        code: -1,
-                    });
+            filter_args.push("--exact".to_string());
+            filter_args.extend(names.iter().map(ToString::to_string));
+        }
+        TestFramework::Pytest => filter_args.extend(names.iter().map(ToString::to_string)),
+        TestFramework::GoTest => {
+            let mut top_level = Vec::new();
+            for name in names {
+                let top = name.split('/').next().unwrap_or(name);
+                if !top_level.contains(&top) {
+                    top_level.push(top);
                 }
             }
+            filter_args.push("-v".to_string());
+            filter_args.push("-run".to_string());
+            filter_args.push(format!("^({})$", top_level.join("|")));
+        }
+        TestFramework::JUnit => return None,
+    }
+    let mut filtered = config.clone();
+    if is_shell_line {
+        let quoted = filter_args
+            .iter()
+            .map(|arg| format!("'{}'", arg.replace('\'', "'\\''")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        filtered.args[1] = format!("{} {quoted}", config.args[1].trim_end());
+    } else {
+        filtered.args.extend(filter_args);
+    }
+    Some(filtered)
+}
+
+/// Rejects command lists, pipelines, background jobs and command substitutions; `2>&1`
+/// style redirections are fine.
+fn is_single_shell_command(line: &str) -> bool {
+    !line.contains(['\n', ';', '|', '`'])
+        && !line.contains("$(")
+        && line
+            .char_indices()
+            .all(|(idx, ch)| ch != '&' || line[..idx].ends_with('>'))
+}
+
+fn is_test_invocation(words: &[&str], framework: TestFramework) -> bool {
+    // Leading `NAME=value` words only set the environment of the command.
+    let words = words
+        .iter()
+        .copied()
+        .skip_while(|word| {
+            word.split_once('=').is_some_and(|(name, _)| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+            })
+        })
+        .collect::<Vec<_>>();
+    let program = words
+        .first()
+        .and_then(|program| Path::new(program).file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    match framework {
+        TestFramework::Libtest => program == "cargo" && words.get(1) == Some(&"test"),
+        TestFramework::Pytest => {
+            program == "pytest"
+                || (program.starts_with("python") && words.get(1..3) == Some(&["-m", "pytest"][..]))
+        }
+        TestFramework::GoTest => program == "go" && words.get(1) == Some(&"test"),
+        TestFramework::JUnit => false,
+    }
+}
+
+#[derive(Clone)]
+struct TaggedSender {
+    tag: Option<u64>,
+    tx: Sender<(Option<u64>, AgentEvent)>,
+    reports: Arc<Mutex<HashMap<Option<u64>, TestRunSummary>>>,
+    control: RunControl,
+    generation: u64,
+    recording: Option<RecordingHandle>,
+}
+
+impl TaggedSender {
+    fn send(&self, event: AgentEvent) {
+        if let Some(recording) = self.recording.as_ref()
+            && self.is_current()
+            && let Err(err) = recording.record(&event)
+        {
+            self.control.send_if_current(self.generation, || {
+                let _ = self.tx.send((
+                    self.tag,
+                    AgentEvent::System(format!("Recorder could not write its fixture: {err}")),
+                ));
+            });
+        }
+        self.control.send_if_current(self.generation, || {
+            let _ = self.tx.send((self.tag, event));
+        });
+    }
+
+    fn is_current(&self) -> bool {
+        self.control.generation() == self.generation
+    }
+
+    fn store_report(&self, report: TestRunSummary) {
+        self.control.send_if_current(self.generation, || {
+            if let Ok(mut reports) = self.reports.lock() {
+                reports.insert(self.tag, report);
+            }
         });
     }
 }
 
 fn spawn_reader<R: std::io::Read + Send + 'static>(
     reader: R,
-    tx: Sender<AgentEvent>,
+    tx: TaggedSender,
+    captured: Arc<Mutex<Vec<String>>>,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
         for line in BufReader::new(reader).lines().map_while(Result::ok) {
-            let _ = tx.send(AgentEvent::Output(line));
+            if let Ok(mut captured) = captured.lock() {
+                captured.push(line.clone());
+            }
+            tx.send(AgentEvent::Output(line));
         }
     })
 }
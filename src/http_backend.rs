@@ -0,0 +1,207 @@
+use std::io::{BufRead, BufReader};
+use std::time::Duration;
+
+use serde_json::json;
+
+/// An OpenAI-compatible `/v1/chat/completions` server, e.g. llama.cpp or vLLM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpBackendSpec {
+    /// API root including the version segment, e.g. `http://127.0.0.1:8080/v1`.
+    pub base_url: String,
+    pub model: String,
+    /// Environment variable holding a bearer token; local servers usually need none.
+    pub api_key_env: Option<String>,
+    pub connect_timeout_secs: u64,
+    /// Longest silence tolerated between streamed chunks.
+    pub read_timeout_secs: u64,
+}
+
+impl Default for HttpBackendSpec {
+    fn default() -> Self {
+        Self {
+            base_url: "http://127.0.0.1:8080/v1".to_string(),
+            model: "local".to_string(),
+            api_key_env: None,
+            connect_timeout_secs: 10,
+            read_timeout_secs: 600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub role: &'static str,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: String) -> Self {
+        Self {
+            role: "user",
+            content,
+        }
+    }
+
+    pub fn assistant(content: String) -> Self {
+        Self {
+            role: "assistant",
+            content,
+        }
+    }
+}
+
+/// Outcome of one chat request that did not fail outright.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatOutcome {
+    Completed(String),
+    /// The caller stopped the stream, e.g. because the run was killed.
+    Cancelled,
+}
+
+pub fn completions_url(base_url: &str) -> String {
+    format!("{}/chat/completions", base_url.trim().trim_end_matches('/'))
+}
+
+/// Sends `messages` with `stream: true` and feeds each content delta to `on_delta`, which returns
+/// `false` to stop reading. Returns the full assistant text, or a message suitable for a
+/// `System` event when the server cannot be reached or answers with an error.
+pub fn stream_chat_completion(
+    spec: &HttpBackendSpec,
+    messages: &[ChatMessage],
+    mut on_delta: impl FnMut(&str) -> bool,
+) -> Result<ChatOutcome, String> {
+    let url = completions_url(&spec.base_url);
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(spec.connect_timeout_secs))
+        .timeout_read(Duration::from_secs(spec.read_timeout_secs))
+        .build();
+    let mut request = agent
+        .post(&url)
+        .set("Content-Type", "application/json")
+        .set("Accept", "text/event-stream");
+    if let Some(key) = spec
+        .api_key_env
+        .as_deref()
+        .and_then(|name| std::env::var(name).ok())
+        .filter(|key| !key.trim().is_empty())
+    {
+        request = request.set("Authorization", &format!("Bearer {}", key.trim()));
+    }
+    let body = json!({
+        "model": spec.model,
+        "stream": true,
+        "messages": messages
+            .iter()
+            .map(|message| json!({ "role": message.role, "content": message.content }))
+            .collect::<Vec<_>>(),
+    });
+
+    let response = match request.send_string(&body.to_string()) {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            return Err(format!(
+                "HTTP backend ({url}) returned status {code}: {}",
+                error_detail(&detail)
+            ));
+        }
+        Err(err) => return Err(format!("HTTP backend ({url}) request failed: {err}")),
+    };
+
+    // Servers that ignore `stream` answer with a single JSON body instead of SSE.
+    if !response.content_type().contains("event-stream") {
+        let text = response
+            .into_string()
+            .map_err(|err| format!("HTTP backend ({url}) response could not be read: {err}"))?;
+        let content = serde_json::from_str::<serde_json::Value>(&text)
+            .ok()
+            .and_then(|value| {
+                value
+                    .pointer("/choices/0/message/content")
+                    .and_then(|content| content.as_str())
+                    .map(ToString::to_string)
+            })
+            .ok_or_else(|| {
+                format!(
+                    "HTTP backend ({url}) returned no message: {}",
+                    error_detail(&text)
+                )
+            })?;
+        if !on_delta(&content) {
+            return Ok(ChatOutcome::Cancelled);
+        }
+        return Ok(ChatOutcome::Completed(content));
+    }
+
+    let mut full = String::new();
+    for line in BufReader::new(response.into_reader()).lines() {
+        let line =
+            line.map_err(|err| format!("HTTP backend ({url}) stream was interrupted: {err}"))?;
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            continue;
+        };
+        if data == "[DONE]" {
+            break;
+        }
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(data) else {
+            continue;
+        };
+        if let Some(message) = value.pointer("/error/message").and_then(|m| m.as_str()) {
+            return Err(format!("HTTP backend ({url}) reported an error: {message}"));
+        }
+        let Some(delta) = value
+            .pointer("/choices/0/delta/content")
+            .and_then(|content| content.as_str())
+            .filter(|delta| !delta.is_empty())
+        else {
+            continue;
+        };
+        full.push_str(delta);
+        if !on_delta(delta) {
+            return Ok(ChatOutcome::Cancelled);
+        }
+    }
+    Ok(ChatOutcome::Completed(full))
+}
+
+/// Splits streamed deltas into whole lines so downstream transcript parsing sees the same
+/// line-per-event shape as subprocess backends.
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    pending: String,
+}
+
+impl LineBuffer {
+    pub fn push(&mut self, delta: &str) -> Vec<String> {
+        self.pending.push_str(delta);
+        let mut lines = Vec::new();
+        while let Some(index) = self.pending.find('\n') {
+            let line = self.pending[..index].trim_end_matches('\r').to_string();
+            self.pending.drain(..=index);
+            lines.push(line);
+        }
+        lines
+    }
+
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.pending);
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+fn error_detail(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| {
+            value
+                .pointer("/error/message")
+                .or_else(|| value.get("error"))
+                .and_then(|message| message.as_str())
+                .map(ToString::to_string)
+        })
+        .unwrap_or_else(|| body.trim().chars().take(200).collect())
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/http_backend_tests.rs"]
+mod tests;
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::io;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
@@ -22,16 +22,26 @@ mod agent_models;
 mod api;
 mod app;
 mod artifact_io;
+mod checkpoint;
+mod coverage;
 mod default_config;
 mod deterministic;
 mod events;
+mod http_backend;
+mod pass_diff;
+mod process_control;
+mod prompt_templates;
+mod recorder;
+mod replay;
 mod services;
 mod session_store;
 mod subagents;
+mod test_report;
 mod text_layout;
 mod theme;
 mod ui;
 mod workflow;
+mod worktree;
 
 use agent::{AdapterOutputMode, AgentEvent, BackendKind, CodexAdapter, CodexCommandConfig};
 use agent_models::{CodexAgentKind, CodexAgentModelRouting, CodexModelProfile};
@@ -39,18 +49,22 @@ use app::{App, BackendOption, Pane, ResumeSessionOption, RightPaneMode};
 use artifact_io::{ensure_default_metaagent_config, load_merged_metaagent_config_text};
 use deterministic::TestRunnerAdapter;
 use events::AppEvent;
+use prompt_templates::{PromptTemplate, PromptTemplateDirs, ResolvedPromptTemplate};
+use recorder::SessionRecorder;
 use services::{
     CoreOrchestrationService, DefaultCoreOrchestrationService, DefaultUiPromptService,
     TaskWriteBaseline, UiPromptService,
 };
 use session_store::{
-    PlannerTaskFileEntry, PlannerTaskKindFile, PlannerTaskStatusFile, SessionListEntry,
-    SessionStore, TaskFailFileEntry, load_global_tests_mode_enabled,
-    persist_global_tests_mode_enabled,
+    ExecutionControl, MetadataFingerprint, PlannerTaskFileEntry, PlannerTaskKindFile,
+    PlannerTaskStatusFile, ReviewDecision, SessionListEntry, SessionStore, TaskFailFileEntry,
+    load_global_tests_mode_enabled, load_global_workflow_config, persist_global_tests_mode_enabled,
+    session_id_for_dir,
 };
 use theme::Theme;
 #[cfg(test)]
 use workflow::JobRun;
+use workflow::{JOB_TIMEOUT_EXIT_CODE, TimedOutJob, WorkerRole};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ProjectInfoStage {
@@ -76,8 +90,17 @@ enum SubmitBlockReason {
     ExecutionBusy,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExecutionControlCommand {
+    Pause,
+    Stop,
+    Resume,
+}
+
 const GLOBAL_RIGHT_SCROLL_LINES: u16 = 5;
 const MAX_ADAPTER_EVENTS_PER_LOOP: usize = 32;
+const MAX_REVIEW_DIFF_LINES: usize = 200;
+const DRY_RUN_DIR_NAME: &str = "dry-run";
 const UI_TICK_INTERVAL: Duration = Duration::from_millis(120);
 const PLANNER_AUTOSAVE_DEBOUNCE: Duration = Duration::from_millis(1_000);
 const PLANNER_PREFILL_INIT_PROMPT: &str = "Planner.md has been prefilled by user.";
@@ -92,33 +115,22 @@ fn keyboard_enhancement_flags() -> KeyboardEnhancementFlags {
 }
 
 fn apply_codex_profile(config: &mut CodexCommandConfig, profile: &CodexModelProfile) {
-    if !matches!(config.backend_kind(), BackendKind::Codex) {
+    if !config.accepts_model_profiles() {
         return;
     }
     config.model = Some(profile.model.clone());
     config.model_reasoning_effort = profile.thinking_effort.clone();
 }
 
-fn base_command_config_for_backend(
-    model_routing: &CodexAgentModelRouting,
-    selected_backend: BackendKind,
-) -> CodexCommandConfig {
-    let config = model_routing.base_command_config();
-    if config.backend_kind() == selected_backend {
-        config
-    } else {
-        CodexCommandConfig::default_for_backend(selected_backend)
-    }
-}
-
 fn build_codex_adapter(
     model_routing: &CodexAgentModelRouting,
     selected_backend: BackendKind,
+    kind: CodexAgentKind,
     output_mode: AdapterOutputMode,
     persistent_session: bool,
-    profile: &CodexModelProfile,
-) -> CodexAdapter {
-    let mut config = base_command_config_for_backend(model_routing, selected_backend);
+) -> io::Result<CodexAdapter> {
+    let profile = model_routing.profile_for(kind);
+    let mut config = model_routing.command_config_for(kind, selected_backend)?;
     if matches!(output_mode, AdapterOutputMode::JsonAssistantOnly)
         && matches!(config.backend_kind(), BackendKind::Codex)
     {
@@ -126,22 +138,21 @@ fn build_codex_adapter(
     }
     config.output_mode = output_mode;
     config.persistent_session = persistent_session;
-    apply_codex_profile(&mut config, profile);
-    CodexAdapter::with_config(config)
+    apply_codex_profile(&mut config, &profile);
+    Ok(CodexAdapter::with_config(config))
 }
 
 fn build_json_persistent_adapter(
     model_routing: &CodexAgentModelRouting,
     selected_backend: BackendKind,
     kind: CodexAgentKind,
-) -> CodexAdapter {
-    let profile = model_routing.profile_for(kind);
+) -> io::Result<CodexAdapter> {
     build_codex_adapter(
         model_routing,
         selected_backend,
+        kind,
         AdapterOutputMode::JsonAssistantOnly,
         true,
-        &profile,
     )
 }
 
@@ -150,14 +161,13 @@ fn build_plain_adapter(
     selected_backend: BackendKind,
     kind: CodexAgentKind,
     persistent_session: bool,
-) -> CodexAdapter {
-    let profile = model_routing.profile_for(kind);
+) -> io::Result<CodexAdapter> {
     build_codex_adapter(
         model_routing,
         selected_backend,
+        kind,
         AdapterOutputMode::PlainText,
         persistent_session,
-        &profile,
     )
 }
 
@@ -173,6 +183,11 @@ fn main() -> io::Result<()> {
     } else {
         None
     };
+    let recorder = launch_options
+        .record
+        .as_deref()
+        .map(SessionRecorder::create)
+        .transpose()?;
 
     enable_raw_mode()?;
 
@@ -200,6 +215,7 @@ fn main() -> io::Result<()> {
         &theme,
         cwd,
         startup_message.as_deref(),
+        recorder,
     );
 
     if keyboard_enhancements_enabled {
@@ -223,6 +239,7 @@ fn run_app(
     theme: &Theme,
     cwd: PathBuf,
     startup_message: Option<&str>,
+    recorder: Option<SessionRecorder>,
 ) -> io::Result<()> {
     let orchestration_service = DefaultCoreOrchestrationService;
     let prompt_service = DefaultUiPromptService;
@@ -237,34 +254,43 @@ fn run_app(
             CodexAgentModelRouting::default()
         }
     };
-    let mut selected_backend = model_routing.base_command_config().backend_kind();
+    if let Some(recorder) = recorder.as_ref() {
+        app.push_agent_message(format!(
+            "System: Recording this session to {}.",
+            recorder.fixture_path().display()
+        ));
+    }
+    model_routing.set_recorder(recorder.clone());
+    let mut selected_backend = model_routing.selected_backend();
     let mut master_adapter =
-        build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master);
+        build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master)?;
     let mut master_report_adapter = build_json_persistent_adapter(
         &model_routing,
         selected_backend,
         CodexAgentKind::MasterReport,
-    );
+    )?;
     let mut project_info_adapter = build_json_persistent_adapter(
         &model_routing,
         selected_backend,
         CodexAgentKind::ProjectInfo,
-    );
+    )?;
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key: Option<String> = None;
+    let mut active_worker_context_keys: HashMap<u64, String> = HashMap::new();
     let mut docs_attach_adapter = build_plain_adapter(
         &model_routing,
         selected_backend,
         CodexAgentKind::DocsAttach,
         false,
-    );
+    )?;
     let mut task_check_adapter = build_plain_adapter(
         &model_routing,
         selected_backend,
         CodexAgentKind::TaskCheck,
         false,
-    );
-    let test_runner_adapter = TestRunnerAdapter::new();
+    )?;
+    let mut test_runner_adapter = TestRunnerAdapter::new();
+    test_runner_adapter.set_recorder(recorder);
+    test_runner_adapter.set_replay_player(model_routing.replay_player());
     let mut master_transcript: Vec<String> = Vec::new();
     let mut master_report_transcript: Vec<String> = Vec::new();
     let mut master_report_in_flight = false;
@@ -291,6 +317,33 @@ fn run_app(
         }
     };
     app.set_tests_mode_enabled(tests_mode_enabled);
+    match load_global_workflow_config() {
+        Ok(workflow_config) => {
+            app.set_max_parallel_tasks(workflow_config.max_parallel_tasks);
+            app.set_checkpoints_enabled(workflow_config.checkpoints);
+            match workflow_config.retries.to_budgets() {
+                Ok(budgets) => app.set_retry_budgets(budgets),
+                Err(err) => app.push_agent_message(format!(
+                    "System: Ignoring [workflow.retries] in config.toml: {err}"
+                )),
+            }
+            app.set_job_timeouts(workflow_config.timeouts.to_timeouts());
+            test_runner_adapter.set_flaky_test_reruns(workflow_config.flaky_test_reruns);
+            match workflow_config.custom_roles() {
+                Ok(roles) => app.set_custom_roles(roles),
+                Err(err) => app.push_agent_message(format!(
+                    "System: Ignoring [workflow.roles] in config.toml: {err}"
+                )),
+            }
+        }
+        Err(err) => {
+            app.push_agent_message(format!(
+                "System: Failed to load workflow settings from config.toml; using default workflow settings: {err}"
+            ));
+            test_runner_adapter
+                .set_flaky_test_reruns(session_store::WorkflowConfig::default().flaky_test_reruns);
+        }
+    }
     app.push_agent_message("Agent: What can I help you build?".to_string());
 
     if let Some(message) = startup_message
@@ -303,7 +356,7 @@ fn run_app(
             &master_report_adapter,
             &project_info_adapter,
             &mut worker_agent_adapters,
-            &mut active_worker_context_key,
+            &mut active_worker_context_keys,
             &docs_attach_adapter,
             &test_runner_adapter,
             &mut master_report_in_flight,
@@ -329,9 +382,12 @@ fn run_app(
 
     let mut needs_draw = true;
     let mut last_ui_tick = Instant::now();
+    let mut seen_session_metadata: Option<(PathBuf, MetadataFingerprint)> = None;
     let mut planner_manual_edit_dirty = false;
     let mut planner_last_keystroke_at: Option<Instant> = None;
     while app.running {
+        // Sessions start and resume from many commands, so the recorder follows the active one.
+        sync_recorder_session_dir(&model_routing, session_store.as_ref());
         let input_pending = events::has_pending_input()?;
         let mut chat_updated = false;
 
@@ -392,10 +448,24 @@ fn run_app(
                                         )),
                                     }
                                     }
+                                    let replanned = baseline_tasks_text.as_deref()
+                                        != std::fs::read_to_string(active_session.tasks_file())
+                                            .ok()
+                                            .as_deref();
                                     match app.sync_planner_tasks_from_file(tasks) {
                                         Ok(()) => {
                                             tasks_refresh_ok = true;
                                             task_file_fix_retry_count = 0;
+                                            if replanned {
+                                                for top_task_id in app.pending_top_task_ids() {
+                                                    clear_task_checkpoints(
+                                                        &mut app,
+                                                        active_session,
+                                                        &cwd,
+                                                        top_task_id,
+                                                    );
+                                                }
+                                            }
                                         }
                                         Err(err) => {
                                             app.push_agent_message(format!(
@@ -482,21 +552,25 @@ fn run_app(
                                     &active_session.tasks_file().display().to_string(),
                                     &active_session.project_info_file().display().to_string(),
                                     &active_session.session_meta_file().display().to_string(),
+                                    &app.custom_roles_prompt_block(),
                                 ));
                             }
-                            match orchestration_service.start_next_worker_job_if_any(
+                            match orchestration_service.start_ready_worker_jobs(
                             &mut app,
                             &mut worker_agent_adapters,
-                            &mut active_worker_context_key,
+                            &mut active_worker_context_keys,
                             &test_runner_adapter,
                             active_session,
                             &model_routing,
                         ) {
-                            Ok(Some(job)) => app.push_agent_message(format!(
-                                "System: Starting {:?} for task #{}.",
-                                job.role, job.top_task_id
-                            )),
-                            Ok(None) => {}
+                            Ok(jobs) => {
+                                for job in jobs {
+                                    app.push_agent_message(format!(
+                                        "System: Starting {:?} for task #{}.",
+                                        job.role, job.top_task_id
+                                    ));
+                                }
+                            }
                             Err(err) => app.push_agent_message(format!(
                                 "System: Failed to persist runtime task status to tasks.json: {err}"
                             )),
@@ -509,15 +583,58 @@ fn run_app(
         }
 
         if !input_pending {
-            let worker_events = active_worker_context_key
-                .as_ref()
-                .and_then(|key| worker_agent_adapters.get(key))
-                .map(|adapter| adapter.drain_events_limited(MAX_ADAPTER_EVENTS_PER_LOOP))
-                .unwrap_or_default();
-            for event in worker_events {
+            let timed_out_jobs = kill_timed_out_worker_jobs(
+                &mut app,
+                &worker_agent_adapters,
+                &active_worker_context_keys,
+                &test_runner_adapter,
+            );
+            // Killed runs can no longer send, so draining everything queued before the kill
+            // and dropping it leaves only the synthetic failed completion for those tasks.
+            let event_limit = if timed_out_jobs.is_empty() {
+                MAX_ADAPTER_EVENTS_PER_LOOP
+            } else {
+                usize::MAX
+            };
+            let timed_out_task_ids = timed_out_jobs
+                .iter()
+                .map(|job| job.top_task_id)
+                .collect::<HashSet<_>>();
+            let mut worker_events = timed_out_jobs
+                .iter()
+                .map(|job| {
+                    (
+                        Some(job.top_task_id),
+                        AgentEvent::Completed {
+                            success: false,
+                            code: JOB_TIMEOUT_EXIT_CODE,
+                        },
+                    )
+                })
+                .collect::<Vec<_>>();
+            let mut drained_events = Vec::new();
+            for (top_task_id, key) in &active_worker_context_keys {
+                if let Some(adapter) = worker_agent_adapters.get(key) {
+                    drained_events.extend(
+                        adapter
+                            .drain_events_limited(event_limit)
+                            .into_iter()
+                            .map(|event| (Some(*top_task_id), event)),
+                    );
+                }
+            }
+            drained_events.extend(test_runner_adapter.drain_task_events_limited(event_limit));
+            worker_events.extend(drained_events.into_iter().filter(|(top_task_id, _)| {
+                !top_task_id.is_some_and(|id| timed_out_task_ids.contains(&id))
+            }));
+            for (top_task_id, event) in worker_events {
+                let top_task_id = top_task_id.or_else(|| app.active_job_top_task_id());
                 match event {
                     AgentEvent::Output(line) => {
-                        app.on_worker_output(line);
+                        match top_task_id {
+                            Some(top_task_id) => app.on_worker_output_for_task(top_task_id, line),
+                            None => app.on_worker_output(line),
+                        }
                         chat_updated = true;
                     }
                     AgentEvent::System(line) => {
@@ -525,19 +642,17 @@ fn run_app(
                         chat_updated = true;
                     }
                     AgentEvent::Completed { success, code } => {
-                        let Some(active_session) = session_store.as_ref() else {
-                            active_worker_context_key = None;
-                            let _ = app.on_worker_completed(success, code);
+                        let Some(top_task_id) = top_task_id else {
                             chat_updated = true;
                             continue;
                         };
-                        if let Some(key) = active_worker_context_key.as_ref()
-                            && let Some(adapter) = worker_agent_adapters.get(key)
+                        if let Some(key) = active_worker_context_keys.remove(&top_task_id)
+                            && let Some(adapter) = worker_agent_adapters.get(&key)
                         {
                             for tail_event in drain_post_completion_worker_events(adapter) {
                                 match tail_event {
                                     AgentEvent::Output(line) => {
-                                        app.on_worker_output(line);
+                                        app.on_worker_output_for_task(top_task_id, line);
                                     }
                                     AgentEvent::System(line) => {
                                         app.on_worker_system_output(line);
@@ -546,70 +661,18 @@ fn run_app(
                                 }
                             }
                         }
-                        active_worker_context_key = None;
-                        let outcome = orchestration_service.complete_worker_cycle_and_start_next(
-                            &mut app,
-                            success,
-                            code,
-                            &mut worker_agent_adapters,
-                            &mut active_worker_context_key,
-                            &test_runner_adapter,
-                            active_session,
-                            &model_routing,
-                            &mut master_report_session_intro_needed,
-                            project_info_text.as_deref(),
-                        );
-                        for warning in outcome.warnings {
-                            app.push_agent_message(format!("System: {warning}"));
-                        }
-                        for prompt in [outcome.failure_report_prompt, outcome.context_report_prompt]
-                            .into_iter()
-                            .flatten()
-                        {
-                            if let Some(prompt_to_send) = enqueue_or_dispatch_master_report_prompt(
-                                prompt,
-                                &mut master_report_in_flight,
-                                &mut pending_master_report_prompts,
-                            ) {
-                                master_report_transcript.clear();
-                                master_report_adapter.send_prompt(prompt_to_send);
-                            }
-                        }
-                        if let Some(job) = outcome.started_job {
-                            app.push_agent_message(format!(
-                                "System: Starting {:?} for task #{}.",
-                                job.role, job.top_task_id
-                            ));
-                        }
-                        chat_updated = true;
-                    }
-                }
-            }
-        }
-
-        if !input_pending {
-            for event in test_runner_adapter.drain_events_limited(MAX_ADAPTER_EVENTS_PER_LOOP) {
-                match event {
-                    AgentEvent::Output(line) => {
-                        app.on_worker_output(line);
-                        chat_updated = true;
-                    }
-                    AgentEvent::System(line) => {
-                        app.on_worker_system_output(line);
-                        chat_updated = true;
-                    }
-                    AgentEvent::Completed { success, code } => {
                         let Some(active_session) = session_store.as_ref() else {
-                            let _ = app.on_worker_completed(success, code);
+                            let _ = app.on_worker_completed_for_task(top_task_id, success, code);
                             chat_updated = true;
                             continue;
                         };
                         let outcome = orchestration_service.complete_worker_cycle_and_start_next(
                             &mut app,
+                            top_task_id,
                             success,
                             code,
                             &mut worker_agent_adapters,
-                            &mut active_worker_context_key,
+                            &mut active_worker_context_keys,
                             &test_runner_adapter,
                             active_session,
                             &model_routing,
@@ -632,7 +695,7 @@ fn run_app(
                                 master_report_adapter.send_prompt(prompt_to_send);
                             }
                         }
-                        if let Some(job) = outcome.started_job {
+                        for job in outcome.started_jobs {
                             app.push_agent_message(format!(
                                 "System: Starting {:?} for task #{}.",
                                 job.role, job.top_task_id
@@ -944,6 +1007,41 @@ fn run_app(
                 );
                 if last_ui_tick.elapsed() >= UI_TICK_INTERVAL {
                     app.on_tick();
+                    let metadata_changed = session_store.as_ref().is_some_and(|active_session| {
+                        session_metadata_changed(active_session, &mut seen_session_metadata)
+                    });
+                    if metadata_changed
+                        && let Some(active_session) = session_store.as_ref()
+                        && let Some(command) = external_execution_control_command(
+                            &app,
+                            active_session.read_execution_control(),
+                        )
+                    {
+                        handle_execution_control_command(
+                            &mut app,
+                            command,
+                            &mut worker_agent_adapters,
+                            &mut active_worker_context_keys,
+                            &test_runner_adapter,
+                            active_session,
+                            &model_routing,
+                        );
+                    }
+                    if metadata_changed
+                        && let Some(active_session) = session_store.as_ref()
+                        && let Ok(Some(decision)) = active_session.take_review_decision()
+                    {
+                        handle_review_decision(
+                            &mut app,
+                            decision,
+                            &mut worker_agent_adapters,
+                            &mut active_worker_context_keys,
+                            &test_runner_adapter,
+                            active_session,
+                            &model_routing,
+                        );
+                    }
+                    announce_new_reviews(&mut app, session_store.as_ref(), &cwd);
                     last_ui_tick = Instant::now();
                     needs_draw = true;
                 }
@@ -1128,7 +1226,7 @@ fn run_app(
                             &master_report_adapter,
                             &project_info_adapter,
                             &mut worker_agent_adapters,
-                            &mut active_worker_context_key,
+                            &mut active_worker_context_keys,
                             &mut pending_task_write_baseline,
                             &mut docs_attach_in_flight,
                             &mut master_session_intro_needed,
@@ -1154,7 +1252,7 @@ fn run_app(
                             selection,
                             &mut selected_backend,
                             &mut model_routing,
-                            &mut active_worker_context_key,
+                            &mut active_worker_context_keys,
                             &mut worker_agent_adapters,
                             &mut master_adapter,
                             &mut master_report_adapter,
@@ -1162,6 +1260,7 @@ fn run_app(
                             &mut docs_attach_adapter,
                             &mut task_check_adapter,
                         );
+                        test_runner_adapter.set_replay_player(model_routing.replay_player());
                     }
                 } else if app.active_pane == Pane::LeftBottom {
                     app.input_char(c);
@@ -1262,7 +1361,7 @@ fn run_app(
                             &master_report_adapter,
                             &project_info_adapter,
                             &mut worker_agent_adapters,
-                            &mut active_worker_context_key,
+                            &mut active_worker_context_keys,
                             &mut pending_task_write_baseline,
                             &mut docs_attach_in_flight,
                             &mut master_session_intro_needed,
@@ -1286,7 +1385,7 @@ fn run_app(
                             selection,
                             &mut selected_backend,
                             &mut model_routing,
-                            &mut active_worker_context_key,
+                            &mut active_worker_context_keys,
                             &mut worker_agent_adapters,
                             &mut master_adapter,
                             &mut master_report_adapter,
@@ -1294,6 +1393,7 @@ fn run_app(
                             &mut docs_attach_adapter,
                             &mut task_check_adapter,
                         );
+                        test_runner_adapter.set_replay_player(model_routing.replay_player());
                     }
                 } else if app.active_pane == Pane::Right && app.is_planner_mode() {
                     if session_store.is_none() {
@@ -1369,7 +1469,7 @@ fn run_app(
                                 &master_report_adapter,
                                 &project_info_adapter,
                                 &mut worker_agent_adapters,
-                                &mut active_worker_context_key,
+                                &mut active_worker_context_keys,
                                 &docs_attach_adapter,
                                 &test_runner_adapter,
                                 &mut master_report_in_flight,
@@ -1400,7 +1500,7 @@ fn run_app(
                             &master_report_adapter,
                             &project_info_adapter,
                             &mut worker_agent_adapters,
-                            &mut active_worker_context_key,
+                            &mut active_worker_context_keys,
                             &docs_attach_adapter,
                             &test_runner_adapter,
                             &mut master_report_in_flight,
@@ -1532,7 +1632,7 @@ fn submit_user_message_with_runtime<B: Backend>(
     master_report_adapter: &CodexAdapter,
     project_info_adapter: &CodexAdapter,
     worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-    active_worker_context_key: &mut Option<String>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
     docs_attach_adapter: &CodexAdapter,
     test_runner_adapter: &TestRunnerAdapter,
     master_report_in_flight: &mut bool,
@@ -1570,6 +1670,7 @@ fn submit_user_message_with_runtime<B: Backend>(
     }
 
     initialize_session_for_message_if_needed(app, &message, cwd, session_store, project_info_text)?;
+    sync_recorder_session_dir(model_routing, session_store.as_ref());
 
     if command_requires_active_session(&message) && session_store.is_none() {
         app.push_agent_message(
@@ -1652,7 +1753,7 @@ fn submit_user_message_with_runtime<B: Backend>(
         master_report_adapter.reset_session();
         project_info_adapter.reset_session();
         worker_agent_adapters.clear();
-        *active_worker_context_key = None;
+        active_worker_context_keys.clear();
         *pending_task_write_baseline = None;
         *master_session_intro_needed = true;
         *master_report_session_intro_needed = true;
@@ -1738,7 +1839,8 @@ fn submit_user_message_with_runtime<B: Backend>(
     }
 
     if is_backend_command(&message) {
-        let options = backend_picker_options(*selected_backend);
+        let options =
+            backend_picker_options(*selected_backend, &model_routing.available_backends());
         app.open_backend_picker(options);
         app.push_agent_message(
             "System: Select a backend in the picker and press Enter or Space (Backspace cancels)."
@@ -1856,6 +1958,101 @@ fn submit_user_message_with_runtime<B: Backend>(
         return Ok(());
     }
 
+    if let Some(command) = parse_execution_control_command(&message) {
+        let active_session = session_store
+            .as_ref()
+            .expect("execution control commands require an active session");
+        handle_execution_control_command(
+            app,
+            command,
+            worker_agent_adapters,
+            active_worker_context_keys,
+            test_runner_adapter,
+            active_session,
+            model_routing,
+        );
+        let size = terminal.size()?;
+        let screen = Rect::new(0, 0, size.width, size.height);
+        let max_scroll = ui::chat_max_scroll(screen, app);
+        app.set_chat_scroll(max_scroll);
+        return Ok(());
+    }
+
+    if let Some(decision) = parse_review_command(&message) {
+        let active_session = session_store
+            .as_ref()
+            .expect("review commands require an active session");
+        match decision {
+            Some(decision) => handle_review_decision(
+                app,
+                decision,
+                worker_agent_adapters,
+                active_worker_context_keys,
+                test_runner_adapter,
+                active_session,
+                model_routing,
+            ),
+            None => {
+                app.push_agent_message("System: Usage: /reject <task-id> <feedback>".to_string())
+            }
+        }
+        let size = terminal.size()?;
+        let screen = Rect::new(0, 0, size.width, size.height);
+        let max_scroll = ui::chat_max_scroll(screen, app);
+        app.set_chat_scroll(max_scroll);
+        return Ok(());
+    }
+
+    if App::is_retry_command(&message) || App::is_skip_command(&message) {
+        let active_session = session_store
+            .as_ref()
+            .expect("retry and skip commands require an active session");
+        let changed = if App::is_retry_command(&message) {
+            handle_retry_command(app, &message, active_session, cwd)
+        } else {
+            handle_skip_command(app, &message)
+        };
+        if changed {
+            start_ready_worker_jobs_and_report(
+                app,
+                worker_agent_adapters,
+                active_worker_context_keys,
+                test_runner_adapter,
+                active_session,
+                model_routing,
+            );
+        }
+        let size = terminal.size()?;
+        let screen = Rect::new(0, 0, size.width, size.height);
+        let max_scroll = ui::chat_max_scroll(screen, app);
+        app.set_chat_scroll(max_scroll);
+        return Ok(());
+    }
+
+    if App::is_dry_run_command(&message) {
+        let active_session = session_store
+            .as_ref()
+            .expect("dry-run command requires an active session");
+        handle_dry_run_command(app, active_session, model_routing);
+        let size = terminal.size()?;
+        let screen = Rect::new(0, 0, size.width, size.height);
+        let max_scroll = ui::chat_max_scroll(screen, app);
+        app.set_chat_scroll(max_scroll);
+        return Ok(());
+    }
+
+    if App::is_rollback_command(&message) {
+        let active_session = session_store
+            .as_ref()
+            .expect("rollback command requires an active session");
+        handle_rollback_command(app, &message, active_session, cwd);
+        let size = terminal.size()?;
+        let screen = Rect::new(0, 0, size.width, size.height);
+        let max_scroll = ui::chat_max_scroll(screen, app);
+        app.set_chat_scroll(max_scroll);
+        return Ok(());
+    }
+
     if message.trim().starts_with('/') && !is_known_slash_command(&message) {
         app.push_agent_message(format!(
             "System: Unknown command `{}`. Type `/` to see available commands.",
@@ -1913,22 +2110,26 @@ fn submit_user_message_with_runtime<B: Backend>(
             app.push_agent_message("System: Started execution".to_string());
         }
         *pending_task_write_baseline = None;
+        app.set_coverage_threshold(services::session_coverage_threshold(active_session));
         for system_message in app.start_execution() {
             app.push_agent_message(system_message);
         }
-        match orchestration_service.start_next_worker_job_if_any(
+        match orchestration_service.start_ready_worker_jobs(
             app,
             worker_agent_adapters,
-            active_worker_context_key,
+            active_worker_context_keys,
             test_runner_adapter,
             active_session,
             model_routing,
         ) {
-            Ok(Some(job)) => app.push_agent_message(format!(
-                "System: Starting {:?} for task #{}.",
-                job.role, job.top_task_id
-            )),
-            Ok(None) => {}
+            Ok(jobs) => {
+                for job in jobs {
+                    app.push_agent_message(format!(
+                        "System: Starting {:?} for task #{}.",
+                        job.role, job.top_task_id
+                    ));
+                }
+            }
             Err(err) => app.push_agent_message(format!(
                 "System: Failed to persist runtime task status to tasks.json: {err}"
             )),
@@ -1954,7 +2155,7 @@ fn submit_user_message<B: Backend>(
     master_report_adapter: &CodexAdapter,
     project_info_adapter: &CodexAdapter,
     worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-    active_worker_context_key: &mut Option<String>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
     docs_attach_adapter: &CodexAdapter,
     test_runner_adapter: &TestRunnerAdapter,
     master_report_in_flight: &mut bool,
@@ -1976,7 +2177,7 @@ fn submit_user_message<B: Backend>(
     model_routing: &CodexAgentModelRouting,
 ) -> io::Result<()> {
     let mut routing = model_routing.clone();
-    let mut selected_backend = routing.base_command_config().backend_kind();
+    let mut selected_backend = routing.selected_backend();
     submit_user_message_with_runtime(
         app,
         message,
@@ -1984,7 +2185,7 @@ fn submit_user_message<B: Backend>(
         master_report_adapter,
         project_info_adapter,
         worker_agent_adapters,
-        active_worker_context_key,
+        active_worker_context_keys,
         docs_attach_adapter,
         test_runner_adapter,
         master_report_in_flight,
@@ -2071,7 +2272,7 @@ fn resume_session<B: Backend>(
     master_report_adapter: &CodexAdapter,
     project_info_adapter: &CodexAdapter,
     worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-    active_worker_context_key: &mut Option<String>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
     pending_task_write_baseline: &mut Option<TaskWriteBaseline>,
     docs_attach_in_flight: &mut bool,
     master_session_intro_needed: &mut bool,
@@ -2112,7 +2313,7 @@ fn resume_session<B: Backend>(
     master_report_adapter.reset_session();
     project_info_adapter.reset_session();
     worker_agent_adapters.clear();
-    *active_worker_context_key = None;
+    active_worker_context_keys.clear();
     *master_session_intro_needed = true;
     *master_report_session_intro_needed = true;
     *pending_task_write_baseline = None;
@@ -2128,6 +2329,7 @@ fn resume_session<B: Backend>(
     reset_task_check_runtime(task_check_in_flight, task_check_baseline);
 
     app.reset_execution_for_session_switch();
+    app.set_execution_paused(active_session.read_execution_control().paused);
     app.set_task_check_in_progress(false);
     app.set_docs_attach_in_progress(false);
     app.set_master_in_progress(false);
@@ -2150,6 +2352,12 @@ fn resume_session<B: Backend>(
         "System: Resumed session {}",
         active_session.session_dir().display()
     ));
+    if app.is_execution_paused() {
+        app.push_agent_message(
+            "System: Execution was paused in this session. Use /resume-execution to continue."
+                .to_string(),
+        );
+    }
 
     let size = terminal.size()?;
     let screen = Rect::new(0, 0, size.width, size.height);
@@ -2201,48 +2409,411 @@ fn handle_final_audit_command<B: Backend>(
     Ok(false)
 }
 
-fn handle_final_audit_tasks_command(
+fn handle_execution_control_command(
     app: &mut App,
-    message: &str,
+    command: ExecutionControlCommand,
+    worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
+    test_runner_adapter: &TestRunnerAdapter,
     session_store: &SessionStore,
-) -> io::Result<bool> {
-    if (App::is_add_final_audit_command(message) || App::is_remove_final_audit_command(message))
-        && app.is_execution_busy()
-    {
-        app.push_agent_message(
-            "System: Cannot modify final-audit tasks while worker execution is running. Wait for active jobs to finish first."
-                .to_string(),
-        );
-        return Ok(true);
-    }
-
-    if App::is_add_final_audit_command(message) {
-        let mut tasks = match session_store.read_tasks() {
-            Ok(tasks) => tasks,
-            Err(err) => {
+    model_routing: &CodexAgentModelRouting,
+) {
+    let orchestration_service = DefaultCoreOrchestrationService;
+    match command {
+        ExecutionControlCommand::Pause => {
+            for system_message in app.pause_execution() {
+                app.push_agent_message(system_message);
+            }
+        }
+        ExecutionControlCommand::Stop => {
+            let stopped = app.stop_active_jobs();
+            for top_task_id in &stopped {
+                if let Some(key) = active_worker_context_keys.remove(top_task_id)
+                    && let Some(adapter) = worker_agent_adapters.get(&key)
+                {
+                    adapter.kill_running();
+                }
+                test_runner_adapter.kill_task_run(*top_task_id);
+            }
+            if stopped.is_empty() && !app.is_execution_paused() {
+                app.push_agent_message(
+                    "System: Execution is not running; nothing to stop.".to_string(),
+                );
+            } else if stopped.is_empty() {
+                app.push_agent_message(
+                    "System: No worker jobs were running. Execution is paused; use /resume-execution to continue."
+                        .to_string(),
+                );
+            } else {
+                let task_list = stopped
+                    .iter()
+                    .map(|id| format!("#{id}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
                 app.push_agent_message(format!(
-                    "System: Could not read tasks file; final audit command aborted: {err}"
+                    "System: Stopped running job(s) for task {task_list} and marked them NeedsChanges. Execution is paused; use /resume-execution to re-run them."
                 ));
-                return Ok(true);
             }
-        };
-        ensure_final_audit_task(&mut tasks);
-        normalize_root_orders_with_final_last(&mut tasks);
-        match app.sync_planner_tasks_from_file(tasks.clone()) {
-            Ok(_) => match serde_json::to_string_pretty(&tasks)
-                .map_err(io::Error::other)
-                .and_then(|text| std::fs::write(session_store.tasks_file(), text))
+            if let Err(err) =
+                orchestration_service.persist_runtime_tasks_snapshot(app, session_store)
             {
-                Ok(()) => app.push_agent_message("System: Added final audit task.".to_string()),
+                app.push_agent_message(format!(
+                    "System: Failed to persist runtime task status to tasks.json: {err}"
+                ));
+            }
+        }
+        ExecutionControlCommand::Resume => {
+            for system_message in app.resume_execution() {
+                app.push_agent_message(system_message);
+            }
+            match orchestration_service.start_ready_worker_jobs(
+                app,
+                worker_agent_adapters,
+                active_worker_context_keys,
+                test_runner_adapter,
+                session_store,
+                model_routing,
+            ) {
+                Ok(jobs) => {
+                    for job in jobs {
+                        app.push_agent_message(format!(
+                            "System: Starting {:?} for task #{}.",
+                            job.role, job.top_task_id
+                        ));
+                    }
+                }
                 Err(err) => app.push_agent_message(format!(
-                    "System: Failed to write tasks file while adding final audit task: {err}"
+                    "System: Failed to persist runtime task status to tasks.json: {err}"
                 )),
-            },
-            Err(err) => app.push_agent_message(format!(
-                "System: Final audit command aborted; task tree refresh failed before write: {err}"
-            )),
+            }
         }
-        return Ok(true);
+    }
+    let control = ExecutionControl {
+        paused: app.is_execution_paused(),
+        stop_requested: false,
+    };
+    if let Err(err) = session_store.write_execution_control(control) {
+        app.push_agent_message(format!(
+            "System: Failed to persist paused state to metadata.json: {err}"
+        ));
+    }
+}
+
+fn handle_review_decision(
+    app: &mut App,
+    decision: ReviewDecision,
+    worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
+    test_runner_adapter: &TestRunnerAdapter,
+    session_store: &SessionStore,
+    model_routing: &CodexAgentModelRouting,
+) {
+    let messages = match decision {
+        ReviewDecision::Approve { task_id } => app.approve_review(task_id.as_deref()),
+        ReviewDecision::Reject { task_id, feedback } => {
+            app.reject_review(task_id.as_deref(), &feedback)
+        }
+    };
+    for system_message in messages {
+        app.push_agent_message(system_message);
+    }
+    start_ready_worker_jobs_and_report(
+        app,
+        worker_agent_adapters,
+        active_worker_context_keys,
+        test_runner_adapter,
+        session_store,
+        model_routing,
+    );
+}
+
+fn handle_retry_command(
+    app: &mut App,
+    message: &str,
+    session_store: &SessionStore,
+    cwd: &Path,
+) -> bool {
+    let Some((task_ref, guidance)) = App::retry_command_args(message) else {
+        app.push_agent_message("System: Usage: /retry <task-id> [guidance]".to_string());
+        return false;
+    };
+    let Some(task_id) = app.resolve_task_id(task_ref) else {
+        app.push_agent_message(format!(
+            "System: No task matches `{task_ref}`; retry skipped."
+        ));
+        return false;
+    };
+    match app.retry_task(task_id, guidance) {
+        Ok(system_message) => {
+            app.push_agent_message(system_message);
+            let reset_merges = app.drain_reset_merges();
+            for (top_task_id, leftover_workspace) in &reset_merges {
+                if let Some(workspace_dir) = leftover_workspace {
+                    discard_task_worktree(app, cwd, *top_task_id, workspace_dir);
+                }
+                clear_task_checkpoints(app, session_store, cwd, *top_task_id);
+            }
+            if app.resolve_top_task_id(task_ref) == Some(task_id)
+                && !reset_merges
+                    .iter()
+                    .any(|(top_task_id, _)| *top_task_id == task_id)
+            {
+                clear_task_checkpoints(app, session_store, cwd, task_id);
+            }
+            true
+        }
+        Err(err) => {
+            app.push_agent_message(format!("System: Retry failed: {err}"));
+            false
+        }
+    }
+}
+
+/// Drops the git checkpoints of an earlier run of the task, so a later `/rollback` restores
+/// the state from before the new run rather than a stale snapshot.
+fn clear_task_checkpoints(
+    app: &mut App,
+    session_store: &SessionStore,
+    cwd: &Path,
+    top_task_id: u64,
+) {
+    let task_key = app
+        .top_task_key(top_task_id)
+        .unwrap_or_else(|| format!("internal-{top_task_id}"));
+    let Ok(repo_root) = worktree::repo_root(cwd) else {
+        return;
+    };
+    if let Err(err) =
+        checkpoint::clear_checkpoints(&repo_root, &session_store.session_id(), &task_key)
+    {
+        app.push_agent_message(format!(
+            "System: Could not clear old git checkpoints for task #{top_task_id}: {err}"
+        ));
+    }
+}
+
+/// Removes the worktree kept from a failed merge-back, so the retried task starts from the
+/// current main checkout.
+fn discard_task_worktree(app: &mut App, cwd: &Path, top_task_id: u64, workspace_dir: &Path) {
+    let task_key = app
+        .top_task_key(top_task_id)
+        .unwrap_or_else(|| format!("internal-{top_task_id}"));
+    let result = worktree::repo_root(cwd)
+        .and_then(|repo_root| worktree::remove_task_worktree(&repo_root, workspace_dir, &task_key));
+    if let Err(err) = result {
+        app.push_agent_message(format!(
+            "System: Could not remove the old worktree of task #{top_task_id}: {err}"
+        ));
+    }
+}
+
+fn handle_skip_command(app: &mut App, message: &str) -> bool {
+    let Some(task_ref) = App::skip_command_target(message) else {
+        app.push_agent_message("System: Usage: /skip <task-id>".to_string());
+        return false;
+    };
+    let Some(task_id) = app.resolve_task_id(task_ref) else {
+        app.push_agent_message(format!(
+            "System: No task matches `{task_ref}`; nothing was skipped."
+        ));
+        return false;
+    };
+    match app.skip_task(task_id) {
+        Ok(messages) => {
+            for system_message in messages {
+                app.push_agent_message(system_message);
+            }
+            true
+        }
+        Err(err) => {
+            app.push_agent_message(format!("System: Skip failed: {err}"));
+            false
+        }
+    }
+}
+
+fn start_ready_worker_jobs_and_report(
+    app: &mut App,
+    worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
+    test_runner_adapter: &TestRunnerAdapter,
+    session_store: &SessionStore,
+    model_routing: &CodexAgentModelRouting,
+) {
+    // Task edits made outside a worker completion still need to reach tasks.json.
+    let orchestration_service = DefaultCoreOrchestrationService;
+    if let Err(err) = orchestration_service.persist_runtime_tasks_snapshot(app, session_store) {
+        app.push_agent_message(format!(
+            "System: Failed to persist runtime task status to tasks.json: {err}"
+        ));
+    }
+    match orchestration_service.start_ready_worker_jobs(
+        app,
+        worker_agent_adapters,
+        active_worker_context_keys,
+        test_runner_adapter,
+        session_store,
+        model_routing,
+    ) {
+        Ok(jobs) => {
+            for job in jobs {
+                app.push_agent_message(format!(
+                    "System: Starting {:?} for task #{}.",
+                    job.role, job.top_task_id
+                ));
+            }
+        }
+        Err(err) => app.push_agent_message(format!(
+            "System: Failed to persist runtime task status to tasks.json: {err}"
+        )),
+    }
+}
+
+fn announce_new_reviews(app: &mut App, session_store: Option<&SessionStore>, cwd: &Path) {
+    let session_id = session_store
+        .map(SessionStore::session_id)
+        .unwrap_or_default();
+    for review in app.drain_new_reviews() {
+        let task_key = app
+            .top_task_key(review.top_task_id)
+            .unwrap_or_else(|| format!("internal-{}", review.top_task_id));
+        let workspace = match app.task_workspace(review.top_task_id) {
+            Some(workspace_dir) => Ok(workspace_dir),
+            None => worktree::repo_root(cwd),
+        };
+        let diff = match workspace
+            .and_then(|workspace| checkpoint::task_diff(&workspace, &session_id, &task_key))
+        {
+            Ok(diff) if diff.trim().is_empty() => "(no changes in the workspace)".to_string(),
+            Ok(diff) => truncate_review_diff(&diff),
+            Err(err) => format!("(diff unavailable: {err})"),
+        };
+        let task_ref = app
+            .top_task_key(review.top_task_id)
+            .unwrap_or_else(|| review.top_task_id.to_string());
+        app.push_agent_message(format!(
+            "System: Review requested for task #{} \"{}\".\nChanged files:\n{}\nDiff:\n{}\nRun /approve {task_ref} to continue or /reject {task_ref} <feedback> to send it back to the implementor.",
+            review.top_task_id,
+            review.top_task_title,
+            review
+                .changed_files_summary
+                .as_deref()
+                .unwrap_or("(implementor did not provide a changed-files summary)"),
+            diff
+        ));
+    }
+}
+
+fn truncate_review_diff(diff: &str) -> String {
+    let total = diff.lines().count();
+    if total <= MAX_REVIEW_DIFF_LINES {
+        return diff.trim_end().to_string();
+    }
+    let shown = diff
+        .lines()
+        .take(MAX_REVIEW_DIFF_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{shown}\n... ({} more diff lines not shown)",
+        total - MAX_REVIEW_DIFF_LINES
+    )
+}
+
+fn handle_rollback_command(app: &mut App, message: &str, session_store: &SessionStore, cwd: &Path) {
+    let Some(task_ref) = App::rollback_command_target(message) else {
+        app.push_agent_message("System: Usage: /rollback <task-id>".to_string());
+        return;
+    };
+    let Some(top_task_id) = app.resolve_top_task_id(task_ref) else {
+        app.push_agent_message(format!(
+            "System: No top-level task matches `{task_ref}`; rollback skipped."
+        ));
+        return;
+    };
+    let task_key = app
+        .top_task_key(top_task_id)
+        .unwrap_or_else(|| format!("internal-{top_task_id}"));
+    let workspace = match app.task_workspace(top_task_id) {
+        Some(workspace_dir) => Ok(workspace_dir),
+        None => worktree::repo_root(cwd),
+    };
+    let session_id = session_store.session_id();
+    match workspace.and_then(|workspace| {
+        checkpoint::rollback_task(&workspace, &session_id, &task_key)
+    }) {
+        Ok(outcome) => app.push_agent_message(format!(
+            "System: Rolled task #{top_task_id} back to {}. The replaced workspace state is saved at {}.",
+            outcome.checkpoint_ref, outcome.backup_ref
+        )),
+        Err(err) => app.push_agent_message(format!(
+            "System: Rollback for task #{top_task_id} failed: {err}"
+        )),
+    }
+}
+
+fn handle_dry_run_command(
+    app: &mut App,
+    session_store: &SessionStore,
+    model_routing: &CodexAgentModelRouting,
+) {
+    let output_dir = session_store.session_dir().join(DRY_RUN_DIR_NAME);
+    let result = app.dry_run_prompts().and_then(|prompts| {
+        let prompts = dry_run_prompt_contracts(prompts, model_routing);
+        write_dry_run_prompt_files(&output_dir, &prompts).map_err(|err| err.to_string())?;
+        Ok(prompts.len())
+    });
+    match result {
+        Ok(count) => app.push_agent_message(format!(
+            "System: Dry run rendered {count} worker prompt(s) without invoking a backend; wrote them to {}.",
+            output_dir.display()
+        )),
+        Err(err) => app.push_agent_message(format!("System: Dry run failed: {err}")),
+    }
+}
+
+fn handle_final_audit_tasks_command(
+    app: &mut App,
+    message: &str,
+    session_store: &SessionStore,
+) -> io::Result<bool> {
+    if (App::is_add_final_audit_command(message) || App::is_remove_final_audit_command(message))
+        && app.is_execution_busy()
+    {
+        app.push_agent_message(
+            "System: Cannot modify final-audit tasks while worker execution is running. Wait for active jobs to finish first."
+                .to_string(),
+        );
+        return Ok(true);
+    }
+
+    if App::is_add_final_audit_command(message) {
+        let mut tasks = match session_store.read_tasks() {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                app.push_agent_message(format!(
+                    "System: Could not read tasks file; final audit command aborted: {err}"
+                ));
+                return Ok(true);
+            }
+        };
+        ensure_final_audit_task(&mut tasks);
+        normalize_root_orders_with_final_last(&mut tasks);
+        match app.sync_planner_tasks_from_file(tasks.clone()) {
+            Ok(_) => match serde_json::to_string_pretty(&tasks)
+                .map_err(io::Error::other)
+                .and_then(|text| std::fs::write(session_store.tasks_file(), text))
+            {
+                Ok(()) => app.push_agent_message("System: Added final audit task.".to_string()),
+                Err(err) => app.push_agent_message(format!(
+                    "System: Failed to write tasks file while adding final audit task: {err}"
+                )),
+            },
+            Err(err) => app.push_agent_message(format!(
+                "System: Final audit command aborted; task tree refresh failed before write: {err}"
+            )),
+        }
+        return Ok(true);
     }
 
     if App::is_remove_final_audit_command(message) {
@@ -2323,6 +2894,12 @@ fn ensure_final_audit_task(tasks: &mut Vec<PlannerTaskFileEntry>) {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(u32::MAX),
+        depends_on: Vec::new(),
+        max_retries: None,
+        command: None,
+        working_dir: None,
+        review_quorum: None,
+        role: None,
     });
 }
 
@@ -2588,24 +3165,52 @@ fn backend_label(kind: BackendKind) -> &'static str {
     match kind {
         BackendKind::Codex => "codex",
         BackendKind::Claude => "claude",
+        BackendKind::Custom => "custom",
+        BackendKind::Http => "http",
+        BackendKind::Replay => "replay",
     }
 }
 
-fn backend_picker_options(selected_backend: BackendKind) -> Vec<BackendOption> {
-    let mut options = vec![
-        BackendOption {
-            kind: BackendKind::Codex,
-            label: "Codex",
-            description: "OpenAI Codex backend",
-        },
-        BackendOption {
-            kind: BackendKind::Claude,
-            label: "Claude",
-            description: "Anthropic Claude backend",
-        },
-    ];
-    if selected_backend == BackendKind::Claude {
-        options.swap(0, 1);
+fn backend_picker_options(
+    selected_backend: BackendKind,
+    available: &[BackendKind],
+) -> Vec<BackendOption> {
+    let mut options = available
+        .iter()
+        .map(|kind| match kind {
+            BackendKind::Codex => BackendOption {
+                kind: BackendKind::Codex,
+                label: "Codex",
+                description: "OpenAI Codex backend",
+            },
+            BackendKind::Claude => BackendOption {
+                kind: BackendKind::Claude,
+                label: "Claude",
+                description: "Anthropic Claude backend",
+            },
+            BackendKind::Custom => BackendOption {
+                kind: BackendKind::Custom,
+                label: "Custom",
+                description: "CLI configured in [backend.custom]",
+            },
+            BackendKind::Http => BackendOption {
+                kind: BackendKind::Http,
+                label: "HTTP",
+                description: "Chat completions server configured in [backend.http]",
+            },
+            BackendKind::Replay => BackendOption {
+                kind: BackendKind::Replay,
+                label: "Replay",
+                description: "Scripted fixture configured in [backend.replay]",
+            },
+        })
+        .collect::<Vec<_>>();
+    if let Some(index) = options
+        .iter()
+        .position(|option| option.kind == selected_backend)
+    {
+        let selected = options.remove(index);
+        options.insert(0, selected);
     }
     options
 }
@@ -2650,6 +3255,8 @@ fn persist_backend_selection(selected_backend: BackendKind) -> io::Result<std::p
     Ok(config_file)
 }
 
+/// Builds every adapter before replacing any, so a backend that cannot be used leaves the
+/// running ones untouched.
 fn rebuild_runtime_adapters(
     model_routing: &CodexAgentModelRouting,
     selected_backend: BackendKind,
@@ -2658,32 +3265,41 @@ fn rebuild_runtime_adapters(
     project_info_adapter: &mut CodexAdapter,
     docs_attach_adapter: &mut CodexAdapter,
     task_check_adapter: &mut CodexAdapter,
-    active_worker_context_key: &mut Option<String>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
     worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-) {
-    *master_adapter =
-        build_json_persistent_adapter(model_routing, selected_backend, CodexAgentKind::Master);
-    *master_report_adapter = build_json_persistent_adapter(
+) -> io::Result<()> {
+    let master =
+        build_json_persistent_adapter(model_routing, selected_backend, CodexAgentKind::Master)?;
+    let master_report = build_json_persistent_adapter(
         model_routing,
         selected_backend,
         CodexAgentKind::MasterReport,
-    );
-    *project_info_adapter =
-        build_json_persistent_adapter(model_routing, selected_backend, CodexAgentKind::ProjectInfo);
-    *docs_attach_adapter = build_plain_adapter(
+    )?;
+    let project_info = build_json_persistent_adapter(
+        model_routing,
+        selected_backend,
+        CodexAgentKind::ProjectInfo,
+    )?;
+    let docs_attach = build_plain_adapter(
         model_routing,
         selected_backend,
         CodexAgentKind::DocsAttach,
         false,
-    );
-    *task_check_adapter = build_plain_adapter(
+    )?;
+    let task_check = build_plain_adapter(
         model_routing,
         selected_backend,
         CodexAgentKind::TaskCheck,
         false,
-    );
+    )?;
+    *master_adapter = master;
+    *master_report_adapter = master_report;
+    *project_info_adapter = project_info;
+    *docs_attach_adapter = docs_attach;
+    *task_check_adapter = task_check;
     worker_agent_adapters.clear();
-    *active_worker_context_key = None;
+    active_worker_context_keys.clear();
+    Ok(())
 }
 
 fn rebuild_model_routing_with_backend_selection(
@@ -2701,7 +3317,7 @@ fn apply_backend_selection(
     selected: BackendOption,
     selected_backend: &mut BackendKind,
     model_routing: &mut CodexAgentModelRouting,
-    active_worker_context_key: &mut Option<String>,
+    active_worker_context_keys: &mut HashMap<u64, String>,
     worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
     master_adapter: &mut CodexAdapter,
     master_report_adapter: &mut CodexAdapter,
@@ -2715,7 +3331,8 @@ fn apply_backend_selection(
         app.push_agent_message(format!("System: Backend remains {}.", selected.label));
         return;
     }
-    *selected_backend = target;
+    let previous_routing = model_routing.clone();
+    let recorder = model_routing.recorder().cloned();
     if let Err(err) = rebuild_model_routing_with_backend_selection(model_routing, target) {
         app.push_agent_message(format!(
             "System: Backend switched to {} in memory, but model config reload failed: {err}. Using fallback defaults for future adapters.",
@@ -2727,7 +3344,8 @@ fn apply_backend_selection(
         ))
         .unwrap_or_default();
     }
-    rebuild_runtime_adapters(
+    model_routing.set_recorder(recorder);
+    if let Err(err) = rebuild_runtime_adapters(
         model_routing,
         target,
         master_adapter,
@@ -2735,9 +3353,18 @@ fn apply_backend_selection(
         project_info_adapter,
         docs_attach_adapter,
         task_check_adapter,
-        active_worker_context_key,
+        active_worker_context_keys,
         worker_agent_adapters,
-    );
+    ) {
+        *model_routing = previous_routing;
+        app.push_agent_message(format!(
+            "System: Could not switch backend to {}: {err}. Keeping {}.",
+            selected.label,
+            backend_label(was)
+        ));
+        return;
+    }
+    *selected_backend = target;
 
     match persist_backend_selection(target) {
         Ok(config_file) => app.push_agent_message(format!(
@@ -2786,6 +3413,89 @@ fn command_requires_active_session(message: &str) -> bool {
         || parse_silent_master_command(trimmed).is_some()
         || App::is_add_final_audit_command(trimmed)
         || App::is_remove_final_audit_command(trimmed)
+        || App::is_rollback_command(trimmed)
+        || App::is_retry_command(trimmed)
+        || App::is_skip_command(trimmed)
+        || App::is_dry_run_command(trimmed)
+        || is_execution_control_command(trimmed)
+        || parse_review_command(trimmed).is_some()
+}
+
+fn parse_execution_control_command(message: &str) -> Option<ExecutionControlCommand> {
+    if App::is_pause_command(message) {
+        Some(ExecutionControlCommand::Pause)
+    } else if App::is_stop_command(message) {
+        Some(ExecutionControlCommand::Stop)
+    } else if App::is_resume_execution_command(message) {
+        Some(ExecutionControlCommand::Resume)
+    } else {
+        None
+    }
+}
+
+/// Reports whether the session's metadata.json changed since the last call, so the UI tick
+/// only parses it after `api session control-execution` or `api session review` wrote to it.
+fn session_metadata_changed(
+    session_store: &SessionStore,
+    seen: &mut Option<(PathBuf, MetadataFingerprint)>,
+) -> bool {
+    let Some(fingerprint) = session_store.metadata_fingerprint() else {
+        return false;
+    };
+    let current = (session_store.session_dir().to_path_buf(), fingerprint);
+    if seen.as_ref() == Some(&current) {
+        return false;
+    }
+    *seen = Some(current);
+    true
+}
+
+fn external_execution_control_command(
+    app: &App,
+    control: ExecutionControl,
+) -> Option<ExecutionControlCommand> {
+    // `api session control-execution` writes metadata.json; the TUI picks it up on the next tick.
+    if control.stop_requested {
+        Some(ExecutionControlCommand::Stop)
+    } else if control.paused && !app.is_execution_paused() {
+        Some(ExecutionControlCommand::Pause)
+    } else if !control.paused && app.is_execution_paused() {
+        Some(ExecutionControlCommand::Resume)
+    } else {
+        None
+    }
+}
+
+fn is_execution_control_command(message: &str) -> bool {
+    parse_execution_control_command(message).is_some()
+}
+
+/// `Some(None)` is a `/reject` without a task id and feedback, which only prints usage.
+fn parse_review_command(message: &str) -> Option<Option<ReviewDecision>> {
+    if App::is_approve_command(message) {
+        Some(Some(ReviewDecision::Approve {
+            task_id: App::approve_command_target(message).map(ToString::to_string),
+        }))
+    } else if App::is_reject_command(message) {
+        Some(
+            App::reject_command_args(message).map(|(task_ref, feedback)| ReviewDecision::Reject {
+                task_id: Some(task_ref.to_string()),
+                feedback: feedback.to_string(),
+            }),
+        )
+    } else {
+        None
+    }
+}
+
+/// Lets a recording capture the session files the master and other session agents write.
+fn sync_recorder_session_dir(
+    model_routing: &CodexAgentModelRouting,
+    session_store: Option<&SessionStore>,
+) {
+    if let Some(recorder) = model_routing.recorder() {
+        recorder.set_session_dir(session_store.map(SessionStore::session_dir));
+    }
 }
 
 fn initialize_session_for_message_if_needed(
@@ -2863,6 +3573,7 @@ fn conflicts_with_running_execution(message: &str) -> bool {
         || parse_silent_master_command(message).is_some()
         || App::is_add_final_audit_command(message)
         || App::is_remove_final_audit_command(message)
+        || App::is_rollback_command(message)
 }
 
 fn submit_block_reason(
@@ -2881,6 +3592,9 @@ fn submit_block_reason(
     if App::is_toggle_tests_command(message) {
         return None;
     }
+    if is_execution_control_command(message) || parse_review_command(message).is_some() {
+        return None;
+    }
     if project_info_in_flight {
         return Some(SubmitBlockReason::ProjectInfoGathering);
     }
@@ -2931,6 +3645,26 @@ fn should_start_task_check(
     changed_tasks && !task_check_in_flight && !docs_attach_in_flight
 }
 
+fn kill_timed_out_worker_jobs(
+    app: &mut App,
+    worker_agent_adapters: &HashMap<String, CodexAdapter>,
+    active_worker_context_keys: &HashMap<u64, String>,
+    test_runner_adapter: &TestRunnerAdapter,
+) -> Vec<TimedOutJob> {
+    let timed_out_jobs = app.timed_out_jobs(Instant::now());
+    for job in &timed_out_jobs {
+        if let Some(key) = active_worker_context_keys.get(&job.top_task_id)
+            && let Some(adapter) = worker_agent_adapters.get(key)
+        {
+            adapter.kill_running();
+        }
+        test_runner_adapter.kill_task_run(job.top_task_id);
+        app.record_job_timeout(job);
+        app.push_agent_message(format!("System: Task #{}: {}", job.top_task_id, job.reason));
+    }
+    timed_out_jobs
+}
+
 fn drain_post_completion_worker_events(adapter: &CodexAdapter) -> Vec<AgentEvent> {
     const MAX_TAIL_POLLS: usize = 24;
     const MAX_IDLE_POLLS: usize = 8;
@@ -3055,6 +3789,12 @@ fn is_known_slash_command(message: &str) -> bool {
         || App::is_merge_audits_command(trimmed)
         || App::is_add_final_audit_command(trimmed)
         || App::is_remove_final_audit_command(trimmed)
+        || App::is_rollback_command(trimmed)
+        || App::is_retry_command(trimmed)
+        || App::is_skip_command(trimmed)
+        || App::is_dry_run_command(trimmed)
+        || is_execution_control_command(trimmed)
+        || parse_review_command(trimmed).is_some()
 }
 
 #[allow(dead_code)]
@@ -3092,6 +3832,21 @@ enum CliOutputMode {
     Json,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+enum CliExecutionAction {
+    Pause,
+    Stop,
+    Resume,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+enum CliReviewDecision {
+    Approve,
+    Reject,
+}
+
 impl Default for CliOutputMode {
     fn default() -> Self {
         Self::Human
@@ -3106,6 +3861,9 @@ struct LaunchCli {
     output_mode: CliOutputMode,
     #[arg(long = "verbose", default_value_t = false)]
     verbose: bool,
+    /// Records every prompt, event and workspace edit into `<DIR>/fixture.json` for replay.
+    #[arg(long = "record", value_name = "DIR")]
+    record: Option<PathBuf>,
     #[command(subcommand)]
     command: Option<RootCommand>,
 }
@@ -3139,6 +3897,10 @@ enum ApiResourceCommand {
         #[command(subcommand)]
         action: SessionCommand,
     },
+    Prompts {
+        #[command(subcommand)]
+        action: PromptsCommand,
+    },
 }
 
 #[derive(Debug, Clone, Subcommand)]
@@ -3184,6 +3946,51 @@ enum WorkflowCommand {
         #[arg(long, default_value_t = 100)]
         width: u16,
     },
+    Rollback {
+        #[arg(long)]
+        task_id: String,
+        #[arg(long)]
+        session_dir: PathBuf,
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    Retry {
+        #[arg(long)]
+        tasks_file: PathBuf,
+        #[arg(long)]
+        task_id: String,
+        #[arg(long)]
+        guidance: Option<String>,
+    },
+    Skip {
+        #[arg(long)]
+        tasks_file: PathBuf,
+        #[arg(long)]
+        task_id: String,
+    },
+    DryRun {
+        #[arg(long)]
+        tasks_file: PathBuf,
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum PromptsCommand {
+    List {
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+    Render {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+        /// Placeholder value as `NAME=VALUE`; placeholders left unset stay as `{NAME}`.
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        values: Vec<String>,
+    },
 }
 
 #[derive(Debug, Clone, Subcommand)]
@@ -3259,6 +4066,26 @@ enum SessionCommand {
         #[arg(long)]
         session_dir: PathBuf,
     },
+    ControlExecution {
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+        #[arg(long)]
+        session_dir: PathBuf,
+        #[arg(long, value_enum)]
+        action: CliExecutionAction,
+    },
+    Review {
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+        #[arg(long)]
+        session_dir: PathBuf,
+        #[arg(long, value_enum)]
+        decision: CliReviewDecision,
+        #[arg(long)]
+        task_id: Option<String>,
+        #[arg(long)]
+        feedback: Option<String>,
+    },
 }
 
 #[derive(Debug, Serialize)]
@@ -3463,6 +4290,66 @@ impl CliTransportAdapter {
                         )?,
                     }
                 }
+                WorkflowCommand::Rollback {
+                    task_id,
+                    session_dir,
+                    cwd,
+                } => {
+                    let workspace = resolve_cli_cwd(cwd)?;
+                    CliContractInvocation {
+                        request: build_cli_envelope(
+                            api::CapabilityId::WorkflowCheckpointRollback,
+                            api::ApiRequestContract::Workflow(api::WorkflowRequest::RollbackTask {
+                                task_id,
+                                workspace: workspace.to_string_lossy().to_string(),
+                                session_dir: session_dir.to_string_lossy().to_string(),
+                            }),
+                            self.id(),
+                        ),
+                    }
+                }
+                WorkflowCommand::Retry {
+                    tasks_file,
+                    task_id,
+                    guidance,
+                } => CliContractInvocation {
+                    request: build_cli_envelope(
+                        api::CapabilityId::WorkflowTaskControl,
+                        api::ApiRequestContract::Workflow(api::WorkflowRequest::RetryTask {
+                            tasks_file: tasks_file.to_string_lossy().to_string(),
+                            task_id,
+                            guidance,
+                        }),
+                        self.id(),
+                    ),
+                },
+                WorkflowCommand::Skip {
+                    tasks_file,
+                    task_id,
+                } => CliContractInvocation {
+                    request: build_cli_envelope(
+                        api::CapabilityId::WorkflowTaskControl,
+                        api::ApiRequestContract::Workflow(api::WorkflowRequest::SkipTask {
+                            tasks_file: tasks_file.to_string_lossy().to_string(),
+                            task_id,
+                        }),
+                        self.id(),
+                    ),
+                },
+                WorkflowCommand::DryRun {
+                    tasks_file,
+                    output_dir,
+                } => CliContractInvocation {
+                    request: build_cli_envelope(
+                        api::CapabilityId::WorkflowDryRun,
+                        api::ApiRequestContract::Workflow(api::WorkflowRequest::DryRun {
+                            tasks_file: tasks_file.to_string_lossy().to_string(),
+                            output_dir: output_dir
+                                .map(|output_dir| output_dir.to_string_lossy().to_string()),
+                        }),
+                        self.id(),
+                    ),
+                },
             },
             ApiResourceCommand::Session { action } => {
                 let payload = match action {
@@ -3613,6 +4500,55 @@ impl CliTransportAdapter {
                             )?,
                         });
                     }
+                    SessionCommand::ControlExecution {
+                        cwd,
+                        session_dir,
+                        action,
+                    } => {
+                        let action = match action {
+                            CliExecutionAction::Pause => api::ExecutionControlActionContract::Pause,
+                            CliExecutionAction::Stop => api::ExecutionControlActionContract::Stop,
+                            CliExecutionAction::Resume => {
+                                api::ExecutionControlActionContract::Resume
+                            }
+                        };
+                        return Ok(CliContractInvocation {
+                            request: build_cli_envelope_with_actor(
+                                api::CapabilityId::SessionExecutionControl,
+                                api::ApiRequestContract::Session(
+                                    api::SessionRequest::ControlExecution { control: action },
+                                ),
+                                self.id(),
+                                json!(resolve_session_lookup_context(cwd, session_dir)?),
+                            )?,
+                        });
+                    }
+                    SessionCommand::Review {
+                        cwd,
+                        session_dir,
+                        decision,
+                        task_id,
+                        feedback,
+                    } => {
+                        let decision = match decision {
+                            CliReviewDecision::Approve => api::ReviewDecisionContract::Approve,
+                            CliReviewDecision::Reject => api::ReviewDecisionContract::Reject,
+                        };
+                        return Ok(CliContractInvocation {
+                            request: build_cli_envelope_with_actor(
+                                api::CapabilityId::SessionReviewDecision,
+                                api::ApiRequestContract::Session(
+                                    api::SessionRequest::SubmitReview {
+                                        decision,
+                                        task_id: task_id.clone(),
+                                        feedback: feedback.clone(),
+                                    },
+                                ),
+                                self.id(),
+                                json!(resolve_session_lookup_context(cwd, session_dir)?),
+                            )?,
+                        });
+                    }
                 };
                 CliContractInvocation {
                     request: build_cli_envelope(
@@ -3629,6 +4565,27 @@ impl CliTransportAdapter {
                     ),
                 }
             }
+            ApiResourceCommand::Prompts { action } => {
+                let payload = match action {
+                    PromptsCommand::List { cwd } => api::SubagentRequest::ListPromptTemplates {
+                        cwd: resolve_cli_cwd(cwd)?.to_string_lossy().to_string(),
+                    },
+                    PromptsCommand::Render { name, cwd, values } => {
+                        api::SubagentRequest::RenderPromptTemplate {
+                            cwd: resolve_cli_cwd(cwd)?.to_string_lossy().to_string(),
+                            name,
+                            values: parse_prompt_placeholder_values(&values)?,
+                        }
+                    }
+                };
+                CliContractInvocation {
+                    request: build_cli_envelope(
+                        api::CapabilityId::SubagentPromptTemplates,
+                        api::ApiRequestContract::Subagent(payload),
+                        self.id(),
+                    ),
+                }
+            }
             ApiResourceCommand::Capability { .. } => {
                 return Err(CliCommandError::new(
                     api::ApiErrorCode::Unsupported,
@@ -3664,6 +4621,32 @@ impl CliTransportAdapter {
                     data: json!({ "prompt": text }),
                 })
             }
+            api::ApiResponseContract::Subagent(api::SubagentResponse::PromptTemplates {
+                search_dirs,
+                templates,
+            }) => Ok(CliCommandOutput {
+                summary: format!(
+                    "Listed {} prompt templates ({} overridden)",
+                    templates.len(),
+                    templates
+                        .iter()
+                        .filter(|template| template.source.is_some())
+                        .count()
+                ),
+                data: json!({ "search_dirs": search_dirs, "templates": templates }),
+            }),
+            api::ApiResponseContract::Subagent(api::SubagentResponse::RenderedPromptTemplate {
+                template,
+                text,
+                unfilled,
+            }) => Ok(CliCommandOutput {
+                summary: format!(
+                    "Rendered prompt template `{}` from {}",
+                    template.name,
+                    template.source.as_deref().unwrap_or("the built-in text")
+                ),
+                data: json!({ "template": template, "prompt": text, "unfilled": unfilled }),
+            }),
             api::ApiResponseContract::Workflow(api::WorkflowResponse::PlannerTasks { tasks }) => {
                 Ok(CliCommandOutput {
                     summary: format!("Validated {} planner tasks", tasks.len()),
@@ -3680,6 +4663,33 @@ impl CliTransportAdapter {
                 summary: "Rendered right-pane workflow block".to_string(),
                 data: json!({ "lines": lines, "toggles": toggles }),
             }),
+            api::ApiResponseContract::Workflow(api::WorkflowResponse::RolledBack {
+                checkpoint_ref,
+                backup_ref,
+            }) => Ok(CliCommandOutput {
+                summary: format!("Rolled workspace back to {checkpoint_ref}"),
+                data: json!({ "checkpoint_ref": checkpoint_ref, "backup_ref": backup_ref }),
+            }),
+            api::ApiResponseContract::Workflow(api::WorkflowResponse::TasksEdited {
+                messages,
+                tasks,
+            }) => Ok(CliCommandOutput {
+                summary: messages
+                    .first()
+                    .map(|message| message.trim_start_matches("System: ").to_string())
+                    .unwrap_or_else(|| "Updated planner tasks".to_string()),
+                data: json!({ "messages": messages, "tasks": tasks }),
+            }),
+            api::ApiResponseContract::Workflow(api::WorkflowResponse::DryRun {
+                prompts,
+                output_dir,
+            }) => Ok(CliCommandOutput {
+                summary: format!(
+                    "Rendered {} worker prompt(s) without a backend",
+                    prompts.len()
+                ),
+                data: json!({ "prompts": prompts, "output_dir": output_dir }),
+            }),
             api::ApiResponseContract::Session(api::SessionResponse::Initialized { session }) => {
                 Ok(CliCommandOutput {
                     summary: "Initialized session".to_string(),
@@ -3735,6 +4745,30 @@ impl CliTransportAdapter {
                     data: json!({ "meta": meta }),
                 })
             }
+            api::ApiResponseContract::Session(api::SessionResponse::ExecutionControl {
+                paused,
+                stop_requested,
+            }) => Ok(CliCommandOutput {
+                summary: if stop_requested {
+                    "Requested stop of running worker jobs".to_string()
+                } else if paused {
+                    "Requested execution pause".to_string()
+                } else {
+                    "Requested execution resume".to_string()
+                },
+                data: json!({ "paused": paused, "stop_requested": stop_requested }),
+            }),
+            api::ApiResponseContract::Session(api::SessionResponse::ReviewDecision {
+                decision,
+                task_id,
+                feedback,
+            }) => Ok(CliCommandOutput {
+                summary: match decision {
+                    api::ReviewDecisionContract::Approve => "Submitted review approval".to_string(),
+                    api::ReviewDecisionContract::Reject => "Submitted review rejection".to_string(),
+                },
+                data: json!({ "decision": decision, "task_id": task_id, "feedback": feedback }),
+            }),
             api::ApiResponseContract::Session(api::SessionResponse::Ack) => Ok(CliCommandOutput {
                 summary: "Completed session operation".to_string(),
                 data: json!({}),
@@ -3805,6 +4839,161 @@ fn read_cli_tasks_contract(
     Ok(tasks.into_iter().map(file_task_to_contract_task).collect())
 }
 
+fn edit_cli_tasks_file(
+    tasks_file: &Path,
+    task_ref: &str,
+    edit: impl FnOnce(&mut workflow::Workflow, u64) -> Result<Vec<String>, String>,
+) -> Result<api::WorkflowResponse, CliCommandError> {
+    let file_tasks = read_cli_tasks_contract(tasks_file)?
+        .into_iter()
+        .map(contract_task_to_file_task)
+        .collect();
+    let mut workflow = workflow::Workflow::default();
+    workflow
+        .sync_planner_tasks_from_file(file_tasks)
+        .map_err(|err| CliCommandError::new(api::ApiErrorCode::ValidationFailed, err))?;
+    let task_id = workflow.resolve_task_id(task_ref).ok_or_else(|| {
+        CliCommandError::new(
+            api::ApiErrorCode::NotFound,
+            format!("No task matches `{task_ref}`"),
+        )
+        .with_details(json!({ "tasks_file": tasks_file }))
+    })?;
+    let messages = edit(&mut workflow, task_id)
+        .map_err(|err| CliCommandError::new(api::ApiErrorCode::Conflict, err))?;
+    let tasks = workflow.planner_tasks_for_file();
+    let text = serde_json::to_string_pretty(&tasks).map_err(|err| {
+        CliCommandError::new(
+            api::ApiErrorCode::Internal,
+            format!("Failed to serialize tasks: {err}"),
+        )
+    })?;
+    std::fs::write(tasks_file, text).map_err(|err| {
+        CliCommandError::new(
+            api::ApiErrorCode::IoFailure,
+            format!("Failed to write tasks file: {err}"),
+        )
+        .with_details(json!({ "tasks_file": tasks_file }))
+    })?;
+    Ok(api::WorkflowResponse::TasksEdited {
+        messages,
+        tasks: tasks.into_iter().map(file_task_to_contract_task).collect(),
+    })
+}
+
+fn dry_run_prompt_contracts(
+    prompts: Vec<workflow::DryRunPrompt>,
+    model_routing: &CodexAgentModelRouting,
+) -> Vec<api::DryRunPromptContract> {
+    prompts
+        .into_iter()
+        .enumerate()
+        .map(|(index, prompt)| {
+            let profile = prompt
+                .model_profile
+                .as_deref()
+                .and_then(|label| model_routing.named_profile(label))
+                .unwrap_or_else(|| {
+                    model_routing.profile_for(services::worker_role_agent_kind(prompt.role))
+                });
+            api::DryRunPromptContract {
+                sequence: index + 1,
+                role: worker_role_to_contract(prompt.role),
+                task_id: prompt.task_id,
+                top_task_id: prompt.top_task_id,
+                pass: prompt.pass,
+                model: profile.model,
+                thinking_effort: profile.thinking_effort,
+                prompt: prompt.prompt,
+            }
+        })
+        .collect()
+}
+
+/// Writes one markdown file per prompt, replacing any earlier dry run in `dir`.
+/// Writes one `NNN-role-task.md` file per prompt. The directory may be user-supplied, so only
+/// prompt files from an earlier dry run are removed; anything else in it is left alone.
+fn write_dry_run_prompt_files(dir: &Path, prompts: &[api::DryRunPromptContract]) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let is_prompt_file = entry
+            .file_name()
+            .to_str()
+            .is_some_and(is_dry_run_prompt_file_name);
+        if is_prompt_file && entry.file_type()?.is_file() {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    for prompt in prompts {
+        let role = worker_role_file_label(&prompt.role);
+        let task_id = prompt
+            .task_id
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                    ch
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let header = format!(
+            "<!-- role: {role}; task: {}; top task: #{}; pass: {}; model: {}{} -->",
+            prompt.task_id,
+            prompt.top_task_id,
+            prompt.pass,
+            prompt.model,
+            prompt
+                .thinking_effort
+                .as_deref()
+                .map(|effort| format!(" ({effort})"))
+                .unwrap_or_default()
+        );
+        std::fs::write(
+            dir.join(format!("{:03}-{role}-{task_id}.md", prompt.sequence)),
+            format!("{header}\n\n{}\n", prompt.prompt),
+        )?;
+    }
+    Ok(())
+}
+
+fn is_dry_run_prompt_file_name(name: &str) -> bool {
+    let Some((sequence, rest)) = name.split_once('-') else {
+        return false;
+    };
+    sequence.len() >= 3
+        && sequence.chars().all(|ch| ch.is_ascii_digit())
+        && rest.contains('-')
+        && rest.ends_with(".md")
+}
+
+fn worker_role_to_contract(role: WorkerRole) -> api::WorkerRoleContract {
+    match role {
+        WorkerRole::Implementor => api::WorkerRoleContract::Implementor,
+        WorkerRole::Auditor => api::WorkerRoleContract::Auditor,
+        WorkerRole::TestWriter => api::WorkerRoleContract::TestWriter,
+        WorkerRole::TestRunner => api::WorkerRoleContract::TestRunner,
+        WorkerRole::LintRunner => api::WorkerRoleContract::LintRunner,
+        WorkerRole::CoverageRunner => api::WorkerRoleContract::CoverageRunner,
+        WorkerRole::FinalAudit => api::WorkerRoleContract::FinalAudit,
+        WorkerRole::Custom => api::WorkerRoleContract::Custom,
+    }
+}
+
+fn worker_role_file_label(role: &api::WorkerRoleContract) -> &'static str {
+    match role {
+        api::WorkerRoleContract::Implementor => "implementor",
+        api::WorkerRoleContract::Auditor => "auditor",
+        api::WorkerRoleContract::TestWriter => "test-writer",
+        api::WorkerRoleContract::TestRunner => "test-runner",
+        api::WorkerRoleContract::LintRunner => "lint-runner",
+        api::WorkerRoleContract::CoverageRunner => "coverage-runner",
+        api::WorkerRoleContract::FinalAudit => "final-audit",
+        api::WorkerRoleContract::Custom => "custom-role",
+    }
+}
+
 fn read_json_from_file<T>(path: &Path, label: &str) -> Result<T, CliCommandError>
 where
     T: DeserializeOwned,
@@ -3886,7 +5075,10 @@ fn execute_core_api_contract(
         api::ApiRequestContract::Session(session_request) => api::ApiResponseContract::Session(
             execute_core_session_request(session_request, metadata)?,
         ),
-        api::ApiRequestContract::Events(_) | api::ApiRequestContract::Subagent(_) => {
+        api::ApiRequestContract::Subagent(subagent_request) => {
+            api::ApiResponseContract::Subagent(execute_core_subagent_request(subagent_request)?)
+        }
+        api::ApiRequestContract::Events(_) => {
             return Err(CliCommandError::new(
                 api::ApiErrorCode::Unsupported,
                 "CLI transport does not expose this API contract domain",
@@ -3900,6 +5092,117 @@ fn execute_core_api_contract(
     })
 }
 
+fn execute_core_subagent_request(
+    request: api::SubagentRequest,
+) -> Result<api::SubagentResponse, CliCommandError> {
+    match request {
+        api::SubagentRequest::ListPromptTemplates { cwd } => {
+            let dirs = PromptTemplateDirs::discover(Path::new(&cwd));
+            Ok(api::SubagentResponse::PromptTemplates {
+                search_dirs: dirs
+                    .dirs()
+                    .iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect(),
+                templates: PromptTemplate::ALL
+                    .into_iter()
+                    .map(|template| prompt_template_contract(&dirs.resolve(template)))
+                    .collect(),
+            })
+        }
+        api::SubagentRequest::RenderPromptTemplate { cwd, name, values } => {
+            let template = PromptTemplate::from_name(&name).ok_or_else(|| {
+                CliCommandError::new(api::ApiErrorCode::NotFound, "Prompt template not found")
+                    .with_details(json!({
+                        "name": name,
+                        "available": PromptTemplate::ALL.map(PromptTemplate::name),
+                    }))
+            })?;
+            if let Some(unknown) = values.keys().find(|key| {
+                !template
+                    .placeholders()
+                    .iter()
+                    .any(|(placeholder, _)| placeholder == key)
+            }) {
+                return Err(CliCommandError::new(
+                    api::ApiErrorCode::InvalidRequest,
+                    format!(
+                        "Prompt template `{}` has no placeholder `{unknown}`",
+                        template.name()
+                    ),
+                )
+                .with_details(json!({
+                    "placeholders": template
+                        .placeholders()
+                        .iter()
+                        .map(|(placeholder, _)| *placeholder)
+                        .collect::<Vec<_>>(),
+                })));
+            }
+            let resolved = PromptTemplateDirs::discover(Path::new(&cwd)).resolve(template);
+            let pairs = values
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect::<Vec<_>>();
+            Ok(api::SubagentResponse::RenderedPromptTemplate {
+                text: resolved.render(&pairs),
+                unfilled: resolved
+                    .used_placeholders()
+                    .into_iter()
+                    .filter(|placeholder| !values.contains_key(*placeholder))
+                    .map(ToString::to_string)
+                    .collect(),
+                template: prompt_template_contract(&resolved),
+            })
+        }
+        _ => Err(CliCommandError::new(
+            api::ApiErrorCode::Unsupported,
+            "Subagent request is not available in CLI transport mode",
+        )),
+    }
+}
+
+fn prompt_template_contract(resolved: &ResolvedPromptTemplate) -> api::PromptTemplateContract {
+    let used = resolved.used_placeholders();
+    api::PromptTemplateContract {
+        name: resolved.template.name().to_string(),
+        source: resolved
+            .source
+            .as_ref()
+            .map(|path| path.display().to_string()),
+        placeholders: resolved
+            .template
+            .placeholders()
+            .iter()
+            .map(|(name, description)| api::PromptPlaceholderContract {
+                name: name.to_string(),
+                description: description.to_string(),
+                used: used.contains(name),
+            })
+            .collect(),
+    }
+}
+
+fn parse_prompt_placeholder_values(
+    values: &[String],
+) -> Result<BTreeMap<String, String>, CliCommandError> {
+    values
+        .iter()
+        .map(|value| {
+            value
+                .split_once('=')
+                .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+                .filter(|(name, _)| !name.is_empty())
+                .ok_or_else(|| {
+                    CliCommandError::new(
+                        api::ApiErrorCode::InvalidRequest,
+                        format!("Placeholder values must look like NAME=VALUE, got `{value}`"),
+                    )
+                })
+        })
+        .collect()
+}
+
 fn execute_core_app_request(request: api::AppRequest) -> Result<api::AppResponse, CliCommandError> {
     match request {
         api::AppRequest::PrepareMasterPrompt {
@@ -3991,6 +5294,98 @@ fn execute_core_workflow_request(
                     .collect(),
             })
         }
+        api::WorkflowRequest::RollbackTask {
+            task_id,
+            workspace,
+            session_dir,
+        } => {
+            let session_id = session_id_for_dir(Path::new(&session_dir));
+            let outcome = checkpoint::rollback_task(Path::new(&workspace), &session_id, &task_id)
+                .map_err(|err| {
+                CliCommandError::new(
+                    if err.kind() == io::ErrorKind::NotFound {
+                        api::ApiErrorCode::NotFound
+                    } else {
+                        api::ApiErrorCode::ExternalFailure
+                    },
+                    format!("Failed to roll back task: {err}"),
+                )
+                .with_details(json!({ "task_id": task_id, "workspace": workspace }))
+            })?;
+            Ok(api::WorkflowResponse::RolledBack {
+                checkpoint_ref: outcome.checkpoint_ref,
+                backup_ref: outcome.backup_ref,
+            })
+        }
+        api::WorkflowRequest::RetryTask {
+            tasks_file,
+            task_id,
+            guidance,
+        } => edit_cli_tasks_file(Path::new(&tasks_file), &task_id, |workflow, id| {
+            let mut messages = vec![workflow.retry_task(id, guidance.as_deref())?];
+            // tasks.json sits in the session dir, which scopes the task's checkpoint refs.
+            if workflow.resolve_top_task_id(&task_id) == Some(id)
+                && let (Some(session_dir), Some(task_key)) =
+                    (Path::new(&tasks_file).parent(), workflow.top_task_key(id))
+                && let Ok(repo_root) =
+                    std::env::current_dir().and_then(|cwd| worktree::repo_root(&cwd))
+            {
+                let session_id = session_id_for_dir(session_dir);
+                match checkpoint::clear_checkpoints(&repo_root, &session_id, &task_key) {
+                    Ok(0) => {}
+                    Ok(cleared) => messages.push(format!(
+                        "System: Cleared {cleared} git checkpoint(s) from the task's earlier run."
+                    )),
+                    Err(err) => messages.push(format!(
+                        "System: Could not clear old git checkpoints for the task: {err}"
+                    )),
+                }
+            }
+            Ok(messages)
+        }),
+        api::WorkflowRequest::SkipTask {
+            tasks_file,
+            task_id,
+        } => edit_cli_tasks_file(Path::new(&tasks_file), &task_id, |workflow, id| {
+            workflow.skip_task(id)
+        }),
+        api::WorkflowRequest::DryRun {
+            tasks_file,
+            output_dir,
+        } => {
+            let file_tasks = read_cli_tasks_contract(Path::new(&tasks_file))?
+                .into_iter()
+                .map(contract_task_to_file_task)
+                .collect();
+            let mut workflow = workflow::Workflow::default();
+            workflow
+                .sync_planner_tasks_from_file(file_tasks)
+                .map_err(|err| CliCommandError::new(api::ApiErrorCode::ValidationFailed, err))?;
+            let custom_roles = load_global_workflow_config()
+                .unwrap_or_default()
+                .custom_roles()
+                .map_err(|err| CliCommandError::new(api::ApiErrorCode::ValidationFailed, err))?;
+            workflow.set_custom_roles(custom_roles);
+            let prompts = workflow
+                .dry_run_prompts()
+                .map_err(|err| CliCommandError::new(api::ApiErrorCode::Conflict, err))?;
+            let model_routing =
+                CodexAgentModelRouting::load_from_metaagent_config().unwrap_or_default();
+            let prompts = dry_run_prompt_contracts(prompts, &model_routing);
+            if let Some(output_dir) = output_dir.as_deref() {
+                write_dry_run_prompt_files(Path::new(output_dir), &prompts).map_err(|err| {
+                    CliCommandError::new(
+                        api::ApiErrorCode::IoFailure,
+                        format!("Failed to write dry-run prompts: {err}"),
+                    )
+                    .with_details(json!({ "output_dir": output_dir }))
+                })?;
+            }
+            Ok(api::WorkflowResponse::DryRun {
+                prompts,
+                output_dir,
+            })
+        }
         _ => Err(CliCommandError::new(
             api::ApiErrorCode::Unsupported,
             "Workflow request is not available in CLI transport mode",
@@ -4181,6 +5576,71 @@ fn execute_core_session_request(
                 meta: file_session_meta_to_contract(meta),
             })
         }
+        api::SessionRequest::ControlExecution { control } => {
+            let session = open_actor_session()?;
+            let control = match control {
+                api::ExecutionControlActionContract::Pause => ExecutionControl {
+                    paused: true,
+                    stop_requested: false,
+                },
+                api::ExecutionControlActionContract::Stop => ExecutionControl {
+                    paused: true,
+                    stop_requested: true,
+                },
+                api::ExecutionControlActionContract::Resume => ExecutionControl::default(),
+            };
+            session.write_execution_control(control).map_err(|err| {
+                CliCommandError::new(
+                    api::ApiErrorCode::IoFailure,
+                    format!("Failed to write execution control: {err}"),
+                )
+            })?;
+            Ok(api::SessionResponse::ExecutionControl {
+                paused: control.paused,
+                stop_requested: control.stop_requested,
+            })
+        }
+        api::SessionRequest::SubmitReview {
+            decision,
+            task_id,
+            feedback,
+        } => {
+            let task_id = task_id
+                .map(|task_id| task_id.trim().to_string())
+                .filter(|task_id| !task_id.is_empty());
+            let feedback = feedback
+                .map(|feedback| feedback.trim().to_string())
+                .filter(|feedback| !feedback.is_empty());
+            let review_decision = match (decision, feedback.clone()) {
+                (api::ReviewDecisionContract::Approve, _) => ReviewDecision::Approve {
+                    task_id: task_id.clone(),
+                },
+                (api::ReviewDecisionContract::Reject, Some(feedback)) => ReviewDecision::Reject {
+                    task_id: task_id.clone(),
+                    feedback,
+                },
+                (api::ReviewDecisionContract::Reject, None) => {
+                    return Err(CliCommandError::new(
+                        api::ApiErrorCode::InvalidRequest,
+                        "Rejecting a review requires non-empty feedback".to_string(),
+                    ));
+                }
+            };
+            let session = open_actor_session()?;
+            session
+                .write_review_decision(review_decision)
+                .map_err(|err| {
+                    CliCommandError::new(
+                        api::ApiErrorCode::IoFailure,
+                        format!("Failed to write review decision: {err}"),
+                    )
+                })?;
+            Ok(api::SessionResponse::ReviewDecision {
+                decision,
+                task_id,
+                feedback,
+            })
+        }
     }
 }
 
@@ -4198,6 +5658,12 @@ fn file_task_to_contract_task(task: PlannerTaskFileEntry) -> api::PlannerTaskEnt
         status: file_status_to_contract_status(task.status),
         parent_id: task.parent_id,
         order: task.order,
+        depends_on: task.depends_on,
+        max_retries: task.max_retries,
+        command: task.command,
+        working_dir: task.working_dir,
+        review_quorum: task.review_quorum.map(file_quorum_to_contract_quorum),
+        role: task.role,
     }
 }
 
@@ -4215,6 +5681,32 @@ fn contract_task_to_file_task(task: api::PlannerTaskEntryContract) -> PlannerTas
         status: contract_status_to_file_status(task.status),
         parent_id: task.parent_id,
         order: task.order,
+        depends_on: task.depends_on,
+        max_retries: task.max_retries,
+        command: task.command,
+        working_dir: task.working_dir,
+        review_quorum: task.review_quorum.map(contract_quorum_to_file_quorum),
+        role: task.role,
+    }
+}
+
+fn file_quorum_to_contract_quorum(
+    quorum: session_store::ReviewQuorumFileEntry,
+) -> api::ReviewQuorumContract {
+    api::ReviewQuorumContract {
+        reviewers: quorum.reviewers,
+        required: quorum.required,
+        profiles: quorum.profiles,
+    }
+}
+
+fn contract_quorum_to_file_quorum(
+    quorum: api::ReviewQuorumContract,
+) -> session_store::ReviewQuorumFileEntry {
+    session_store::ReviewQuorumFileEntry {
+        reviewers: quorum.reviewers,
+        required: quorum.required,
+        profiles: quorum.profiles,
     }
 }
 
@@ -4244,6 +5736,8 @@ fn file_task_fail_to_contract(
     let kind = match entry.kind.as_str() {
         "audit" => api::WorkflowFailureKindContract::Audit,
         "test" => api::WorkflowFailureKindContract::Test,
+        "merge" => api::WorkflowFailureKindContract::Merge,
+        "timeout" => api::WorkflowFailureKindContract::Timeout,
         other => {
             return Err(CliCommandError::new(
                 api::ApiErrorCode::ValidationFailed,
@@ -4258,6 +5752,8 @@ fn file_task_fail_to_contract(
         attempts: entry.attempts,
         reason: entry.reason,
         action_taken: entry.action_taken,
+        retry_budget: entry.retry_budget,
+        checkpoint_refs: entry.checkpoint_refs,
     })
 }
 
@@ -4269,12 +5765,16 @@ fn contract_task_fail_to_file(
         kind: match entry.kind {
             api::WorkflowFailureKindContract::Audit => "audit".to_string(),
             api::WorkflowFailureKindContract::Test => "test".to_string(),
+            api::WorkflowFailureKindContract::Merge => "merge".to_string(),
+            api::WorkflowFailureKindContract::Timeout => "timeout".to_string(),
         },
         top_task_id: entry.top_task_id,
         top_task_title: entry.top_task_title,
         attempts: entry.attempts,
         reason: entry.reason,
         action_taken: entry.action_taken,
+        retry_budget: entry.retry_budget,
+        checkpoint_refs: entry.checkpoint_refs,
         created_at_epoch_secs,
     }
 }
@@ -4285,6 +5785,10 @@ fn file_session_meta_to_contract(meta: session_store::SessionMetaFile) -> api::S
         created_at: meta.created_at,
         stack_description: meta.stack_description,
         test_command: meta.test_command,
+        lint_command: meta.lint_command,
+        test_report_path: meta.test_report_path,
+        coverage_command: meta.coverage_command,
+        coverage_threshold: meta.coverage_threshold,
     }
 }
 
@@ -4302,7 +5806,10 @@ fn file_kind_to_contract_kind(kind: PlannerTaskKindFile) -> api::PlannerTaskKind
         PlannerTaskKindFile::Auditor => api::PlannerTaskKindContract::Auditor,
         PlannerTaskKindFile::TestWriter => api::PlannerTaskKindContract::TestWriter,
         PlannerTaskKindFile::TestRunner => api::PlannerTaskKindContract::TestRunner,
+        PlannerTaskKindFile::LintRunner => api::PlannerTaskKindContract::LintRunner,
         PlannerTaskKindFile::FinalAudit => api::PlannerTaskKindContract::FinalAudit,
+        PlannerTaskKindFile::Review => api::PlannerTaskKindContract::Review,
+        PlannerTaskKindFile::Role => api::PlannerTaskKindContract::Role,
     }
 }
 
@@ -4313,7 +5820,10 @@ fn contract_kind_to_file_kind(kind: api::PlannerTaskKindContract) -> PlannerTask
         api::PlannerTaskKindContract::Auditor => PlannerTaskKindFile::Auditor,
         api::PlannerTaskKindContract::TestWriter => PlannerTaskKindFile::TestWriter,
         api::PlannerTaskKindContract::TestRunner => PlannerTaskKindFile::TestRunner,
+        api::PlannerTaskKindContract::LintRunner => PlannerTaskKindFile::LintRunner,
         api::PlannerTaskKindContract::FinalAudit => PlannerTaskKindFile::FinalAudit,
+        api::PlannerTaskKindContract::Review => PlannerTaskKindFile::Review,
+        api::PlannerTaskKindContract::Role => PlannerTaskKindFile::Role,
     }
 }
 
@@ -4323,6 +5833,7 @@ fn file_status_to_contract_status(status: PlannerTaskStatusFile) -> api::Planner
         PlannerTaskStatusFile::InProgress => api::PlannerTaskStatusContract::InProgress,
         PlannerTaskStatusFile::NeedsChanges => api::PlannerTaskStatusContract::NeedsChanges,
         PlannerTaskStatusFile::Done => api::PlannerTaskStatusContract::Done,
+        PlannerTaskStatusFile::Skipped => api::PlannerTaskStatusContract::Skipped,
     }
 }
 
@@ -4332,6 +5843,7 @@ fn contract_status_to_file_status(status: api::PlannerTaskStatusContract) -> Pla
         api::PlannerTaskStatusContract::InProgress => PlannerTaskStatusFile::InProgress,
         api::PlannerTaskStatusContract::NeedsChanges => PlannerTaskStatusFile::NeedsChanges,
         api::PlannerTaskStatusContract::Done => PlannerTaskStatusFile::Done,
+        api::PlannerTaskStatusContract::Skipped => PlannerTaskStatusFile::Skipped,
     }
 }
 
@@ -4412,6 +5924,14 @@ fn render_cli_payload_human(payload: &Value, verbose: bool) {
         }
         return;
     }
+    if is_prompt_template_list(payload) {
+        render_prompt_template_list_human(payload);
+        return;
+    }
+    if is_rendered_prompt_template(payload) {
+        render_prompt_template_human(payload);
+        return;
+    }
 
     if verbose {
         let text = match serde_json::to_string_pretty(payload) {
@@ -4521,6 +6041,57 @@ fn render_value_short(value: &Value) -> String {
     }
 }
 
+fn is_prompt_template_list(payload: &Value) -> bool {
+    payload.get("search_dirs").is_some_and(Value::is_array)
+        && payload.get("templates").is_some_and(Value::is_array)
+}
+
+fn render_prompt_template_list_human(payload: &Value) {
+    println!("Override directories (first match wins):");
+    for dir in payload["search_dirs"].as_array().into_iter().flatten() {
+        println!("  {}", dir.as_str().unwrap_or("<missing-dir>"));
+    }
+    for template in payload["templates"].as_array().into_iter().flatten() {
+        println!(
+            "{}: {}",
+            template["name"].as_str().unwrap_or("<missing-name>"),
+            template["source"].as_str().unwrap_or("built-in")
+        );
+        for placeholder in template["placeholders"].as_array().into_iter().flatten() {
+            println!(
+                "  {{{}}}{} - {}",
+                placeholder["name"].as_str().unwrap_or("<missing-name>"),
+                if placeholder["used"].as_bool().unwrap_or(true) {
+                    ""
+                } else {
+                    " (unused)"
+                },
+                placeholder["description"].as_str().unwrap_or_default()
+            );
+        }
+    }
+}
+
+fn is_rendered_prompt_template(payload: &Value) -> bool {
+    payload.get("template").is_some_and(Value::is_object)
+        && payload.get("prompt").is_some_and(Value::is_string)
+        && payload.get("unfilled").is_some_and(Value::is_array)
+}
+
+fn render_prompt_template_human(payload: &Value) {
+    let unfilled = payload["unfilled"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect::<Vec<_>>();
+    if !unfilled.is_empty() {
+        println!("Unfilled placeholders: {}", unfilled.join(", "));
+    }
+    println!();
+    println!("{}", payload["prompt"].as_str().unwrap_or_default());
+}
+
 fn is_capability_definition_list(payload: &Value) -> bool {
     let Some(values) = payload.as_array() else {
         return false;
@@ -4632,6 +6203,7 @@ struct LaunchOptions {
     send_file: Option<PathBuf>,
     output_mode: CliOutputMode,
     verbose: bool,
+    record: Option<PathBuf>,
     command: Option<RootCommand>,
 }
 
@@ -4673,6 +6245,7 @@ where
         send_file: parsed.send_file,
         output_mode: parsed.output_mode,
         verbose: parsed.verbose,
+        record: parsed.record,
         command: parsed.command,
     })
 }
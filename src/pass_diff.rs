@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{DefaultHasher, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::checkpoint::snapshot_tree;
+use crate::worktree::{run_git, sanitize_task_key};
+
+const PASS_DIFFS_DIR_NAME: &str = "diffs";
+/// Directories never hashed into a manifest; they are either VCS metadata or build output.
+const MANIFEST_SKIPPED_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceSnapshot {
+    GitTree(String),
+    Manifest(BTreeMap<String, u64>),
+}
+
+/// The workspace state captured right before a worker pass started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassBaseline {
+    pub workspace: PathBuf,
+    pub label: String,
+    pub snapshot: WorkspaceSnapshot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassDiffFile {
+    pub status: char,
+    pub path: String,
+}
+
+/// What a worker pass actually changed on disk, independent of what the worker reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PassDiff {
+    pub files: Vec<PassDiffFile>,
+    /// Unified diff of the pass; empty when the workspace is not a git repository.
+    pub patch: String,
+    pub diff_file: Option<PathBuf>,
+}
+
+/// Line numbers a task changed, per path, in each file's current numbering.
+pub type ChangedLines = BTreeMap<String, BTreeSet<usize>>;
+
+/// One `@@` hunk of a unified diff.
+#[derive(Debug, Default)]
+struct DiffHunk {
+    old_start: usize,
+    old_len: usize,
+    new_start: usize,
+    new_len: usize,
+    /// Where each old line inside the hunk ended up; `None` for removed lines.
+    old_to_new: Vec<(usize, Option<usize>)>,
+    added: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+struct FilePatch {
+    path: String,
+    deleted: bool,
+    hunks: Vec<DiffHunk>,
+}
+
+/// Snapshots the workspace as a git tree, or as a path-to-content-hash manifest when the
+/// directory is not inside a git repository.
+pub fn capture_snapshot(workspace: &Path) -> io::Result<WorkspaceSnapshot> {
+    match snapshot_tree(workspace) {
+        Ok(tree) => Ok(WorkspaceSnapshot::GitTree(tree.trim().to_string())),
+        Err(_) => file_manifest(workspace).map(WorkspaceSnapshot::Manifest),
+    }
+}
+
+/// Snapshots the workspace as a content-hash manifest even inside a git repository. Unlike a
+/// git tree snapshot it never touches the shared checkpoint index, so it is safe to take from
+/// adapter threads.
+pub fn capture_manifest_snapshot(workspace: &Path) -> io::Result<WorkspaceSnapshot> {
+    file_manifest(workspace).map(WorkspaceSnapshot::Manifest)
+}
+
+pub fn diff_since(workspace: &Path, before: &WorkspaceSnapshot) -> io::Result<PassDiff> {
+    match before {
+        WorkspaceSnapshot::GitTree(before_tree) => {
+            let after_tree = snapshot_tree(workspace)?;
+            let after_tree = after_tree.trim();
+            let name_status = run_git(
+                workspace,
+                &[
+                    "diff",
+                    "--name-status",
+                    "--no-renames",
+                    before_tree,
+                    after_tree,
+                ],
+            )?;
+            let patch = run_git(
+                workspace,
+                &[
+                    "diff",
+                    "--patch",
+                    "--no-color",
+                    "--no-renames",
+                    before_tree,
+                    after_tree,
+                ],
+            )?;
+            let files = name_status
+                .lines()
+                .filter_map(|line| {
+                    let (status, path) = line.split_once('\t')?;
+                    Some(PassDiffFile {
+                        status: status.chars().next()?,
+                        path: path.trim().to_string(),
+                    })
+                })
+                .collect();
+            Ok(PassDiff {
+                files,
+                patch,
+                diff_file: None,
+            })
+        }
+        WorkspaceSnapshot::Manifest(before_manifest) => {
+            let after_manifest = file_manifest(workspace)?;
+            let paths = before_manifest
+                .keys()
+                .chain(after_manifest.keys())
+                .collect::<BTreeSet<_>>();
+            let files = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let status = match (before_manifest.get(path), after_manifest.get(path)) {
+                        (None, Some(_)) => 'A',
+                        (Some(_), None) => 'D',
+                        (Some(before), Some(after)) if before != after => 'M',
+                        _ => return None,
+                    };
+                    Some(PassDiffFile {
+                        status,
+                        path: path.clone(),
+                    })
+                })
+                .collect();
+            Ok(PassDiff {
+                files,
+                patch: String::new(),
+                diff_file: None,
+            })
+        }
+    }
+}
+
+impl PassDiff {
+    pub fn file_list(&self) -> String {
+        if self.files.is_empty() {
+            return "(no files changed)".to_string();
+        }
+        self.files
+            .iter()
+            .map(|file| format!("{} {}", file.status, file.path))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Folds this pass into the lines changed by earlier passes: earlier lines are renumbered
+    /// through the pass's hunks, lines it removed drop out, and lines it added join. Returns
+    /// `false` when files changed but there is no patch to read lines from, as in a workspace
+    /// outside git.
+    pub fn apply_to_changed_lines(&self, changed: &mut ChangedLines) -> bool {
+        if self.patch.is_empty() {
+            return self.files.is_empty();
+        }
+        for file in parse_patch(&self.patch) {
+            let earlier = changed.remove(&file.path).unwrap_or_default();
+            if file.deleted {
+                continue;
+            }
+            let mut lines: BTreeSet<usize> = earlier
+                .into_iter()
+                .filter_map(|line| file.renumber(line))
+                .collect();
+            lines.extend(
+                file.hunks
+                    .iter()
+                    .flat_map(|hunk| hunk.added.iter().copied()),
+            );
+            if !lines.is_empty() {
+                changed.insert(file.path, lines);
+            }
+        }
+        true
+    }
+
+    /// Compares the observed file list with a FILES_CHANGED block and describes every path
+    /// that appears on only one side.
+    pub fn discrepancies(&self, self_report: &str) -> Vec<String> {
+        let reported = reported_paths(self_report);
+        let mut discrepancies = Vec::new();
+        for file in &self.files {
+            if !reported.iter().any(|path| paths_match(path, &file.path)) {
+                discrepancies.push(format!(
+                    "{} changed in the workspace but is missing from FILES_CHANGED.",
+                    file.path
+                ));
+            }
+        }
+        for path in &reported {
+            if !self.files.iter().any(|file| paths_match(path, &file.path)) {
+                discrepancies.push(format!(
+                    "{path} is listed in FILES_CHANGED but was not changed in the workspace."
+                ));
+            }
+        }
+        discrepancies
+    }
+}
+
+/// Writes the diff under `<session>/diffs/<task>/<label>.diff` and returns the file path.
+pub fn write_pass_diff(
+    session_dir: &Path,
+    task_key: &str,
+    label: &str,
+    diff: &PassDiff,
+) -> io::Result<PathBuf> {
+    let dir = session_dir
+        .join(PASS_DIFFS_DIR_NAME)
+        .join(sanitize_task_key(task_key));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.diff", sanitize_task_key(label)));
+    let mut text = format!("# {label}\n# Changed files:\n");
+    for line in diff.file_list().lines() {
+        text.push_str(&format!("#   {line}\n"));
+    }
+    text.push('\n');
+    text.push_str(&diff.patch);
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Pulls file paths out of FILES_CHANGED bullets (`- path: description`). Bullets whose
+/// first token does not look like a path, such as `- none: reason`, are ignored.
+fn reported_paths(self_report: &str) -> Vec<String> {
+    self_report
+        .lines()
+        .filter_map(|line| {
+            let entry = line.trim().strip_prefix(['-', '*'])?.trim();
+            let path = entry
+                .split_once(": ")
+                .map_or(entry, |(path, _)| path)
+                .trim()
+                .trim_matches('`');
+            let path = path.strip_suffix(':').unwrap_or(path);
+            let looks_like_path = !path.is_empty()
+                && !path.contains(char::is_whitespace)
+                && (path.contains('/') || path.contains('.'));
+            looks_like_path.then(|| path.trim_start_matches("./").to_string())
+        })
+        .collect()
+}
+
+impl FilePatch {
+    /// Maps a line number from before the patch to after it; `None` when the patch removed it.
+    fn renumber(&self, line: usize) -> Option<usize> {
+        let mut shift = 0isize;
+        for hunk in &self.hunks {
+            if let Some((_, new)) = hunk.old_to_new.iter().find(|(old, _)| *old == line) {
+                return *new;
+            }
+            // A pure insertion (`-k,0`) goes after old line k, so only later lines move.
+            if line >= hunk.old_start + hunk.old_len.max(1) {
+                shift += hunk.new_len as isize - hunk.old_len as isize;
+            }
+        }
+        line.checked_add_signed(shift)
+    }
+}
+
+/// Splits a `git diff --patch` into files and hunks. Binary and mode-only changes come back
+/// without hunks.
+fn parse_patch(patch: &str) -> Vec<FilePatch> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    let (mut old_left, mut new_left) = (0, 0);
+    for line in patch.lines() {
+        if (old_left > 0 || new_left > 0)
+            && let Some(file) = files.last_mut()
+            && let Some(hunk) = file.hunks.last_mut()
+        {
+            match line.chars().next() {
+                Some('+') => {
+                    hunk.added.push(new_line);
+                    new_line += 1;
+                    new_left -= 1;
+                }
+                Some('-') => {
+                    hunk.old_to_new.push((old_line, None));
+                    old_line += 1;
+                    old_left -= 1;
+                }
+                Some('\\') => {}
+                _ => {
+                    hunk.old_to_new.push((old_line, Some(new_line)));
+                    old_line += 1;
+                    new_line += 1;
+                    old_left -= 1;
+                    new_left -= 1;
+                }
+            }
+            continue;
+        }
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let path = header.rsplit_once(" b/").map_or(header, |(_, path)| path);
+            files.push(FilePatch {
+                path: path.to_string(),
+                ..FilePatch::default()
+            });
+        } else if let Some(file) = files.last_mut() {
+            if line == "+++ /dev/null" {
+                file.deleted = true;
+            } else if let Some(path) = line.strip_prefix("+++ b/") {
+                file.path = path.to_string();
+            } else if let Some(hunk) = parse_hunk_header(line) {
+                (old_line, new_line) = (hunk.old_start, hunk.new_start);
+                old_left = hunk.old_len;
+                new_left = hunk.new_len;
+                file.hunks.push(hunk);
+            }
+        }
+    }
+    files
+}
+
+/// Reads `@@ -a[,b] +c[,d] @@`; an omitted length means one line.
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let ranges = line.strip_prefix("@@ -")?.split(" @@").next()?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_len) = range(old)?;
+    let (new_start, new_len) = range(new)?;
+    Some(DiffHunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        ..DiffHunk::default()
+    })
+}
+
+pub fn paths_match(reported: &str, observed: &str) -> bool {
+    // Workers run from a subdirectory at times, so a report relative to it still counts.
+    reported == observed
+        || observed.ends_with(&format!("/{reported}"))
+        || reported.ends_with(&format!("/{observed}"))
+}
+
+fn file_manifest(workspace: &Path) -> io::Result<BTreeMap<String, u64>> {
+    let mut manifest = BTreeMap::new();
+    let mut pending = vec![workspace.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                let skipped = entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| MANIFEST_SKIPPED_DIRS.contains(&name));
+                if !skipped {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                let mut hasher = DefaultHasher::new();
+                hasher.write(&std::fs::read(&path)?);
+                let relative = path
+                    .strip_prefix(workspace)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                manifest.insert(relative, hasher.finish());
+            }
+        }
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/pass_diff_tests.rs"]
+mod tests;
@@ -0,0 +1,96 @@
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct RunState {
+    generation: u64,
+    pid: Option<u32>,
+}
+
+/// Shared handle to the child process behind an adapter run.
+///
+/// Every run captures the generation current at dispatch. `kill` bumps the generation, so
+/// events from a killed run (including its late `Completed`) are dropped instead of being
+/// attributed to whatever job the adapter runs next.
+#[derive(Debug, Clone, Default)]
+pub struct RunControl {
+    state: Arc<Mutex<RunState>>,
+}
+
+impl RunControl {
+    pub fn generation(&self) -> u64 {
+        self.state.lock().map(|state| state.generation).unwrap_or(0)
+    }
+
+    pub fn send_if_current(&self, generation: u64, send: impl FnOnce()) {
+        // Holding the lock while sending keeps a concurrent kill from interleaving.
+        if let Ok(state) = self.state.lock()
+            && state.generation == generation
+        {
+            send();
+        }
+    }
+
+    pub fn attach(&self, generation: u64, pid: u32) {
+        let superseded = match self.state.lock() {
+            Ok(mut state) if state.generation == generation => {
+                state.pid = Some(pid);
+                false
+            }
+            _ => true,
+        };
+        if superseded {
+            kill_process_group(pid);
+        }
+    }
+
+    pub fn detach(&self, generation: u64) {
+        if let Ok(mut state) = self.state.lock()
+            && state.generation == generation
+        {
+            state.pid = None;
+        }
+    }
+
+    pub fn kill(&self) -> bool {
+        let pid = match self.state.lock() {
+            Ok(mut state) => {
+                state.generation = state.generation.wrapping_add(1);
+                state.pid.take()
+            }
+            Err(_) => None,
+        };
+        match pid {
+            Some(pid) => {
+                kill_process_group(pid);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub fn isolate_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+fn kill_process_group(pid: u32) {
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{pid}")])
+        .status();
+    #[cfg(not(unix))]
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .status();
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/process_control_tests.rs"]
+mod tests;
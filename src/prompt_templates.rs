@@ -0,0 +1,644 @@
+use std::path::{Path, PathBuf};
+
+use crate::artifact_io;
+
+pub const PROMPTS_DIR_NAME: &str = "prompts";
+const TEMPLATE_FILE_EXTENSION: &str = "md";
+
+/// Every prompt the orchestrator sends to an agent. Each one can be overridden by a
+/// `<name>.md` file in a prompts directory; the built-in text is the fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptTemplate {
+    Master,
+    Planner,
+    ConvertPlan,
+    AttachDocs,
+    ProjectInfo,
+    SessionMeta,
+    TaskCheck,
+    FailureReport,
+    Implementor,
+    ImplementationAuditor,
+    TestWriter,
+    TestAuditor,
+    FinalAudit,
+    WorkerRole,
+}
+
+impl PromptTemplate {
+    pub const ALL: [Self; 14] = [
+        Self::Master,
+        Self::Planner,
+        Self::ConvertPlan,
+        Self::AttachDocs,
+        Self::ProjectInfo,
+        Self::SessionMeta,
+        Self::TaskCheck,
+        Self::FailureReport,
+        Self::Implementor,
+        Self::ImplementationAuditor,
+        Self::TestWriter,
+        Self::TestAuditor,
+        Self::FinalAudit,
+        Self::WorkerRole,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Master => "master",
+            Self::Planner => "planner",
+            Self::ConvertPlan => "convert_plan",
+            Self::AttachDocs => "attach_docs",
+            Self::ProjectInfo => "project_info",
+            Self::SessionMeta => "session_meta",
+            Self::TaskCheck => "task_check",
+            Self::FailureReport => "failure_report",
+            Self::Implementor => "implementor",
+            Self::ImplementationAuditor => "implementation_auditor",
+            Self::TestWriter => "test_writer",
+            Self::TestAuditor => "test_auditor",
+            Self::FinalAudit => "final_audit",
+            Self::WorkerRole => "worker_role",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|template| template.name() == name)
+    }
+
+    /// Placeholder names with a short description of the value filled in for each.
+    pub fn placeholders(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Master => &[
+                (
+                    "workflow_prompt",
+                    "Conversation prompt with execution state, rolling context, task tree and the user message",
+                ),
+                ("tasks_file", "Path of the session tasks.json"),
+                ("tests_policy", "Planning rules for the current tests mode"),
+                (
+                    "custom_roles",
+                    "How to use `role` tasks and the roles configured in [workflow.roles]",
+                ),
+            ],
+            Self::Planner => &[
+                ("planner_file", "Path of the session planner markdown"),
+                ("project_info_file", "Path of the project-info.md brief"),
+                ("tests_policy", "Planning rules for the current tests mode"),
+                (
+                    "step_sections",
+                    "Which sections every plan step must contain in the current tests mode",
+                ),
+                ("rolling_context", "Rolling task context entries"),
+                ("message", "The user's message"),
+            ],
+            Self::ConvertPlan => &[
+                ("planner_file", "Path of the session planner markdown"),
+                ("tasks_file", "Path of the session tasks.json"),
+            ],
+            Self::AttachDocs => &[("tasks_file", "Path of the session tasks.json")],
+            Self::ProjectInfo => &[
+                ("cwd", "Workspace directory"),
+                ("question", "The user's first message"),
+                (
+                    "output_path",
+                    "Path the project-info.md brief is written to",
+                ),
+            ],
+            Self::SessionMeta => &[
+                ("output_path", "Path the session meta.json is written to"),
+                ("user_prompt", "The user's original request"),
+            ],
+            Self::TaskCheck => &[
+                ("tasks_file", "Path of the session tasks.json"),
+                ("project_info_file", "Path of the project-info.md brief"),
+                ("session_meta_file", "Path of the session meta.json"),
+                (
+                    "custom_roles",
+                    "How to use `role` tasks and the roles configured in [workflow.roles]",
+                ),
+            ],
+            Self::FailureReport => &[
+                ("task_fails_file", "Path of the task failure log"),
+                ("entries", "One line per branch that failed this cycle"),
+                (
+                    "rollback_hint",
+                    "Rollback offer when a failed task has a checkpoint, otherwise empty",
+                ),
+                (
+                    "test_failure_question",
+                    "TODO.md question when tests could not be kept, otherwise empty",
+                ),
+            ],
+            Self::Implementor => &[
+                ("top_task_title", "Title of the top-level task"),
+                ("subtask_title", "Title of the implementor task"),
+                ("subtask_details", "Details of the implementor task"),
+                ("rolling_context", "Rolling task context entries"),
+                (
+                    "feedback",
+                    "Audit or test feedback to address, or a note that there is none yet",
+                ),
+                ("tests_policy", "Test rules for the current tests mode"),
+            ],
+            Self::ImplementationAuditor => &[
+                ("top_task_title", "Title of the top-level task"),
+                ("implementor_title", "Title of the audited implementor task"),
+                (
+                    "implementor_details",
+                    "Details of the audited implementor task",
+                ),
+                ("audit_details", "Details of the auditor task"),
+                ("pass", "Current audit pass"),
+                ("max_passes", "Audit retry budget"),
+                (
+                    "review_panel",
+                    "Reviewer line when the audit runs a quorum, otherwise empty",
+                ),
+                ("rolling_context", "Rolling task context entries"),
+                (
+                    "changed_files_summary",
+                    "Changed-files summary reported by the implementor",
+                ),
+                (
+                    "observed_changes",
+                    "Workspace changes observed for the latest implementor pass",
+                ),
+                ("implementation_output", "Output of the implementor pass"),
+                ("tests_policy", "Test rules for the current tests mode"),
+                ("strictness_policy", "Strictness rules for this audit pass"),
+                ("verdict_protocol", "Required JSON verdict format"),
+            ],
+            Self::TestWriter => &[
+                ("top_task_title", "Title of the top-level task"),
+                ("test_writer_title", "Title of the test-writer task"),
+                ("test_writer_details", "Details of the test-writer task"),
+                ("rolling_context", "Rolling task context entries"),
+                (
+                    "feedback",
+                    "Audit or test-run feedback to address, or a note that there is none yet",
+                ),
+                ("tests_policy", "Test rules for the current tests mode"),
+                (
+                    "cleanup_instruction",
+                    "Cleanup instruction after exhausted test retries, otherwise empty",
+                ),
+                ("output_instruction", "What the response should report"),
+            ],
+            Self::TestAuditor => &[
+                ("top_task_title", "Title of the top-level task"),
+                ("test_writer_title", "Title of the audited test-writer task"),
+                (
+                    "test_writer_details",
+                    "Details of the audited test-writer task",
+                ),
+                ("audit_details", "Details of the auditor task"),
+                ("pass", "Current audit pass"),
+                ("max_passes", "Audit retry budget"),
+                ("rolling_context", "Rolling task context entries"),
+                (
+                    "observed_changes",
+                    "Workspace changes observed for the latest test-writer pass",
+                ),
+                ("test_writer_output", "Output of the test-writer pass"),
+                ("tests_policy", "Test rules for the current tests mode"),
+                ("strictness_policy", "Strictness rules for this audit pass"),
+                ("verdict_protocol", "Required JSON verdict format"),
+            ],
+            Self::FinalAudit => &[
+                ("rolling_context", "Rolling task context entries"),
+                ("task_tree", "Compact view of the current task tree"),
+                ("tests_policy", "Test rules for the current tests mode"),
+                (
+                    "feedback",
+                    "Previous final-audit feedback, or a note that there is none",
+                ),
+                ("verdict_protocol", "Required JSON verdict format"),
+            ],
+            Self::WorkerRole => &[
+                ("role_name", "Name of the role in [workflow.roles]"),
+                ("role_title", "Display title of the role"),
+                ("role_instructions", "The role's configured instructions"),
+                ("top_task_title", "Title of the top-level task"),
+                ("implementor_title", "Title of the implementor task"),
+                ("implementor_details", "Details of the implementor task"),
+                ("role_details", "Details of the role task"),
+                ("pass", "Current pass of the role"),
+                ("max_passes", "Retry budget of the role"),
+                ("rolling_context", "Rolling task context entries"),
+                (
+                    "changed_files_summary",
+                    "Changed-files summary reported by the implementor",
+                ),
+                (
+                    "observed_changes",
+                    "Workspace changes observed for the latest implementor pass",
+                ),
+                ("implementation_output", "Output of the implementor pass"),
+                (
+                    "feedback",
+                    "Findings of the role's previous pass when it reruns itself, otherwise a note that there are none",
+                ),
+                ("tests_policy", "Test rules for the current tests mode"),
+                (
+                    "verdict_protocol",
+                    "Required verdict format: the JSON verdict or a PASS/FAIL first line",
+                ),
+            ],
+        }
+    }
+
+    pub fn builtin(self) -> &'static str {
+        match self {
+            Self::Master => MASTER_TEMPLATE,
+            Self::Planner => PLANNER_TEMPLATE,
+            Self::ConvertPlan => CONVERT_PLAN_TEMPLATE,
+            Self::AttachDocs => ATTACH_DOCS_TEMPLATE,
+            Self::ProjectInfo => PROJECT_INFO_TEMPLATE,
+            Self::SessionMeta => SESSION_META_TEMPLATE,
+            Self::TaskCheck => TASK_CHECK_TEMPLATE,
+            Self::FailureReport => FAILURE_REPORT_TEMPLATE,
+            Self::Implementor => IMPLEMENTOR_TEMPLATE,
+            Self::ImplementationAuditor => IMPLEMENTATION_AUDITOR_TEMPLATE,
+            Self::TestWriter => TEST_WRITER_TEMPLATE,
+            Self::TestAuditor => TEST_AUDITOR_TEMPLATE,
+            Self::FinalAudit => FINAL_AUDIT_TEMPLATE,
+            Self::WorkerRole => WORKER_ROLE_TEMPLATE,
+        }
+    }
+}
+
+/// Directories searched for template overrides, most specific first: the workspace's
+/// `.agentbob/prompts/`, then `prompts/` in the config dir.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptTemplateDirs {
+    dirs: Vec<PathBuf>,
+}
+
+/// The text a template resolved to and the override file it came from, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPromptTemplate {
+    pub template: PromptTemplate,
+    pub text: String,
+    pub source: Option<PathBuf>,
+}
+
+impl PromptTemplateDirs {
+    #[cfg(test)]
+    pub fn new(dirs: Vec<PathBuf>) -> Self {
+        Self { dirs }
+    }
+
+    pub fn discover(workspace: &Path) -> Self {
+        let mut dirs = vec![artifact_io::workspace_app_dir(workspace).join(PROMPTS_DIR_NAME)];
+        if let Ok(config_dir) = artifact_io::runtime_storage_dir() {
+            let config_prompts = config_dir.join(PROMPTS_DIR_NAME);
+            if !dirs.contains(&config_prompts) {
+                dirs.push(config_prompts);
+            }
+        }
+        Self { dirs }
+    }
+
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Picks the first readable `<name>.md` override, falling back to the built-in text.
+    /// Trailing whitespace is trimmed so editors' final newlines do not reach the prompt.
+    pub fn resolve(&self, template: PromptTemplate) -> ResolvedPromptTemplate {
+        for dir in &self.dirs {
+            let path = dir.join(format!("{}.{TEMPLATE_FILE_EXTENSION}", template.name()));
+            if let Ok(text) = artifact_io::read_text_file(&path) {
+                return ResolvedPromptTemplate {
+                    template,
+                    text: text.trim_end().to_string(),
+                    source: Some(path),
+                };
+            }
+        }
+        ResolvedPromptTemplate {
+            template,
+            text: template.builtin().to_string(),
+            source: None,
+        }
+    }
+}
+
+impl ResolvedPromptTemplate {
+    pub fn render(&self, values: &[(&str, &str)]) -> String {
+        fill_placeholders(&self.text, |name| {
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+        })
+    }
+
+    /// Placeholders of this template that its text actually uses.
+    pub fn used_placeholders(&self) -> Vec<&'static str> {
+        self.template
+            .placeholders()
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| self.text.contains(&format!("{{{name}}}")))
+            .collect()
+    }
+}
+
+/// Renders the effective `template` for the current workspace with `values` filled in.
+pub fn render(template: PromptTemplate, values: &[(&str, &str)]) -> String {
+    std::env::current_dir()
+        .map(|cwd| PromptTemplateDirs::discover(&cwd))
+        .unwrap_or_default()
+        .resolve(template)
+        .render(values)
+}
+
+/// Replaces each `{name}` whose name `lookup` knows in a single pass, so substituted values
+/// are never expanded again. Other braces, such as JSON examples, are kept verbatim.
+pub fn fill_placeholders<'a>(
+    text: &str,
+    mut lookup: impl FnMut(&str) -> Option<&'a str>,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after
+            .find('}')
+            .map(|end| &after[..end])
+            .filter(|name| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+            })
+            .and_then(|name| lookup(name).map(|value| (name.len(), value)));
+        match value {
+            Some((len, value)) => {
+                out.push_str(value);
+                rest = &after[len + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+const MASTER_TEMPLATE: &str = "{workflow_prompt}\n\
+     Planner storage:\n\
+     - Read and update this JSON file directly: {tasks_file}\n\
+     - Never modify project workspace/source files directly.\n\
+     - You may only edit files in the current meta-agent session directory (including tasks/context artifacts).\n\
+     - If user requests more work after existing tasks are completed, append new tasks; do not delete completed task history.\n\
+     - After task list updates are ready, tell the user `/start` is ready to run.\n\
+     - `/start` always resumes from the last unfinished task.\n\
+     - File schema: array of objects with fields id, title, details, docs, kind, status, parent_id, order, depends_on, max_retries, command, working_dir, review_quorum, role\n\
+     - kind values: task, final_audit, implementor, auditor, test_writer, test_runner, lint_runner, review, role\n\
+     - A `lint_runner` task is a deterministic lint/static-check step placed as a direct child of an implementor (at most one per implementor). It runs session meta.json lint_command after every implementor pass, before the auditors, and sends lint failures back to the implementor. Add one under each implementor when meta.json has a lint_command.\n\
+     - A `review` task is a human approval gate placed as a direct child of an implementor, ordered after its auditors. Execution pauses there until the user runs `/approve` or `/reject <feedback>`; add one only when the user asks to review changes before work continues.\n\
+     {custom_roles}\n\
+     - `depends_on` is an array of top-level task ids that must finish before this top-level task may start. Only top-level `task` entries may set it; use [] otherwise.\n\
+     - Top-level tasks without a dependency relationship may run in any order or in parallel, so express every real ordering requirement through `depends_on`.\n\
+     - `depends_on` must not reference missing tasks or form cycles.\n\
+     - `max_retries` optionally overrides the retry budget for audit/test loops within a task and its subtasks (null uses config defaults). Raise it for risky work such as migrations; lower it (minimum 1) for trivial work.\n\
+     - `command` and `working_dir` may be set only on `test_runner` tasks (null otherwise). `command` is a bash-runnable verification command for that branch, such as `cargo test -p storage` or an integration script, and overrides meta.json test_command; `working_dir` is relative to the workspace root. Leave both null when the session test_command already covers the branch.\n\
+     - `review_quorum` may be set only on `auditor` tasks (null otherwise). It is an object with `reviewers` (e.g. 3), `required` (e.g. 2) and optional `profiles` (model profile labels such as large-smart or large-genius). The audit then runs `reviewers` independent reviewers and passes once `required` of them pass; `profiles` optionally assigns model profiles to reviewers in order. Use it only for high-stakes work such as security, data migrations or public APIs, since every reviewer costs a full audit.\n\
+     - `docs` is reserved for `/attach-docs`. Do not populate or modify `docs` in master edits.\n\
+     - For new tasks created by master, set `docs` to [] and leave it empty.\n\
+     - Every task and sub-task must include a non-empty details field with concrete implementation/audit/test intent.\n\
+     - Every details field must be self-contained for isolated-context execution and explicitly cover: target files/modules, expected behavior changes, constraints/non-goals, and verification approach.\n\
+     - Every details field must include an explicit isolated-context rationale stating why the assigned sub-agent can execute using only the task record and referenced artifacts, without hidden chat context.\n\
+     {tests_policy}\n\
+     - Update tasks.json only when task state should change.\n\
+     - Conversational answers that do not change task state do not require tasks.json edits.\n\
+     - Do not ask the user to start execution until task updates are ready.\n\
+     - After updating tasks.json, explain to the user what changed.";
+
+const PLANNER_TEMPLATE: &str = "You are the master Codex agent in planner mode.\n\
+     Goal: collaboratively build a codebase-aware implementation plan before task generation.\n\
+     Planner storage:\n\
+     - Read and update this markdown file directly: {planner_file}\n\
+     - Do not edit tasks.json while planner mode is active.\n\
+     Codebase context:\n\
+     - Prefer grounding plan details using local repository files and this context brief: {project_info_file}\n\
+     - Keep references concrete by naming likely files/modules when confident.\n\
+     Clarification-first behavior:\n\
+     - Do not generate or update planner markdown until you have asked follow-up questions that clarify scope, constraints, and success criteria.\n\
+     - If key details are ambiguous, ask concise follow-up questions first and wait for answers before planning.\n\
+     Tests-mode policy:\n\
+     {tests_policy}\n\
+     Plan formatting requirements:\n\
+     - Break work down into concrete numbered steps.\n\
+     {step_sections}\n\
+     - Every step must be self-contained for isolated-context execution.\n\
+     - In each step, require explicit details for: files/modules to touch, intended behavior/outcomes, constraints/non-goals, and verification approach (commands/checks).\n\
+     - In each step, include an \"Isolated-context rationale\" sentence explaining why an implementor/auditor/test-writer can execute correctly from task details + listed artifacts without hidden chat context.\n\
+     - Maintain readable markdown with sections and checklists.\n\
+     - Track open questions/risks and assumptions.\n\
+     - Keep it collaborative and iterative; update the markdown on each turn when the plan changes.\n\
+     - When the plan is ready for execution, explicitly tell the user to run `/convert` to proceed to implementation.\n\
+     Rolling task context:\n\
+     {rolling_context}\n\
+     User message:\n\
+     {message}\n\
+     After saving planner markdown updates, send a concise conversational summary of what changed and remind the user they can run `/convert` when ready to implement.";
+
+const CONVERT_PLAN_TEMPLATE: &str = "You are the master Codex agent and are now in task mode.\n\
+     Convert the current planner markdown into executable tasks.\n\
+     Read planner markdown at: {planner_file}\n\
+     Update tasks JSON at: {tasks_file}\n\
+     Requirements:\n\
+     - Convert the current plan into concrete task entries and subtasks suitable for execution.\n\
+     - Preserve existing completed task history where possible; append/update pending/in-progress work to reflect the plan.\n\
+     - Keep task hierarchy valid for this workflow (implementor/auditor/test structure guardrails still apply).\n\
+     - Do not modify docs fields except preserving existing values.\n\
+     - Save tasks.json and then provide a concise summary of what changed.";
+
+const ATTACH_DOCS_TEMPLATE: &str = "You are a docs-research sub-agent.\n\
+     Goal: update the planner task file with implementation documentation links.\n\
+     Read and edit this JSON file directly: {tasks_file}\n\
+     Requirements:\n\
+     - For every task/subtask where kind is not \"test_runner\" or \"lint_runner\", populate or refresh a `docs` array.\n\
+     - Each docs item must include: title, url, summary.\n\
+     - Use the latest authoritative online docs relevant to implementing that task.\n\
+     - Keep existing task structure/order/status intact; only add/update docs.\n\
+     - Leave test_runner and lint_runner tasks with docs as-is (do not add docs there).\n\
+     - Save tasks.json, then output a short confirmation summary.";
+
+const PROJECT_INFO_TEMPLATE: &str = "You are a project-context discovery sub-agent.\n\
+     Analyze the repository and gather concise project context for the user question.\n\
+     Current working directory: {cwd}\n\
+     User question:\n\
+     {question}\n\
+     Requirements:\n\
+     - Inspect only local files in the repository to understand structure, tech stack, and constraints.\n\
+     - Do not browse the web, call external tools/services, or include internet-sourced references.\n\
+     - Write a concise Markdown brief to this exact path: {output_path}\n\
+     - Include sections: \"Project Overview\", \"Language & Tech Stack\", \"File Structure\", \"Relevant Code Areas\", \"Constraints & Conventions\", \"Testing Setup\".\n\
+     - In \"Testing Setup\", explicitly state whether tests currently exist, where they are, and the best command to run the project's tests end-to-end.\n\
+     - The test command in \"Testing Setup\" must be a single verbatim shell command runnable in bash as-is from the repository root (not a description).\n\
+     - Also in \"Testing Setup\", state the lint/static-check command the project already uses (for example a clippy, ruff, or eslint invocation), as one verbatim bash command, or that none is configured.\n\
+     - If unknown, state unknown and why.\n\
+     - Do not propose implementation ideas, plans, or code-level solutions.\n\
+     - Focus only on repository lay-of-the-land and concise file/folder summaries that help future agents work quickly without re-scanning the whole project.\n\
+     - Do not make unrelated file changes.\n\
+     Then output a short completion summary.";
+
+const SESSION_META_TEMPLATE: &str = "Using the same session context and project info you already gathered, create session metadata.\n\
+     Write valid JSON to this exact path: {output_path}\n\
+     JSON schema:\n\
+     {\"title\":\"...\",\"created_at\":\"...\",\"stack_description\":\"...\",\"test_command\":\"...\",\"lint_command\":\"...\",\"test_report_path\":null,\"coverage_command\":null,\"coverage_threshold\":null}\n\
+     Requirements:\n\
+     - title: a concise 4-10 word title derived from the user's original request.\n\
+     - created_at: current date-time in ISO-8601 UTC format (example: 2026-02-16T20:14:00Z).\n\
+     - stack_description: a concise 1-2 sentence description of the project's language/technology stack based on gathered project info.\n\
+     - If stack details are uncertain, state that clearly rather than guessing.\n\
+     - test_command: the best command to run the project's tests end-to-end.\n\
+     - test_command must be one exact command string runnable in bash as-is from the repository root (for example: \"cargo test\", \"go test ./...\", \"npm test\").\n\
+     - Do not describe the command or wrap it in markdown/backticks; provide only the raw command string value.\n\
+     - If tests are not set up or unknown, set test_command to JSON null.\n\
+     - lint_command: the project's lint/static-check command (for example: \"cargo clippy --all-targets -- -D warnings\", \"ruff check .\", \"npx eslint .\"), following the same exact-command rules as test_command.\n\
+     - Only propose a lint command the repository already configures or clearly supports; otherwise set lint_command to JSON null.\n\
+     - test_report_path: the path, relative to the repository root, of a JUnit XML report that test_command already writes (for example via pytest --junitxml). Leave it JSON null unless test_command produces such a report.\n\
+     - coverage_command: a command that runs the tests under coverage and prints per-line coverage as lcov or Cobertura XML on stdout (for example: \"cargo llvm-cov --lcov\", \"coverage run -m pytest && coverage xml -o -\", \"npx nyc --reporter=text-lcov npm test\"), following the same exact-command rules as test_command. Set it only when the coverage tool is already installed or configured; otherwise JSON null.\n\
+     - coverage_threshold: minimum line coverage, in whole percent, required on the lines a task changes when coverage_command is set (null uses 80).\n\
+     - Output file content only as JSON (no markdown).\n\
+     - Overwrite the file if it exists.\n\
+     Original user request:\n\
+     {user_prompt}\n\
+     Then output a one-line completion summary.";
+
+const TASK_CHECK_TEMPLATE: &str = "You are a task-structure audit sub-agent.\n\
+     Review the planner JSON file at: {tasks_file}\n\
+     You may also read project context at: {project_info_file}\n\
+     You may also read session metadata at: {session_meta_file}\n\
+     Requirements:\n\
+     - If issues are found, edit this tasks.json directly to fix them.\n\
+     - Keep task intent/status/order as stable as possible while fixing structure.\n\
+     - Validate task hierarchy and ordering against execution guardrails.\n\
+     - Enforce self-contained details for isolated-context execution on every task/subtask:\n\
+       details must explicitly cover files/modules, behavior/outcomes, constraints/non-goals, and verification approach.\n\
+     - Enforce explicit isolated-context rationale on every task/subtask:\n\
+       details must explain why execution is possible from task artifacts alone (without hidden chat memory).\n\
+     - If any task details are missing these fields, fix details text directly while preserving intent/status/order.\n\
+     - Enforce test-task shape deterministically: each test_writer must be a direct child of a top-level task (no nested test_writer groups).\n\
+     - Focus especially on implementor/auditor/test-runner and test-writer/test-runner relationships.\n\
+     - Enforce special-case sequencing for test bootstrapping:\n\
+       If tests are absent/unknown (from project-info Testing Setup and/or meta.json test_command is null/empty) and the plan includes test-writing/execution work, ensure there is a dedicated testing-setup top-level task before dependent work.\n\
+       That setup task must include implementor and auditor subtasks.\n\
+       The setup implementor details must explicitly include both setting up testing tooling and updating meta.json test_command to the exact bash-runnable command string.\n\
+       Do not allow non-setup test_writer/test_runner branches to run before that setup task in top-level task order.\n\
+       Make every top-level task that relies on that setup list the setup task id in its `depends_on`.\n\
+     - Validate `depends_on`: only top-level `task` entries may set it, every id must reference an existing top-level task, and dependencies must not form cycles.\n\
+     - Add missing `depends_on` entries where one top-level task clearly builds on another's output.\n\
+     - Validate `command`/`working_dir`: only `test_runner` tasks may set them (clear them elsewhere), and a set `command` must be a bash-runnable verification command that fits the branch it verifies.\n\
+     - Validate `review_quorum`: only `auditor` tasks may set it, and `required` must be between 1 and `reviewers`.\n\
+     - Validate `role`: only `role` tasks may set it, it must name a configured role, and every role task must be a direct child of an implementor.\n\
+     {custom_roles}\n\
+     - Return a concise report with either \"PASS\" or \"FIXED\" on the first line, followed by findings.\n\
+     - If fixes were applied, list the specific task ids/titles adjusted.\n\
+     Then exit.";
+
+const FAILURE_REPORT_TEMPLATE: &str = "Internal update from execution engine:\n\
+     Retry limits were exhausted or merge-back failed for one or more branches.\n\
+     The canonical failure log has been appended at: {task_fails_file}\n\
+     Newly failed entries this cycle:\n\
+     {entries}\n\
+     Respond with a short user-facing message summarizing what failed and why.\n\
+     Do not emit task operations and do not modify files.\n\
+     {rollback_hint}{test_failure_question}";
+
+const IMPLEMENTOR_TEMPLATE: &str = "You are an implementation sub-agent.\n\
+     Top-level task: {top_task_title}\n\
+     Implementation subtask: {subtask_title}\n\
+     Implementation details:\n{subtask_details}\n\
+     Rolling task context:\n{rolling_context}\n\
+     {feedback}\n\
+     {tests_policy}\n\
+     End your response with a structured changed-files summary block using this exact format:\n\
+     FILES_CHANGED_BEGIN\n\
+     - path/to/file.ext: brief description of what changed\n\
+     FILES_CHANGED_END\n\
+     Include every file you changed. If no files changed, include a single bullet with reason.\n\
+     Provide concise progress updates and finish with what changed.";
+
+const IMPLEMENTATION_AUDITOR_TEMPLATE: &str = "You are an audit sub-agent reviewing implementation output.\n\
+     Top-level task: {top_task_title}\n\
+     Parent implementor task: {implementor_title}\n\
+     Parent implementor details:\n{implementor_details}\n\
+     Audit subtask details:\n{audit_details}\n\
+     Audit pass: {pass} of {max_passes}\n\
+     {review_panel}Rolling task context:\n{rolling_context}\n\
+     Implementor changed-files summary:\n{changed_files_summary}\n\
+     Observed workspace changes for the latest implementor pass (captured by the orchestrator; trust these over the self-reported summary):\n{observed_changes}\n\
+     Implementation output to audit:\n{implementation_output}\n\
+     {tests_policy}\n\
+     Scope lock (required): audit only the parent implementor task/details above. Do not audit unrelated tasks, broader roadmap items, or unrelated files.\n\
+     Execution guardrail: do not run tests and do not execute/check shell commands. Command/test execution is handled by a subsequent dedicated agent.\n\
+     Strictness policy for this audit pass:\n{strictness_policy}\n\
+     {verdict_protocol}\n\
+     - On pass 4, only report blocking findings for truly critical blockers that would prevent the broader plan from running.";
+
+const TEST_WRITER_TEMPLATE: &str = "You are a test-writer sub-agent.\n\
+     Top-level task: {top_task_title}\n\
+     Test-writer subtask: {test_writer_title}\n\
+     Test-writing details:\n{test_writer_details}\n\
+     Rolling task context:\n{rolling_context}\n\
+     {feedback}\n\
+     {tests_policy}\n\
+     {cleanup_instruction}\n\
+     {output_instruction}";
+
+const TEST_AUDITOR_TEMPLATE: &str = "You are an audit sub-agent reviewing test-writing output.\n\
+     Top-level task: {top_task_title}\n\
+     Parent test-writer task: {test_writer_title}\n\
+     Parent test-writer details:\n{test_writer_details}\n\
+     Audit subtask details:\n{audit_details}\n\
+     Audit pass: {pass} of {max_passes}\n\
+     Rolling task context:\n{rolling_context}\n\
+     Observed workspace changes for the latest test-writer pass (captured by the orchestrator):\n{observed_changes}\n\
+     Test-writer output to audit:\n{test_writer_output}\n\
+     {tests_policy}\n\
+     Execution guardrail: do not run tests and do not execute/check shell commands. Command/test execution is handled by a subsequent dedicated agent.\n\
+     Strictness policy for this audit pass:\n{strictness_policy}\n\
+     {verdict_protocol}\n\
+     - On pass 4, only report blocking findings for truly critical blockers that would prevent the broader plan from running.";
+
+const FINAL_AUDIT_TEMPLATE: &str = "You are a final audit sub-agent.\n\
+     Perform a holistic audit across all completed tasks and their outcomes.\n\
+     Focus on cross-task correctness, missing edge cases, integration risk, and overall quality gaps.\n\
+     Rolling task context:\n{rolling_context}\n\
+     Current task tree:\n{task_tree}\n\
+     {tests_policy}\n\
+     {feedback}\n\
+     {verdict_protocol}";
+
+const WORKER_ROLE_TEMPLATE: &str = "You are the {role_title} sub-agent (`{role_name}`) in an implementation branch.\n\
+     Top-level task: {top_task_title}\n\
+     Parent implementor task: {implementor_title}\n\
+     Parent implementor details:\n{implementor_details}\n\
+     Role instructions:\n{role_instructions}\n\
+     Role subtask details:\n{role_details}\n\
+     Role pass: {pass} of {max_passes}\n\
+     {feedback}\n\
+     Rolling task context:\n{rolling_context}\n\
+     Implementor changed-files summary:\n{changed_files_summary}\n\
+     Observed workspace changes for the latest implementor pass (captured by the orchestrator; trust these over the self-reported summary):\n{observed_changes}\n\
+     Implementation output:\n{implementation_output}\n\
+     {tests_policy}\n\
+     Scope lock (required): work only on the parent implementor task above and within your role instructions.\n\
+     {verdict_protocol}";
+
+#[cfg(test)]
+#[path = "../tests/unit/prompt_templates_tests.rs"]
+mod tests;
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::agent::AgentEvent;
+use crate::pass_diff::{self, WorkspaceSnapshot};
+use crate::replay::{ReplayEdit, ReplayStep};
+
+pub const RECORDED_FIXTURE_FILE_NAME: &str = "fixture.json";
+/// Session files the master and the other session agents write; runs without a task record
+/// changes to them as absolute-path edits.
+const SESSION_ARTIFACTS: &[&str] = &["tasks.json", "planner.md", "meta.json", "project-info.md"];
+
+/// Captures every agent prompt and deterministic run of a `--record <dir>` session into a
+/// fixture the replay backend can play back. Steps carry the replay fields plus the prompt,
+/// model, timestamped events and context key they were recorded with.
+#[derive(Debug, Clone)]
+pub struct SessionRecorder {
+    inner: Arc<RecorderInner>,
+}
+
+#[derive(Debug)]
+struct RecorderInner {
+    dir: PathBuf,
+    started: Instant,
+    started_at_epoch_secs: u64,
+    state: Mutex<RecorderState>,
+}
+
+#[derive(Debug, Default)]
+struct RecorderState {
+    passes: HashMap<(String, Option<u64>), u32>,
+    runs: Vec<Arc<Mutex<RecordedStep>>>,
+    session_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct RecordedFixture<'a> {
+    recorded_at_epoch_secs: u64,
+    steps: Vec<&'a RecordedStep>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RecordedStep {
+    #[serde(flatten)]
+    step: ReplayStep,
+    context_key: String,
+    model: Option<String>,
+    thinking_effort: Option<String>,
+    prompt: String,
+    started_at_ms: u64,
+    events: Vec<RecordedEvent>,
+    #[serde(skip)]
+    finished: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum RecordedEvent {
+    Output {
+        at_ms: u64,
+        text: String,
+    },
+    System {
+        at_ms: u64,
+        text: String,
+    },
+    Completed {
+        at_ms: u64,
+        success: bool,
+        code: i32,
+    },
+}
+
+/// Who a recorded run answers for; attached to adapter configs the way replay specs are.
+#[derive(Debug, Clone)]
+pub struct RecordingSpec {
+    pub recorder: SessionRecorder,
+    pub role: String,
+    pub task: Option<u64>,
+    pub context_key: String,
+}
+
+/// One prompt being recorded. Events are appended as they are sent; the completion event
+/// captures the workspace changes and rewrites the fixture.
+#[derive(Debug, Clone)]
+pub struct RecordingHandle {
+    recorder: SessionRecorder,
+    step: Arc<Mutex<RecordedStep>>,
+    root: PathBuf,
+    snapshot: Option<WorkspaceSnapshot>,
+    /// Session artifact paths with their contents when the run started.
+    session_files: Vec<(PathBuf, Option<String>)>,
+}
+
+impl SessionRecorder {
+    pub fn create(dir: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let dir = dir.canonicalize()?;
+        let recorder = Self {
+            inner: Arc::new(RecorderInner {
+                dir,
+                started: Instant::now(),
+                started_at_epoch_secs: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                state: Mutex::new(RecorderState::default()),
+            }),
+        };
+        recorder.flush()?;
+        Ok(recorder)
+    }
+
+    pub fn fixture_path(&self) -> PathBuf {
+        self.inner.dir.join(RECORDED_FIXTURE_FILE_NAME)
+    }
+
+    /// Points session-agent runs at the active session's directory; `None` before a session
+    /// exists.
+    pub fn set_session_dir(&self, dir: Option<&Path>) {
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.session_dir = dir.map(Path::to_path_buf);
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        u64::try_from(self.inner.started.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        let steps = match self.inner.state.lock() {
+            Ok(state) => state
+                .runs
+                .iter()
+                .filter_map(|run| run.lock().ok().map(|step| step.clone()))
+                .filter(|step| step.finished)
+                .collect::<Vec<_>>(),
+            Err(_) => return Err(io::Error::other("recorder state is unavailable")),
+        };
+        let fixture = RecordedFixture {
+            recorded_at_epoch_secs: self.inner.started_at_epoch_secs,
+            steps: steps.iter().collect(),
+        };
+        let text = serde_json::to_string_pretty(&fixture).map_err(io::Error::other)?;
+        let path = self.fixture_path();
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, text)?;
+        std::fs::rename(&temp, &path)
+    }
+}
+
+impl RecordingSpec {
+    /// Starts recording one prompt. The pass number is assigned here, in send order, so it
+    /// matches the count a replay player keeps for the same role and task. Only runs scoped to
+    /// a task capture workspace edits: the master, master report and other session agents share
+    /// the workspace with running workers, so a diff around their runs would claim worker edits.
+    /// Those runs capture the session artifacts they write instead.
+    pub fn begin(
+        &self,
+        prompt: &str,
+        model: Option<&str>,
+        thinking_effort: Option<&str>,
+        root: &Path,
+    ) -> RecordingHandle {
+        let recorder = self.recorder.clone();
+        let step = Arc::new(Mutex::new(RecordedStep {
+            step: ReplayStep {
+                role: self.role.clone(),
+                task: self.task,
+                ..ReplayStep::default()
+            },
+            context_key: self.context_key.clone(),
+            model: model.map(ToString::to_string),
+            thinking_effort: thinking_effort.map(ToString::to_string),
+            prompt: prompt.to_string(),
+            started_at_ms: recorder.elapsed_ms(),
+            events: Vec::new(),
+            finished: false,
+        }));
+        let mut session_files = Vec::new();
+        if let Ok(mut state) = recorder.inner.state.lock() {
+            if self.task.is_none()
+                && let Some(dir) = state.session_dir.as_ref()
+            {
+                session_files = SESSION_ARTIFACTS
+                    .iter()
+                    .map(|name| {
+                        let path = dir.join(name);
+                        let content = std::fs::read_to_string(&path).ok();
+                        (path, content)
+                    })
+                    .collect();
+            }
+            let pass = state
+                .passes
+                .entry((self.role.clone(), self.task))
+                .or_insert(0);
+            *pass += 1;
+            if let Ok(mut recorded) = step.lock() {
+                recorded.step.pass = Some(*pass);
+            }
+            state.runs.push(Arc::clone(&step));
+        }
+        RecordingHandle {
+            recorder,
+            step,
+            root: root.to_path_buf(),
+            snapshot: self
+                .task
+                .and_then(|_| pass_diff::capture_manifest_snapshot(root).ok()),
+            session_files,
+        }
+    }
+}
+
+impl RecordingHandle {
+    /// Appends `event`. Completion and anything arriving after it rewrite the fixture, whose
+    /// write error is returned so the caller can surface it.
+    pub fn record(&self, event: &AgentEvent) -> io::Result<()> {
+        let at_ms = self.recorder.elapsed_ms();
+        let completed = matches!(event, AgentEvent::Completed { .. });
+        let edits = if completed {
+            let mut edits = self.workspace_edits();
+            edits.extend(self.session_edits());
+            edits
+        } else {
+            Vec::new()
+        };
+        let flush = {
+            let Ok(mut recorded) = self.step.lock() else {
+                return Ok(());
+            };
+            if let AgentEvent::Output(text) = event {
+                recorded.step.output.push(text.clone());
+            }
+            recorded.events.push(match event {
+                AgentEvent::Output(text) => RecordedEvent::Output {
+                    at_ms,
+                    text: text.clone(),
+                },
+                AgentEvent::System(text) => RecordedEvent::System {
+                    at_ms,
+                    text: text.clone(),
+                },
+                AgentEvent::Completed { success, code } => RecordedEvent::Completed {
+                    at_ms,
+                    success: *success,
+                    code: *code,
+                },
+            });
+            if completed && !recorded.finished {
+                recorded.finished = true;
+                if let AgentEvent::Completed { code, .. } = event {
+                    recorded.step.exit_code = *code;
+                }
+                recorded.step.edits = edits;
+            }
+            recorded.finished
+        };
+        if flush { self.recorder.flush() } else { Ok(()) }
+    }
+
+    /// Files the run changed, with their resulting contents. Files under the recording dir
+    /// and files that are not UTF-8 text are left out.
+    fn workspace_edits(&self) -> Vec<ReplayEdit> {
+        let Some(snapshot) = self.snapshot.as_ref() else {
+            return Vec::new();
+        };
+        let Ok(diff) = pass_diff::diff_since(&self.root, snapshot) else {
+            return Vec::new();
+        };
+        diff.files
+            .into_iter()
+            .filter(|file| {
+                !self
+                    .root
+                    .join(&file.path)
+                    .starts_with(&self.recorder.inner.dir)
+            })
+            .filter_map(|file| {
+                if file.status == 'D' {
+                    return Some(ReplayEdit {
+                        path: file.path,
+                        content: None,
+                        delete: true,
+                    });
+                }
+                let content = std::fs::read_to_string(self.root.join(&file.path)).ok()?;
+                Some(ReplayEdit {
+                    path: file.path,
+                    content: Some(content),
+                    delete: false,
+                })
+            })
+            .collect()
+    }
+
+    /// Session artifacts the run wrote or removed, keyed by absolute path so replay writes
+    /// them back into the session directory rather than the working directory.
+    fn session_edits(&self) -> Vec<ReplayEdit> {
+        self.session_files
+            .iter()
+            .filter_map(|(path, before)| {
+                let after = std::fs::read_to_string(path).ok();
+                if after == *before {
+                    return None;
+                }
+                Some(ReplayEdit {
+                    path: path.to_string_lossy().to_string(),
+                    delete: after.is_none(),
+                    content: after,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/recorder_tests.rs"]
+mod tests;
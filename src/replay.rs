@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::AgentEvent;
+
+/// A scripted run: each step answers one prompt for a role, optionally narrowed to a task and
+/// pass, with canned output lines, an exit code and file edits applied before the output.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReplayFixture {
+    pub steps: Vec<ReplayStep>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReplayStep {
+    /// Agent role as named in `[codex.agent_profiles]`, e.g. `master` or `worker_auditor`.
+    pub role: String,
+    /// Top-level task id; omitted steps match any task.
+    pub task: Option<u64>,
+    /// 1-based count of prompts the role has received for the task; omitted steps match any pass.
+    pub pass: Option<u32>,
+    pub output: Vec<String>,
+    pub exit_code: i32,
+    pub edits: Vec<ReplayEdit>,
+}
+
+/// Writes `content` to `path`, or removes it when `delete` is set. Relative paths resolve
+/// against the adapter's working directory; absolute paths (e.g. a session's `tasks.json`) are
+/// used as given.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReplayEdit {
+    pub path: String,
+    pub content: Option<String>,
+    pub delete: bool,
+}
+
+/// Serves fixture steps to every adapter built from one routing, so pass counts span the run.
+#[derive(Debug, Default)]
+pub struct ReplayPlayer {
+    fixture: ReplayFixture,
+    passes: Mutex<HashMap<(String, Option<u64>), u32>>,
+}
+
+impl ReplayPlayer {
+    pub fn new(fixture: ReplayFixture) -> Self {
+        Self {
+            fixture,
+            passes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("failed to read replay fixture {}: {err}", path.display()),
+            )
+        })?;
+        let fixture = serde_json::from_str::<ReplayFixture>(&text).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid replay fixture {}: {err}", path.display()),
+            )
+        })?;
+        Ok(Self::new(fixture))
+    }
+
+    /// Advances the pass count for `role`/`task` and returns the most specific matching step.
+    /// Steps naming a task outrank steps naming only a pass; ties go to the earlier step.
+    pub fn next_step(&self, role: &str, task: Option<u64>) -> Result<ReplayStep, String> {
+        let pass = match self.passes.lock() {
+            Ok(mut passes) => {
+                let count = passes.entry((role.to_string(), task)).or_insert(0);
+                *count += 1;
+                *count
+            }
+            Err(_) => return Err("replay pass counters are unavailable".to_string()),
+        };
+        self.fixture
+            .steps
+            .iter()
+            .filter(|step| {
+                step.role.trim().eq_ignore_ascii_case(role)
+                    && step.task.is_none_or(|step_task| Some(step_task) == task)
+                    && step.pass.is_none_or(|step_pass| step_pass == pass)
+            })
+            .min_by_key(|step| {
+                std::cmp::Reverse(u8::from(step.task.is_some()) * 2 + u8::from(step.pass.is_some()))
+            })
+            .cloned()
+            .ok_or_else(|| {
+                let task = task.map_or_else(|| "-".to_string(), |task| task.to_string());
+                format!("Replay fixture has no step for role {role}, task {task}, pass {pass}.")
+            })
+    }
+
+    /// Whether any step answers for `role`.
+    pub fn has_role(&self, role: &str) -> bool {
+        self.fixture
+            .steps
+            .iter()
+            .any(|step| step.role.trim().eq_ignore_ascii_case(role))
+    }
+}
+
+/// Answers one prompt from the fixture: applies the step's edits under `root`, then emits its
+/// output and exit code the way a finished subprocess would. `source` names the runner in
+/// system messages.
+pub fn play_next_step(
+    player: &ReplayPlayer,
+    role: &str,
+    task: Option<u64>,
+    root: &Path,
+    source: &str,
+    mut emit: impl FnMut(AgentEvent),
+) {
+    let step = match player.next_step(role, task) {
+        Ok(step) => step,
+        Err(message) => {
+            emit(AgentEvent::System(message));
+            emit(AgentEvent::Completed {
+                success: false,
+                code: -1,
+            });
+            return;
+        }
+    };
+    if let Err(err) = apply_edits(&step.edits, root) {
+        emit(AgentEvent::System(format!(
+            "{source} could not apply fixture edits: {err}"
+        )));
+        emit(AgentEvent::Completed {
+            success: false,
+            code: -1,
+        });
+        return;
+    }
+    for line in step.output {
+        emit(AgentEvent::Output(line));
+    }
+    emit(AgentEvent::Completed {
+        success: step.exit_code == 0,
+        code: step.exit_code,
+    });
+    if step.exit_code != 0 {
+        emit(AgentEvent::System(format!(
+            "{source} exited with status code {}",
+            step.exit_code
+        )));
+    }
+}
+
+/// Adapter-side replay settings: the shared player plus the role and task it answers for.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayBackendSpec {
+    pub player: Arc<ReplayPlayer>,
+    pub role: String,
+    pub task: Option<u64>,
+}
+
+pub fn apply_edits(edits: &[ReplayEdit], root: &Path) -> io::Result<()> {
+    for edit in edits {
+        let path = resolve_edit_path(root, &edit.path)?;
+        if edit.delete {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, edit.content.as_deref().unwrap_or_default())?;
+    }
+    Ok(())
+}
+
+fn resolve_edit_path(root: &Path, path: &str) -> io::Result<PathBuf> {
+    let path = Path::new(path.trim());
+    if path.as_os_str().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "replay edit is missing a path",
+        ));
+    }
+    Ok(root.join(path))
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/replay_tests.rs"]
+mod tests;
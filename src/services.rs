@@ -1,15 +1,26 @@
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::agent::{BackendKind, CodexAdapter};
+use crate::agent::{BackendKind, CodexAdapter, PermissionProfile};
 use crate::agent_models::{CodexAgentKind, CodexAgentModelRouting};
 use crate::app::App;
 use crate::artifact_io::{read_text_file, write_text_file};
+use crate::checkpoint;
+use crate::coverage;
 use crate::deterministic::TestRunnerAdapter;
-use crate::session_store::{SessionStore, TaskFailFileEntry};
+use crate::pass_diff::{self, PassBaseline};
+use crate::session_store::{
+    FlakyTestFileEntry, ReviewVoteFileEntry, SessionStore, TaskFailFileEntry,
+};
 use crate::subagents;
-use crate::workflow::{JobRun, StartedJob, WorkerRole, WorkflowFailure, WorkflowFailureKind};
+use crate::test_report::TestRunSummary;
+use crate::workflow::{
+    JobRun, RetryBudget, StartedJob, WorkerRole, WorkflowFailure, WorkflowFailureKind,
+};
+use crate::worktree::{self, MergeOutcome};
 
 #[derive(Debug, Clone)]
 pub struct TaskWriteBaseline {
@@ -20,7 +31,7 @@ pub struct TaskWriteBaseline {
 pub struct WorkerCompletionOutcome {
     pub failure_report_prompt: Option<String>,
     pub context_report_prompt: Option<String>,
-    pub started_job: Option<StartedJob>,
+    pub started_jobs: Vec<StartedJob>,
     pub warnings: Vec<String>,
 }
 
@@ -35,22 +46,32 @@ pub trait CoreOrchestrationService {
         &self,
         job: &StartedJob,
         worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-        active_worker_context_key: &mut Option<String>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
         test_runner_adapter: &TestRunnerAdapter,
         session_store: &SessionStore,
         model_routing: &CodexAgentModelRouting,
-    );
+    ) -> io::Result<()>;
 
     fn start_next_worker_job_if_any(
         &self,
         app: &mut App,
         worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-        active_worker_context_key: &mut Option<String>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
         test_runner_adapter: &TestRunnerAdapter,
         session_store: &SessionStore,
         model_routing: &CodexAgentModelRouting,
     ) -> io::Result<Option<StartedJob>>;
 
+    fn start_ready_worker_jobs(
+        &self,
+        app: &mut App,
+        worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
+        test_runner_adapter: &TestRunnerAdapter,
+        session_store: &SessionStore,
+        model_routing: &CodexAgentModelRouting,
+    ) -> io::Result<Vec<StartedJob>>;
+
     fn capture_tasks_baseline(&self, session_store: &SessionStore) -> Option<TaskWriteBaseline>;
 
     fn build_exhausted_loop_failures_prompt(
@@ -65,10 +86,11 @@ pub trait CoreOrchestrationService {
     fn complete_worker_cycle_and_start_next(
         &self,
         app: &mut App,
+        top_task_id: u64,
         success: bool,
         code: i32,
         worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-        active_worker_context_key: &mut Option<String>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
         test_runner_adapter: &TestRunnerAdapter,
         session_store: &SessionStore,
         model_routing: &CodexAgentModelRouting,
@@ -117,66 +139,156 @@ impl CoreOrchestrationService for DefaultCoreOrchestrationService {
         &self,
         job: &StartedJob,
         worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-        active_worker_context_key: &mut Option<String>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
         test_runner_adapter: &TestRunnerAdapter,
         session_store: &SessionStore,
         model_routing: &CodexAgentModelRouting,
-    ) {
+    ) -> io::Result<()> {
         match &job.run {
             JobRun::AgentPrompt(prompt) => {
                 let key = job
                     .parent_context_key
                     .clone()
                     .unwrap_or_else(|| format!("top:{}", job.top_task_id));
-                worker_agent_adapters
-                    .entry(key.clone())
-                    .or_insert_with(|| build_worker_adapter(model_routing, job.role));
-                let adapter = worker_agent_adapters
-                    .get(&key)
-                    .expect("worker adapter should be present after insertion");
+                let adapter = match worker_agent_adapters.entry(key.clone()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(
+                        build_worker_adapter(
+                            model_routing,
+                            job.role,
+                            job.model_profile.as_deref(),
+                            job.permissions,
+                            job.workspace_dir.clone(),
+                        )?
+                        .with_worker_context(job.top_task_id, &key),
+                    ),
+                };
                 adapter.send_prompt(prompt.clone());
-                *active_worker_context_key = Some(key);
+                active_worker_context_keys.insert(job.top_task_id, key);
             }
             JobRun::DeterministicTestRun => {
-                *active_worker_context_key = None;
-                let test_command = session_test_command(session_store);
-                test_runner_adapter.run_tests_with_command(test_command.as_deref());
+                active_worker_context_keys.remove(&job.top_task_id);
+                let test_command = job
+                    .test_command
+                    .clone()
+                    .or_else(|| session_test_command(session_store));
+                let test_report_path = session_test_report_path(session_store);
+                test_runner_adapter.run_tests_for_task(
+                    job.top_task_id,
+                    test_command.as_deref(),
+                    job.test_working_dir
+                        .as_deref()
+                        .or(job.workspace_dir.as_deref()),
+                    test_report_path.as_deref().map(Path::new),
+                );
+            }
+            JobRun::DeterministicCoverageRun => {
+                active_worker_context_keys.remove(&job.top_task_id);
+                let coverage_command = session_coverage_command(session_store);
+                test_runner_adapter.run_coverage_for_task(
+                    job.top_task_id,
+                    coverage_command.as_deref(),
+                    job.workspace_dir.as_deref(),
+                );
+            }
+            JobRun::DeterministicLintRun => {
+                active_worker_context_keys.remove(&job.top_task_id);
+                let lint_command = session_lint_command(session_store);
+                test_runner_adapter.run_lint_for_task(
+                    job.top_task_id,
+                    lint_command.as_deref(),
+                    job.workspace_dir.as_deref(),
+                );
             }
         }
+        Ok(())
     }
 
     fn start_next_worker_job_if_any(
         &self,
         app: &mut App,
         worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-        active_worker_context_key: &mut Option<String>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
         test_runner_adapter: &TestRunnerAdapter,
         session_store: &SessionStore,
         model_routing: &CodexAgentModelRouting,
     ) -> io::Result<Option<StartedJob>> {
         loop {
-            let Some(job) = self.claim_next_worker_job_and_persist_snapshot(app, session_store)?
+            let Some(mut job) =
+                self.claim_next_worker_job_and_persist_snapshot(app, session_store)?
             else {
                 return Ok(None);
             };
             if !app.tests_mode_enabled() && matches!(job.run, JobRun::DeterministicTestRun) {
                 // Safety net: deterministic test runs should not execute while tests mode is OFF.
-                let _ = app.on_worker_completed(false, 0);
+                let _ = app.on_worker_completed_for_task(job.top_task_id, false, 0);
                 self.persist_runtime_tasks_snapshot(app, session_store)?;
                 continue;
             }
-            self.dispatch_worker_job(
+            if app.max_parallel_tasks() > 1
+                && job.workspace_dir.is_none()
+                && job.role != WorkerRole::FinalAudit
+            {
+                match prepare_task_worktree(app, session_store, job.top_task_id) {
+                    Ok(workspace_dir) => {
+                        app.assign_task_workspace(job.top_task_id, workspace_dir.clone());
+                        job.workspace_dir = Some(workspace_dir);
+                    }
+                    Err(err) => app.push_agent_message(format!(
+                        "System: Could not create a git worktree for task #{}; running it in the main workspace: {err}",
+                        job.top_task_id
+                    )),
+                }
+            }
+            if let Some(label) = job.checkpoint_label.as_deref() {
+                record_pass_checkpoint(app, session_store, &job, label);
+            }
+            if let Some(label) = job.pass_label.as_deref() {
+                capture_pass_baseline(app, &job, label);
+            }
+            if let Err(err) = self.dispatch_worker_job(
                 &job,
                 worker_agent_adapters,
-                active_worker_context_key,
+                active_worker_context_keys,
                 test_runner_adapter,
                 session_store,
                 model_routing,
-            );
+            ) {
+                app.push_agent_message(format!(
+                    "System: Could not start the worker for task #{}: {err}",
+                    job.top_task_id
+                ));
+                let _ = app.on_worker_completed_for_task(job.top_task_id, false, 0);
+                self.persist_runtime_tasks_snapshot(app, session_store)?;
+                continue;
+            }
             return Ok(Some(job));
         }
     }
 
+    fn start_ready_worker_jobs(
+        &self,
+        app: &mut App,
+        worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
+        test_runner_adapter: &TestRunnerAdapter,
+        session_store: &SessionStore,
+        model_routing: &CodexAgentModelRouting,
+    ) -> io::Result<Vec<StartedJob>> {
+        let mut started = Vec::new();
+        while let Some(job) = self.start_next_worker_job_if_any(
+            app,
+            worker_agent_adapters,
+            active_worker_context_keys,
+            test_runner_adapter,
+            session_store,
+            model_routing,
+        )? {
+            started.push(job);
+        }
+        Ok(started)
+    }
+
     fn capture_tasks_baseline(&self, session_store: &SessionStore) -> Option<TaskWriteBaseline> {
         let tasks_json = read_text_file(session_store.tasks_file()).ok()?;
         Some(TaskWriteBaseline { tasks_json })
@@ -202,12 +314,17 @@ impl CoreOrchestrationService for DefaultCoreOrchestrationService {
                 kind: match failure.kind {
                     WorkflowFailureKind::Audit => "audit".to_string(),
                     WorkflowFailureKind::Test => "test".to_string(),
+                    WorkflowFailureKind::Lint => "lint".to_string(),
+                    WorkflowFailureKind::Merge => "merge".to_string(),
+                    WorkflowFailureKind::Timeout => "timeout".to_string(),
                 },
                 top_task_id: failure.top_task_id,
                 top_task_title: failure.top_task_title.clone(),
                 attempts: failure.attempts,
                 reason: failure.reason.clone(),
                 action_taken: failure.action_taken.clone(),
+                retry_budget: failure.retry_budget.as_ref().map(RetryBudget::describe),
+                checkpoint_refs: failure.checkpoint_refs.clone(),
                 created_at_epoch_secs: now_secs,
             })
             .collect();
@@ -230,10 +347,11 @@ impl CoreOrchestrationService for DefaultCoreOrchestrationService {
     fn complete_worker_cycle_and_start_next(
         &self,
         app: &mut App,
+        top_task_id: u64,
         success: bool,
         code: i32,
         worker_agent_adapters: &mut HashMap<String, CodexAdapter>,
-        active_worker_context_key: &mut Option<String>,
+        active_worker_context_keys: &mut HashMap<u64, String>,
         test_runner_adapter: &TestRunnerAdapter,
         session_store: &SessionStore,
         model_routing: &CodexAgentModelRouting,
@@ -244,8 +362,16 @@ impl CoreOrchestrationService for DefaultCoreOrchestrationService {
         let mut failure_report_prompt = None;
         let mut context_report_prompt = None;
 
-        let new_context_entries = app.on_worker_completed(success, code);
+        record_observed_pass_diff(app, session_store, top_task_id);
+        if let Some(report) = test_runner_adapter.take_task_report(top_task_id) {
+            record_flaky_tests(app, session_store, top_task_id, &report);
+            app.record_test_report(top_task_id, report);
+        }
+        let new_context_entries = app.on_worker_completed_for_task(top_task_id, success, code);
+        record_review_votes(app, session_store, &mut warnings);
+        merge_ready_task_worktrees(app);
         let exhausted_failures = app.drain_worker_failures();
+        offer_rollback_for_exhausted_tasks(app, &exhausted_failures);
         if !exhausted_failures.is_empty() {
             match self.build_exhausted_loop_failures_prompt(
                 session_store,
@@ -274,27 +400,27 @@ impl CoreOrchestrationService for DefaultCoreOrchestrationService {
             ));
         }
 
-        let started_job = match self.start_next_worker_job_if_any(
+        let started_jobs = match self.start_ready_worker_jobs(
             app,
             worker_agent_adapters,
-            active_worker_context_key,
+            active_worker_context_keys,
             test_runner_adapter,
             session_store,
             model_routing,
         ) {
-            Ok(job) => job,
+            Ok(jobs) => jobs,
             Err(err) => {
                 warnings.push(format!(
                     "Failed to persist runtime task status to tasks.json: {err}"
                 ));
-                None
+                Vec::new()
             }
         };
 
         WorkerCompletionOutcome {
             failure_report_prompt,
             context_report_prompt,
-            started_job,
+            started_jobs,
             warnings,
         }
     }
@@ -352,7 +478,7 @@ impl UiPromptService for DefaultUiPromptService {
 }
 
 impl DefaultCoreOrchestrationService {
-    fn persist_runtime_tasks_snapshot(
+    pub fn persist_runtime_tasks_snapshot(
         &self,
         app: &App,
         session_store: &SessionStore,
@@ -363,18 +489,32 @@ impl DefaultCoreOrchestrationService {
     }
 }
 
-fn worker_role_agent_kind(role: WorkerRole) -> CodexAgentKind {
+pub fn worker_role_agent_kind(role: WorkerRole) -> CodexAgentKind {
     match role {
         WorkerRole::Implementor => CodexAgentKind::WorkerImplementor,
-        WorkerRole::Auditor => CodexAgentKind::WorkerAuditor,
+        // Custom roles run on the auditor profile unless their config names a profile.
+        WorkerRole::Auditor | WorkerRole::Custom => CodexAgentKind::WorkerAuditor,
         WorkerRole::TestWriter => CodexAgentKind::WorkerTestWriter,
         WorkerRole::FinalAudit => CodexAgentKind::WorkerFinalAudit,
-        WorkerRole::TestRunner => CodexAgentKind::WorkerTestWriter,
+        WorkerRole::TestRunner | WorkerRole::LintRunner | WorkerRole::CoverageRunner => {
+            CodexAgentKind::WorkerTestWriter
+        }
     }
 }
 
-fn build_worker_adapter(model_routing: &CodexAgentModelRouting, role: WorkerRole) -> CodexAdapter {
-    let mut config = model_routing.base_command_config();
+fn build_worker_adapter(
+    model_routing: &CodexAgentModelRouting,
+    role: WorkerRole,
+    model_profile: Option<&str>,
+    permissions: Option<PermissionProfile>,
+    working_dir: Option<PathBuf>,
+) -> io::Result<CodexAdapter> {
+    let kind = worker_role_agent_kind(role);
+    let mut config = model_routing.command_config_for(kind, model_routing.selected_backend())?;
+    if let Some(permissions) = permissions {
+        config.permissions = permissions;
+    }
+    config.working_dir = working_dir;
     config.output_mode = if matches!(config.backend_kind(), BackendKind::Claude) {
         crate::agent::AdapterOutputMode::JsonAssistantOnly
     } else {
@@ -382,15 +522,285 @@ fn build_worker_adapter(model_routing: &CodexAgentModelRouting, role: WorkerRole
     };
     config.persistent_session = true;
     config.skip_reader_join_after_wait = true;
-    let profile = model_routing.profile_for(worker_role_agent_kind(role));
-    if matches!(config.backend_kind(), BackendKind::Codex) {
+    let profile = model_profile
+        .and_then(|label| model_routing.named_profile(label))
+        .unwrap_or_else(|| model_routing.profile_for(kind));
+    if config.accepts_model_profiles() {
         config.model = Some(profile.model.clone());
         config.model_reasoning_effort = profile.thinking_effort;
     } else {
         config.model = None;
         config.model_reasoning_effort = None;
     }
-    CodexAdapter::with_config(config)
+    Ok(CodexAdapter::with_config(config))
+}
+
+fn prepare_task_worktree(
+    app: &App,
+    session_store: &SessionStore,
+    top_task_id: u64,
+) -> io::Result<PathBuf> {
+    let task_key = app
+        .top_task_key(top_task_id)
+        .unwrap_or_else(|| format!("internal-{top_task_id}"));
+    let repo_root = worktree::repo_root(&std::env::current_dir()?)?;
+    worktree::ensure_task_worktree(&repo_root, session_store.session_dir(), &task_key)
+}
+
+/// Tells the user which tasks that ran out of retries can be restored to their pre-task
+/// checkpoint. Merge failures keep their finished work in the worktree, so they are not offered.
+fn offer_rollback_for_exhausted_tasks(app: &mut App, failures: &[WorkflowFailure]) {
+    let mut offered = HashSet::new();
+    for failure in failures {
+        if failure.kind == WorkflowFailureKind::Merge || !offered.insert(failure.top_task_id) {
+            continue;
+        }
+        let Some(checkpoint_ref) = failure.checkpoint_refs.first() else {
+            continue;
+        };
+        let task_key = app
+            .top_task_key(failure.top_task_id)
+            .unwrap_or_else(|| format!("internal-{}", failure.top_task_id));
+        app.push_agent_message(format!(
+            "System: Task #{} \"{}\" ran out of retries. Run /rollback {task_key} once execution is idle to restore the workspace to its pre-task checkpoint {checkpoint_ref}.",
+            failure.top_task_id, failure.top_task_title
+        ));
+    }
+}
+
+fn record_pass_checkpoint(
+    app: &mut App,
+    session_store: &SessionStore,
+    job: &StartedJob,
+    label: &str,
+) {
+    let workspace = match &job.workspace_dir {
+        Some(dir) => dir.clone(),
+        // Outside a git repository there is nothing to snapshot; run the pass as before.
+        None => match std::env::current_dir().and_then(|cwd| worktree::repo_root(&cwd)) {
+            Ok(root) => root,
+            Err(_) => return,
+        },
+    };
+    let task_key = app
+        .top_task_key(job.top_task_id)
+        .unwrap_or_else(|| format!("internal-{}", job.top_task_id));
+    match checkpoint::create_checkpoint(&workspace, &session_store.session_id(), &task_key, label) {
+        Ok(checkpoint_ref) => app.record_task_checkpoint(job.top_task_id, checkpoint_ref),
+        Err(err) => app.push_agent_message(format!(
+            "System: Could not record a git checkpoint before task #{} {label}; continuing without one: {err}",
+            job.top_task_id
+        )),
+    }
+}
+
+fn capture_pass_baseline(app: &mut App, job: &StartedJob, label: &str) {
+    let workspace = match &job.workspace_dir {
+        Some(dir) => dir.clone(),
+        None => match std::env::current_dir() {
+            Ok(cwd) => worktree::repo_root(&cwd).unwrap_or(cwd),
+            Err(_) => return,
+        },
+    };
+    match pass_diff::capture_snapshot(&workspace) {
+        Ok(snapshot) => app.set_pass_baseline(
+            job.top_task_id,
+            PassBaseline {
+                workspace,
+                label: label.to_string(),
+                snapshot,
+            },
+        ),
+        Err(err) => app.push_agent_message(format!(
+            "System: Could not snapshot the workspace before task #{} {label}; its audit will rely on the worker's own changed-files summary: {err}",
+            job.top_task_id
+        )),
+    }
+}
+
+/// Diffs the workspace against the snapshot taken when the pass started and saves the result
+/// under the session dir so audits see what actually changed.
+fn record_observed_pass_diff(app: &mut App, session_store: &SessionStore, top_task_id: u64) {
+    let Some(baseline) = app.take_pass_baseline(top_task_id) else {
+        return;
+    };
+    let mut diff = match pass_diff::diff_since(&baseline.workspace, &baseline.snapshot) {
+        Ok(diff) => diff,
+        Err(err) => {
+            app.push_agent_message(format!(
+                "System: Could not diff the workspace after task #{top_task_id} {}: {err}",
+                baseline.label
+            ));
+            return;
+        }
+    };
+    let task_key = app
+        .top_task_key(top_task_id)
+        .unwrap_or_else(|| format!("internal-{top_task_id}"));
+    match pass_diff::write_pass_diff(
+        session_store.session_dir(),
+        &task_key,
+        &baseline.label,
+        &diff,
+    ) {
+        Ok(path) => diff.diff_file = Some(path),
+        Err(err) => app.push_agent_message(format!(
+            "System: Could not save the task #{top_task_id} {} diff: {err}",
+            baseline.label
+        )),
+    }
+    app.record_pass_diff(top_task_id, diff);
+}
+
+fn record_flaky_tests(
+    app: &mut App,
+    session_store: &SessionStore,
+    top_task_id: u64,
+    report: &TestRunSummary,
+) {
+    if report.flaky.is_empty() {
+        return;
+    }
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let entries: Vec<FlakyTestFileEntry> = report
+        .flaky
+        .iter()
+        .map(|test| FlakyTestFileEntry {
+            top_task_id,
+            test_name: test.name.clone(),
+            file: test.file.clone(),
+            message: test.message.clone(),
+            created_at_epoch_secs: now_secs,
+        })
+        .collect();
+    let names = entries
+        .iter()
+        .map(|entry| entry.test_name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    match session_store.append_flaky_tests(&entries) {
+        Ok(()) => app.push_agent_message(format!(
+            "System: Task #{top_task_id} has {} flaky test(s) that passed on rerun: {names}. Recorded in {}.",
+            entries.len(),
+            session_store.flaky_tests_file().display()
+        )),
+        Err(err) => app.push_agent_message(format!(
+            "System: Task #{top_task_id} has {} flaky test(s) that passed on rerun: {names}. Could not record them: {err}",
+            entries.len()
+        )),
+    }
+}
+
+fn record_review_votes(app: &mut App, session_store: &SessionStore, warnings: &mut Vec<String>) {
+    let votes = app.drain_review_votes();
+    if votes.is_empty() {
+        return;
+    }
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let entries: Vec<ReviewVoteFileEntry> = votes
+        .into_iter()
+        .map(|vote| ReviewVoteFileEntry {
+            top_task_id: vote.top_task_id,
+            auditor_task_id: vote.auditor_task_id,
+            audit_pass: vote.pass,
+            reviewer: vote.reviewer,
+            reviewers: vote.reviewers,
+            model_profile: vote.model_profile,
+            passed: vote.passed,
+            feedback: vote.feedback,
+            created_at_epoch_secs: now_secs,
+        })
+        .collect();
+    if let Err(err) = session_store.append_review_votes(&entries) {
+        warnings.push(format!("Failed to append review-votes.json: {err}"));
+    }
+}
+
+fn merge_ready_task_worktrees(app: &mut App) {
+    let ready = app.take_ready_merges();
+    if ready.is_empty() {
+        return;
+    }
+    let repo_root = match std::env::current_dir().and_then(|cwd| worktree::repo_root(&cwd)) {
+        Ok(root) => root,
+        Err(err) => {
+            for merge in ready {
+                app.record_merge_failure(
+                    merge.top_task_id,
+                    format!("Could not resolve the main repository for merge-back: {err}"),
+                    format!(
+                        "Worktree {} kept for manual merge.",
+                        merge.workspace_dir.display()
+                    ),
+                );
+            }
+            return;
+        }
+    };
+    for merge in ready {
+        let task_key = app
+            .top_task_key(merge.top_task_id)
+            .unwrap_or_else(|| format!("internal-{}", merge.top_task_id));
+        let branch = worktree::task_branch_name(&task_key);
+        match worktree::merge_task_worktree(
+            &repo_root,
+            &merge.workspace_dir,
+            &task_key,
+            &merge.top_task_title,
+        ) {
+            Ok(MergeOutcome::Merged) => {
+                app.push_agent_message(format!(
+                    "System: Merged task #{} ({branch}) back into the main workspace.",
+                    merge.top_task_id
+                ));
+                app.mark_task_merged(merge.top_task_id);
+            }
+            Ok(MergeOutcome::NothingToMerge) => {
+                app.push_agent_message(format!(
+                    "System: Task #{} finished without changes to merge.",
+                    merge.top_task_id
+                ));
+                app.mark_task_merged(merge.top_task_id);
+            }
+            Ok(MergeOutcome::Conflict { files }) => app.record_merge_failure(
+                merge.top_task_id,
+                if files.is_empty() {
+                    "Merge-back reported a conflict.".to_string()
+                } else {
+                    format!("Merge conflict in: {}", files.join(", "))
+                },
+                format!(
+                    "Merge aborted; branch {branch} and worktree {} kept for manual resolution.",
+                    merge.workspace_dir.display()
+                ),
+            ),
+            Ok(MergeOutcome::DirtyWorkspace { files }) => app.record_merge_failure(
+                merge.top_task_id,
+                format!(
+                    "Main workspace has uncommitted changes in: {}",
+                    files.join(", ")
+                ),
+                format!(
+                    "Merge skipped; commit or stash those changes, then merge branch {branch} from worktree {} manually.",
+                    merge.workspace_dir.display()
+                ),
+            ),
+            Err(err) => app.record_merge_failure(
+                merge.top_task_id,
+                format!("Merge-back failed: {err}"),
+                format!(
+                    "Branch {branch} and worktree {} kept for manual merge.",
+                    merge.workspace_dir.display()
+                ),
+            ),
+        }
+    }
 }
 
 fn session_test_command(session_store: &SessionStore) -> Option<String> {
@@ -400,6 +810,37 @@ fn session_test_command(session_store: &SessionStore) -> Option<String> {
         .and_then(|meta| normalize_test_command(meta.test_command))
 }
 
+fn session_lint_command(session_store: &SessionStore) -> Option<String> {
+    session_store
+        .read_session_meta()
+        .ok()
+        .and_then(|meta| normalize_test_command(meta.lint_command))
+}
+
+fn session_coverage_command(session_store: &SessionStore) -> Option<String> {
+    session_store
+        .read_session_meta()
+        .ok()
+        .and_then(|meta| normalize_test_command(meta.coverage_command))
+}
+
+/// The coverage gate threshold in percent, or `None` when meta.json has no coverage command.
+pub fn session_coverage_threshold(session_store: &SessionStore) -> Option<f64> {
+    let meta = session_store.read_session_meta().ok()?;
+    normalize_test_command(meta.coverage_command)?;
+    Some(
+        meta.coverage_threshold
+            .map_or(coverage::DEFAULT_COVERAGE_THRESHOLD, f64::from),
+    )
+}
+
+fn session_test_report_path(session_store: &SessionStore) -> Option<String> {
+    session_store
+        .read_session_meta()
+        .ok()
+        .and_then(|meta| normalize_test_command(meta.test_report_path))
+}
+
 fn normalize_test_command(value: Option<String>) -> Option<String> {
     value
         .map(|command| command.trim().to_string())
@@ -1,19 +1,26 @@
+use std::collections::BTreeMap;
 use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
+use crate::agent::PermissionProfile;
 use crate::artifact_io::{
     ensure_default_metaagent_config, home_dir, read_text_file, write_text_file,
     write_text_file_if_missing,
 };
+use crate::workflow::{
+    CustomRole, JobTimeout, JobTimeouts, RetryBudgets, RoleFailurePolicy, RoleStage, RoleVerdict,
+};
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct MetaAgentConfig {
     pub storage: StorageConfig,
+    pub workflow: WorkflowConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -22,14 +29,244 @@ pub struct StorageConfig {
     pub root_dir: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WorkflowConfig {
+    pub max_parallel_tasks: usize,
+    pub checkpoints: bool,
+    /// Reruns of failing tests before a failure counts as consistent; 0 disables the check.
+    pub flaky_test_reruns: u8,
+    pub retries: RetriesConfig,
+    pub timeouts: TimeoutsConfig,
+    /// Extra worker roles keyed by name, from `[workflow.roles.<name>]`.
+    pub roles: BTreeMap<String, WorkerRoleConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetriesConfig {
+    pub audit: u8,
+    pub test: u8,
+    pub final_audit: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TimeoutsConfig {
+    pub implementor: RoleTimeoutConfig,
+    pub auditor: RoleTimeoutConfig,
+    pub test_writer: RoleTimeoutConfig,
+    pub test_runner: RoleTimeoutConfig,
+    pub final_audit: RoleTimeoutConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WorkerRoleConfig {
+    pub title: Option<String>,
+    /// What the role checks or does; filled into the shared `worker_role` prompt template.
+    pub instructions: String,
+    /// Full prompt template for this role, using the `worker_role` placeholders.
+    pub prompt: Option<String>,
+    pub profile: Option<String>,
+    /// `read-only`, `workspace-write` or `full`.
+    pub permissions: Option<String>,
+    pub verdict: RoleVerdict,
+    pub stage: RoleStage,
+    pub on_fail: RoleFailurePolicy,
+    pub max_retries: Option<u8>,
+    pub auto: bool,
+}
+
+/// Limits in minutes; 0 disables the limit.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct RoleTimeoutConfig {
+    pub max_minutes: u64,
+    pub stall_minutes: u64,
+}
+
 impl Default for MetaAgentConfig {
     fn default() -> Self {
         Self {
             storage: StorageConfig::default(),
+            workflow: WorkflowConfig::default(),
+        }
+    }
+}
+
+impl Default for WorkflowConfig {
+    fn default() -> Self {
+        Self {
+            max_parallel_tasks: 1,
+            checkpoints: true,
+            flaky_test_reruns: 2,
+            retries: RetriesConfig::default(),
+            timeouts: TimeoutsConfig::default(),
+            roles: BTreeMap::new(),
+        }
+    }
+}
+
+impl WorkflowConfig {
+    /// Validates `[workflow.roles]` into the roles the workflow schedules, in name order.
+    pub fn custom_roles(&self) -> Result<Vec<CustomRole>, String> {
+        self.roles
+            .iter()
+            .map(|(name, role)| role.to_role(name))
+            .collect()
+    }
+}
+
+impl Default for WorkerRoleConfig {
+    fn default() -> Self {
+        Self {
+            title: None,
+            instructions: String::new(),
+            prompt: None,
+            profile: None,
+            permissions: None,
+            verdict: RoleVerdict::default(),
+            stage: RoleStage::default(),
+            on_fail: RoleFailurePolicy::default(),
+            max_retries: None,
+            auto: true,
         }
     }
 }
 
+impl WorkerRoleConfig {
+    fn to_role(&self, name: &str) -> Result<CustomRole, String> {
+        let trimmed = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToString::to_string)
+        };
+        let name = name.trim();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_')
+        {
+            return Err(format!(
+                "[workflow.roles.{name}] role names may only use lowercase letters, digits and underscores"
+            ));
+        }
+        let instructions = self.instructions.trim().to_string();
+        let prompt = trimmed(self.prompt.as_deref());
+        if instructions.is_empty() && prompt.is_none() {
+            return Err(format!(
+                "[workflow.roles.{name}] must set instructions or prompt"
+            ));
+        }
+        if self.max_retries == Some(0) {
+            return Err(format!(
+                "[workflow.roles.{name}] max_retries must be at least 1"
+            ));
+        }
+        let permissions = match trimmed(self.permissions.as_deref()) {
+            None => None,
+            Some(label) => Some(PermissionProfile::from_label(&label).ok_or_else(|| {
+                format!(
+                    "[workflow.roles.{name}] permissions must be \"read-only\", \"workspace-write\" or \"full\", got {label:?}"
+                )
+            })?),
+        };
+        Ok(CustomRole {
+            name: name.to_string(),
+            title: trimmed(self.title.as_deref()).unwrap_or_else(|| name.replace('_', " ")),
+            instructions,
+            prompt,
+            profile: trimmed(self.profile.as_deref()),
+            permissions,
+            verdict: self.verdict,
+            stage: self.stage,
+            on_fail: self.on_fail,
+            max_retries: self.max_retries,
+            auto: self.auto,
+        })
+    }
+}
+
+impl Default for RetriesConfig {
+    fn default() -> Self {
+        let defaults = RetryBudgets::default();
+        Self {
+            audit: defaults.audit,
+            test: defaults.test,
+            final_audit: defaults.final_audit,
+        }
+    }
+}
+
+impl RetriesConfig {
+    /// Validates `[workflow.retries]`; a budget of 0 would leave a failing task nothing to retry.
+    pub fn to_budgets(&self) -> Result<RetryBudgets, String> {
+        for (name, budget) in [
+            ("audit", self.audit),
+            ("test", self.test),
+            ("final_audit", self.final_audit),
+        ] {
+            if budget == 0 {
+                return Err(format!("[workflow.retries] {name} must be at least 1"));
+            }
+        }
+        Ok(RetryBudgets {
+            audit: self.audit,
+            test: self.test,
+            final_audit: self.final_audit,
+        })
+    }
+}
+
+impl Default for TimeoutsConfig {
+    fn default() -> Self {
+        let defaults = JobTimeouts::default();
+        Self {
+            implementor: RoleTimeoutConfig::from_timeout(defaults.implementor),
+            auditor: RoleTimeoutConfig::from_timeout(defaults.auditor),
+            test_writer: RoleTimeoutConfig::from_timeout(defaults.test_writer),
+            test_runner: RoleTimeoutConfig::from_timeout(defaults.test_runner),
+            final_audit: RoleTimeoutConfig::from_timeout(defaults.final_audit),
+        }
+    }
+}
+
+impl TimeoutsConfig {
+    pub fn to_timeouts(&self) -> JobTimeouts {
+        JobTimeouts {
+            implementor: self.implementor.to_timeout(),
+            auditor: self.auditor.to_timeout(),
+            test_writer: self.test_writer.to_timeout(),
+            test_runner: self.test_runner.to_timeout(),
+            final_audit: self.final_audit.to_timeout(),
+        }
+    }
+}
+
+impl Default for RoleTimeoutConfig {
+    fn default() -> Self {
+        Self::from_timeout(JobTimeouts::default().implementor)
+    }
+}
+
+impl RoleTimeoutConfig {
+    fn from_timeout(timeout: JobTimeout) -> Self {
+        let minutes = |limit: Option<std::time::Duration>| {
+            limit.map(|limit| limit.as_secs() / 60).unwrap_or(0)
+        };
+        Self {
+            max_minutes: minutes(timeout.max_runtime),
+            stall_minutes: minutes(timeout.stall_after),
+        }
+    }
+
+    pub fn to_timeout(self) -> JobTimeout {
+        JobTimeout::minutes(self.max_minutes, self.stall_minutes)
+    }
+}
+
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
@@ -38,7 +275,7 @@ impl Default for StorageConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlannerTaskFileEntry {
     #[serde(deserialize_with = "deserialize_id_to_string")]
     pub id: String,
@@ -54,6 +291,55 @@ pub struct PlannerTaskFileEntry {
     #[serde(default, deserialize_with = "deserialize_optional_id_to_string")]
     pub parent_id: Option<String>,
     pub order: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_id_list_to_strings")]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub max_retries: Option<u8>,
+    /// Test command for a `test_runner` task, overriding meta.json `test_command`.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Directory the test command runs in, relative to the task workspace.
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    /// Fans an `auditor` task out to several reviewers whose verdicts are combined by quorum.
+    #[serde(default)]
+    pub review_quorum: Option<ReviewQuorumFileEntry>,
+    /// Name of the `[workflow.roles]` entry a `role` task runs.
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+#[cfg(test)]
+impl PlannerTaskFileEntry {
+    /// A pending entry titled after its id with `"<id> details"`; tests override the rest with
+    /// struct update syntax.
+    pub(crate) fn test_entry(
+        id: &str,
+        kind: PlannerTaskKindFile,
+        parent_id: Option<&str>,
+        order: u32,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            title: id.to_string(),
+            details: format!("{id} details"),
+            kind,
+            parent_id: parent_id.map(ToString::to_string),
+            order: Some(order),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReviewQuorumFileEntry {
+    pub reviewers: u8,
+    /// Passing verdicts needed for the audit to pass.
+    pub required: u8,
+    /// Model profile per reviewer, reused in order when shorter than `reviewers`. Empty uses
+    /// the `worker_auditor` profile for every reviewer.
+    #[serde(default)]
+    pub profiles: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -65,7 +351,10 @@ pub enum PlannerTaskKindFile {
     Auditor,
     TestWriter,
     TestRunner,
+    LintRunner,
     FinalAudit,
+    Review,
+    Role,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -76,6 +365,7 @@ pub enum PlannerTaskStatusFile {
     InProgress,
     NeedsChanges,
     Done,
+    Skipped,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -92,6 +382,9 @@ struct SessionMetadata {
     workspace: String,
     created_at_epoch_secs: u64,
     last_used_epoch_secs: u64,
+    execution_paused: bool,
+    stop_requested: bool,
+    review_decision: Option<ReviewDecision>,
 }
 
 impl Default for SessionMetadata {
@@ -100,10 +393,43 @@ impl Default for SessionMetadata {
             workspace: String::new(),
             created_at_epoch_secs: 0,
             last_used_epoch_secs: 0,
+            execution_paused: false,
+            stop_requested: false,
+            review_decision: None,
         }
     }
 }
 
+// Persisted in metadata.json so a paused run stays paused across restarts and so
+// `api session control-execution` can steer a running TUI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionControl {
+    pub paused: bool,
+    pub stop_requested: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataFingerprint {
+    modified: SystemTime,
+    len: u64,
+    content_hash: u64,
+}
+
+// Written by `api session review` and consumed by the running TUI on its next tick.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum ReviewDecision {
+    Approve {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<String>,
+    },
+    Reject {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        task_id: Option<String>,
+        feedback: String,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SessionListEntry {
     pub session_dir: PathBuf,
@@ -122,6 +448,15 @@ pub struct SessionMetaFile {
     pub stack_description: String,
     #[serde(default)]
     pub test_command: Option<String>,
+    #[serde(default)]
+    pub lint_command: Option<String>,
+    #[serde(default)]
+    pub test_report_path: Option<String>,
+    #[serde(default)]
+    pub coverage_command: Option<String>,
+    /// Minimum line coverage of the lines a task changed, in whole percent.
+    #[serde(default)]
+    pub coverage_threshold: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -132,6 +467,38 @@ pub struct TaskFailFileEntry {
     pub attempts: u8,
     pub reason: String,
     pub action_taken: String,
+    #[serde(default)]
+    pub retry_budget: Option<String>,
+    #[serde(default)]
+    pub checkpoint_refs: Vec<String>,
+    pub created_at_epoch_secs: u64,
+}
+
+/// A test that failed and then passed when re-run, so it did not trigger a retry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FlakyTestFileEntry {
+    pub top_task_id: u64,
+    pub test_name: String,
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub message: String,
+    pub created_at_epoch_secs: u64,
+}
+
+/// One reviewer's verdict in a quorum audit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReviewVoteFileEntry {
+    pub top_task_id: u64,
+    pub auditor_task_id: String,
+    pub audit_pass: u8,
+    pub reviewer: u8,
+    pub reviewers: u8,
+    #[serde(default)]
+    pub model_profile: Option<String>,
+    pub passed: bool,
+    #[serde(default)]
+    pub feedback: String,
     pub created_at_epoch_secs: u64,
 }
 
@@ -142,6 +509,8 @@ pub struct SessionStore {
     planner_file: PathBuf,
     context_file: PathBuf,
     task_fails_file: PathBuf,
+    flaky_tests_file: PathBuf,
+    review_votes_file: PathBuf,
     project_info_file: PathBuf,
     session_meta_file: PathBuf,
     metadata_file: PathBuf,
@@ -168,6 +537,8 @@ impl SessionStore {
             planner_file: session_dir.join("planner.md"),
             context_file: session_dir.join("rolling_context.json"),
             task_fails_file: session_dir.join("task-fails.json"),
+            flaky_tests_file: session_dir.join("flaky-tests.json"),
+            review_votes_file: session_dir.join("review-votes.json"),
             project_info_file: session_dir.join("project-info.md"),
             session_meta_file: session_dir.join("meta.json"),
             metadata_file: session_dir.join("metadata.json"),
@@ -202,6 +573,11 @@ impl SessionStore {
         &self.session_dir
     }
 
+    /// The session directory's name, which identifies the session in git checkpoint refs.
+    pub fn session_id(&self) -> String {
+        session_id_for_dir(&self.session_dir)
+    }
+
     pub fn tasks_file(&self) -> &Path {
         &self.tasks_file
     }
@@ -258,6 +634,44 @@ impl SessionStore {
         write_text_file(&self.task_fails_file, &text)
     }
 
+    pub fn flaky_tests_file(&self) -> &Path {
+        &self.flaky_tests_file
+    }
+
+    pub fn read_flaky_tests(&self) -> io::Result<Vec<FlakyTestFileEntry>> {
+        let text = read_text_file(&self.flaky_tests_file)?;
+        let parsed = serde_json::from_str::<Vec<FlakyTestFileEntry>>(&text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(parsed)
+    }
+
+    pub fn append_flaky_tests(&self, entries: &[FlakyTestFileEntry]) -> io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut existing = self.read_flaky_tests().unwrap_or_default();
+        existing.extend_from_slice(entries);
+        let text = serde_json::to_string_pretty(&existing).map_err(io::Error::other)?;
+        write_text_file(&self.flaky_tests_file, &text)
+    }
+
+    pub fn read_review_votes(&self) -> io::Result<Vec<ReviewVoteFileEntry>> {
+        let text = read_text_file(&self.review_votes_file)?;
+        let parsed = serde_json::from_str::<Vec<ReviewVoteFileEntry>>(&text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(parsed)
+    }
+
+    pub fn append_review_votes(&self, entries: &[ReviewVoteFileEntry]) -> io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut existing = self.read_review_votes().unwrap_or_default();
+        existing.extend_from_slice(entries);
+        let text = serde_json::to_string_pretty(&existing).map_err(io::Error::other)?;
+        write_text_file(&self.review_votes_file, &text)
+    }
+
     pub fn project_info_file(&self) -> &Path {
         &self.project_info_file
     }
@@ -280,17 +694,73 @@ impl SessionStore {
             .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
     }
 
+    /// Identifies the current metadata.json contents, so pollers can skip parsing an unchanged
+    /// file. The content hash catches rewrites that land within the filesystem's mtime
+    /// granularity and keep the file length.
+    pub fn metadata_fingerprint(&self) -> Option<MetadataFingerprint> {
+        let metadata = fs::metadata(&self.metadata_file).ok()?;
+        let contents = fs::read(&self.metadata_file).ok()?;
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        Some(MetadataFingerprint {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+            content_hash: hasher.finish(),
+        })
+    }
+
+    pub fn read_execution_control(&self) -> ExecutionControl {
+        read_metadata_file(&self.metadata_file)
+            .map(|metadata| ExecutionControl {
+                paused: metadata.execution_paused,
+                stop_requested: metadata.stop_requested,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn write_execution_control(&self, control: ExecutionControl) -> io::Result<()> {
+        let mut metadata = read_metadata_file(&self.metadata_file).unwrap_or_default();
+        metadata.execution_paused = control.paused;
+        metadata.stop_requested = control.stop_requested;
+        let text = serde_json::to_string_pretty(&metadata).map_err(io::Error::other)?;
+        write_text_file(&self.metadata_file, &text)
+    }
+
+    pub fn write_review_decision(&self, decision: ReviewDecision) -> io::Result<()> {
+        let mut metadata = read_metadata_file(&self.metadata_file).unwrap_or_default();
+        metadata.review_decision = Some(decision);
+        let text = serde_json::to_string_pretty(&metadata).map_err(io::Error::other)?;
+        write_text_file(&self.metadata_file, &text)
+    }
+
+    pub fn take_review_decision(&self) -> io::Result<Option<ReviewDecision>> {
+        let Ok(mut metadata) = read_metadata_file(&self.metadata_file) else {
+            return Ok(None);
+        };
+        let Some(decision) = metadata.review_decision.take() else {
+            return Ok(None);
+        };
+        let text = serde_json::to_string_pretty(&metadata).map_err(io::Error::other)?;
+        write_text_file(&self.metadata_file, &text)?;
+        Ok(Some(decision))
+    }
+
     fn bootstrap_files(&self, cwd: &Path, now_secs: u64) -> io::Result<()> {
         write_text_file_if_missing(&self.tasks_file, "[]\n")?;
         write_text_file_if_missing(&self.planner_file, "")?;
         write_text_file_if_missing(&self.context_file, "[]\n")?;
         write_text_file_if_missing(&self.task_fails_file, "[]\n")?;
+        write_text_file_if_missing(&self.flaky_tests_file, "[]\n")?;
+        write_text_file_if_missing(&self.review_votes_file, "[]\n")?;
         write_text_file_if_missing(&self.project_info_file, "")?;
         if write_text_file_if_missing(&self.metadata_file, "")? {
             let metadata = SessionMetadata {
                 workspace: cwd.to_string_lossy().to_string(),
                 created_at_epoch_secs: now_secs,
                 last_used_epoch_secs: now_secs,
+                execution_paused: false,
+                stop_requested: false,
+                review_decision: None,
             };
             let text = serde_json::to_string_pretty(&metadata).map_err(io::Error::other)?;
             write_text_file(&self.metadata_file, &text)?;
@@ -304,6 +774,8 @@ impl SessionStore {
             planner_file: session_dir.join("planner.md"),
             context_file: session_dir.join("rolling_context.json"),
             task_fails_file: session_dir.join("task-fails.json"),
+            flaky_tests_file: session_dir.join("flaky-tests.json"),
+            review_votes_file: session_dir.join("review-votes.json"),
             project_info_file: session_dir.join("project-info.md"),
             session_meta_file: session_dir.join("meta.json"),
             metadata_file: session_dir.join("metadata.json"),
@@ -325,6 +797,17 @@ impl SessionStore {
     }
 }
 
+pub fn session_id_for_dir(session_dir: &Path) -> String {
+    session_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "session".to_string())
+}
+
+pub fn load_global_workflow_config() -> io::Result<WorkflowConfig> {
+    Ok(load_config()?.workflow)
+}
+
 pub fn load_global_tests_mode_enabled() -> io::Result<bool> {
     let config_file = ensure_default_metaagent_config()?;
     let text = read_text_file(&config_file)?;
@@ -504,6 +987,21 @@ where
     }))
 }
 
+fn deserialize_id_list_to_strings<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<Vec<IdInput>>::deserialize(deserializer)?;
+    Ok(value
+        .unwrap_or_default()
+        .into_iter()
+        .map(|v| match v {
+            IdInput::Str(s) => s,
+            IdInput::Num(n) => n.to_string(),
+        })
+        .collect())
+}
+
 fn deserialize_docs_compat<'de, D>(
     deserializer: D,
 ) -> Result<Vec<PlannerTaskDocFileEntry>, D::Error>
@@ -1,7 +1,10 @@
+use crate::prompt_templates::{self, PromptTemplate};
+
 pub(crate) fn build_master_prompt(
     tasks_file: &str,
     workflow_prompt: &str,
     tests_mode_enabled: bool,
+    custom_roles: &str,
 ) -> String {
     let tests_policy = if tests_mode_enabled {
         "- Tests mode is ON. Test requirements are enabled for planning.\n\
@@ -22,55 +25,31 @@ pub(crate) fn build_master_prompt(
            Every test_writer must include at least one test_runner subtask.\n\
            For tests-absent/unknown projects, create testing setup first:\n\
            Create a dedicated testing-setup top-level task at the earliest position (before feature work that depends on tests).\n\
+           List the testing-setup task id in `depends_on` of every top-level task whose tests rely on it.\n\
            That setup task must include an implementor + auditor flow where implementor sets up the test framework/tooling and updates session meta.json test_command to the exact bash-runnable command string.\n\
            Do not add non-setup test_writer or test_runner branches until after that setup task in task order."
-            .to_string()
     } else {
         "- Tests mode is OFF. Testing is globally disabled for this planning run.\n\
          - Prohibition: do not request, create, modify, or schedule any testing work.\n\
          - Do not create or update test_writer/test_runner tasks.\n\
          - Do not ask testing-decision questions; treat testing as explicitly out of scope.\n\
          - Keep implementor and auditor tasks focused on non-test implementation concerns only."
-            .to_string()
     };
-    format!(
-        "{}\n\
-         Planner storage:\n\
-         - Read and update this JSON file directly: {tasks_file}\n\
-         - Never modify project workspace/source files directly.\n\
-         - You may only edit files in the current meta-agent session directory (including tasks/context artifacts).\n\
-         - If user requests more work after existing tasks are completed, append new tasks; do not delete completed task history.\n\
-         - After task list updates are ready, tell the user `/start` is ready to run.\n\
-         - `/start` always resumes from the last unfinished task.\n\
-         - File schema: array of objects with fields id, title, details, docs, kind, status, parent_id, order\n\
-         - kind values: task, final_audit, implementor, auditor, test_writer, test_runner\n\
-         - `docs` is reserved for `/attach-docs`. Do not populate or modify `docs` in master edits.\n\
-         - For new tasks created by master, set `docs` to [] and leave it empty.\n\
-         - Every task and sub-task must include a non-empty details field with concrete implementation/audit/test intent.\n\
-         - Every details field must be self-contained for isolated-context execution and explicitly cover: target files/modules, expected behavior changes, constraints/non-goals, and verification approach.\n\
-         - Every details field must include an explicit isolated-context rationale stating why the assigned sub-agent can execute using only the task record and referenced artifacts, without hidden chat context.\n\
-         {}\n\
-         - Update tasks.json only when task state should change.\n\
-         - Conversational answers that do not change task state do not require tasks.json edits.\n\
-         - Do not ask the user to start execution until task updates are ready.\n\
-         - After updating tasks.json, explain to the user what changed.",
-        workflow_prompt,
-        tests_policy
+    prompt_templates::render(
+        PromptTemplate::Master,
+        &[
+            ("workflow_prompt", workflow_prompt),
+            ("tasks_file", tasks_file),
+            ("tests_policy", tests_policy),
+            ("custom_roles", custom_roles),
+        ],
     )
 }
 
 pub(crate) fn build_convert_plan_prompt(planner_file: &str, tasks_file: &str) -> String {
-    format!(
-        "You are the master Codex agent and are now in task mode.\n\
-         Convert the current planner markdown into executable tasks.\n\
-         Read planner markdown at: {planner_file}\n\
-         Update tasks JSON at: {tasks_file}\n\
-         Requirements:\n\
-         - Convert the current plan into concrete task entries and subtasks suitable for execution.\n\
-         - Preserve existing completed task history where possible; append/update pending/in-progress work to reflect the plan.\n\
-         - Keep task hierarchy valid for this workflow (implementor/auditor/test structure guardrails still apply).\n\
-         - Do not modify docs fields except preserving existing values.\n\
-         - Save tasks.json and then provide a concise summary of what changed."
+    prompt_templates::render(
+        PromptTemplate::ConvertPlan,
+        &[("planner_file", planner_file), ("tasks_file", tasks_file)],
     )
 }
 
@@ -115,11 +94,21 @@ pub(crate) fn build_failure_report_prompt(
         .iter()
         .map(|entry| {
             format!(
-                "- kind={} task_id={} title=\"{}\" attempts={} reason={} action={}",
+                "- kind={} task_id={} title=\"{}\" attempts={}{}{} reason={} action={}",
                 entry.kind,
                 entry.top_task_id,
                 entry.top_task_title,
                 entry.attempts,
+                entry
+                    .retry_budget
+                    .as_deref()
+                    .map(|budget| format!(" budget={budget}"))
+                    .unwrap_or_default(),
+                entry
+                    .checkpoint_refs
+                    .first()
+                    .map(|checkpoint| format!(" pre_task_checkpoint={checkpoint}"))
+                    .unwrap_or_default(),
                 entry.reason,
                 entry.action_taken
             )
@@ -127,22 +116,30 @@ pub(crate) fn build_failure_report_prompt(
         .collect::<Vec<_>>()
         .join("\n");
 
-    let mut prompt = format!(
-        "Internal update from execution engine:\n\
-         Retry limits were exhausted for one or more branches.\n\
-         The canonical failure log has been appended at: {task_fails_file}\n\
-         Newly failed entries this cycle:\n\
-         {entries}\n\
-         Respond with a short user-facing message summarizing what failed and why.\n\
-         Do not emit task operations and do not modify files.\n"
-    );
-    if has_test_failure {
-        prompt.push_str(
-            "Also ask the user: tests could not be written/kept for some tasks. \
-             Would they like these unresolved items written to TODO.md?\n",
-        );
-    }
-    prompt
+    let rollback_hint = if failed_this_cycle
+        .iter()
+        .any(|entry| !entry.checkpoint_refs.is_empty())
+    {
+        "For entries with a pre_task_checkpoint, offer to roll the workspace back to its state before the task \
+         with `/rollback <task_id>` (available once execution is idle).\n"
+    } else {
+        ""
+    };
+    let test_failure_question = if has_test_failure {
+        "Also ask the user: tests could not be written/kept for some tasks. \
+         Would they like these unresolved items written to TODO.md?\n"
+    } else {
+        ""
+    };
+    prompt_templates::render(
+        PromptTemplate::FailureReport,
+        &[
+            ("task_fails_file", task_fails_file),
+            ("entries", &entries),
+            ("rollback_hint", rollback_hint),
+            ("test_failure_question", test_failure_question),
+        ],
+    )
 }
 
 pub(crate) fn split_audits_command_prompt() -> String {
@@ -0,0 +1,357 @@
+/// Most lines of captured output kept per failing test.
+const MAX_SNIPPET_LINES: usize = 12;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestFailure {
+    pub name: String,
+    pub file: Option<String>,
+    pub message: String,
+    pub snippet: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFramework {
+    Libtest,
+    Pytest,
+    GoTest,
+    JUnit,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestCounts {
+    pub passed: usize,
+    pub failed: usize,
+    pub flaky: usize,
+}
+
+impl TestCounts {
+    pub fn label(self) -> String {
+        let mut label = format!("{} passed, {} failed", self.passed, self.failed);
+        if self.flaky > 0 {
+            label.push_str(&format!(", {} flaky", self.flaky));
+        }
+        label
+    }
+}
+
+/// Structured result of one test command run; only failing cases are kept in detail.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestRunSummary {
+    pub framework: Option<TestFramework>,
+    pub counts: TestCounts,
+    pub failures: Vec<TestFailure>,
+    /// Failures that passed when re-run; they are excluded from `failures` and `counts.failed`.
+    pub flaky: Vec<TestFailure>,
+}
+
+impl TestRunSummary {
+    pub fn failing_names(&self) -> Vec<&str> {
+        self.failures
+            .iter()
+            .map(|failure| failure.name.as_str())
+            .collect()
+    }
+}
+
+/// Recognizes cargo test (libtest), pytest, and `go test` output. Returns `None` when the
+/// output matches none of them.
+pub fn parse_test_output(lines: &[String]) -> Option<TestRunSummary> {
+    parse_libtest(lines)
+        .or_else(|| parse_pytest(lines))
+        .or_else(|| parse_go_test(lines))
+}
+
+fn parse_libtest(lines: &[String]) -> Option<TestRunSummary> {
+    let mut counts = None::<TestCounts>;
+    let mut failed_names = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("test result: ") {
+            let totals = counts.get_or_insert_with(TestCounts::default);
+            totals.passed += count_before(rest, " passed");
+            totals.failed += count_before(rest, " failed");
+        } else if let Some(name) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.strip_suffix(" ... FAILED"))
+        {
+            failed_names.push(name.to_string());
+        }
+    }
+    let counts = counts?;
+
+    let mut failures = Vec::<TestFailure>::new();
+    let mut current: Option<TestFailure> = None;
+    for line in lines {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            failures.extend(current.take());
+            current = Some(TestFailure {
+                name: name.to_string(),
+                ..TestFailure::default()
+            });
+            continue;
+        }
+        let Some(failure) = current.as_mut() else {
+            continue;
+        };
+        if trimmed == "failures:" || trimmed.starts_with("test result: ") {
+            failures.extend(current.take());
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("note: run with `RUST_BACKTRACE") {
+            continue;
+        }
+        if let Some(location) = trimmed
+            .split_once("panicked at ")
+            .map(|(_, location)| location.trim_end_matches(':'))
+        {
+            failure.file = Some(location.to_string());
+        } else if failure.message.is_empty() && failure.file.is_some() {
+            failure.message = trimmed.to_string();
+        }
+        push_snippet_line(failure, line);
+    }
+    failures.extend(current);
+    for name in failed_names {
+        if !failures.iter().any(|failure| failure.name == name) {
+            failures.push(TestFailure {
+                name,
+                ..TestFailure::default()
+            });
+        }
+    }
+    Some(TestRunSummary {
+        framework: Some(TestFramework::Libtest),
+        counts,
+        failures,
+        flaky: Vec::new(),
+    })
+}
+
+fn parse_pytest(lines: &[String]) -> Option<TestRunSummary> {
+    let counts = lines.iter().rev().find_map(|line| {
+        let inner = line.trim().trim_matches('=').trim();
+        let is_summary = line.trim().starts_with('=')
+            && inner.contains(" in ")
+            && (inner.contains(" passed") || inner.contains(" failed"));
+        is_summary.then(|| TestCounts {
+            passed: count_before(inner, " passed"),
+            failed: count_before(inner, " failed") + count_before(inner, " error"),
+            flaky: 0,
+        })
+    })?;
+
+    let mut failures = Vec::new();
+    for line in lines {
+        let trimmed = line.trim();
+        let Some(rest) = trimmed
+            .strip_prefix("FAILED ")
+            .or_else(|| trimmed.strip_prefix("ERROR "))
+        else {
+            continue;
+        };
+        let (node_id, message) = rest.split_once(" - ").unwrap_or((rest, ""));
+        let mut failure = TestFailure {
+            name: node_id.to_string(),
+            file: node_id.split_once("::").map(|(file, _)| file.to_string()),
+            message: message.to_string(),
+            snippet: Vec::new(),
+        };
+        let test_name = node_id.rsplit("::").next().unwrap_or(node_id);
+        for section_line in pytest_section(lines, test_name) {
+            let section_trimmed = section_line.trim();
+            if section_trimmed.starts_with("E ")
+                || section_trimmed
+                    .split_once(": ")
+                    .is_some_and(|(location, _)| location.contains(".py:"))
+            {
+                push_snippet_line(&mut failure, section_line);
+            }
+        }
+        failures.push(failure);
+    }
+    Some(TestRunSummary {
+        framework: Some(TestFramework::Pytest),
+        counts,
+        failures,
+        flaky: Vec::new(),
+    })
+}
+
+/// Returns the body of a pytest `____ test_name ____` failure section.
+fn pytest_section<'a>(lines: &'a [String], test_name: &str) -> &'a [String] {
+    let is_header = |line: &String| {
+        let trimmed = line.trim();
+        trimmed.starts_with("__") && trimmed.ends_with("__")
+    };
+    let Some(start) = lines.iter().position(|line| {
+        is_header(line) && line.trim().trim_matches('_').trim().ends_with(test_name)
+    }) else {
+        return &[];
+    };
+    let body = &lines[start + 1..];
+    let end = body
+        .iter()
+        .position(|line| is_header(line) || line.trim().starts_with('='))
+        .unwrap_or(body.len());
+    &body[..end]
+}
+
+fn parse_go_test(lines: &[String]) -> Option<TestRunSummary> {
+    let mut counts = TestCounts::default();
+    let mut failures = Vec::new();
+    let mut recognized = false;
+    let mut current: Option<TestFailure> = None;
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.starts_with("--- PASS: ") {
+            recognized = true;
+            counts.passed += 1;
+            failures.extend(current.take());
+        } else if let Some(rest) = trimmed.strip_prefix("--- FAIL: ") {
+            recognized = true;
+            counts.failed += 1;
+            failures.extend(current.take());
+            let name = rest.split(" (").next().unwrap_or(rest);
+            current = Some(TestFailure {
+                name: name.to_string(),
+                ..TestFailure::default()
+            });
+        } else if (trimmed.starts_with("ok ") && trimmed.contains('\t'))
+            || trimmed.starts_with("FAIL\t")
+        {
+            recognized = true;
+            failures.extend(current.take());
+        } else if let Some(failure) = current.as_mut() {
+            if trimmed.is_empty() || trimmed.starts_with("=== ") {
+                continue;
+            }
+            if failure.file.is_none()
+                && let Some((location, message)) = trimmed.split_once(": ")
+                && location.contains(".go:")
+            {
+                failure.file = Some(location.to_string());
+                failure.message = message.to_string();
+            }
+            push_snippet_line(failure, line);
+        }
+    }
+    failures.extend(current);
+    recognized.then_some(TestRunSummary {
+        framework: Some(TestFramework::GoTest),
+        counts,
+        failures,
+        flaky: Vec::new(),
+    })
+}
+
+/// Parses a JUnit XML report as written by most test frameworks' reporters.
+pub fn parse_junit_xml(xml: &str) -> Option<TestRunSummary> {
+    if !xml.contains("<testcase") {
+        return None;
+    }
+    let mut summary = TestRunSummary {
+        framework: Some(TestFramework::JUnit),
+        ..TestRunSummary::default()
+    };
+    let mut rest = xml;
+    while let Some(start) = rest.find("<testcase") {
+        rest = &rest[start..];
+        let tag_end = rest.find('>')?;
+        let open_tag = &rest[..tag_end];
+        let (body, next) = if open_tag.ends_with('/') {
+            ("", &rest[tag_end + 1..])
+        } else {
+            let close = rest.find("</testcase>")?;
+            (
+                &rest[tag_end + 1..close],
+                &rest[close + "</testcase>".len()..],
+            )
+        };
+        rest = next;
+
+        let failure_tag = ["<failure", "<error"]
+            .iter()
+            .filter_map(|tag| body.find(tag).map(|index| (index, *tag)))
+            .min();
+        let Some((index, tag)) = failure_tag else {
+            if !body.contains("<skipped") {
+                summary.counts.passed += 1;
+            }
+            continue;
+        };
+        summary.counts.failed += 1;
+        let failure_body = &body[index..];
+        let failure_tag_end = failure_body.find('>').unwrap_or(failure_body.len());
+        let failure_open = &failure_body[..failure_tag_end];
+        let text = if failure_open.ends_with('/') {
+            ""
+        } else {
+            let close_tag = format!("</{}>", &tag[1..]);
+            let text_start = (failure_tag_end + 1).min(failure_body.len());
+            let text_end = failure_body.find(&close_tag).unwrap_or(failure_body.len());
+            &failure_body[text_start..text_end.max(text_start)]
+        };
+        let name = xml_attr(open_tag, "name").unwrap_or_default();
+        let mut failure = TestFailure {
+            name: match xml_attr(open_tag, "classname") {
+                Some(class) if !class.is_empty() => format!("{class}::{name}"),
+                _ => name,
+            },
+            file: xml_attr(open_tag, "file"),
+            message: xml_attr(failure_open, "message").unwrap_or_default(),
+            snippet: Vec::new(),
+        };
+        let text = unescape_xml(
+            text.trim()
+                .trim_start_matches("<![CDATA[")
+                .trim_end_matches("]]>"),
+        );
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            push_snippet_line(&mut failure, line);
+        }
+        summary.failures.push(failure);
+    }
+    Some(summary)
+}
+
+fn xml_attr(tag: &str, name: &str) -> Option<String> {
+    let needle = format!(" {name}=\"");
+    let start = tag.find(&needle)? + needle.len();
+    let end = tag[start..].find('"')? + start;
+    Some(unescape_xml(&tag[start..end]))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+/// Reads the number right before `label`, e.g. `3` from `"ok. 3 passed; 0 failed"`.
+fn count_before(text: &str, label: &str) -> usize {
+    let Some(index) = text.find(label) else {
+        return 0;
+    };
+    text[..index]
+        .rsplit(|ch: char| !ch.is_ascii_digit())
+        .next()
+        .and_then(|digits| digits.parse().ok())
+        .unwrap_or(0)
+}
+
+fn push_snippet_line(failure: &mut TestFailure, line: &str) {
+    if failure.snippet.len() < MAX_SNIPPET_LINES {
+        failure.snippet.push(line.trim_end().to_string());
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/test_report_tests.rs"]
+mod tests;
@@ -1,20 +1,44 @@
-use std::collections::{HashSet, VecDeque};
-
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+mod audit_verdict;
+mod coverage_runner;
+mod custom_role;
+mod dry_run;
 mod implementation_auditor;
 mod implementor;
+mod lint_runner;
+mod review_quorum;
 mod test_auditor;
 mod test_runner;
 mod test_writer;
 
+use self::audit_verdict::{AUDIT_VERDICT_PROTOCOL, parse_audit_verdict};
+pub use self::custom_role::{CustomRole, RoleFailurePolicy, RoleStage, RoleVerdict};
+pub use self::dry_run::DryRunPrompt;
+pub use self::review_quorum::ReviewerVote;
+use crate::agent::PermissionProfile;
+use crate::pass_diff::{ChangedLines, PassBaseline, PassDiff};
+use crate::prompt_templates::{self, PromptTemplate};
 use crate::session_store::{
     PlannerTaskDocFileEntry, PlannerTaskFileEntry, PlannerTaskKindFile, PlannerTaskStatusFile,
+    ReviewQuorumFileEntry,
 };
+use crate::test_report::{TestCounts, TestRunSummary, parse_test_output};
 
 const FILES_CHANGED_BEGIN: &str = "FILES_CHANGED_BEGIN";
 const FILES_CHANGED_END: &str = "FILES_CHANGED_END";
+/// Longest observed diff inlined into an audit prompt; the full diff stays in the session dir.
+const MAX_PROMPT_DIFF_CHARS: usize = 20_000;
+/// Failing tests spelled out in retry feedback; the rest are only counted.
+const MAX_REPORTED_TEST_FAILURES: usize = 20;
 const MAX_AUDIT_RETRIES: u8 = 4;
 const MAX_TEST_RETRIES: u8 = 5;
 const MAX_FINAL_AUDIT_RETRIES: u8 = 4;
+const JOB_TIMEOUT_MARKER: &str = "JOB_TIMEOUT:";
+/// Exit code reported for a job the watchdog killed (matches coreutils `timeout`).
+pub const JOB_TIMEOUT_EXIT_CODE: i32 = 124;
 const ENFORCE_TESTS_MODE_RUNTIME_GATING: bool = !cfg!(test);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,6 +47,14 @@ pub enum TaskStatus {
     InProgress,
     NeedsChanges,
     Done,
+    Skipped,
+}
+
+impl TaskStatus {
+    /// Skipped work counts as finished so later steps and dependent tasks can proceed.
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Skipped)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,13 +63,19 @@ pub enum WorkerRole {
     Auditor,
     TestWriter,
     TestRunner,
+    LintRunner,
+    CoverageRunner,
     FinalAudit,
+    /// A role declared under `[workflow.roles]`.
+    Custom,
 }
 
 #[derive(Debug, Clone)]
 pub enum JobRun {
     AgentPrompt(String),
     DeterministicTestRun,
+    DeterministicLintRun,
+    DeterministicCoverageRun,
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +84,16 @@ pub struct StartedJob {
     pub role: WorkerRole,
     pub top_task_id: u64,
     pub parent_context_key: Option<String>,
+    pub workspace_dir: Option<PathBuf>,
+    pub checkpoint_label: Option<String>,
+    pub pass_label: Option<String>,
+    /// Branch-level test command override for deterministic test runs.
+    pub test_command: Option<String>,
+    pub test_working_dir: Option<PathBuf>,
+    /// Model profile label for a quorum reviewer, overriding the role's default profile.
+    pub model_profile: Option<String>,
+    /// Permissions of a custom role, overriding the role's default permissions.
+    pub permissions: Option<PermissionProfile>,
 }
 
 #[derive(Debug, Clone)]
@@ -58,6 +106,9 @@ pub struct ActiveJobMeta {
 pub enum WorkflowFailureKind {
     Audit,
     Test,
+    Lint,
+    Merge,
+    Timeout,
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +119,123 @@ pub struct WorkflowFailure {
     pub attempts: u8,
     pub reason: String,
     pub action_taken: String,
+    pub retry_budget: Option<RetryBudget>,
+    pub checkpoint_refs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryBudgets {
+    pub audit: u8,
+    pub test: u8,
+    pub final_audit: u8,
+}
+
+impl Default for RetryBudgets {
+    fn default() -> Self {
+        Self {
+            audit: MAX_AUDIT_RETRIES,
+            test: MAX_TEST_RETRIES,
+            final_audit: MAX_FINAL_AUDIT_RETRIES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryBudget {
+    pub limit: u8,
+    pub source: String,
+}
+
+impl RetryBudget {
+    pub fn describe(&self) -> String {
+        format!("{} ({})", self.limit, self.source)
+    }
+}
+
+/// Wall-clock and output-inactivity limits for one worker role; `None` disables a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobTimeout {
+    pub max_runtime: Option<Duration>,
+    pub stall_after: Option<Duration>,
+}
+
+impl JobTimeout {
+    pub fn minutes(max_runtime: u64, stall_after: u64) -> Self {
+        let limit = |minutes: u64| (minutes > 0).then(|| Duration::from_secs(minutes * 60));
+        Self {
+            max_runtime: limit(max_runtime),
+            stall_after: limit(stall_after),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobTimeouts {
+    pub implementor: JobTimeout,
+    pub auditor: JobTimeout,
+    pub test_writer: JobTimeout,
+    pub test_runner: JobTimeout,
+    pub final_audit: JobTimeout,
+}
+
+impl Default for JobTimeouts {
+    fn default() -> Self {
+        Self {
+            implementor: JobTimeout::minutes(60, 15),
+            auditor: JobTimeout::minutes(30, 15),
+            test_writer: JobTimeout::minutes(60, 15),
+            test_runner: JobTimeout::minutes(30, 10),
+            final_audit: JobTimeout::minutes(45, 15),
+        }
+    }
+}
+
+impl JobTimeouts {
+    pub fn for_role(&self, role: WorkerRole) -> JobTimeout {
+        match role {
+            WorkerRole::Implementor => self.implementor,
+            // Custom roles review or extend an implementation pass, so they share audit limits.
+            WorkerRole::Auditor | WorkerRole::Custom => self.auditor,
+            WorkerRole::TestWriter => self.test_writer,
+            // Lint and coverage runs are deterministic checks like test runs and share their limits.
+            WorkerRole::TestRunner | WorkerRole::LintRunner | WorkerRole::CoverageRunner => {
+                self.test_runner
+            }
+            WorkerRole::FinalAudit => self.final_audit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedOutJob {
+    pub top_task_id: u64,
+    pub role: WorkerRole,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RetryLoop {
+    Audit,
+    Test,
+    FinalAudit,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadyMerge {
+    pub top_task_id: u64,
+    pub top_task_title: String,
+    pub workspace_dir: PathBuf,
+}
+
+/// An implementation branch held at a `review` gate until a human approves or rejects it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingReview {
+    pub top_task_id: u64,
+    pub top_task_title: String,
+    pub changed_files_summary: Option<String>,
+    implementor_id: u64,
+    review_id: u64,
+    pass: u8,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -78,6 +246,15 @@ enum TaskKind {
     Auditor,
     TestWriter,
     TestRunner,
+    LintRunner,
+    Review,
+    Role,
+}
+
+impl TaskKind {
+    fn is_deterministic(self) -> bool {
+        matches!(self, TaskKind::TestRunner | TaskKind::LintRunner)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -89,6 +266,14 @@ struct TaskNode {
     docs: Vec<PlannerTaskDocFileEntry>,
     status: TaskStatus,
     kind: TaskKind,
+    depends_on: Vec<u64>,
+    max_retries: Option<u8>,
+    /// Test command and working directory of a `test_runner` node, overriding meta.json.
+    command: Option<String>,
+    working_dir: Option<String>,
+    review_quorum: Option<ReviewQuorumFileEntry>,
+    /// Configured role a `role` node runs.
+    role: Option<String>,
     children: Vec<TaskNode>,
 }
 
@@ -117,6 +302,8 @@ enum WorkerJobKind {
         implementor_id: u64,
         auditor_id: u64,
         pass: u8,
+        /// 1-based reviewer index; always 1 unless the auditor has a review quorum.
+        reviewer: u8,
         implementation_report: Option<String>,
         changed_files_summary: Option<String>,
     },
@@ -144,11 +331,37 @@ enum WorkerJobKind {
         test_runner_id: u64,
         pass: u8,
     },
+    CoverageRunner {
+        test_writer_id: u64,
+        test_runner_id: u64,
+        pass: u8,
+    },
+    ImplementorLintRunner {
+        implementor_id: u64,
+        lint_runner_id: u64,
+        pass: u8,
+        implementation_report: Option<String>,
+        changed_files_summary: Option<String>,
+        resume_auditor_id: Option<u64>,
+        resume_audit_pass: Option<u8>,
+    },
     FinalAudit {
         final_audit_id: u64,
         pass: u8,
         feedback: Option<String>,
     },
+    Role {
+        implementor_id: u64,
+        role_id: u64,
+        pass: u8,
+        /// The role's own findings when its failure policy reruns it directly.
+        feedback: Option<String>,
+        implementation_report: Option<String>,
+        changed_files_summary: Option<String>,
+        /// The audit an implementor re-run was resuming; a before-audit role hands it on.
+        resume_auditor_id: Option<u64>,
+        resume_audit_pass: Option<u8>,
+    },
 }
 
 impl WorkerJobKind {
@@ -160,7 +373,40 @@ impl WorkerJobKind {
             WorkerJobKind::TestWriter { .. } => WorkerRole::TestWriter,
             WorkerJobKind::TestRunner { .. } => WorkerRole::TestRunner,
             WorkerJobKind::ImplementorTestRunner { .. } => WorkerRole::TestRunner,
+            WorkerJobKind::CoverageRunner { .. } => WorkerRole::CoverageRunner,
+            WorkerJobKind::ImplementorLintRunner { .. } => WorkerRole::LintRunner,
             WorkerJobKind::FinalAudit { .. } => WorkerRole::FinalAudit,
+            WorkerJobKind::Role { .. } => WorkerRole::Custom,
+        }
+    }
+
+    fn node_id(&self) -> u64 {
+        match self {
+            WorkerJobKind::Implementor { implementor_id, .. } => *implementor_id,
+            WorkerJobKind::Auditor { auditor_id, .. } => *auditor_id,
+            WorkerJobKind::TestWriterAuditor { auditor_id, .. } => *auditor_id,
+            WorkerJobKind::TestWriter { test_writer_id, .. } => *test_writer_id,
+            WorkerJobKind::TestRunner { test_runner_id, .. } => *test_runner_id,
+            WorkerJobKind::ImplementorTestRunner { test_runner_id, .. } => *test_runner_id,
+            WorkerJobKind::CoverageRunner { test_runner_id, .. } => *test_runner_id,
+            WorkerJobKind::ImplementorLintRunner { lint_runner_id, .. } => *lint_runner_id,
+            WorkerJobKind::FinalAudit { final_audit_id, .. } => *final_audit_id,
+            WorkerJobKind::Role { role_id, .. } => *role_id,
+        }
+    }
+
+    fn pass(&self) -> u8 {
+        match self {
+            WorkerJobKind::Implementor { pass, .. }
+            | WorkerJobKind::Auditor { pass, .. }
+            | WorkerJobKind::TestWriterAuditor { pass, .. }
+            | WorkerJobKind::TestWriter { pass, .. }
+            | WorkerJobKind::TestRunner { pass, .. }
+            | WorkerJobKind::ImplementorTestRunner { pass, .. }
+            | WorkerJobKind::CoverageRunner { pass, .. }
+            | WorkerJobKind::ImplementorLintRunner { pass, .. }
+            | WorkerJobKind::FinalAudit { pass, .. }
+            | WorkerJobKind::Role { pass, .. } => *pass,
         }
     }
 
@@ -169,8 +415,17 @@ impl WorkerJobKind {
             WorkerJobKind::Implementor { implementor_id, .. } => {
                 Some(format!("implementor:{implementor_id}"))
             }
-            WorkerJobKind::Auditor { auditor_id, .. } => Some(format!("auditor:{auditor_id}")),
-            WorkerJobKind::ImplementorTestRunner { implementor_id, .. } => {
+            WorkerJobKind::Auditor {
+                auditor_id,
+                reviewer,
+                ..
+            } => Some(if *reviewer > 1 {
+                format!("auditor:{auditor_id}:reviewer:{reviewer}")
+            } else {
+                format!("auditor:{auditor_id}")
+            }),
+            WorkerJobKind::ImplementorTestRunner { implementor_id, .. }
+            | WorkerJobKind::ImplementorLintRunner { implementor_id, .. } => {
                 Some(format!("implementor:{implementor_id}"))
             }
             WorkerJobKind::TestWriter { test_writer_id, .. } => {
@@ -179,12 +434,14 @@ impl WorkerJobKind {
             WorkerJobKind::TestWriterAuditor { auditor_id, .. } => {
                 Some(format!("test_auditor:{auditor_id}"))
             }
-            WorkerJobKind::TestRunner { test_writer_id, .. } => {
+            WorkerJobKind::TestRunner { test_writer_id, .. }
+            | WorkerJobKind::CoverageRunner { test_writer_id, .. } => {
                 Some(format!("test_writer:{test_writer_id}"))
             }
             WorkerJobKind::FinalAudit { final_audit_id, .. } => {
                 Some(format!("final_audit:{final_audit_id}"))
             }
+            WorkerJobKind::Role { role_id, .. } => Some(format!("role:{role_id}")),
         }
     }
 }
@@ -199,20 +456,57 @@ struct WorkerJob {
 struct ActiveJob {
     job: WorkerJob,
     transcript: Vec<String>,
+    started_at: Instant,
+    last_output_at: Instant,
+    pass_baseline: Option<PassBaseline>,
+    test_report: Option<TestRunSummary>,
 }
 
 #[derive(Debug)]
 pub struct Workflow {
     tasks: Vec<TaskNode>,
     queue: VecDeque<WorkerJob>,
-    active: Option<ActiveJob>,
+    active: Vec<ActiveJob>,
     rolling_context: VecDeque<String>,
     max_context_entries: usize,
     next_id: u64,
     execution_enabled: bool,
+    execution_paused: bool,
     tests_mode_enabled: bool,
+    max_parallel_tasks: usize,
+    checkpoints_enabled: bool,
+    retry_budgets: RetryBudgets,
+    job_timeouts: JobTimeouts,
+    task_workspaces: HashMap<u64, PathBuf>,
+    task_checkpoints: HashMap<u64, Vec<String>>,
+    pass_diffs: HashMap<u64, PassDiff>,
+    /// Lines changed by the passes of a top-level task, keyed by the top task id; `None` once
+    /// a pass changed files without a patch to read lines from.
+    task_changed_lines: HashMap<u64, Option<ChangedLines>>,
+    test_counts: HashMap<u64, TestCounts>,
+    /// Minimum line coverage of the lines a task changed, in percent; `None` disables the
+    /// coverage gate.
+    coverage_threshold: Option<f64>,
+    merged_top_tasks: HashSet<u64>,
+    /// Top tasks whose merge-back failed; their worktrees are kept and dependents stay blocked.
+    failed_merges: HashSet<u64>,
+    /// Merged or failed-merge top tasks reopened by a retry, with the worktree left over from
+    /// the earlier run; drained by the caller, which removes the worktree and old checkpoints.
+    reset_merges: Vec<(u64, Option<PathBuf>)>,
     recent_failures: Vec<WorkflowFailure>,
     exhausted_final_audits: HashSet<u64>,
+    /// Top tasks whose review was rejected after the audit retry budget ran out; they stay
+    /// blocked until /retry or /skip.
+    rejected_reviews: HashSet<u64>,
+    /// Last pass of each before-audit role that sent work back while an audit waited to
+    /// resume, keyed by role node id; its re-run after the implementor continues from there.
+    before_audit_role_passes: HashMap<u64, u8>,
+    pending_reviews: VecDeque<PendingReview>,
+    new_reviews: Vec<PendingReview>,
+    /// Verdicts cast so far in the current quorum round, keyed by auditor node id.
+    review_votes: HashMap<u64, Vec<ReviewerVote>>,
+    new_review_votes: Vec<ReviewerVote>,
+    custom_roles: Vec<CustomRole>,
 }
 
 impl Default for Workflow {
@@ -220,14 +514,35 @@ impl Default for Workflow {
         Self {
             tasks: Vec::new(),
             queue: VecDeque::new(),
-            active: None,
+            active: Vec::new(),
             rolling_context: VecDeque::new(),
             max_context_entries: 16,
             next_id: 1,
             execution_enabled: false,
+            execution_paused: false,
             tests_mode_enabled: true,
+            max_parallel_tasks: 1,
+            checkpoints_enabled: false,
+            retry_budgets: RetryBudgets::default(),
+            job_timeouts: JobTimeouts::default(),
+            task_workspaces: HashMap::new(),
+            task_checkpoints: HashMap::new(),
+            pass_diffs: HashMap::new(),
+            task_changed_lines: HashMap::new(),
+            test_counts: HashMap::new(),
+            coverage_threshold: None,
+            merged_top_tasks: HashSet::new(),
+            failed_merges: HashSet::new(),
+            reset_merges: Vec::new(),
             recent_failures: Vec::new(),
             exhausted_final_audits: HashSet::new(),
+            rejected_reviews: HashSet::new(),
+            before_audit_role_passes: HashMap::new(),
+            pending_reviews: VecDeque::new(),
+            new_reviews: Vec::new(),
+            review_votes: HashMap::new(),
+            new_review_votes: Vec::new(),
+            custom_roles: Vec::new(),
         }
     }
 }
@@ -272,6 +587,273 @@ impl Workflow {
         self.tests_mode_enabled
     }
 
+    pub fn set_max_parallel_tasks(&mut self, limit: usize) {
+        self.max_parallel_tasks = limit.max(1);
+    }
+
+    pub fn max_parallel_tasks(&self) -> usize {
+        self.max_parallel_tasks
+    }
+
+    pub fn set_checkpoints_enabled(&mut self, enabled: bool) {
+        self.checkpoints_enabled = enabled;
+    }
+
+    pub fn set_coverage_threshold(&mut self, threshold: Option<f64>) {
+        self.coverage_threshold = threshold;
+    }
+
+    pub(crate) fn coverage_threshold(&self) -> Option<f64> {
+        self.coverage_threshold
+    }
+
+    /// Returns `None` when a pass of the task ran outside git, so its changed lines are unknown.
+    pub(crate) fn task_changed_lines(&self, top_task_id: u64) -> Option<ChangedLines> {
+        self.task_changed_lines
+            .get(&top_task_id)
+            .cloned()
+            .unwrap_or_else(|| Some(ChangedLines::new()))
+    }
+
+    pub fn set_retry_budgets(&mut self, budgets: RetryBudgets) {
+        self.retry_budgets = RetryBudgets {
+            audit: budgets.audit.max(1),
+            test: budgets.test.max(1),
+            final_audit: budgets.final_audit.max(1),
+        };
+    }
+
+    pub fn set_job_timeouts(&mut self, timeouts: JobTimeouts) {
+        self.job_timeouts = timeouts;
+    }
+
+    pub fn top_task_key(&self, top_task_id: u64) -> Option<String> {
+        find_node(&self.tasks, top_task_id).map(|node| {
+            node.external_id
+                .clone()
+                .unwrap_or_else(|| format!("internal-{}", node.id))
+        })
+    }
+
+    pub fn assign_task_workspace(&mut self, top_task_id: u64, workspace_dir: PathBuf) {
+        self.task_workspaces.insert(top_task_id, workspace_dir);
+    }
+
+    pub fn task_workspace(&self, top_task_id: u64) -> Option<PathBuf> {
+        self.task_workspaces.get(&top_task_id).cloned()
+    }
+
+    pub fn resolve_top_task_id(&self, task_ref: &str) -> Option<u64> {
+        // Accepts the tasks.json id first, then the `#N` number shown in system messages.
+        let task_ref = task_ref.trim();
+        let top_ids = self
+            .tasks
+            .iter()
+            .filter(|node| node.kind == TaskKind::Top)
+            .map(|node| node.id)
+            .collect::<Vec<_>>();
+        if let Some(id) = top_ids
+            .iter()
+            .find(|id| self.top_task_key(**id).as_deref() == Some(task_ref))
+        {
+            return Some(*id);
+        }
+        let numeric = task_ref
+            .strip_prefix('#')
+            .unwrap_or(task_ref)
+            .parse::<u64>()
+            .ok()?;
+        top_ids.into_iter().find(|id| *id == numeric)
+    }
+
+    /// Top-level tasks that have not started in the current plan.
+    pub fn pending_top_task_ids(&self) -> Vec<u64> {
+        self.tasks
+            .iter()
+            .filter(|node| node.kind == TaskKind::Top && node.status == TaskStatus::Pending)
+            .map(|node| node.id)
+            .collect()
+    }
+
+    pub fn resolve_task_id(&self, task_ref: &str) -> Option<u64> {
+        // Like `resolve_top_task_id`, but any task or subtask in the tree can be addressed.
+        let task_ref = task_ref.trim();
+        let mut ids = Vec::new();
+        collect_subtree_ids(&self.tasks, &mut ids);
+        if let Some(id) = ids.iter().find(|id| {
+            find_node(&self.tasks, **id)
+                .is_some_and(|node| node.external_id.as_deref() == Some(task_ref))
+        }) {
+            return Some(*id);
+        }
+        let numeric = task_ref
+            .strip_prefix('#')
+            .unwrap_or(task_ref)
+            .parse::<u64>()
+            .ok()?;
+        ids.into_iter().find(|id| *id == numeric)
+    }
+
+    pub fn record_task_checkpoint(&mut self, top_task_id: u64, checkpoint_ref: String) {
+        self.task_checkpoints
+            .entry(top_task_id)
+            .or_default()
+            .push(checkpoint_ref);
+    }
+
+    pub fn task_checkpoint_refs(&self, top_task_id: u64) -> Vec<String> {
+        self.task_checkpoints
+            .get(&top_task_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_pass_baseline(&mut self, top_task_id: u64, baseline: PassBaseline) {
+        if let Some(active) = self
+            .active
+            .iter_mut()
+            .find(|active| active.job.top_task_id == top_task_id)
+        {
+            active.pass_baseline = Some(baseline);
+        }
+    }
+
+    pub fn take_pass_baseline(&mut self, top_task_id: u64) -> Option<PassBaseline> {
+        self.active
+            .iter_mut()
+            .find(|active| active.job.top_task_id == top_task_id)
+            .and_then(|active| active.pass_baseline.take())
+    }
+
+    /// Attaches a report parsed outside the transcript (a JUnit XML file) to the task's active
+    /// test run; it takes precedence over parsing the captured output.
+    pub fn record_test_report(&mut self, top_task_id: u64, report: TestRunSummary) {
+        if let Some(active) = self
+            .active
+            .iter_mut()
+            .find(|active| active.job.top_task_id == top_task_id)
+        {
+            active.test_report = Some(report);
+        }
+    }
+
+    /// Stores the observed diff of the task's active pass against the worker node that ran it,
+    /// replacing the diff of any earlier pass.
+    pub fn record_pass_diff(&mut self, top_task_id: u64, diff: PassDiff) {
+        if let Some(active) = self
+            .active
+            .iter()
+            .find(|active| active.job.top_task_id == top_task_id)
+        {
+            let lines = self
+                .task_changed_lines
+                .entry(top_task_id)
+                .or_insert_with(|| Some(ChangedLines::new()));
+            if let Some(changed) = lines
+                && !diff.apply_to_changed_lines(changed)
+            {
+                *lines = None;
+            }
+            self.pass_diffs.insert(active.job.kind.node_id(), diff);
+        }
+    }
+
+    /// Returns the finished worktree tasks whose dependencies have all merged. They stay
+    /// unmerged until the caller reports the outcome through `mark_task_merged` or
+    /// `record_merge_failure`; tasks that ran in the main workspace are marked merged here.
+    pub fn take_ready_merges(&mut self) -> Vec<ReadyMerge> {
+        let root_ids: Vec<u64> = self
+            .ordered_root_nodes()
+            .iter()
+            .filter(|node| node.kind == TaskKind::Top)
+            .map(|node| node.id)
+            .collect();
+        let mut ready = Vec::new();
+        // Merge back in dependency order: a task never lands before the tasks it depends on.
+        let mut progressed = true;
+        while progressed {
+            progressed = false;
+            for top_id in &root_ids {
+                if self.merged_top_tasks.contains(top_id)
+                    || self.failed_merges.contains(top_id)
+                    || self.status_of(*top_id) != Some(TaskStatus::Done)
+                {
+                    continue;
+                }
+                // Skipped dependencies have nothing to merge, like in dependencies_satisfied.
+                let deps_merged = find_node(&self.tasks, *top_id).is_some_and(|node| {
+                    node.depends_on.iter().all(|dep| {
+                        self.merged_top_tasks.contains(dep)
+                            || self.status_of(*dep) == Some(TaskStatus::Skipped)
+                    })
+                });
+                if !deps_merged {
+                    continue;
+                }
+                match self.task_workspaces.get(top_id) {
+                    Some(workspace_dir) => {
+                        if !ready.iter().any(|merge: &ReadyMerge| merge.top_task_id == *top_id) {
+                            ready.push(ReadyMerge {
+                                top_task_id: *top_id,
+                                top_task_title: self.task_title(*top_id),
+                                workspace_dir: workspace_dir.clone(),
+                            });
+                        }
+                    }
+                    None => {
+                        self.merged_top_tasks.insert(*top_id);
+                        progressed = true;
+                    }
+                }
+            }
+        }
+        // A later sweep can release a task listed before ones found earlier; keep plan order.
+        ready.sort_by_key(|merge| {
+            root_ids
+                .iter()
+                .position(|id| *id == merge.top_task_id)
+                .unwrap_or(usize::MAX)
+        });
+        ready
+    }
+
+    /// Records a successful (or empty) merge-back and releases the task's dependents.
+    pub fn mark_task_merged(&mut self, top_task_id: u64) {
+        self.merged_top_tasks.insert(top_task_id);
+        self.failed_merges.remove(&top_task_id);
+        self.task_workspaces.remove(&top_task_id);
+        if self.execution_enabled {
+            // Dependents wait for merge-back, so they become schedulable only now.
+            let _ = self.enqueue_ready_top_tasks();
+        }
+    }
+
+    pub fn record_merge_failure(
+        &mut self,
+        top_task_id: u64,
+        reason: String,
+        mut action_taken: String,
+    ) {
+        self.failed_merges.insert(top_task_id);
+        let has_dependents = self
+            .tasks
+            .iter()
+            .any(|node| node.depends_on.contains(&top_task_id));
+        if has_dependents {
+            action_taken.push_str(" Tasks that depend on it stay blocked until it merges.");
+        }
+        self.recent_failures.push(WorkflowFailure {
+            kind: WorkflowFailureKind::Merge,
+            top_task_id,
+            top_task_title: self.task_title(top_task_id),
+            attempts: 1,
+            reason,
+            action_taken,
+            retry_budget: None,
+            checkpoint_refs: self.task_checkpoint_refs(top_task_id),
+        });
+    }
+
     pub fn rolling_context_entries(&self) -> Vec<String> {
         self.rolling_context.iter().cloned().collect()
     }
@@ -292,6 +874,7 @@ impl Workflow {
         }
 
         fn collect(
+            roots: &[TaskNode],
             node: &TaskNode,
             parent_id: Option<&str>,
             order: u32,
@@ -307,15 +890,26 @@ impl Workflow {
                 status: task_status_to_file(node.status),
                 parent_id: parent_id.map(ToString::to_string),
                 order: Some(order),
+                depends_on: node
+                    .depends_on
+                    .iter()
+                    .filter_map(|dep| find_node(roots, *dep))
+                    .map(file_id_for_node)
+                    .collect(),
+                max_retries: node.max_retries,
+                command: node.command.clone(),
+                working_dir: node.working_dir.clone(),
+                review_quorum: node.review_quorum.clone(),
+                role: node.role.clone(),
             });
             for (idx, child) in node.children.iter().enumerate() {
-                collect(child, Some(&node_id), idx as u32, out);
+                collect(roots, child, Some(&node_id), idx as u32, out);
             }
         }
 
         let mut out = Vec::new();
         for (idx, task) in self.ordered_root_nodes().iter().enumerate() {
-            collect(task, None, idx as u32, &mut out);
+            collect(&self.tasks, task, None, idx as u32, &mut out);
         }
         out
     }
@@ -323,17 +917,27 @@ impl Workflow {
     pub fn reset_execution_runtime(&mut self) {
         self.execution_enabled = false;
         self.queue.clear();
-        self.active = None;
+        self.active.clear();
+        self.task_workspaces.clear();
+        self.task_checkpoints.clear();
+        self.merged_top_tasks.clear();
+        self.failed_merges.clear();
+        self.reset_merges.clear();
         self.recent_failures.clear();
         self.exhausted_final_audits.clear();
+        self.rejected_reviews.clear();
+        self.before_audit_role_passes.clear();
+        self.pending_reviews.clear();
+        self.new_reviews.clear();
+        self.review_votes.clear();
+        self.new_review_votes.clear();
     }
 
     pub fn sync_planner_tasks_from_file(
         &mut self,
         entries: Vec<PlannerTaskFileEntry>,
     ) -> Result<usize, String> {
-        let execution_busy = self.active.is_some() || !self.queue.is_empty();
-        if self.execution_enabled && execution_busy {
+        if self.execution_busy() {
             return Err("Cannot reload planner tasks while execution is enabled".to_string());
         }
         if self.execution_enabled {
@@ -356,6 +960,47 @@ impl Workflow {
             if id_to_num.contains_key(&entry.id) {
                 return Err(format!("Duplicate planner task id {}", entry.id));
             }
+            if entry.max_retries == Some(0) {
+                return Err(format!(
+                    "Planner task {} max_retries must be at least 1",
+                    entry.id
+                ));
+            }
+            if (entry.command.is_some() || entry.working_dir.is_some())
+                && entry.kind != PlannerTaskKindFile::TestRunner
+            {
+                return Err(format!(
+                    "Planner task {} sets command/working_dir, which only test_runner tasks may set",
+                    entry.id
+                ));
+            }
+            if let Some(quorum) = &entry.review_quorum {
+                if entry.kind != PlannerTaskKindFile::Auditor {
+                    return Err(format!(
+                        "Planner task {} sets review_quorum, which only auditor tasks may set",
+                        entry.id
+                    ));
+                }
+                if quorum.required == 0 || quorum.required > quorum.reviewers {
+                    return Err(format!(
+                        "Planner task {} review_quorum.required must be between 1 and reviewers ({})",
+                        entry.id, quorum.reviewers
+                    ));
+                }
+            }
+            if entry.kind == PlannerTaskKindFile::Role {
+                if normalize_optional_text(entry.role.as_deref()).is_none() {
+                    return Err(format!(
+                        "Planner task {} has kind \"role\" but does not name a role",
+                        entry.id
+                    ));
+                }
+            } else if entry.role.is_some() {
+                return Err(format!(
+                    "Planner task {} sets role, which only role tasks may set",
+                    entry.id
+                ));
+            }
             let num = self.alloc_id();
             id_to_num.insert(entry.id.clone(), num);
         }
@@ -370,6 +1015,7 @@ impl Workflow {
                 ));
             }
         }
+        validate_task_dependencies(&entries)?;
 
         let mut children_map =
             std::collections::HashMap::<Option<String>, Vec<&PlannerTaskFileEntry>>::new();
@@ -429,8 +1075,19 @@ impl Workflow {
                             PlannerTaskStatusFile::InProgress => TaskStatus::InProgress,
                             PlannerTaskStatusFile::NeedsChanges => TaskStatus::NeedsChanges,
                             PlannerTaskStatusFile::Done => TaskStatus::Done,
+                            PlannerTaskStatusFile::Skipped => TaskStatus::Skipped,
                         },
                         kind,
+                        depends_on: entry
+                            .depends_on
+                            .iter()
+                            .filter_map(|dep| id_to_num.get(dep).copied())
+                            .collect(),
+                        max_retries: entry.max_retries,
+                        command: normalize_optional_text(entry.command.as_deref()),
+                        working_dir: normalize_optional_text(entry.working_dir.as_deref()),
+                        review_quorum: entry.review_quorum.as_ref().map(normalize_review_quorum),
+                        role: normalize_optional_text(entry.role.as_deref()),
                         children: child_nodes,
                     });
                 }
@@ -455,61 +1112,391 @@ impl Workflow {
 
         self.tasks = root_nodes;
         self.queue.clear();
-        self.active = None;
+        self.active.clear();
+        self.task_workspaces.clear();
+        self.task_checkpoints.clear();
+        self.merged_top_tasks.clear();
+        self.failed_merges.clear();
+        self.reset_merges.clear();
         self.recent_failures.clear();
         self.exhausted_final_audits.clear();
+        self.rejected_reviews.clear();
+        self.before_audit_role_passes.clear();
+        self.review_votes.clear();
         if ENFORCE_TESTS_MODE_RUNTIME_GATING && !self.tests_mode_enabled {
             self.mark_tests_disabled_state();
         }
         Ok(entries.len())
     }
 
-    pub fn start_execution(&mut self) -> Vec<String> {
+    pub fn start_execution(&mut self) -> Vec<String> {
+        if self.execution_paused {
+            return vec![
+                "System: Execution is paused. Use /resume-execution to continue.".to_string(),
+            ];
+        }
+        if self.execution_enabled {
+            if !self.active.is_empty() {
+                return vec![
+                    "System: Execution is already running; continuing current task.".to_string(),
+                ];
+            }
+            let queued = self.enqueue_ready_top_tasks();
+            if queued > 0 {
+                return vec![format!(
+                    "System: Resumed from last unfinished task(s). Queued {} task job(s).",
+                    queued
+                )];
+            }
+            return vec![
+                "System: Execution is already enabled. No unfinished tasks to resume.".to_string(),
+            ];
+        }
+
+        self.execution_enabled = true;
+        if ENFORCE_TESTS_MODE_RUNTIME_GATING && !self.tests_mode_enabled {
+            self.drop_queued_test_jobs_when_disabled();
+            self.mark_tests_disabled_state();
+        }
+        let queued = self.enqueue_ready_top_tasks();
+        vec![format!(
+            "System: Execution enabled. Queued {} task job(s).",
+            queued
+        )]
+    }
+
+    #[cfg(test)]
+    pub fn execution_enabled(&self) -> bool {
+        self.execution_enabled
+    }
+
+    pub fn execution_busy(&self) -> bool {
+        // Queued work does not keep a paused run busy; only jobs still finishing do.
+        self.execution_enabled
+            && (!self.active.is_empty()
+                || !self.pending_reviews.is_empty()
+                || (!self.execution_paused && !self.queue.is_empty()))
+    }
+
+    pub fn execution_paused(&self) -> bool {
+        self.execution_paused
+    }
+
+    pub fn set_execution_paused(&mut self, paused: bool) {
+        self.execution_paused = paused;
+    }
+
+    pub fn pause_execution(&mut self) -> Vec<String> {
+        if self.execution_paused {
+            return vec!["System: Execution is already paused.".to_string()];
+        }
+        if !self.execution_enabled {
+            return vec!["System: Execution is not running; nothing to pause.".to_string()];
+        }
+        self.execution_paused = true;
+        if self.active.is_empty() {
+            vec!["System: Execution paused. Use /resume-execution to continue.".to_string()]
+        } else {
+            vec![format!(
+                "System: Execution paused. {} running job(s) will finish; no new jobs will start until /resume-execution.",
+                self.active.len()
+            )]
+        }
+    }
+
+    pub fn stop_active_jobs(&mut self) -> Vec<u64> {
+        // Stopped jobs go back to the front of the queue so resuming re-runs the same pass.
+        if !self.execution_enabled {
+            return Vec::new();
+        }
+        self.execution_paused = true;
+        let stopped = std::mem::take(&mut self.active);
+        let mut top_task_ids = Vec::new();
+        for active in stopped.into_iter().rev() {
+            self.set_status(active.job.kind.node_id(), TaskStatus::NeedsChanges);
+            self.set_status(active.job.top_task_id, TaskStatus::NeedsChanges);
+            top_task_ids.push(active.job.top_task_id);
+            self.queue.push_front(active.job);
+        }
+        top_task_ids.reverse();
+        top_task_ids
+    }
+
+    pub fn drain_new_reviews(&mut self) -> Vec<PendingReview> {
+        std::mem::take(&mut self.new_reviews)
+    }
+
+    pub fn approve_review(&mut self, task_ref: Option<&str>) -> Vec<String> {
+        let review = match self.take_pending_review(task_ref) {
+            Ok(review) => review,
+            Err(message) => return vec![message],
+        };
+        self.set_status(review.review_id, TaskStatus::Done);
+        let mut messages = vec![format!(
+            "System: Task #{} review approved.",
+            review.top_task_id
+        )];
+        let _ = self.queue_next_implementor_audit(
+            review.top_task_id,
+            review.implementor_id,
+            review.pass,
+            None,
+            review.changed_files_summary,
+            &mut messages,
+        );
+        if self.execution_enabled {
+            let _ = self.enqueue_ready_top_tasks();
+        }
+        messages
+    }
+
+    pub fn reject_review(&mut self, task_ref: Option<&str>, feedback: &str) -> Vec<String> {
+        // Rejection is handled like a failed audit: the implementor reruns with the reviewer's
+        // notes and the same review gate is requested again once it passes. Once the audit
+        // retry budget runs out the task is held, since rejected changes must never proceed.
+        let review = match self.take_pending_review(task_ref) {
+            Ok(review) => review,
+            Err(message) => return vec![message],
+        };
+        self.set_status(review.implementor_id, TaskStatus::NeedsChanges);
+        self.set_status(review.review_id, TaskStatus::NeedsChanges);
+        let budget = self.retry_budget(review.review_id, RetryLoop::Audit);
+        if review.pass >= budget.limit {
+            self.recent_failures.push(WorkflowFailure {
+                kind: WorkflowFailureKind::Audit,
+                top_task_id: review.top_task_id,
+                top_task_title: review.top_task_title.clone(),
+                attempts: review.pass,
+                reason: format!("Human review rejected the changes: {}", feedback.trim()),
+                action_taken: "Review retries exhausted; task held until /retry or /skip."
+                    .to_string(),
+                retry_budget: Some(budget.clone()),
+                checkpoint_refs: self.task_checkpoint_refs(review.top_task_id),
+            });
+            self.rejected_reviews.insert(review.top_task_id);
+            if self.execution_enabled {
+                let _ = self.enqueue_ready_top_tasks();
+            }
+            return vec![format!(
+                "System: Task #{} review rejected at pass {}. Max retries ({}) reached; the task is held until /retry or /skip.",
+                review.top_task_id, review.pass, budget.limit
+            )];
+        }
+        let pass = review.pass.saturating_add(1);
+        self.queue.push_back(WorkerJob {
+            top_task_id: review.top_task_id,
+            kind: WorkerJobKind::Implementor {
+                implementor_id: review.implementor_id,
+                pass,
+                feedback: Some(format!(
+                    "Human review rejected the changes. Reviewer feedback:\n{}",
+                    feedback.trim()
+                )),
+                resume_auditor_id: None,
+                resume_audit_pass: None,
+            },
+        });
+        vec![format!(
+            "System: Task #{} review rejected; implementor pass {} queued with the reviewer feedback.",
+            review.top_task_id, pass
+        )]
+    }
+
+    /// Removes the review held for `task_ref`; without a task reference the review is only
+    /// taken when it is the sole one waiting, so a decision never lands on the wrong task.
+    fn take_pending_review(&mut self, task_ref: Option<&str>) -> Result<PendingReview, String> {
+        if self.pending_reviews.is_empty() {
+            return Err("System: No task is waiting for review.".to_string());
+        }
+        let index = match task_ref {
+            Some(task_ref) => {
+                let top_task_id = self
+                    .resolve_top_task_id(task_ref)
+                    .ok_or_else(|| format!("System: Unknown task \"{}\".", task_ref.trim()))?;
+                self.pending_reviews
+                    .iter()
+                    .position(|review| review.top_task_id == top_task_id)
+                    .ok_or_else(|| {
+                        format!("System: Task #{top_task_id} is not waiting for review.")
+                    })?
+            }
+            None if self.pending_reviews.len() == 1 => 0,
+            None => {
+                let waiting = self
+                    .pending_reviews
+                    .iter()
+                    .map(|review| format!("#{}", review.top_task_id))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(format!(
+                    "System: Several tasks are waiting for review ({waiting}); name one with /approve <task-id> or /reject <task-id> <feedback>."
+                ));
+            }
+        };
+        Ok(self
+            .pending_reviews
+            .remove(index)
+            .expect("pending review index is in range"))
+    }
+
+    /// Resets a task subtree to Pending so it runs again from pass 1. Ancestors are reopened
+    /// too, since a finished parent would otherwise never schedule the reset work.
+    pub fn retry_task(&mut self, task_id: u64, guidance: Option<&str>) -> Result<String, String> {
+        let (top_task_id, subtree) = self.editable_subtree(task_id)?;
+        let was_merged = self.merged_top_tasks.remove(&top_task_id);
+        if was_merged || self.failed_merges.remove(&top_task_id) {
+            // The rerun merges back again, so it starts over in a fresh worktree from the
+            // current main checkout instead of building on the earlier run's state.
+            self.forget_task_run(top_task_id);
+            let leftover_workspace = self.task_workspaces.remove(&top_task_id);
+            self.reset_merges.push((top_task_id, leftover_workspace));
+        }
+        for node_id in &subtree {
+            self.set_status(*node_id, TaskStatus::Pending);
+            self.exhausted_final_audits.remove(node_id);
+        }
+        let mut path = Vec::new();
+        find_path(&self.tasks, task_id, &mut path);
+        let ancestors = path.iter().map(|node| node.id).collect::<Vec<_>>();
+        for ancestor_id in ancestors {
+            if self.status_of(ancestor_id) != Some(TaskStatus::InProgress) {
+                self.set_status(ancestor_id, TaskStatus::Pending);
+            }
+        }
+        if let Some(guidance) = guidance.map(str::trim).filter(|text| !text.is_empty()) {
+            self.append_retry_guidance(task_id, &subtree, guidance);
+        }
+        if self.execution_enabled {
+            let _ = self.enqueue_ready_top_tasks();
+        }
+        Ok(format!(
+            "System: Task \"{}\" reset to Pending under task #{}; it will rerun with fresh retry counters.",
+            self.node_title(task_id, "Task"),
+            top_task_id
+        ))
+    }
+
+    fn forget_task_run(&mut self, top_task_id: u64) {
+        self.task_checkpoints.remove(&top_task_id);
+        self.task_changed_lines.remove(&top_task_id);
+        if let Some(top) = find_node(&self.tasks, top_task_id) {
+            let mut node_ids = Vec::new();
+            collect_subtree_ids(std::slice::from_ref(top), &mut node_ids);
+            for node_id in node_ids {
+                self.pass_diffs.remove(&node_id);
+            }
+        }
+    }
+
+    /// Top tasks whose merge-back was reset by a retry since the last call.
+    pub fn drain_reset_merges(&mut self) -> Vec<(u64, Option<PathBuf>)> {
+        std::mem::take(&mut self.reset_merges)
+    }
+
+    /// Marks a task subtree Skipped so its parent and any dependent tasks can proceed.
+    pub fn skip_task(&mut self, task_id: u64) -> Result<Vec<String>, String> {
+        let (top_task_id, subtree) = self.editable_subtree(task_id)?;
+        for node_id in &subtree {
+            self.set_status(*node_id, TaskStatus::Skipped);
+        }
+        let mut messages = vec![format!(
+            "System: Task \"{}\" skipped under task #{}.",
+            self.node_title(task_id, "Task"),
+            top_task_id
+        )];
+        if task_id != top_task_id {
+            self.try_mark_top_done(top_task_id, &mut messages);
+        }
         if self.execution_enabled {
-            if self.active.is_some() {
-                return vec![
-                    "System: Execution is already running; continuing current task.".to_string(),
-                ];
-            }
-            let queued = self.enqueue_ready_top_tasks();
-            if queued > 0 {
-                return vec![format!(
-                    "System: Resumed from last unfinished task(s). Queued {} task job(s).",
-                    queued
-                )];
-            }
-            return vec![
-                "System: Execution is already enabled. No unfinished tasks to resume.".to_string(),
-            ];
+            let _ = self.enqueue_ready_top_tasks();
         }
+        Ok(messages)
+    }
 
-        self.execution_enabled = true;
-        if ENFORCE_TESTS_MODE_RUNTIME_GATING && !self.tests_mode_enabled {
-            self.drop_queued_test_jobs_when_disabled();
-            self.mark_tests_disabled_state();
+    fn editable_subtree(&mut self, task_id: u64) -> Result<(u64, Vec<u64>), String> {
+        let mut path = Vec::new();
+        if !find_path(&self.tasks, task_id, &mut path) {
+            return Err(format!("No task matches id {task_id}."));
         }
-        let queued = self.enqueue_ready_top_tasks();
-        vec![format!(
-            "System: Execution enabled. Queued {} task job(s).",
-            queued
-        )]
+        let top_task_id = path[0].id;
+        if self
+            .active
+            .iter()
+            .any(|active| active.job.top_task_id == top_task_id)
+        {
+            return Err(format!(
+                "Task #{top_task_id} has a running job; wait for it to finish or /stop it first."
+            ));
+        }
+        let mut subtree = Vec::new();
+        if let Some(node) = path.last() {
+            collect_subtree_ids(std::slice::from_ref(*node), &mut subtree);
+        }
+        // Drop queued passes for the affected nodes; scheduling recreates them from pass 1.
+        self.queue
+            .retain(|job| !subtree.contains(&job.kind.node_id()));
+        self.pending_reviews
+            .retain(|review| review.top_task_id != top_task_id);
+        self.rejected_reviews.remove(&top_task_id);
+        for node_id in &subtree {
+            self.before_audit_role_passes.remove(node_id);
+            // A quorum round cut short by the reset starts over with fresh votes.
+            self.review_votes.remove(node_id);
+        }
+        Ok((top_task_id, subtree))
     }
 
-    #[cfg(test)]
-    pub fn execution_enabled(&self) -> bool {
-        self.execution_enabled
+    fn append_retry_guidance(&mut self, task_id: u64, subtree: &[u64], guidance: &str) {
+        // Guidance goes into the details of the nodes whose prompts include their own
+        // details, so it survives restarts through tasks.json.
+        let mut targets = subtree
+            .iter()
+            .copied()
+            .filter(|id| {
+                find_node(&self.tasks, *id).is_some_and(|node| {
+                    matches!(
+                        node.kind,
+                        TaskKind::Implementor | TaskKind::TestWriter | TaskKind::FinalAudit
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            targets.push(task_id);
+        }
+        for node_id in targets {
+            if let Some(node) = find_node_mut(&mut self.tasks, node_id) {
+                node.details = format!("{}\n\nRetry guidance: {guidance}", node.details.trim_end());
+            }
+        }
     }
 
-    pub fn execution_busy(&self) -> bool {
-        self.execution_enabled && (self.active.is_some() || !self.queue.is_empty())
+    pub fn resume_execution(&mut self) -> Vec<String> {
+        if !self.execution_paused {
+            return vec!["System: Execution is not paused.".to_string()];
+        }
+        self.execution_paused = false;
+        let mut messages = vec!["System: Execution resumed.".to_string()];
+        messages.extend(self.start_execution());
+        messages
     }
 
     pub fn start_next_job(&mut self) -> Option<StartedJob> {
-        if !self.execution_enabled || self.active.is_some() {
+        if !self.execution_enabled
+            || self.execution_paused
+            || !self.pending_reviews.is_empty()
+            || self.active.len() >= self.max_parallel_tasks
+        {
             return None;
         }
         let job = loop {
-            let next = self.queue.pop_front()?;
+            let position = self.queue.iter().position(|job| {
+                !self
+                    .active
+                    .iter()
+                    .any(|active| active.job.top_task_id == job.top_task_id)
+            })?;
+            let next = self.queue.remove(position)?;
             if !ENFORCE_TESTS_MODE_RUNTIME_GATING
                 || self.tests_mode_enabled
                 || !next.kind.is_test_flow_job()
@@ -521,40 +1508,165 @@ impl Workflow {
         self.mark_job_started(&job);
         let role = job.kind.role();
         let run = self.run_for_job(&job);
+        let (test_command, test_working_dir) = self.test_command_for_job(&job);
         let started = StartedJob {
             run,
             role,
             top_task_id: job.top_task_id,
             parent_context_key: job.kind.parent_context_key(),
+            workspace_dir: self.task_workspaces.get(&job.top_task_id).cloned(),
+            checkpoint_label: self.checkpoint_label_for_job(&job),
+            pass_label: pass_label_for_job(&job),
+            test_command,
+            test_working_dir,
+            model_profile: self
+                .reviewer_profile_for_job(&job)
+                .or_else(|| self.role_profile_for_job(&job)),
+            permissions: self.role_permissions_for_job(&job),
         };
-        self.active = Some(ActiveJob {
+        let now = Instant::now();
+        self.active.push(ActiveJob {
             job,
             transcript: Vec::new(),
+            started_at: now,
+            last_output_at: now,
+            pass_baseline: None,
+            test_report: None,
         });
         Some(started)
     }
 
+    /// Returns the `command`/`working_dir` set on a test runner node, with the directory
+    /// resolved against the task workspace.
+    fn test_command_for_job(&self, job: &WorkerJob) -> (Option<String>, Option<PathBuf>) {
+        if !matches!(
+            job.kind,
+            WorkerJobKind::TestRunner { .. } | WorkerJobKind::ImplementorTestRunner { .. }
+        ) {
+            return (None, None);
+        }
+        let Some(node) = find_node(&self.tasks, job.kind.node_id()) else {
+            return (None, None);
+        };
+        let working_dir = node.working_dir.as_ref().map(|dir| {
+            self.task_workspaces
+                .get(&job.top_task_id)
+                .map_or_else(|| PathBuf::from(dir), |workspace| workspace.join(dir))
+        });
+        (node.command.clone(), working_dir)
+    }
+
     pub fn active_job_meta(&self) -> Option<ActiveJobMeta> {
-        self.active.as_ref().map(|active| ActiveJobMeta {
+        self.active.first().map(|active| ActiveJobMeta {
             role: active.job.kind.role(),
             top_task_id: active.job.top_task_id,
         })
     }
 
+    pub fn active_jobs_meta(&self) -> Vec<ActiveJobMeta> {
+        self.active
+            .iter()
+            .map(|active| ActiveJobMeta {
+                role: active.job.kind.role(),
+                top_task_id: active.job.top_task_id,
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
     pub fn append_active_output(&mut self, line: String) {
-        if let Some(active) = self.active.as_mut() {
+        if let Some(active) = self.active.first_mut() {
+            active.transcript.push(line);
+            active.last_output_at = Instant::now();
+        }
+    }
+
+    pub fn append_output_for_task(&mut self, top_task_id: u64, line: String) {
+        if let Some(active) = self
+            .active
+            .iter_mut()
+            .find(|active| active.job.top_task_id == top_task_id)
+        {
             active.transcript.push(line);
+            active.last_output_at = Instant::now();
         }
     }
 
+    pub fn timed_out_jobs(&self, now: Instant) -> Vec<TimedOutJob> {
+        self.active
+            .iter()
+            .filter_map(|active| {
+                let role = active.job.kind.role();
+                let limits = self.job_timeouts.for_role(role);
+                let runtime = now.saturating_duration_since(active.started_at);
+                let idle = now.saturating_duration_since(active.last_output_at);
+                let reason =
+                    if let Some(limit) = limits.max_runtime.filter(|limit| runtime >= *limit) {
+                        format!(
+                            "{role:?} job exceeded its {} wall-clock limit",
+                            describe_duration(limit)
+                        )
+                    } else if let Some(limit) = limits.stall_after.filter(|limit| idle >= *limit) {
+                        format!(
+                            "{role:?} job produced no output for {}",
+                            describe_duration(limit)
+                        )
+                    } else {
+                        return None;
+                    };
+                Some(TimedOutJob {
+                    top_task_id: active.job.top_task_id,
+                    role,
+                    reason: format!("{reason}; its process tree was killed."),
+                })
+            })
+            .collect()
+    }
+
+    pub fn record_job_timeout(&mut self, timeout: &TimedOutJob) {
+        // The marker line lets the role's completion handler put the reason in retry feedback.
+        let Some(active) = self
+            .active
+            .iter_mut()
+            .find(|active| active.job.top_task_id == timeout.top_task_id)
+        else {
+            return;
+        };
+        // A timeout is only reported as a failure once it exhausts the role's retry budget;
+        // `finish_job_for_task` then labels that exhausted failure as a timeout.
+        active
+            .transcript
+            .push(format!("{JOB_TIMEOUT_MARKER} {}", timeout.reason));
+    }
+
+    #[cfg(test)]
     pub fn finish_active_job(&mut self, success: bool, code: i32) -> Vec<String> {
-        let Some(active) = self.active.take() else {
+        let Some(top_task_id) = self.active.first().map(|active| active.job.top_task_id) else {
+            return Vec::new();
+        };
+        self.finish_job_for_task(top_task_id, success, code)
+    }
+
+    pub fn finish_job_for_task(
+        &mut self,
+        top_task_id: u64,
+        success: bool,
+        code: i32,
+    ) -> Vec<String> {
+        let Some(position) = self
+            .active
+            .iter()
+            .position(|active| active.job.top_task_id == top_task_id)
+        else {
             return Vec::new();
         };
+        let active = self.active.remove(position);
 
         let job = active.job;
         let transcript = active.transcript;
+        let test_report = active.test_report;
         let mut messages = Vec::new();
+        let failures_before = self.recent_failures.len();
 
         match job.kind {
             WorkerJobKind::Implementor {
@@ -581,9 +1693,9 @@ impl Workflow {
                 implementor_id,
                 auditor_id,
                 pass,
+                reviewer,
                 implementation_report,
                 changed_files_summary,
-                ..
             } => {
                 implementation_auditor::on_completion(
                     self,
@@ -591,6 +1703,7 @@ impl Workflow {
                     implementor_id,
                     auditor_id,
                     pass,
+                    reviewer,
                     implementation_report,
                     changed_files_summary,
                     &transcript,
@@ -645,12 +1758,14 @@ impl Workflow {
                 test_runner_id,
                 pass,
             } => {
+                let report = self.record_test_counts(test_runner_id, test_report, &transcript);
                 test_runner::on_writer_completion(
                     self,
                     job.top_task_id,
                     test_writer_id,
                     test_runner_id,
                     pass,
+                    report.as_ref(),
                     &transcript,
                     success,
                     code,
@@ -662,12 +1777,56 @@ impl Workflow {
                 test_runner_id,
                 pass,
             } => {
+                let report = self.record_test_counts(test_runner_id, test_report, &transcript);
                 test_runner::on_implementor_completion(
                     self,
                     job.top_task_id,
                     implementor_id,
                     test_runner_id,
                     pass,
+                    report.as_ref(),
+                    &transcript,
+                    success,
+                    code,
+                    &mut messages,
+                );
+            }
+            WorkerJobKind::CoverageRunner {
+                test_writer_id,
+                test_runner_id,
+                pass,
+            } => {
+                coverage_runner::on_completion(
+                    self,
+                    job.top_task_id,
+                    test_writer_id,
+                    test_runner_id,
+                    pass,
+                    &transcript,
+                    success,
+                    code,
+                    &mut messages,
+                );
+            }
+            WorkerJobKind::ImplementorLintRunner {
+                implementor_id,
+                lint_runner_id,
+                pass,
+                implementation_report,
+                changed_files_summary,
+                resume_auditor_id,
+                resume_audit_pass,
+            } => {
+                lint_runner::on_implementor_completion(
+                    self,
+                    job.top_task_id,
+                    implementor_id,
+                    lint_runner_id,
+                    pass,
+                    implementation_report,
+                    changed_files_summary,
+                    resume_auditor_id,
+                    resume_audit_pass,
                     &transcript,
                     success,
                     code,
@@ -679,11 +1838,7 @@ impl Workflow {
                 pass,
                 ..
             } => {
-                let explicit_pass = success
-                    && matches!(
-                        parse_audit_result_token(&transcript),
-                        Some(AuditResultToken::Pass)
-                    );
+                let explicit_pass = success && !audit_detects_issues(&transcript);
                 if explicit_pass {
                     self.exhausted_final_audits.remove(&final_audit_id);
                     self.set_status(final_audit_id, TaskStatus::Done);
@@ -694,7 +1849,8 @@ impl Workflow {
                 } else {
                     self.set_status(final_audit_id, TaskStatus::NeedsChanges);
                     let feedback = audit_feedback(&transcript, code, success);
-                    if pass >= MAX_FINAL_AUDIT_RETRIES {
+                    let budget = self.retry_budget(final_audit_id, RetryLoop::FinalAudit);
+                    if pass >= budget.limit {
                         self.exhausted_final_audits.insert(final_audit_id);
                         self.recent_failures.push(WorkflowFailure {
                             kind: WorkflowFailureKind::Audit,
@@ -706,10 +1862,12 @@ impl Workflow {
                                 "Final audit retries exhausted at pass {}; stopped requeueing and awaiting user action.",
                                 pass
                             ),
+                            retry_budget: Some(budget.clone()),
+                            checkpoint_refs: self.task_checkpoint_refs(job.top_task_id),
                         });
                         messages.push(format!(
                             "System: Final audit task #{} still failed at pass {}. Max retries ({}) reached; no further final-audit retries queued.",
-                            job.top_task_id, pass, MAX_FINAL_AUDIT_RETRIES
+                            job.top_task_id, pass, budget.limit
                         ));
                     } else {
                         self.queue.push_back(WorkerJob {
@@ -734,6 +1892,40 @@ impl Workflow {
                     }
                 }
             }
+            WorkerJobKind::Role {
+                implementor_id,
+                role_id,
+                pass,
+                implementation_report,
+                changed_files_summary,
+                resume_auditor_id,
+                resume_audit_pass,
+                ..
+            } => {
+                custom_role::on_completion(
+                    self,
+                    job.top_task_id,
+                    implementor_id,
+                    role_id,
+                    pass,
+                    implementation_report,
+                    changed_files_summary,
+                    resume_auditor_id,
+                    resume_audit_pass,
+                    &transcript,
+                    success,
+                    code,
+                    &mut messages,
+                );
+            }
+        }
+
+        if job_timeout_reason(&transcript).is_some() {
+            for failure in &mut self.recent_failures[failures_before..] {
+                if failure.top_task_id == job.top_task_id {
+                    failure.kind = WorkflowFailureKind::Timeout;
+                }
+            }
         }
 
         if self.execution_enabled {
@@ -785,7 +1977,7 @@ impl Workflow {
         fn walk(node: &TaskNode, keys: &mut HashSet<String>) {
             let key = task_detail_key(node);
             keys.insert(key.clone());
-            if node.kind != TaskKind::TestRunner && !node.docs.is_empty() {
+            if !node.kind.is_deterministic() && !node.docs.is_empty() {
                 keys.insert(docs_toggle_key(&key));
             }
             for child in &node.children {
@@ -820,7 +2012,7 @@ impl Workflow {
                 lines.push(format!("  {}. {}", idx + 1, task.title));
                 lines.push(String::new());
                 lines.extend(render_detail_lines(&task.details, width, false, 2, false));
-                if task.kind != TaskKind::TestRunner && !task.docs.is_empty() {
+                if !task.kind.is_deterministic() && !task.docs.is_empty() {
                     let task_key = task_detail_key(task);
                     let docs_key = docs_toggle_key(&task_key);
                     let docs_collapsed = !expanded_detail_keys.contains(&docs_key);
@@ -845,6 +2037,7 @@ impl Workflow {
                         &mut lines,
                         &mut toggles,
                         expanded_detail_keys,
+                        &self.test_counts,
                         2,
                     );
                 }
@@ -896,7 +2089,7 @@ impl Workflow {
                 implementation_report,
                 changed_files_summary,
                 pass,
-                ..
+                reviewer,
             } => {
                 let prompt = implementation_auditor::build_prompt(
                     self,
@@ -906,6 +2099,7 @@ impl Workflow {
                     implementation_report,
                     changed_files_summary,
                     *pass,
+                    *reviewer,
                 );
                 JobRun::AgentPrompt(self.prepend_task_docs_to_prompt(*auditor_id, prompt))
             }
@@ -943,6 +2137,8 @@ impl Workflow {
             }
             WorkerJobKind::TestRunner { .. } => JobRun::DeterministicTestRun,
             WorkerJobKind::ImplementorTestRunner { .. } => JobRun::DeterministicTestRun,
+            WorkerJobKind::CoverageRunner { .. } => JobRun::DeterministicCoverageRun,
+            WorkerJobKind::ImplementorLintRunner { .. } => JobRun::DeterministicLintRun,
             WorkerJobKind::FinalAudit {
                 final_audit_id,
                 feedback,
@@ -953,31 +2149,110 @@ impl Workflow {
                 } else {
                     "Tests mode policy (OFF): do not fail solely for missing new tests; treat test additions/changes as out of scope."
                 };
-                let prompt = format!(
-                    "You are a final audit sub-agent.\n\
-                 Perform a holistic audit across all completed tasks and their outcomes.\n\
-                 Focus on cross-task correctness, missing edge cases, integration risk, and overall quality gaps.\n\
-                 Rolling task context:\n{}\n\
-                 Current task tree:\n{}\n\
-                 {}\n\
-                 {}\n\
-                 Response protocol (required):\n\
-                 - First line must be exactly one of:\n\
-                   PASS\n\
-                   FAIL\n\
-                 - PASS: no additional text after the token.\n\
-                 - FAIL: include one or more lines of findings and rationale after the token.",
-                    self.context_block(),
-                    self.task_tree_compact(),
-                    tests_policy,
-                    feedback
-                        .as_deref()
-                        .map(|f| format!("Previous final-audit feedback to address:\n{f}"))
-                        .unwrap_or_else(|| "No prior final-audit feedback.".to_string())
+                let feedback = feedback
+                    .as_deref()
+                    .map(|f| format!("Previous final-audit feedback to address:\n{f}"))
+                    .unwrap_or_else(|| "No prior final-audit feedback.".to_string());
+                let prompt = prompt_templates::render(
+                    PromptTemplate::FinalAudit,
+                    &[
+                        ("rolling_context", &self.context_block()),
+                        ("task_tree", &self.task_tree_compact()),
+                        ("tests_policy", tests_policy),
+                        ("feedback", &feedback),
+                        ("verdict_protocol", AUDIT_VERDICT_PROTOCOL),
+                    ],
                 );
                 JobRun::AgentPrompt(self.prepend_task_docs_to_prompt(*final_audit_id, prompt))
             }
+            WorkerJobKind::Role {
+                implementor_id,
+                role_id,
+                pass,
+                feedback,
+                implementation_report,
+                changed_files_summary,
+                ..
+            } => {
+                let prompt = custom_role::build_prompt(
+                    self,
+                    job.top_task_id,
+                    *implementor_id,
+                    *role_id,
+                    *pass,
+                    feedback.as_deref(),
+                    implementation_report,
+                    changed_files_summary,
+                );
+                JobRun::AgentPrompt(self.prepend_task_docs_to_prompt(*role_id, prompt))
+            }
+        }
+    }
+
+    fn checkpoint_label_for_job(&self, job: &WorkerJob) -> Option<String> {
+        if !self.checkpoints_enabled {
+            return None;
+        }
+        pass_label_for_job(job)
+    }
+
+    /// Resolves the structured result of a finished test run and keeps its pass/fail counts
+    /// for the right pane.
+    fn record_test_counts(
+        &mut self,
+        test_runner_id: u64,
+        report: Option<TestRunSummary>,
+        transcript: &[String],
+    ) -> Option<TestRunSummary> {
+        let report = report.or_else(|| parse_test_output(transcript));
+        match &report {
+            Some(report) => {
+                self.test_counts.insert(test_runner_id, report.counts);
+            }
+            None => {
+                self.test_counts.remove(&test_runner_id);
+            }
+        }
+        report
+    }
+
+    /// Renders the diff captured around the node's latest pass for an audit prompt, flagging
+    /// every mismatch with the worker's FILES_CHANGED block when one is given.
+    fn observed_changes_block(&self, node_id: u64, self_report: Option<&str>) -> String {
+        let Some(diff) = self.pass_diffs.get(&node_id) else {
+            return "(no workspace diff was captured for this pass)".to_string();
+        };
+        let mut lines = vec!["Files:".to_string(), diff.file_list()];
+        if let Some(path) = &diff.diff_file {
+            lines.push(format!("Full diff saved at: {}", path.display()));
+        }
+        if !diff.patch.trim().is_empty() {
+            let patch = match diff.patch.char_indices().nth(MAX_PROMPT_DIFF_CHARS) {
+                Some((cut, _)) => format!(
+                    "{}\n... (diff truncated; read the full diff file)",
+                    &diff.patch[..cut]
+                ),
+                None => diff.patch.trim_end().to_string(),
+            };
+            lines.push(format!("Diff:\n```diff\n{patch}\n```"));
+        } else if !diff.files.is_empty() {
+            lines.push(
+                "(workspace is not a git repository; only the changed-file list is available)"
+                    .to_string(),
+            );
+        }
+        if let Some(report) = self_report {
+            let discrepancies = diff.discrepancies(report);
+            if discrepancies.is_empty() {
+                lines.push("FILES_CHANGED matches the observed file list.".to_string());
+            } else {
+                lines.push(
+                    "FILES_CHANGED discrepancies (report each one as a finding):".to_string(),
+                );
+                lines.extend(discrepancies.into_iter().map(|item| format!("- {item}")));
+            }
         }
+        lines.join("\n")
     }
 
     fn prepend_task_docs_to_prompt(&self, task_id: u64, prompt: String) -> String {
@@ -1034,6 +2309,12 @@ impl Workflow {
                 docs: Vec::new(),
                 status: TaskStatus::Pending,
                 kind,
+                depends_on: Vec::new(),
+                max_retries: None,
+                command: None,
+                working_dir: None,
+                review_quorum: None,
+                role: None,
                 children: Vec::new(),
             });
         }
@@ -1053,7 +2334,10 @@ impl Workflow {
         {
             return Some(existing.id);
         }
+        self.add_generated_child(parent_id, kind, title)
+    }
 
+    fn add_generated_child(&mut self, parent_id: u64, kind: TaskKind, title: &str) -> Option<u64> {
         let id = self.alloc_id();
         if let Some(parent) = find_node_mut(&mut self.tasks, parent_id) {
             parent.children.push(TaskNode {
@@ -1064,6 +2348,12 @@ impl Workflow {
                 docs: Vec::new(),
                 status: TaskStatus::Pending,
                 kind,
+                depends_on: Vec::new(),
+                max_retries: None,
+                command: None,
+                working_dir: None,
+                review_quorum: None,
+                role: None,
                 children: Vec::new(),
             });
         }
@@ -1082,7 +2372,7 @@ impl Workflow {
         find_node(&self.tasks, parent_id)?
             .children
             .iter()
-            .find(|child| child.kind == kind && child.status != TaskStatus::Done)
+            .find(|child| child.kind == kind && !child.status.is_finished())
             .map(|child| child.id)
     }
 
@@ -1095,11 +2385,52 @@ impl Workflow {
         changed_files_summary: Option<String>,
         messages: &mut Vec<String>,
     ) -> bool {
+        if let Some(role_id) =
+            self.find_next_pending_role(implementor_id, RoleStage::BeforeAudit, messages)
+        {
+            self.queue_role(
+                top_task_id,
+                implementor_id,
+                role_id,
+                pass,
+                implementation_report,
+                changed_files_summary,
+                messages,
+            );
+            return true;
+        }
         let Some(auditor_id) = self.find_next_pending_child_kind(implementor_id, TaskKind::Auditor)
         else {
+            if let Some(role_id) =
+                self.find_next_pending_role(implementor_id, RoleStage::AfterAudit, messages)
+            {
+                self.queue_role(
+                    top_task_id,
+                    implementor_id,
+                    role_id,
+                    pass,
+                    implementation_report,
+                    changed_files_summary,
+                    messages,
+                );
+                return true;
+            }
+            if let Some(review_id) =
+                self.find_next_pending_child_kind(implementor_id, TaskKind::Review)
+            {
+                self.request_review(
+                    top_task_id,
+                    implementor_id,
+                    review_id,
+                    pass,
+                    changed_files_summary,
+                    messages,
+                );
+                return true;
+            }
             if (!ENFORCE_TESTS_MODE_RUNTIME_GATING || self.tests_mode_enabled)
                 && let Some(test_runner_id) =
-                self.find_next_pending_child_kind(implementor_id, TaskKind::TestRunner)
+                    self.find_next_pending_child_kind(implementor_id, TaskKind::TestRunner)
             {
                 self.queue.push_back(WorkerJob {
                     top_task_id,
@@ -1115,6 +2446,20 @@ impl Workflow {
                 ));
                 return true;
             }
+            if let Some(role_id) =
+                self.find_next_pending_role(implementor_id, RoleStage::BeforeTestWriting, messages)
+            {
+                self.queue_role(
+                    top_task_id,
+                    implementor_id,
+                    role_id,
+                    pass,
+                    implementation_report,
+                    changed_files_summary,
+                    messages,
+                );
+                return true;
+            }
             self.set_status(implementor_id, TaskStatus::Done);
             messages.push(format!(
                 "System: Task #{} implementation branch passed all audits.",
@@ -1125,19 +2470,56 @@ impl Workflow {
         };
         self.queue.push_back(WorkerJob {
             top_task_id,
-            kind: WorkerJobKind::Auditor {
-                implementor_id,
-                auditor_id,
-                pass,
-                implementation_report,
-                changed_files_summary,
-            },
-        });
+            kind: WorkerJobKind::Auditor {
+                implementor_id,
+                auditor_id,
+                pass,
+                reviewer: 1,
+                implementation_report,
+                changed_files_summary,
+            },
+        });
+        messages.push(format!(
+            "System: Task #{} audit queued (audit pass {}).",
+            top_task_id, pass
+        ));
+        true
+    }
+
+    fn request_review(
+        &mut self,
+        top_task_id: u64,
+        implementor_id: u64,
+        review_id: u64,
+        pass: u8,
+        changed_files_summary: Option<String>,
+        messages: &mut Vec<String>,
+    ) {
+        if self
+            .pending_reviews
+            .iter()
+            .any(|review| review.review_id == review_id)
+        {
+            return;
+        }
+        self.set_status(review_id, TaskStatus::InProgress);
+        let review = PendingReview {
+            top_task_id,
+            top_task_title: self.task_title(top_task_id),
+            changed_files_summary,
+            implementor_id,
+            review_id,
+            pass,
+        };
+        self.pending_reviews.push_back(review.clone());
+        self.new_reviews.push(review);
+        let task_ref = self
+            .top_task_key(top_task_id)
+            .unwrap_or_else(|| top_task_id.to_string());
         messages.push(format!(
-            "System: Task #{} audit queued (audit pass {}).",
-            top_task_id, pass
+            "System: Task #{} is waiting for human review; queued jobs are held until /approve {task_ref} or /reject {task_ref} <feedback>.",
+            top_task_id
         ));
-        true
     }
 
     fn enqueue_ready_top_tasks(&mut self) -> usize {
@@ -1149,12 +2531,13 @@ impl Workflow {
             .collect();
 
         let mut queued = 0usize;
+        let mut occupied_slots = 0usize;
         let mut on_completion_messages = Vec::<String>::new();
         let mut non_final_all_done = self
             .tasks
             .iter()
             .filter(|node| node.kind != TaskKind::FinalAudit)
-            .all(|node| node.status == TaskStatus::Done);
+            .all(|node| node.status.is_finished());
 
         if !non_final_all_done {
             self.queue
@@ -1165,140 +2548,41 @@ impl Workflow {
             let Some(top) = find_node(&self.tasks, *top_id) else {
                 continue;
             };
-            let top_status = top.status;
-            let top_kind = top.kind;
-            let top_children_empty = top.children.is_empty();
-            let has_existing_top_level_test_writer = top
-                .children
-                .iter()
-                .any(|child| child.kind == TaskKind::TestWriter);
-
-            if top_status == TaskStatus::Done {
+            if top.status.is_finished() {
                 continue;
             }
-
-            if top_kind == TaskKind::FinalAudit {
+            if top.kind == TaskKind::FinalAudit {
                 break;
             }
-
-            let has_top_level_test_writer = if top_children_empty
-                && (!ENFORCE_TESTS_MODE_RUNTIME_GATING || self.tests_mode_enabled)
-            {
-                if let Some(test_writer_id) =
-                    self.start_kind_for_top(*top_id, TaskKind::TestWriter, "Test Writing")
-                {
-                    let _ = self.find_or_create_child_kind(
-                        test_writer_id,
-                        TaskKind::TestRunner,
-                        "Deterministic Test Run",
-                    );
-                }
-                true
-            } else {
-                has_existing_top_level_test_writer
-            };
-
-            let Some(implementor_id) =
-                self.start_kind_for_top(*top_id, TaskKind::Implementor, "Implementation")
+            if !self.dependencies_satisfied(*top_id) || self.rejected_reviews.contains(top_id) {
+                continue;
+            }
+            // A top task that has (or just received) queued work occupies one worker slot;
+            // later top tasks are only scanned while slots remain.
+            let Some(queued_for_top) =
+                self.enqueue_top_task_step(*top_id, &mut on_completion_messages)
             else {
                 continue;
             };
-            self.find_or_create_child_kind(implementor_id, TaskKind::Auditor, "Audit");
-
-            if let Some(implementor_id) =
-                self.find_next_pending_child_kind(*top_id, TaskKind::Implementor)
-            {
-                if !self.branch_has_active_or_queued(*top_id, TaskKind::Implementor) {
-                    let impl_status = self.status_of(implementor_id);
-                    let has_pending_impl_audit = self
-                        .find_next_pending_child_kind(implementor_id, TaskKind::Auditor)
-                        .is_some();
-                    if impl_status == Some(TaskStatus::InProgress) && has_pending_impl_audit {
-                        // Backward-compat: older runs can leave implementor InProgress while
-                        // an auditor is pending/running. Resume at auditor, not implementor.
-                        self.set_status(implementor_id, TaskStatus::Done);
-                        if self.queue_next_implementor_audit(
-                            *top_id,
-                            implementor_id,
-                            1,
-                            None,
-                            None,
-                            &mut on_completion_messages,
-                        ) {
-                            queued += 1;
-                        }
-                        return queued;
-                    }
-                    self.queue.push_back(WorkerJob {
-                        top_task_id: *top_id,
-                        kind: WorkerJobKind::Implementor {
-                            implementor_id,
-                            pass: 1,
-                            feedback: None,
-                            resume_auditor_id: None,
-                            resume_audit_pass: None,
-                        },
-                    });
-                    queued += 1;
-                }
-                return queued;
-            }
-
-            if self.branch_has_active_or_queued(*top_id, TaskKind::Implementor) {
+            queued += queued_for_top;
+            occupied_slots += 1;
+            if occupied_slots >= self.max_parallel_tasks {
                 return queued;
             }
-
-            let queued_next_impl_step = self.queue_next_implementor_audit(
-                *top_id,
-                implementor_id,
-                1,
-                None,
-                None,
-                &mut on_completion_messages,
-            );
-            if queued_next_impl_step {
-                return queued;
-            }
-
-            if has_top_level_test_writer {
-                if let Some(test_writer_id) =
-                    self.find_next_pending_child_kind(*top_id, TaskKind::TestWriter)
-                {
-                    if (!ENFORCE_TESTS_MODE_RUNTIME_GATING || self.tests_mode_enabled)
-                        && !self.branch_has_active_or_queued(*top_id, TaskKind::TestWriter)
-                    {
-                        self.queue.push_front(WorkerJob {
-                            top_task_id: *top_id,
-                            kind: WorkerJobKind::TestWriter {
-                                test_writer_id,
-                                pass: 1,
-                                feedback: None,
-                                skip_test_runner_on_success: false,
-                                resume_auditor_id: None,
-                                resume_audit_pass: None,
-                            },
-                        });
-                        queued += 1;
-                    }
-                    return queued;
-                }
-            }
-            // No additional work was enqueued for this top; allow scanning subsequent top-level
-            // tasks (including final-audit scheduling) when non-final work is complete.
         }
 
         non_final_all_done = self
             .tasks
             .iter()
             .filter(|node| node.kind != TaskKind::FinalAudit)
-            .all(|node| node.status == TaskStatus::Done);
+            .all(|node| node.status.is_finished());
 
         if non_final_all_done {
             for top_id in root_ids {
                 let Some(top) = find_node(&self.tasks, top_id) else {
                     continue;
                 };
-                if top.kind != TaskKind::FinalAudit || top.status == TaskStatus::Done {
+                if top.kind != TaskKind::FinalAudit || top.status.is_finished() {
                     continue;
                 }
                 if self.exhausted_final_audits.contains(&top_id) {
@@ -1322,8 +2606,139 @@ impl Workflow {
         queued
     }
 
+    fn dependencies_satisfied(&self, top_id: u64) -> bool {
+        let Some(top) = find_node(&self.tasks, top_id) else {
+            return false;
+        };
+        // A dependency counts as satisfied once it is done and, when it ran in its own
+        // worktree, its changes have been handed off for merge-back. Skipped work has
+        // nothing to merge.
+        top.depends_on.iter().all(|dep| match self.status_of(*dep) {
+            Some(TaskStatus::Done) => !self.task_workspaces.contains_key(dep),
+            Some(TaskStatus::Skipped) => true,
+            _ => false,
+        })
+    }
+
+    fn enqueue_top_task_step(
+        &mut self,
+        top_id: u64,
+        on_completion_messages: &mut Vec<String>,
+    ) -> Option<usize> {
+        let top = find_node(&self.tasks, top_id)?;
+        let top_children_empty = top.children.is_empty();
+        let has_existing_top_level_test_writer = top
+            .children
+            .iter()
+            .any(|child| child.kind == TaskKind::TestWriter);
+
+        let has_top_level_test_writer = if top_children_empty
+            && (!ENFORCE_TESTS_MODE_RUNTIME_GATING || self.tests_mode_enabled)
+        {
+            if let Some(test_writer_id) =
+                self.start_kind_for_top(top_id, TaskKind::TestWriter, "Test Writing")
+            {
+                let _ = self.find_or_create_child_kind(
+                    test_writer_id,
+                    TaskKind::TestRunner,
+                    "Deterministic Test Run",
+                );
+            }
+            true
+        } else {
+            has_existing_top_level_test_writer
+        };
+
+        let implementor_id =
+            self.start_kind_for_top(top_id, TaskKind::Implementor, "Implementation")?;
+        self.find_or_create_child_kind(implementor_id, TaskKind::Auditor, "Audit");
+        self.attach_auto_roles(implementor_id);
+
+        let mut queued = 0usize;
+        if let Some(implementor_id) =
+            self.find_next_pending_child_kind(top_id, TaskKind::Implementor)
+        {
+            if !self.branch_has_active_or_queued(top_id, TaskKind::Implementor) {
+                let impl_status = self.status_of(implementor_id);
+                let has_pending_impl_audit = self
+                    .find_next_pending_child_kind(implementor_id, TaskKind::Auditor)
+                    .is_some();
+                if impl_status == Some(TaskStatus::InProgress) && has_pending_impl_audit {
+                    // Backward-compat: older runs can leave implementor InProgress while
+                    // an auditor is pending/running. Resume at auditor, not implementor.
+                    self.set_status(implementor_id, TaskStatus::Done);
+                    if self.queue_next_implementor_audit(
+                        top_id,
+                        implementor_id,
+                        1,
+                        None,
+                        None,
+                        on_completion_messages,
+                    ) {
+                        queued += 1;
+                    }
+                    return Some(queued);
+                }
+                self.queue.push_back(WorkerJob {
+                    top_task_id: top_id,
+                    kind: WorkerJobKind::Implementor {
+                        implementor_id,
+                        pass: 1,
+                        feedback: None,
+                        resume_auditor_id: None,
+                        resume_audit_pass: None,
+                    },
+                });
+                queued += 1;
+            }
+            return Some(queued);
+        }
+
+        if self.branch_has_active_or_queued(top_id, TaskKind::Implementor) {
+            return Some(queued);
+        }
+
+        let queued_next_impl_step = self.queue_next_implementor_audit(
+            top_id,
+            implementor_id,
+            1,
+            None,
+            None,
+            on_completion_messages,
+        );
+        if queued_next_impl_step {
+            return Some(queued);
+        }
+
+        if has_top_level_test_writer
+            && let Some(test_writer_id) =
+                self.find_next_pending_child_kind(top_id, TaskKind::TestWriter)
+        {
+            if (!ENFORCE_TESTS_MODE_RUNTIME_GATING || self.tests_mode_enabled)
+                && !self.branch_has_active_or_queued(top_id, TaskKind::TestWriter)
+            {
+                self.queue.push_front(WorkerJob {
+                    top_task_id: top_id,
+                    kind: WorkerJobKind::TestWriter {
+                        test_writer_id,
+                        pass: 1,
+                        feedback: None,
+                        skip_test_runner_on_success: false,
+                        resume_auditor_id: None,
+                        resume_audit_pass: None,
+                    },
+                });
+                queued += 1;
+            }
+            return Some(queued);
+        }
+        // No additional work was enqueued for this top; allow scanning subsequent top-level
+        // tasks (including final-audit scheduling) when non-final work is complete.
+        None
+    }
+
     fn final_audit_has_active_or_queued(&self, final_audit_id: u64) -> bool {
-        if self.active.as_ref().is_some_and(|active| {
+        if self.active.iter().any(|active| {
             matches!(
                 active.job.kind,
                 WorkerJobKind::FinalAudit { final_audit_id: id, .. } if id == final_audit_id
@@ -1411,7 +2826,9 @@ impl Workflow {
                     kind,
                     WorkerJobKind::Implementor { .. }
                         | WorkerJobKind::ImplementorTestRunner { .. }
+                        | WorkerJobKind::ImplementorLintRunner { .. }
                         | WorkerJobKind::Auditor { .. }
+                        | WorkerJobKind::Role { .. }
                 )
             }
             TaskKind::TestWriter => {
@@ -1419,6 +2836,7 @@ impl Workflow {
                     kind,
                     WorkerJobKind::TestWriter { .. }
                         | WorkerJobKind::TestRunner { .. }
+                        | WorkerJobKind::CoverageRunner { .. }
                         | WorkerJobKind::TestWriterAuditor { .. }
                 )
             }
@@ -1427,8 +2845,8 @@ impl Workflow {
 
         if self
             .active
-            .as_ref()
-            .is_some_and(|active| active.job.top_task_id == top_id && in_branch(&active.job.kind))
+            .iter()
+            .any(|active| active.job.top_task_id == top_id && in_branch(&active.job.kind))
         {
             return true;
         }
@@ -1440,29 +2858,7 @@ impl Workflow {
 
     fn mark_job_started(&mut self, job: &WorkerJob) {
         self.set_status(job.top_task_id, TaskStatus::InProgress);
-        match &job.kind {
-            WorkerJobKind::Implementor { implementor_id, .. } => {
-                self.set_status(*implementor_id, TaskStatus::InProgress)
-            }
-            WorkerJobKind::Auditor { auditor_id, .. } => {
-                self.set_status(*auditor_id, TaskStatus::InProgress)
-            }
-            WorkerJobKind::TestWriterAuditor { auditor_id, .. } => {
-                self.set_status(*auditor_id, TaskStatus::InProgress)
-            }
-            WorkerJobKind::TestWriter { test_writer_id, .. } => {
-                self.set_status(*test_writer_id, TaskStatus::InProgress)
-            }
-            WorkerJobKind::TestRunner { test_runner_id, .. } => {
-                self.set_status(*test_runner_id, TaskStatus::InProgress)
-            }
-            WorkerJobKind::ImplementorTestRunner { test_runner_id, .. } => {
-                self.set_status(*test_runner_id, TaskStatus::InProgress)
-            }
-            WorkerJobKind::FinalAudit { final_audit_id, .. } => {
-                self.set_status(*final_audit_id, TaskStatus::InProgress)
-            }
-        }
+        self.set_status(job.kind.node_id(), TaskStatus::InProgress);
     }
 
     fn set_status(&mut self, node_id: u64, status: TaskStatus) {
@@ -1488,6 +2884,41 @@ impl Workflow {
             .unwrap_or_else(|| fallback.to_string())
     }
 
+    fn retry_budget(&self, node_id: u64, retry_loop: RetryLoop) -> RetryBudget {
+        if let Some(budget) = self.task_retry_override(node_id) {
+            return budget;
+        }
+        let (limit, key) = match retry_loop {
+            RetryLoop::Audit => (self.retry_budgets.audit, "audit"),
+            RetryLoop::Test => (self.retry_budgets.test, "test"),
+            RetryLoop::FinalAudit => (self.retry_budgets.final_audit, "final_audit"),
+        };
+        RetryBudget {
+            limit,
+            source: format!("[workflow.retries] {key}"),
+        }
+    }
+
+    /// The closest task on the path from the looping node up to its top-level task that sets
+    /// max_retries; `None` leaves the configured default in charge.
+    fn task_retry_override(&self, node_id: u64) -> Option<RetryBudget> {
+        let mut path = Vec::new();
+        if !find_path(&self.tasks, node_id, &mut path) {
+            return None;
+        }
+        path.iter().rev().find_map(|node| {
+            node.max_retries.map(|limit| RetryBudget {
+                limit,
+                source: format!(
+                    "max_retries on task {}",
+                    node.external_id
+                        .clone()
+                        .unwrap_or_else(|| format!("internal-{}", node.id))
+                ),
+            })
+        })
+    }
+
     fn node_details(&self, node_id: u64) -> String {
         find_node(&self.tasks, node_id)
             .map(|node| node.details.trim().to_string())
@@ -1521,7 +2952,7 @@ impl Workflow {
             (
                 impl_done,
                 test_done,
-                top.status == TaskStatus::Done,
+                top.status.is_finished(),
                 requires_test_writer,
             )
         };
@@ -1547,7 +2978,7 @@ impl Workflow {
     }
 
     fn subtree_done(node: &TaskNode) -> bool {
-        if node.status != TaskStatus::Done {
+        if !node.status.is_finished() {
             return false;
         }
         node.children.iter().all(Self::subtree_done)
@@ -1711,6 +3142,7 @@ impl WorkerJobKind {
             WorkerJobKind::TestWriter { .. }
                 | WorkerJobKind::TestWriterAuditor { .. }
                 | WorkerJobKind::TestRunner { .. }
+                | WorkerJobKind::CoverageRunner { .. }
                 | WorkerJobKind::ImplementorTestRunner { .. }
         )
     }
@@ -1728,6 +3160,24 @@ fn find_node(nodes: &[TaskNode], id: u64) -> Option<&TaskNode> {
     None
 }
 
+fn collect_subtree_ids(nodes: &[TaskNode], ids: &mut Vec<u64>) {
+    for node in nodes {
+        ids.push(node.id);
+        collect_subtree_ids(&node.children, ids);
+    }
+}
+
+fn find_path<'a>(nodes: &'a [TaskNode], id: u64, path: &mut Vec<&'a TaskNode>) -> bool {
+    for node in nodes {
+        path.push(node);
+        if node.id == id || find_path(&node.children, id, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
 fn find_node_mut(nodes: &mut [TaskNode], id: u64) -> Option<&mut TaskNode> {
     for node in nodes {
         if node.id == id {
@@ -1749,6 +3199,9 @@ fn render_tree(node: &TaskNode, depth: usize, lines: &mut Vec<String>) {
         TaskKind::Auditor => "Audit",
         TaskKind::TestWriter => "Tests",
         TaskKind::TestRunner => "TestRun",
+        TaskKind::LintRunner => "Lint",
+        TaskKind::Review => "Review",
+        TaskKind::Role => "Role",
     };
     lines.push(format!(
         "{indent}- {} {}: {}",
@@ -1767,6 +3220,7 @@ fn render_subtree_box(
     out: &mut Vec<String>,
     toggles: &mut Vec<RightPaneToggleLine>,
     expanded_detail_keys: &HashSet<String>,
+    test_counts: &HashMap<u64, TestCounts>,
     left_indent: usize,
 ) {
     let width = width.max(8);
@@ -1778,12 +3232,15 @@ fn render_subtree_box(
 
     let key = task_detail_key(node);
     let collapsed = !expanded_detail_keys.contains(&key);
-    let header = format!(
+    let mut header = format!(
         "{} {}: {}",
         status_label(node.status),
         kind_label(node.kind),
         node.title
     );
+    if let Some(counts) = test_counts.get(&node.id) {
+        header.push_str(&format!(" ({})", counts.label()));
+    }
     let inner_text_width = width.saturating_sub(4).max(1);
     let header_segments = wrap_words(&header, inner_text_width);
     for segment in header_segments {
@@ -1806,7 +3263,7 @@ fn render_subtree_box(
         task_key: key.clone(),
     });
 
-    if node.kind != TaskKind::TestRunner && !node.docs.is_empty() {
+    if !node.kind.is_deterministic() && !node.docs.is_empty() {
         let docs_key = docs_toggle_key(&key);
         let docs_collapsed = !expanded_detail_keys.contains(&docs_key);
         let docs_line_index = out.len();
@@ -1852,6 +3309,7 @@ fn render_subtree_box(
                 &mut child_lines,
                 &mut child_toggles,
                 expanded_detail_keys,
+                test_counts,
                 0,
             );
             let line_offset = out.len();
@@ -1893,7 +3351,86 @@ fn kind_label(kind: TaskKind) -> &'static str {
         TaskKind::Auditor => "Audit",
         TaskKind::TestWriter => "Tests",
         TaskKind::TestRunner => "TestRun",
+        TaskKind::LintRunner => "Lint",
+        TaskKind::Review => "Review",
+        TaskKind::Role => "Role",
+    }
+}
+
+fn validate_task_dependencies(entries: &[PlannerTaskFileEntry]) -> Result<(), String> {
+    let by_id: HashMap<&str, &PlannerTaskFileEntry> = entries
+        .iter()
+        .map(|entry| (entry.id.as_str(), entry))
+        .collect();
+    for entry in entries {
+        if entry.depends_on.is_empty() {
+            continue;
+        }
+        if entry.parent_id.is_some() || entry.kind != PlannerTaskKindFile::Task {
+            return Err(format!(
+                "Planner task {} declares depends_on; only top-level \"task\" entries may have dependencies",
+                entry.id
+            ));
+        }
+        let mut seen = HashSet::new();
+        for dep in &entry.depends_on {
+            if dep == &entry.id {
+                return Err(format!("Planner task {} cannot depend on itself", entry.id));
+            }
+            if !seen.insert(dep.as_str()) {
+                return Err(format!(
+                    "Planner task {} lists dependency {} more than once",
+                    entry.id, dep
+                ));
+            }
+            let Some(target) = by_id.get(dep.as_str()) else {
+                return Err(format!(
+                    "Planner task {} depends_on missing task {}",
+                    entry.id, dep
+                ));
+            };
+            if target.parent_id.is_some() || target.kind != PlannerTaskKindFile::Task {
+                return Err(format!(
+                    "Planner task {} depends_on {}, which is not a top-level \"task\" entry",
+                    entry.id, dep
+                ));
+            }
+        }
+    }
+
+    fn visit<'a>(
+        id: &'a str,
+        by_id: &HashMap<&'a str, &'a PlannerTaskFileEntry>,
+        done: &mut HashSet<&'a str>,
+        path: &mut Vec<&'a str>,
+    ) -> Result<(), String> {
+        if done.contains(id) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|step| *step == id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id);
+            return Err(format!(
+                "Planner task dependency cycle: {}",
+                cycle.join(" -> ")
+            ));
+        }
+        path.push(id);
+        if let Some(entry) = by_id.get(id) {
+            for dep in &entry.depends_on {
+                visit(dep, by_id, done, path)?;
+            }
+        }
+        path.pop();
+        done.insert(id);
+        Ok(())
+    }
+
+    let mut done = HashSet::new();
+    for entry in entries {
+        visit(&entry.id, &by_id, &mut done, &mut Vec::new())?;
     }
+    Ok(())
 }
 
 fn validate_required_subtask_structure(nodes: &[TaskNode]) -> Result<(), String> {
@@ -1952,6 +3489,17 @@ fn validate_required_subtask_structure(nodes: &[TaskNode]) -> Result<(), String>
                     node_label(node)
                 ));
             }
+            let lint_runner_count = node
+                .children
+                .iter()
+                .filter(|child| child.kind == TaskKind::LintRunner)
+                .count();
+            if lint_runner_count > 1 {
+                return Err(format!(
+                    "Implementor task \"{}\" must include at most one lint_runner subtask",
+                    node_label(node)
+                ));
+            }
         }
 
         if node.kind == TaskKind::TestWriter && parent_kind != Some(TaskKind::Top) {
@@ -1971,6 +3519,20 @@ fn validate_required_subtask_structure(nodes: &[TaskNode]) -> Result<(), String>
             ));
         }
 
+        if node.kind == TaskKind::Role && parent_kind != Some(TaskKind::Implementor) {
+            return Err(format!(
+                "Role task \"{}\" must be a child of implementor",
+                node_label(node)
+            ));
+        }
+
+        if node.kind == TaskKind::Review && parent_kind != Some(TaskKind::Implementor) {
+            return Err(format!(
+                "Review task \"{}\" must be a child of implementor",
+                node_label(node)
+            ));
+        }
+
         if node.kind == TaskKind::TestRunner
             && parent_kind != Some(TaskKind::Implementor)
             && parent_kind != Some(TaskKind::TestWriter)
@@ -1981,6 +3543,13 @@ fn validate_required_subtask_structure(nodes: &[TaskNode]) -> Result<(), String>
             ));
         }
 
+        if node.kind == TaskKind::LintRunner && parent_kind != Some(TaskKind::Implementor) {
+            return Err(format!(
+                "Lint-runner task \"{}\" must be a child of implementor",
+                node_label(node)
+            ));
+        }
+
         if node.kind == TaskKind::TestWriter
             && !node
                 .children
@@ -2224,6 +3793,26 @@ fn status_label(status: TaskStatus) -> &'static str {
         TaskStatus::InProgress => "[~]",
         TaskStatus::NeedsChanges => "[!]",
         TaskStatus::Done => "[x]",
+        TaskStatus::Skipped => "[-]",
+    }
+}
+
+fn normalize_optional_text(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+fn normalize_review_quorum(quorum: &ReviewQuorumFileEntry) -> ReviewQuorumFileEntry {
+    ReviewQuorumFileEntry {
+        reviewers: quorum.reviewers,
+        required: quorum.required,
+        profiles: quorum
+            .profiles
+            .iter()
+            .filter_map(|profile| normalize_optional_text(Some(profile)))
+            .collect(),
     }
 }
 
@@ -2235,6 +3824,9 @@ fn task_kind_from_file(kind: PlannerTaskKindFile) -> TaskKind {
         PlannerTaskKindFile::Auditor => TaskKind::Auditor,
         PlannerTaskKindFile::TestWriter => TaskKind::TestWriter,
         PlannerTaskKindFile::TestRunner => TaskKind::TestRunner,
+        PlannerTaskKindFile::LintRunner => TaskKind::LintRunner,
+        PlannerTaskKindFile::Review => TaskKind::Review,
+        PlannerTaskKindFile::Role => TaskKind::Role,
     }
 }
 
@@ -2246,6 +3838,9 @@ fn task_kind_to_file(kind: TaskKind) -> PlannerTaskKindFile {
         TaskKind::Auditor => PlannerTaskKindFile::Auditor,
         TaskKind::TestWriter => PlannerTaskKindFile::TestWriter,
         TaskKind::TestRunner => PlannerTaskKindFile::TestRunner,
+        TaskKind::LintRunner => PlannerTaskKindFile::LintRunner,
+        TaskKind::Review => PlannerTaskKindFile::Review,
+        TaskKind::Role => PlannerTaskKindFile::Role,
     }
 }
 
@@ -2255,6 +3850,15 @@ fn task_status_to_file(status: TaskStatus) -> PlannerTaskStatusFile {
         TaskStatus::InProgress => PlannerTaskStatusFile::InProgress,
         TaskStatus::NeedsChanges => PlannerTaskStatusFile::NeedsChanges,
         TaskStatus::Done => PlannerTaskStatusFile::Done,
+        TaskStatus::Skipped => PlannerTaskStatusFile::Skipped,
+    }
+}
+
+fn pass_label_for_job(job: &WorkerJob) -> Option<String> {
+    match &job.kind {
+        WorkerJobKind::Implementor { pass, .. } => Some(format!("implementor-pass-{pass}")),
+        WorkerJobKind::TestWriter { pass, .. } => Some(format!("test_writer-pass-{pass}")),
+        _ => None,
     }
 }
 
@@ -2298,18 +3902,14 @@ fn audit_strictness_policy(pass: u8) -> &'static str {
 }
 
 fn audit_detects_issues(transcript: &[String]) -> bool {
-    if let Some(protocol_result) = parse_audit_result_token(transcript) {
-        return !matches!(protocol_result, AuditResultToken::Pass);
+    if let Some(verdict) = parse_audit_verdict(transcript) {
+        return verdict.requires_changes();
     }
-    let text = transcript.join("\n").to_lowercase();
-    if text.contains("no issues found") || text.contains("no findings") {
-        return false;
-    }
-    text.contains("issue")
-        || text.contains("bug")
-        || text.contains("error")
-        || text.contains("fix required")
-        || text.contains("needs change")
+    // Legacy first-line PASS/FAIL token; anything else is a protocol violation.
+    !matches!(
+        parse_audit_result_token(transcript),
+        Some(AuditResultToken::Pass)
+    )
 }
 
 #[derive(Debug, PartialEq)]
@@ -2383,29 +3983,101 @@ fn default_generated_details(kind: TaskKind) -> &'static str {
         TaskKind::TestRunner => {
             "Run deterministic tests and report pass/fail outcomes for this task branch."
         }
+        TaskKind::LintRunner => {
+            "Run the lint command and send failures back to the implementor before any audit."
+        }
         TaskKind::FinalAudit => {
             "Perform a final cross-task audit after all implementation and testing complete."
         }
+        TaskKind::Review => {
+            "Hold the implementation for a human to review the diff before tests or later tasks run."
+        }
+        TaskKind::Role => {
+            "Run the configured worker role against this implementation branch at its configured stage."
+        }
         TaskKind::Top => "Top-level task scope and expected outcome.",
     }
 }
 
+fn job_timeout_reason(transcript: &[String]) -> Option<&str> {
+    transcript
+        .iter()
+        .rev()
+        .find_map(|line| line.strip_prefix(JOB_TIMEOUT_MARKER))
+        .map(str::trim)
+}
+
+fn failed_run_feedback(label: &str, code: i32, transcript: &[String]) -> String {
+    match job_timeout_reason(transcript) {
+        Some(reason) => format!("Previous {label} run was killed: {reason}"),
+        None => format!("Previous {label} run failed with code {code}."),
+    }
+}
+
+fn describe_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
 fn audit_feedback(transcript: &[String], code: i32, success: bool) -> String {
+    if let Some(reason) = job_timeout_reason(transcript) {
+        return format!("Audit process was killed: {reason} Re-run implementation and validate.");
+    }
     if !success {
         return format!(
             "Audit process exited with code {code}; re-run implementation and validate."
         );
     }
+    if let Some(verdict) = parse_audit_verdict(transcript) {
+        return verdict.unresolved_feedback();
+    }
     let merged = transcript.join(" ");
     if merged.trim().is_empty() {
         "Audit requested fixes without detailed notes; review implementation against requirements."
             .to_string()
+    } else if parse_audit_result_token(transcript).is_none() {
+        format!(
+            "Audit response did not include a JSON verdict; treat these notes as unresolved: {merged}"
+        )
     } else {
         format!("Audit feedback: {merged}")
     }
 }
 
-fn test_runner_feedback(transcript: &[String], code: i32) -> String {
+fn lint_runner_feedback(transcript: &[String], code: i32) -> String {
+    if let Some(reason) = job_timeout_reason(transcript) {
+        return format!("Lint run was killed: {reason}");
+    }
+    let merged = transcript.join("\n");
+    if merged.trim().is_empty() {
+        return format!("Lint run failed with code {code} and no output.");
+    }
+    format!("Lint run failed with code {code}. Fix every reported issue. Output:\n{merged}")
+}
+
+fn test_runner_feedback(
+    transcript: &[String],
+    code: i32,
+    report: Option<&TestRunSummary>,
+) -> String {
+    if let Some(reason) = job_timeout_reason(transcript) {
+        let output = transcript
+            .iter()
+            .filter(|line| !line.starts_with(JOB_TIMEOUT_MARKER))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        return format!(
+            "Deterministic test run was killed: {reason} Output before the kill:\n{output}"
+        );
+    }
+    if let Some(report) = report.filter(|report| !report.failures.is_empty()) {
+        return structured_test_feedback(report, code);
+    }
     let merged = transcript.join("\n");
     if merged.trim().is_empty() {
         return format!("Deterministic test run failed with code {code} and no output.");
@@ -2413,6 +4085,39 @@ fn test_runner_feedback(transcript: &[String], code: i32) -> String {
     format!("Deterministic test run failed with code {code}. Output:\n{merged}")
 }
 
+/// Lists only the failing cases so large suites do not flood the next prompt.
+fn structured_test_feedback(report: &TestRunSummary, code: i32) -> String {
+    let mut lines = vec![format!(
+        "Deterministic test run failed with code {code} ({}). Failing tests:",
+        report.counts.label()
+    )];
+    for (index, failure) in report
+        .failures
+        .iter()
+        .take(MAX_REPORTED_TEST_FAILURES)
+        .enumerate()
+    {
+        match &failure.file {
+            Some(file) => lines.push(format!("{}. {} ({file})", index + 1, failure.name)),
+            None => lines.push(format!("{}. {}", index + 1, failure.name)),
+        }
+        if !failure.message.is_empty() {
+            lines.push(format!("   {}", failure.message));
+        }
+        lines.extend(failure.snippet.iter().map(|line| format!("   | {line}")));
+    }
+    let hidden = report
+        .failures
+        .len()
+        .saturating_sub(MAX_REPORTED_TEST_FAILURES);
+    if hidden > 0 {
+        lines.push(format!(
+            "... and {hidden} more failing tests; fix the ones above first."
+        ));
+    }
+    lines.join("\n")
+}
+
 #[cfg(test)]
 #[path = "../tests/unit/workflow_tests.rs"]
 mod tests;
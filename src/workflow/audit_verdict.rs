@@ -0,0 +1,147 @@
+use serde::Deserialize;
+
+pub(crate) const AUDIT_VERDICT_PROTOCOL: &str = "Response protocol (required):\n\
+     - End your response with exactly one fenced ```json block containing the verdict:\n\
+       {\"status\": \"pass\" | \"fail\", \"findings\": [{\"severity\": \"critical\" | \"high\" | \"medium\" | \"low\" | \"info\", \"file\": \"path or null\", \"line\": 42, \"message\": \"what is wrong\", \"suggested_fix\": \"how to fix it\"}]}\n\
+     - critical and high findings are blocking and send the work back for another pass; medium, low, and info findings are advisory only.\n\
+     - status must be \"fail\" when any blocking finding is listed and \"pass\" otherwise; use an empty findings array when nothing needs attention.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    #[serde(alias = "PASS", alias = "Pass")]
+    Pass,
+    #[serde(alias = "FAIL", alias = "Fail")]
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSeverity {
+    #[serde(alias = "CRITICAL", alias = "Critical")]
+    Critical,
+    #[serde(alias = "HIGH", alias = "High")]
+    High,
+    #[serde(alias = "MEDIUM", alias = "Medium")]
+    Medium,
+    #[serde(alias = "LOW", alias = "Low")]
+    Low,
+    #[serde(alias = "INFO", alias = "Info")]
+    Info,
+}
+
+impl AuditSeverity {
+    pub fn is_blocking(self) -> bool {
+        matches!(self, Self::Critical | Self::High)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Critical => "critical",
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+            Self::Info => "info",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AuditFinding {
+    pub severity: AuditSeverity,
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub line: Option<u32>,
+    pub message: String,
+    #[serde(default)]
+    pub suggested_fix: Option<String>,
+}
+
+impl AuditFinding {
+    pub(crate) fn render(&self) -> String {
+        let location = match (self.file.as_deref(), self.line) {
+            (Some(file), Some(line)) => format!(" {file}:{line}"),
+            (Some(file), None) => format!(" {file}"),
+            _ => String::new(),
+        };
+        let fix = self
+            .suggested_fix
+            .as_deref()
+            .map(str::trim)
+            .filter(|fix| !fix.is_empty())
+            .map(|fix| format!(" Suggested fix: {fix}"))
+            .unwrap_or_default();
+        format!(
+            "- [{}]{location} {}{fix}",
+            self.severity.label(),
+            self.message.trim()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AuditVerdict {
+    pub status: AuditStatus,
+    #[serde(default)]
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditVerdict {
+    pub fn blocking_findings(&self) -> impl Iterator<Item = &AuditFinding> {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity.is_blocking())
+    }
+
+    pub fn requires_changes(&self) -> bool {
+        // A bare "fail" with nothing to act on still goes back for another pass.
+        self.blocking_findings().next().is_some()
+            || (self.status == AuditStatus::Fail && self.findings.is_empty())
+    }
+
+    pub fn unresolved_feedback(&self) -> String {
+        let lines = self
+            .blocking_findings()
+            .map(AuditFinding::render)
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            return "Audit returned fail without findings; review the implementation against the task requirements."
+                .to_string();
+        }
+        format!("Unresolved blocking audit findings:\n{}", lines.join("\n"))
+    }
+}
+
+pub(crate) fn parse_audit_verdict(transcript: &[String]) -> Option<AuditVerdict> {
+    let text = transcript.join("\n");
+    if let Some(verdict) = fenced_json_blocks(&text)
+        .into_iter()
+        .rev()
+        .find_map(|block| serde_json::from_str::<AuditVerdict>(block).ok())
+    {
+        return Some(verdict);
+    }
+    serde_json::from_str::<AuditVerdict>(text.trim()).ok()
+}
+
+fn fenced_json_blocks(text: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("```") {
+        let after_fence = &rest[start + 3..];
+        let Some(newline) = after_fence.find('\n') else {
+            break;
+        };
+        let info = after_fence[..newline].trim();
+        let body = &after_fence[newline + 1..];
+        let Some(end) = body.find("```") else {
+            break;
+        };
+        if info.is_empty() || info.eq_ignore_ascii_case("json") {
+            blocks.push(body[..end].trim());
+        }
+        rest = &body[end + 3..];
+    }
+    blocks
+}
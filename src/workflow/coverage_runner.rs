@@ -0,0 +1,154 @@
+use super::Workflow;
+use super::{RetryLoop, TaskStatus};
+use crate::coverage::{CoverageReport, check_changed_line_coverage, parse_coverage_report};
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn on_completion(
+    workflow: &mut Workflow,
+    top_task_id: u64,
+    test_writer_id: u64,
+    test_runner_id: u64,
+    pass: u8,
+    transcript: &[String],
+    success: bool,
+    code: i32,
+    messages: &mut Vec<String>,
+) {
+    let Some(threshold) = workflow.coverage_threshold() else {
+        finish_branch(workflow, top_task_id, test_writer_id, messages);
+        return;
+    };
+    if !success {
+        // A broken coverage setup is not the test writer's fault, so it does not cost a retry.
+        messages.push(format!(
+            "System: Task #{} coverage command failed with exit code {}; skipping the coverage gate.",
+            top_task_id, code
+        ));
+        finish_branch(workflow, top_task_id, test_writer_id, messages);
+        return;
+    }
+    let measured = match parse_coverage_report(transcript) {
+        CoverageReport::Lines(report) => workflow
+            .task_changed_lines(top_task_id)
+            .map(|changed| (report, changed))
+            .ok_or(
+                "the task's workspace is not a git repository, so its changed lines are unknown",
+            ),
+        CoverageReport::FileTotals(_) => Err(
+            "the coverage command printed only per-file totals, which cannot tell the task's changed lines apart",
+        ),
+        CoverageReport::Unrecognized => {
+            Err("the coverage command printed no lcov or Cobertura line data")
+        }
+    };
+    let (report, changed) = match measured {
+        Ok(measured) => measured,
+        Err(reason) => {
+            gate_unavailable(
+                workflow,
+                top_task_id,
+                test_writer_id,
+                pass,
+                reason,
+                messages,
+            );
+            return;
+        }
+    };
+    let Some(check) = check_changed_line_coverage(&report, &changed) else {
+        messages.push(format!(
+            "System: Task #{} coverage report instruments none of the task's changed non-test lines; skipping the coverage gate.",
+            top_task_id
+        ));
+        finish_branch(workflow, top_task_id, test_writer_id, messages);
+        return;
+    };
+    if check.percent >= threshold {
+        messages.push(format!(
+            "System: Task #{} coverage of changed lines is {:.1}% (threshold {:.1}%).",
+            top_task_id, check.percent, threshold
+        ));
+        finish_branch(workflow, top_task_id, test_writer_id, messages);
+        return;
+    }
+
+    // Coverage retries share the deterministic test retry budget.
+    let budget = workflow.retry_budget(test_runner_id, RetryLoop::Test);
+    if pass >= budget.limit {
+        workflow.recent_failures.push(super::WorkflowFailure {
+            kind: super::WorkflowFailureKind::Test,
+            top_task_id,
+            top_task_title: workflow.task_title(top_task_id),
+            attempts: pass,
+            reason: check.feedback(threshold),
+            action_taken:
+                "Coverage retries exhausted; continued with coverage below the threshold."
+                    .to_string(),
+            retry_budget: Some(budget.clone()),
+            checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
+        });
+        messages.push(format!(
+            "System: Task #{} coverage of changed lines still {:.1}% at pass {}. Max retries ({}) reached; continuing.",
+            top_task_id, check.percent, pass, budget.limit
+        ));
+        finish_branch(workflow, top_task_id, test_writer_id, messages);
+        return;
+    }
+
+    workflow.set_status(test_writer_id, TaskStatus::NeedsChanges);
+    workflow.queue.push_back(super::WorkerJob {
+        top_task_id,
+        kind: super::WorkerJobKind::TestWriter {
+            test_writer_id,
+            pass: pass.saturating_add(1),
+            feedback: Some(check.feedback(threshold)),
+            skip_test_runner_on_success: false,
+            resume_auditor_id: None,
+            resume_audit_pass: None,
+        },
+    });
+    messages.push(format!(
+        "System: Task #{} coverage of changed lines {:.1}% is below {:.1}%; test-writer pass {} queued.",
+        top_task_id,
+        check.percent,
+        threshold,
+        pass.saturating_add(1)
+    ));
+}
+
+/// Records a coverage gate that could not measure changed lines. The gate is not applied on
+/// file totals instead, and the misconfiguration does not cost the test writer a retry.
+fn gate_unavailable(
+    workflow: &mut Workflow,
+    top_task_id: u64,
+    test_writer_id: u64,
+    pass: u8,
+    reason: &str,
+    messages: &mut Vec<String>,
+) {
+    workflow.recent_failures.push(super::WorkflowFailure {
+        kind: super::WorkflowFailureKind::Test,
+        top_task_id,
+        top_task_title: workflow.task_title(top_task_id),
+        attempts: pass,
+        reason: format!("Coverage gate could not run: {reason}."),
+        action_taken: "Continued without the coverage gate; make coverage_command print an lcov or Cobertura report.".to_string(),
+        retry_budget: None,
+        checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
+    });
+    messages.push(format!(
+        "System: Task #{} coverage gate failed: {}. Make coverage_command print per-line data, for example `cargo llvm-cov --lcov`, `coverage xml -o -` or `nyc --reporter=text-lcov`; continuing without the gate.",
+        top_task_id, reason
+    ));
+    finish_branch(workflow, top_task_id, test_writer_id, messages);
+}
+
+fn finish_branch(
+    workflow: &mut Workflow,
+    top_task_id: u64,
+    test_writer_id: u64,
+    messages: &mut Vec<String>,
+) {
+    workflow.set_status(test_writer_id, TaskStatus::Done);
+    workflow.try_mark_top_done(top_task_id, messages);
+}
@@ -0,0 +1,611 @@
+use serde::Deserialize;
+
+use super::audit_verdict::AUDIT_VERDICT_PROTOCOL;
+use super::implementor::queue_audit_after_implementation;
+use super::{
+    AuditResultToken, RetryBudget, TaskKind, TaskStatus, WorkerJob, WorkerJobKind, Workflow,
+    audit_detects_issues, audit_feedback, find_node, job_timeout_reason, make_context_summary,
+    parse_audit_result_token,
+};
+use crate::agent::PermissionProfile;
+use crate::prompt_templates::{self, PromptTemplate};
+
+const PASS_FAIL_VERDICT_PROTOCOL: &str = "Response protocol (required):\n\
+     - The first line of your response must be exactly PASS or FAIL.\n\
+     - On PASS, output nothing else.\n\
+     - On FAIL, list every blocking problem on its own line after FAIL, with the file and a suggested fix where possible.";
+
+/// Where a role runs inside an implementor branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleStage {
+    /// After each implementor pass (and its lint run), before the auditors.
+    BeforeAudit,
+    /// After the auditors, before any human review gate.
+    #[default]
+    AfterAudit,
+    /// Last step of the implementor branch, so before the task's test writer starts.
+    BeforeTestWriting,
+}
+
+impl RoleStage {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::BeforeAudit => "before_audit",
+            Self::AfterAudit => "after_audit",
+            Self::BeforeTestWriting => "before_test_writing",
+        }
+    }
+}
+
+/// How a role reports its result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleVerdict {
+    /// The fenced JSON verdict auditors use; only critical/high findings block.
+    #[default]
+    Json,
+    /// A first line of PASS or FAIL, followed by the blocking problems on FAIL.
+    PassFail,
+}
+
+impl RoleVerdict {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::PassFail => "pass_fail",
+        }
+    }
+
+    fn protocol(self) -> &'static str {
+        match self {
+            Self::Json => AUDIT_VERDICT_PROTOCOL,
+            Self::PassFail => PASS_FAIL_VERDICT_PROTOCOL,
+        }
+    }
+}
+
+/// What happens when a role does not pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleFailurePolicy {
+    /// Send the findings back to the implementor, then run the role again, like an audit.
+    #[default]
+    Implementor,
+    /// Run the role again with its own findings as feedback, e.g. for roles that write files.
+    Retry,
+    /// Treat the findings as advisory: report them and move on.
+    Continue,
+}
+
+impl RoleFailurePolicy {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Implementor => "implementor",
+            Self::Retry => "retry",
+            Self::Continue => "continue",
+        }
+    }
+}
+
+/// A worker role declared under `[workflow.roles.<name>]` in config.toml.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomRole {
+    pub name: String,
+    pub title: String,
+    pub instructions: String,
+    /// Full prompt template replacing the shared `worker_role` template for this role.
+    pub prompt: Option<String>,
+    /// Model profile label; `None` uses the `worker_auditor` profile.
+    pub profile: Option<String>,
+    /// `None` uses the `worker_auditor` permissions.
+    pub permissions: Option<PermissionProfile>,
+    pub verdict: RoleVerdict,
+    pub stage: RoleStage,
+    pub on_fail: RoleFailurePolicy,
+    pub max_retries: Option<u8>,
+    /// Attach the role to every implementor branch, not only where tasks.json lists it.
+    pub auto: bool,
+}
+
+/// Lists the configured roles for the master and task-check prompts.
+pub(crate) fn roles_prompt_block(roles: &[CustomRole]) -> String {
+    if roles.is_empty() {
+        return "- No custom worker roles are configured; do not create `role` tasks.".to_string();
+    }
+    let mut lines = vec![
+        "- A `role` task runs a custom worker role configured in config.toml. Place it as a direct child of an implementor and set `role` to one of the names below (null on every other kind). The role's stage decides when it runs, regardless of its order among siblings. Roles marked auto are added to every implementor automatically; add those only to give a task role-specific details.".to_string(),
+        "- Configured roles:".to_string(),
+    ];
+    for role in roles {
+        lines.push(format!(
+            "  - {} ({}): stage {}, verdict {}, on failure {}{}{}. {}",
+            role.name,
+            role.title,
+            role.stage.label(),
+            role.verdict.label(),
+            role.on_fail.label(),
+            role.permissions
+                .map(|permissions| format!(", {}", permissions.label()))
+                .unwrap_or_default(),
+            if role.auto { ", auto" } else { "" },
+            first_line(&role.instructions)
+        ));
+    }
+    lines.join("\n")
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("(no instructions)")
+}
+
+impl Workflow {
+    pub fn set_custom_roles(&mut self, roles: Vec<CustomRole>) {
+        self.custom_roles = roles;
+    }
+
+    pub(crate) fn custom_roles_prompt_block(&self) -> String {
+        roles_prompt_block(&self.custom_roles)
+    }
+
+    pub(super) fn custom_role(&self, name: &str) -> Option<&CustomRole> {
+        self.custom_roles.iter().find(|role| role.name == name)
+    }
+
+    /// The configured role a `role` node runs, if it is still configured.
+    pub(super) fn custom_role_for_node(&self, role_id: u64) -> Option<&CustomRole> {
+        find_node(&self.tasks, role_id)
+            .and_then(|node| node.role.as_deref())
+            .and_then(|name| self.custom_role(name))
+    }
+
+    /// Adds a node for every auto role the implementor does not list yet.
+    pub(super) fn attach_auto_roles(&mut self, implementor_id: u64) {
+        let auto_roles = self
+            .custom_roles
+            .iter()
+            .filter(|role| role.auto)
+            .map(|role| (role.name.clone(), role.title.clone()))
+            .collect::<Vec<_>>();
+        for (name, title) in auto_roles {
+            let listed = find_node(&self.tasks, implementor_id).is_some_and(|node| {
+                node.children.iter().any(|child| {
+                    child.kind == TaskKind::Role && child.role.as_deref() == Some(name.as_str())
+                })
+            });
+            if listed {
+                continue;
+            }
+            if let Some(role_id) = self.add_generated_child(implementor_id, TaskKind::Role, &title)
+                && let Some(node) = super::find_node_mut(&mut self.tasks, role_id)
+            {
+                node.role = Some(name);
+            }
+        }
+    }
+
+    pub(super) fn role_profile_for_job(&self, job: &WorkerJob) -> Option<String> {
+        let WorkerJobKind::Role { role_id, .. } = &job.kind else {
+            return None;
+        };
+        self.custom_role_for_node(*role_id)?.profile.clone()
+    }
+
+    pub(super) fn role_permissions_for_job(&self, job: &WorkerJob) -> Option<PermissionProfile> {
+        let WorkerJobKind::Role { role_id, .. } = &job.kind else {
+            return None;
+        };
+        self.custom_role_for_node(*role_id)?.permissions
+    }
+
+    /// Before-audit roles check every implementor pass, so a new pass sends the ones that
+    /// already ran back to pending. Skipped roles stay skipped.
+    pub(super) fn reset_before_audit_roles(&mut self, implementor_id: u64) {
+        let finished = find_node(&self.tasks, implementor_id)
+            .map(|node| {
+                node.children
+                    .iter()
+                    .filter(|child| {
+                        child.kind == TaskKind::Role && child.status == TaskStatus::Done
+                    })
+                    .map(|child| child.id)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for role_id in finished {
+            if self.is_before_audit_role(role_id) {
+                self.set_status(role_id, TaskStatus::Pending);
+            }
+        }
+    }
+
+    pub(super) fn is_before_audit_role(&self, role_id: u64) -> bool {
+        self.custom_role_for_node(role_id)
+            .is_some_and(|role| role.stage == RoleStage::BeforeAudit)
+    }
+
+    /// Next unfinished role of `stage` under the implementor. Nodes naming a role that is no
+    /// longer configured are skipped so they cannot stall the branch.
+    pub(super) fn find_next_pending_role(
+        &mut self,
+        implementor_id: u64,
+        stage: RoleStage,
+        messages: &mut Vec<String>,
+    ) -> Option<u64> {
+        let pending = find_node(&self.tasks, implementor_id)?
+            .children
+            .iter()
+            .filter(|child| child.kind == TaskKind::Role && !child.status.is_finished())
+            .map(|child| (child.id, child.role.clone().unwrap_or_default()))
+            .collect::<Vec<_>>();
+        for (role_id, name) in pending {
+            match self.custom_role(&name).map(|role| role.stage) {
+                Some(role_stage) if role_stage == stage => return Some(role_id),
+                Some(_) => {}
+                None => {
+                    self.set_status(role_id, TaskStatus::Skipped);
+                    messages.push(format!(
+                        "System: Skipped role task \"{}\"; role `{name}` is not configured in [workflow.roles].",
+                        self.node_title(role_id, "Role")
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn queue_role(
+        &mut self,
+        top_task_id: u64,
+        implementor_id: u64,
+        role_id: u64,
+        pass: u8,
+        implementation_report: Option<String>,
+        changed_files_summary: Option<String>,
+        messages: &mut Vec<String>,
+    ) {
+        self.queue.push_back(WorkerJob {
+            top_task_id,
+            kind: WorkerJobKind::Role {
+                implementor_id,
+                role_id,
+                pass,
+                feedback: None,
+                implementation_report,
+                changed_files_summary,
+                resume_auditor_id: None,
+                resume_audit_pass: None,
+            },
+        });
+        messages.push(format!(
+            "System: Task #{} role \"{}\" queued (pass {}).",
+            top_task_id,
+            self.node_title(role_id, "Role"),
+            pass
+        ));
+    }
+
+    /// A node's own `max_retries` chain wins, then the role's `max_retries`, then the audit
+    /// default.
+    pub(super) fn role_retry_budget(&self, role_id: u64) -> RetryBudget {
+        if let Some(budget) = self.task_retry_override(role_id) {
+            return budget;
+        }
+        match self.custom_role_for_node(role_id) {
+            Some(CustomRole {
+                name,
+                max_retries: Some(limit),
+                ..
+            }) => RetryBudget {
+                limit: *limit,
+                source: format!("[workflow.roles.{name}] max_retries"),
+            },
+            _ => self.retry_budget(role_id, super::RetryLoop::Audit),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build_prompt(
+    workflow: &Workflow,
+    top_task_id: u64,
+    implementor_id: u64,
+    role_id: u64,
+    pass: u8,
+    feedback: Option<&str>,
+    implementation_report: &Option<String>,
+    changed_files_summary: &Option<String>,
+) -> String {
+    let Some(role) = workflow.custom_role_for_node(role_id) else {
+        return String::new();
+    };
+    let tests_policy = if workflow.tests_mode_enabled() {
+        "Tests mode policy (ON): leave test writing to the dedicated test-writer branch unless your role instructions say otherwise."
+    } else {
+        "Tests mode policy (OFF): testing is disabled; do not add, modify, or request tests."
+    };
+    let feedback = feedback
+        .map(|f| format!("Feedback from your previous pass to address:\n{f}"))
+        .unwrap_or_else(|| "No feedback from a previous pass of this role.".to_string());
+    let values = [
+        ("role_name", role.name.as_str()),
+        ("role_title", role.title.as_str()),
+        ("role_instructions", role.instructions.as_str()),
+        ("top_task_title", &workflow.task_title(top_task_id)),
+        (
+            "implementor_title",
+            &workflow.node_title(implementor_id, "Implementation"),
+        ),
+        (
+            "implementor_details",
+            &workflow.node_details(implementor_id),
+        ),
+        ("role_details", &workflow.node_details(role_id)),
+        ("pass", &pass.to_string()),
+        (
+            "max_passes",
+            &workflow.role_retry_budget(role_id).limit.to_string(),
+        ),
+        ("rolling_context", &workflow.context_block()),
+        (
+            "changed_files_summary",
+            changed_files_summary
+                .as_deref()
+                .unwrap_or("(implementor did not provide a changed-files summary)"),
+        ),
+        (
+            "observed_changes",
+            &workflow.observed_changes_block(implementor_id, changed_files_summary.as_deref()),
+        ),
+        (
+            "implementation_output",
+            implementation_report
+                .as_deref()
+                .unwrap_or("(no implementation output captured)"),
+        ),
+        ("feedback", &feedback),
+        ("tests_policy", tests_policy),
+        ("verdict_protocol", role.verdict.protocol()),
+    ];
+    match &role.prompt {
+        Some(text) => prompt_templates::fill_placeholders(text, |name| {
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+        }),
+        None => prompt_templates::render(PromptTemplate::WorkerRole, &values),
+    }
+}
+
+fn role_requires_changes(verdict: RoleVerdict, transcript: &[String]) -> bool {
+    match verdict {
+        RoleVerdict::Json => audit_detects_issues(transcript),
+        RoleVerdict::PassFail => !matches!(
+            parse_audit_result_token(transcript),
+            Some(AuditResultToken::Pass)
+        ),
+    }
+}
+
+fn role_feedback(role: &CustomRole, transcript: &[String], code: i32, success: bool) -> String {
+    if role.verdict == RoleVerdict::Json || !success {
+        return format!(
+            "Role `{}`: {}",
+            role.name,
+            audit_feedback(transcript, code, success)
+        );
+    }
+    if let Some(reason) = job_timeout_reason(transcript) {
+        return format!("Role `{}` was killed: {reason}", role.name);
+    }
+    let problems = transcript
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .skip(1)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if problems.is_empty() {
+        format!(
+            "Role `{}` did not return PASS; review the work against its instructions.",
+            role.name
+        )
+    } else {
+        format!(
+            "Role `{}` reported blocking problems:\n{problems}",
+            role.name
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn on_completion(
+    workflow: &mut Workflow,
+    top_task_id: u64,
+    implementor_id: u64,
+    role_id: u64,
+    pass: u8,
+    implementation_report: Option<String>,
+    changed_files_summary: Option<String>,
+    resume_auditor_id: Option<u64>,
+    resume_audit_pass: Option<u8>,
+    transcript: &[String],
+    success: bool,
+    code: i32,
+    messages: &mut Vec<String>,
+) {
+    let Some(role) = workflow.custom_role_for_node(role_id).cloned() else {
+        workflow.set_status(role_id, TaskStatus::Skipped);
+        continue_branch(
+            workflow,
+            top_task_id,
+            implementor_id,
+            pass,
+            resume_auditor_id,
+            resume_audit_pass,
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
+        return;
+    };
+    workflow.push_context(make_context_summary(
+        &role.title,
+        &workflow.task_title(top_task_id),
+        transcript,
+        success,
+    ));
+
+    let failed = !success || role_requires_changes(role.verdict, transcript);
+    if !failed {
+        workflow.set_status(role_id, TaskStatus::Done);
+        messages.push(format!(
+            "System: Task #{} role `{}` passed on pass {}.",
+            top_task_id, role.name, pass
+        ));
+        continue_branch(
+            workflow,
+            top_task_id,
+            implementor_id,
+            pass,
+            resume_auditor_id,
+            resume_audit_pass,
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
+        return;
+    }
+
+    let feedback = role_feedback(&role, transcript, code, success);
+    let budget = workflow.role_retry_budget(role_id);
+    if role.on_fail == RoleFailurePolicy::Continue || pass >= budget.limit {
+        workflow.set_status(role_id, TaskStatus::Done);
+        if role.on_fail == RoleFailurePolicy::Continue {
+            messages.push(format!(
+                "System: Task #{} role `{}` reported advisory findings; continuing.\n{}",
+                top_task_id, role.name, feedback
+            ));
+        } else {
+            workflow.recent_failures.push(super::WorkflowFailure {
+                kind: super::WorkflowFailureKind::Audit,
+                top_task_id,
+                top_task_title: workflow.task_title(top_task_id),
+                attempts: pass,
+                reason: feedback,
+                action_taken: format!(
+                    "Role `{}` retries exhausted; continued execution to next step.",
+                    role.name
+                ),
+                retry_budget: Some(budget.clone()),
+                checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
+            });
+            messages.push(format!(
+                "System: Task #{} role `{}` still failing at pass {}. Max retries ({}) reached; proceeding to next step.",
+                top_task_id, role.name, pass, budget.limit
+            ));
+        }
+        continue_branch(
+            workflow,
+            top_task_id,
+            implementor_id,
+            pass,
+            resume_auditor_id,
+            resume_audit_pass,
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
+        return;
+    }
+
+    workflow.set_status(role_id, TaskStatus::NeedsChanges);
+    let next_pass = pass.saturating_add(1);
+    if role.on_fail == RoleFailurePolicy::Retry {
+        workflow.queue.push_back(WorkerJob {
+            top_task_id,
+            kind: WorkerJobKind::Role {
+                implementor_id,
+                role_id,
+                pass: next_pass,
+                feedback: Some(feedback),
+                implementation_report,
+                changed_files_summary,
+                resume_auditor_id,
+                resume_audit_pass,
+            },
+        });
+        messages.push(format!(
+            "System: Task #{} role `{}` did not pass; role pass {} queued.",
+            top_task_id, role.name, next_pass
+        ));
+        return;
+    }
+    // A before-audit role re-runs on the next implementor pass anyway, so it keeps the audit
+    // it was handing on rather than replacing it, and remembers its pass for that re-run.
+    let (resume_auditor_id, resume_audit_pass) = match resume_auditor_id {
+        Some(auditor_id) if role.stage == RoleStage::BeforeAudit => {
+            workflow.before_audit_role_passes.insert(role_id, pass);
+            (Some(auditor_id), resume_audit_pass)
+        }
+        _ => (Some(role_id), Some(next_pass)),
+    };
+    workflow.set_status(implementor_id, TaskStatus::NeedsChanges);
+    workflow.queue.push_back(WorkerJob {
+        top_task_id,
+        kind: WorkerJobKind::Implementor {
+            implementor_id,
+            pass: next_pass,
+            feedback: Some(feedback),
+            resume_auditor_id,
+            resume_audit_pass,
+        },
+    });
+    messages.push(format!(
+        "System: Task #{} role `{}` requested fixes; implementor pass {} queued.",
+        top_task_id, role.name, next_pass
+    ));
+}
+
+/// Moves on once a role is settled: to the audit a before-audit role was handing on, otherwise
+/// to the next step of the implementor branch.
+#[allow(clippy::too_many_arguments)]
+fn continue_branch(
+    workflow: &mut Workflow,
+    top_task_id: u64,
+    implementor_id: u64,
+    pass: u8,
+    resume_auditor_id: Option<u64>,
+    resume_audit_pass: Option<u8>,
+    implementation_report: Option<String>,
+    changed_files_summary: Option<String>,
+    messages: &mut Vec<String>,
+) {
+    if resume_auditor_id.is_some() {
+        queue_audit_after_implementation(
+            workflow,
+            top_task_id,
+            implementor_id,
+            pass,
+            resume_auditor_id,
+            resume_audit_pass,
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
+        return;
+    }
+    let _ = workflow.queue_next_implementor_audit(
+        top_task_id,
+        implementor_id,
+        1,
+        implementation_report,
+        changed_files_summary,
+        messages,
+    );
+}
@@ -0,0 +1,89 @@
+use super::{JobRun, RoleVerdict, WorkerRole, Workflow};
+
+/// Upper bound on simulated jobs so a malformed task tree cannot loop forever.
+const MAX_DRY_RUN_JOBS: usize = 1000;
+const SIMULATED_AUDIT_PASS: &str = "```json\n{\"status\": \"pass\", \"findings\": []}\n```";
+const SIMULATED_WORKER_OUTPUT: &str = "Dry run: simulated successful pass.\n\
+     FILES_CHANGED_BEGIN\n\
+     - (dry run): no files changed\n\
+     FILES_CHANGED_END";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunPrompt {
+    pub role: WorkerRole,
+    pub task_id: String,
+    pub top_task_id: u64,
+    pub pass: u8,
+    /// Model profile overriding the role default, e.g. a quorum reviewer's or custom role's.
+    pub model_profile: Option<String>,
+    pub prompt: String,
+}
+
+impl Workflow {
+    /// Walks a copy of the current task tree with every job passing on its first attempt and
+    /// returns the agent prompts in dispatch order. Review gates are approved automatically.
+    pub fn dry_run_prompts(&self) -> Result<Vec<DryRunPrompt>, String> {
+        let mut sandbox = Workflow {
+            tests_mode_enabled: self.tests_mode_enabled,
+            retry_budgets: self.retry_budgets,
+            custom_roles: self.custom_roles.clone(),
+            ..Workflow::default()
+        };
+        sandbox.sync_planner_tasks_from_file(self.planner_tasks_for_file())?;
+        sandbox.replace_rolling_context_entries(self.rolling_context_entries());
+        let _ = sandbox.start_execution();
+
+        let mut prompts = Vec::new();
+        for _ in 0..MAX_DRY_RUN_JOBS {
+            let Some(started) = sandbox.start_next_job() else {
+                let Some(review) = sandbox.pending_reviews.front() else {
+                    return Ok(prompts);
+                };
+                let task_ref = review.top_task_id.to_string();
+                let _ = sandbox.approve_review(Some(&task_ref));
+                continue;
+            };
+            let Some(kind) = sandbox
+                .active
+                .iter()
+                .find(|active| active.job.top_task_id == started.top_task_id)
+                .map(|active| active.job.kind.clone())
+            else {
+                continue;
+            };
+            let role = started.role;
+            if let JobRun::AgentPrompt(prompt) = started.run {
+                prompts.push(DryRunPrompt {
+                    role,
+                    task_id: sandbox.dry_run_task_id(kind.node_id()),
+                    top_task_id: started.top_task_id,
+                    pass: kind.pass(),
+                    model_profile: started.model_profile,
+                    prompt,
+                });
+            }
+            let output = match role {
+                WorkerRole::Auditor | WorkerRole::FinalAudit => SIMULATED_AUDIT_PASS,
+                WorkerRole::Custom => match sandbox
+                    .custom_role_for_node(kind.node_id())
+                    .map(|role| role.verdict)
+                {
+                    Some(RoleVerdict::PassFail) => "PASS",
+                    _ => SIMULATED_AUDIT_PASS,
+                },
+                _ => SIMULATED_WORKER_OUTPUT,
+            };
+            sandbox.append_output_for_task(started.top_task_id, output.to_string());
+            let _ = sandbox.finish_job_for_task(started.top_task_id, true, 0);
+        }
+        Err(format!(
+            "Dry run stopped after {MAX_DRY_RUN_JOBS} jobs without finishing; check the task tree for cycles."
+        ))
+    }
+
+    fn dry_run_task_id(&self, node_id: u64) -> String {
+        super::find_node(&self.tasks, node_id)
+            .and_then(|node| node.external_id.clone())
+            .unwrap_or_else(|| format!("internal-{node_id}"))
+    }
+}
@@ -1,9 +1,11 @@
 use super::WorkerJob;
 use super::Workflow;
-use super::{
-    MAX_AUDIT_RETRIES, TaskStatus, audit_detects_issues, audit_feedback,
-};
+use super::audit_verdict::AUDIT_VERDICT_PROTOCOL;
+use super::review_quorum::QuorumOutcome;
+use super::{RetryLoop, TaskStatus, audit_detects_issues, audit_feedback};
+use crate::prompt_templates::{self, PromptTemplate};
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn build_prompt(
     workflow: &Workflow,
     top_task_id: u64,
@@ -12,48 +14,65 @@ pub(crate) fn build_prompt(
     implementation_report: &Option<String>,
     changed_files_summary: &Option<String>,
     pass: u8,
+    reviewer: u8,
 ) -> String {
     let tests_policy = if workflow.tests_mode_enabled() {
         "Tests mode policy (ON): do not audit test quality/coverage or request test changes in this pass; limit findings to implementation concerns only."
     } else {
         "Tests mode policy (OFF): testing is disabled; do not request any test additions or test changes."
     };
-    format!(
-        "You are an audit sub-agent reviewing implementation output.\n\
-         Top-level task: {}\n\
-         Parent implementor task: {}\n\
-         Parent implementor details:\n{}\n\
-         Audit subtask details:\n{}\n\
-         Audit pass: {} of {}\n\
-         Rolling task context:\n{}\n\
-         Implementor changed-files summary:\n{}\n\
-         Implementation output to audit:\n{}\n\
-         {}\n\
-         Scope lock (required): audit only the parent implementor task/details above. Do not audit unrelated tasks, broader roadmap items, or unrelated files.\n\
-         Execution guardrail: do not run tests and do not execute/check shell commands. Command/test execution is handled by a subsequent dedicated agent.\n\
-         Strictness policy for this audit pass:\n{}\n\
-         Response protocol (required):\n\
-         - First line must be exactly one of:\n\
-           PASS\n\
-           FAIL\n\
-         - PASS: no additional text after the token.\n\
-         - FAIL: include one or more lines of findings and rationale after the token.\n\
-         - On pass 4, only FAIL for truly critical blockers that would prevent the broader plan from running.",
-        workflow.task_title(top_task_id),
-        workflow.node_title(implementor_id, "Implementation"),
-        workflow.node_details(implementor_id),
-        workflow.node_details(auditor_id),
-        pass,
-        MAX_AUDIT_RETRIES,
-        workflow.context_block(),
-        changed_files_summary
-            .as_deref()
-            .unwrap_or("(implementor did not provide a changed-files summary)"),
-        implementation_report
-            .as_deref()
-            .unwrap_or("(no implementation output captured)"),
-        tests_policy,
-        audit_strictness_policy(pass)
+    let panel = workflow
+        .review_quorum(auditor_id)
+        .map(|quorum| {
+            format!(
+                "Review panel: you are reviewer {} of {}; the audit passes when {} reviewers pass it. Judge independently.\n",
+                reviewer, quorum.reviewers, quorum.required
+            )
+        })
+        .unwrap_or_default();
+    prompt_templates::render(
+        PromptTemplate::ImplementationAuditor,
+        &[
+            ("top_task_title", &workflow.task_title(top_task_id)),
+            (
+                "implementor_title",
+                &workflow.node_title(implementor_id, "Implementation"),
+            ),
+            (
+                "implementor_details",
+                &workflow.node_details(implementor_id),
+            ),
+            ("audit_details", &workflow.node_details(auditor_id)),
+            ("pass", &pass.to_string()),
+            (
+                "max_passes",
+                &workflow
+                    .retry_budget(auditor_id, RetryLoop::Audit)
+                    .limit
+                    .to_string(),
+            ),
+            ("review_panel", &panel),
+            ("rolling_context", &workflow.context_block()),
+            (
+                "changed_files_summary",
+                changed_files_summary
+                    .as_deref()
+                    .unwrap_or("(implementor did not provide a changed-files summary)"),
+            ),
+            (
+                "observed_changes",
+                &workflow.observed_changes_block(implementor_id, changed_files_summary.as_deref()),
+            ),
+            (
+                "implementation_output",
+                implementation_report
+                    .as_deref()
+                    .unwrap_or("(no implementation output captured)"),
+            ),
+            ("tests_policy", tests_policy),
+            ("strictness_policy", audit_strictness_policy(pass)),
+            ("verdict_protocol", AUDIT_VERDICT_PROTOCOL),
+        ],
     )
 }
 
@@ -63,6 +82,7 @@ pub(crate) fn on_completion(
     implementor_id: u64,
     auditor_id: u64,
     pass: u8,
+    reviewer: u8,
     implementation_report: Option<String>,
     changed_files_summary: Option<String>,
     transcript: &[String],
@@ -70,19 +90,53 @@ pub(crate) fn on_completion(
     code: i32,
     messages: &mut Vec<String>,
 ) {
-    let issues = !success || audit_detects_issues(transcript);
-    if issues {
+    let feedback = match workflow.review_quorum(auditor_id) {
+        Some(quorum) => match workflow.record_review_vote(
+            top_task_id,
+            auditor_id,
+            &quorum,
+            pass,
+            reviewer,
+            transcript,
+            success,
+            code,
+            messages,
+        ) {
+            QuorumOutcome::Pending => {
+                workflow.queue.push_back(WorkerJob {
+                    top_task_id,
+                    kind: super::WorkerJobKind::Auditor {
+                        implementor_id,
+                        auditor_id,
+                        pass,
+                        reviewer: reviewer.saturating_add(1),
+                        implementation_report,
+                        changed_files_summary,
+                    },
+                });
+                return;
+            }
+            QuorumOutcome::Passed => None,
+            QuorumOutcome::Failed(feedback) => Some(feedback),
+        },
+        None => (!success || audit_detects_issues(transcript))
+            .then(|| audit_feedback(transcript, code, success)),
+    };
+    if let Some(feedback) = feedback {
         workflow.set_status(implementor_id, TaskStatus::NeedsChanges);
-        if pass >= MAX_AUDIT_RETRIES {
+        let budget = workflow.retry_budget(auditor_id, RetryLoop::Audit);
+        if pass >= budget.limit {
             workflow.set_status(auditor_id, TaskStatus::Done);
             workflow.recent_failures.push(super::WorkflowFailure {
                 kind: super::WorkflowFailureKind::Audit,
                 top_task_id,
                 top_task_title: workflow.task_title(top_task_id),
                 attempts: pass,
-                reason: audit_feedback(transcript, code, success),
+                reason: feedback,
                 action_taken: "Audit retries exhausted; continued execution to next audit/step."
                     .to_string(),
+                retry_budget: Some(budget.clone()),
+                checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
             });
             let _ = workflow.queue_next_implementor_audit(
                 top_task_id,
@@ -94,7 +148,7 @@ pub(crate) fn on_completion(
             );
             messages.push(format!(
                 "System: Task #{} audit still found critical blockers at pass {}. Max retries ({}) reached; proceeding to next audit/step.",
-                top_task_id, pass, MAX_AUDIT_RETRIES
+                top_task_id, pass, budget.limit
             ));
         } else {
             workflow.set_status(auditor_id, TaskStatus::NeedsChanges);
@@ -103,7 +157,7 @@ pub(crate) fn on_completion(
                 kind: super::WorkerJobKind::Implementor {
                     implementor_id,
                     pass: pass.saturating_add(1),
-                    feedback: Some(audit_feedback(transcript, code, success)),
+                    feedback: Some(feedback),
                     resume_auditor_id: Some(auditor_id),
                     resume_audit_pass: Some(pass.saturating_add(1)),
                 },
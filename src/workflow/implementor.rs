@@ -1,7 +1,9 @@
 use super::Workflow;
 use super::{
-    TaskStatus, WorkerJob, WorkerJobKind, extract_changed_files_summary, make_context_summary,
+    RoleStage, TaskStatus, WorkerJob, WorkerJobKind, extract_changed_files_summary,
+    failed_run_feedback, make_context_summary,
 };
+use crate::prompt_templates::{self, PromptTemplate};
 
 pub(crate) fn build_prompt(
     workflow: &Workflow,
@@ -14,29 +16,22 @@ pub(crate) fn build_prompt(
     } else {
         "Tests mode policy (OFF): testing is disabled. Prohibition: do not create, modify, or delete tests under any circumstance."
     };
-    format!(
-        "You are an implementation sub-agent.\n\
-         Top-level task: {}\n\
-         Implementation subtask: {}\n\
-         Implementation details:\n{}\n\
-         Rolling task context:\n{}\n\
-         {}\n\
-         {}\n\
-         End your response with a structured changed-files summary block using this exact format:\n\
-         FILES_CHANGED_BEGIN\n\
-         - path/to/file.ext: brief description of what changed\n\
-         FILES_CHANGED_END\n\
-         Include every file you changed. If no files changed, include a single bullet with reason.\n\
-         Provide concise progress updates and finish with what changed.",
-        workflow.task_title(top_task_id),
-        workflow.node_title(implementor_id, "Implementation"),
-        workflow.node_details(implementor_id),
-        workflow.context_block(),
-        feedback
-            .as_ref()
-            .map(|f| format!("Audit feedback to address:\n{f}"))
-            .unwrap_or_else(|| "No audit feedback yet; implement from task prompt.".to_string()),
-        tests_policy
+    let feedback = feedback
+        .map(|f| format!("Audit feedback to address:\n{f}"))
+        .unwrap_or_else(|| "No audit feedback yet; implement from task prompt.".to_string());
+    prompt_templates::render(
+        PromptTemplate::Implementor,
+        &[
+            ("top_task_title", &workflow.task_title(top_task_id)),
+            (
+                "subtask_title",
+                &workflow.node_title(implementor_id, "Implementation"),
+            ),
+            ("subtask_details", &workflow.node_details(implementor_id)),
+            ("rolling_context", &workflow.context_block()),
+            ("feedback", &feedback),
+            ("tests_policy", tests_policy),
+        ],
     )
 }
 
@@ -63,41 +58,42 @@ pub(crate) fn on_completion(
         // Mark implementation pass complete before moving into audit. If an audit fails,
         // status is set back to NeedsChanges and implementor retries.
         workflow.set_status(implementor_id, TaskStatus::Done);
-        if let Some(auditor_id) = resume_auditor_id {
+        workflow.reset_before_audit_roles(implementor_id);
+        let implementation_report = Some(transcript.join("\n"));
+        let changed_files_summary = Some(extract_changed_files_summary(transcript));
+        if let Some(lint_runner_id) =
+            workflow.find_child_kind(implementor_id, super::TaskKind::LintRunner)
+        {
+            // Lint reruns after every implementor pass so audits only see lint-clean code.
             workflow.queue.push_back(WorkerJob {
                 top_task_id,
-                kind: WorkerJobKind::Auditor {
+                kind: WorkerJobKind::ImplementorLintRunner {
                     implementor_id,
-                    auditor_id,
-                    pass: resume_audit_pass.unwrap_or(1),
-                    implementation_report: Some(transcript.join("\n")),
-                    changed_files_summary: Some(extract_changed_files_summary(transcript)),
+                    lint_runner_id,
+                    pass,
+                    implementation_report,
+                    changed_files_summary,
+                    resume_auditor_id,
+                    resume_audit_pass,
                 },
             });
             messages.push(format!(
-                "System: Task #{} implementation pass {} complete; resumed audit queued.",
+                "System: Task #{} implementation pass {} complete; lint run queued.",
                 top_task_id, pass
             ));
-        } else {
-            if workflow
-                .find_child_kind(implementor_id, super::TaskKind::Auditor)
-                .is_none()
-            {
-                let _ = workflow.find_or_create_child_kind(
-                    implementor_id,
-                    super::TaskKind::Auditor,
-                    "Audit",
-                );
-            }
-            let _ = workflow.queue_next_implementor_audit(
-                top_task_id,
-                implementor_id,
-                pass,
-                Some(transcript.join("\n")),
-                Some(extract_changed_files_summary(transcript)),
-                messages,
-            );
+            return;
         }
+        queue_audit_after_implementation(
+            workflow,
+            top_task_id,
+            implementor_id,
+            pass,
+            resume_auditor_id,
+            resume_audit_pass,
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
     } else {
         workflow.set_status(implementor_id, TaskStatus::NeedsChanges);
         workflow.queue.push_back(WorkerJob {
@@ -105,7 +101,7 @@ pub(crate) fn on_completion(
             kind: WorkerJobKind::Implementor {
                 implementor_id,
                 pass: pass.saturating_add(1),
-                feedback: Some(format!("Previous implementor run failed with code {code}.")),
+                feedback: Some(failed_run_feedback("implementor", code, transcript)),
                 resume_auditor_id: None,
                 resume_audit_pass: None,
             },
@@ -116,3 +112,98 @@ pub(crate) fn on_completion(
         ));
     }
 }
+
+/// Queues the audit step that follows a successful implementor pass (and its lint run, if any).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn queue_audit_after_implementation(
+    workflow: &mut Workflow,
+    top_task_id: u64,
+    implementor_id: u64,
+    pass: u8,
+    resume_auditor_id: Option<u64>,
+    resume_audit_pass: Option<u8>,
+    implementation_report: Option<String>,
+    changed_files_summary: Option<String>,
+    messages: &mut Vec<String>,
+) {
+    if resume_auditor_id.is_some_and(|id| !workflow.is_before_audit_role(id))
+        && let Some(role_id) =
+            workflow.find_next_pending_role(implementor_id, RoleStage::BeforeAudit, messages)
+    {
+        // Before-audit roles check this pass too, ahead of the audit being resumed. The role
+        // counts its own passes: it starts over unless it sent this implementor pass back.
+        let role_pass = workflow
+            .before_audit_role_passes
+            .remove(&role_id)
+            .map_or(1, |pass| pass.saturating_add(1));
+        workflow.queue.push_back(WorkerJob {
+            top_task_id,
+            kind: WorkerJobKind::Role {
+                implementor_id,
+                role_id,
+                pass: role_pass,
+                feedback: None,
+                implementation_report,
+                changed_files_summary,
+                resume_auditor_id,
+                resume_audit_pass,
+            },
+        });
+        messages.push(format!(
+            "System: Task #{} role \"{}\" queued before the resumed audit (pass {}).",
+            top_task_id,
+            workflow.node_title(role_id, "Role"),
+            role_pass
+        ));
+        return;
+    }
+    if let Some(role_id) = resume_auditor_id.filter(|id| {
+        super::find_node(&workflow.tasks, *id)
+            .is_some_and(|node| node.kind == super::TaskKind::Role)
+    }) {
+        // A custom role that sent the work back resumes exactly like an audit.
+        workflow.queue_role(
+            top_task_id,
+            implementor_id,
+            role_id,
+            resume_audit_pass.unwrap_or(1),
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
+        return;
+    }
+    if let Some(auditor_id) = resume_auditor_id {
+        workflow.queue.push_back(WorkerJob {
+            top_task_id,
+            kind: WorkerJobKind::Auditor {
+                implementor_id,
+                auditor_id,
+                pass: resume_audit_pass.unwrap_or(1),
+                reviewer: 1,
+                implementation_report,
+                changed_files_summary,
+            },
+        });
+        messages.push(format!(
+            "System: Task #{} implementation pass {} complete; resumed audit queued.",
+            top_task_id, pass
+        ));
+        return;
+    }
+    if workflow
+        .find_child_kind(implementor_id, super::TaskKind::Auditor)
+        .is_none()
+    {
+        let _ =
+            workflow.find_or_create_child_kind(implementor_id, super::TaskKind::Auditor, "Audit");
+    }
+    let _ = workflow.queue_next_implementor_audit(
+        top_task_id,
+        implementor_id,
+        pass,
+        implementation_report,
+        changed_files_summary,
+        messages,
+    );
+}
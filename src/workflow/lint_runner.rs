@@ -0,0 +1,92 @@
+use super::Workflow;
+use super::implementor::queue_audit_after_implementation;
+use super::{RetryLoop, TaskStatus, lint_runner_feedback};
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn on_implementor_completion(
+    workflow: &mut Workflow,
+    top_task_id: u64,
+    implementor_id: u64,
+    lint_runner_id: u64,
+    pass: u8,
+    implementation_report: Option<String>,
+    changed_files_summary: Option<String>,
+    resume_auditor_id: Option<u64>,
+    resume_audit_pass: Option<u8>,
+    transcript: &[String],
+    success: bool,
+    code: i32,
+    messages: &mut Vec<String>,
+) {
+    if success {
+        workflow.set_status(lint_runner_id, TaskStatus::Done);
+        messages.push(format!(
+            "System: Task #{} lint passed on run {}.",
+            top_task_id, pass
+        ));
+        queue_audit_after_implementation(
+            workflow,
+            top_task_id,
+            implementor_id,
+            pass,
+            resume_auditor_id,
+            resume_audit_pass,
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
+        return;
+    }
+
+    // Lint shares the deterministic test retry budget.
+    let budget = workflow.retry_budget(lint_runner_id, RetryLoop::Test);
+    if pass >= budget.limit {
+        workflow.set_status(lint_runner_id, TaskStatus::Done);
+        workflow.recent_failures.push(super::WorkflowFailure {
+            kind: super::WorkflowFailureKind::Lint,
+            top_task_id,
+            top_task_title: workflow.task_title(top_task_id),
+            attempts: pass,
+            reason: lint_runner_feedback(transcript, code),
+            action_taken:
+                "Lint retries exhausted; continued to audit with lint failures outstanding."
+                    .to_string(),
+            retry_budget: Some(budget.clone()),
+            checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
+        });
+        messages.push(format!(
+            "System: Task #{} lint still failing at pass {}. Max retries ({}) reached; proceeding to audit.",
+            top_task_id, pass, budget.limit
+        ));
+        queue_audit_after_implementation(
+            workflow,
+            top_task_id,
+            implementor_id,
+            pass,
+            resume_auditor_id,
+            resume_audit_pass,
+            implementation_report,
+            changed_files_summary,
+            messages,
+        );
+        return;
+    }
+
+    workflow.set_status(lint_runner_id, TaskStatus::NeedsChanges);
+    workflow.set_status(implementor_id, TaskStatus::NeedsChanges);
+    workflow.queue.push_back(super::WorkerJob {
+        top_task_id,
+        kind: super::WorkerJobKind::Implementor {
+            implementor_id,
+            pass: pass.saturating_add(1),
+            feedback: Some(lint_runner_feedback(transcript, code)),
+            resume_auditor_id,
+            resume_audit_pass,
+        },
+    });
+    messages.push(format!(
+        "System: Task #{} lint failed; implementor pass {} queued.",
+        top_task_id,
+        pass.saturating_add(1)
+    ));
+}
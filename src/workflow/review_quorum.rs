@@ -0,0 +1,203 @@
+use super::audit_verdict::{AuditFinding, AuditSeverity, parse_audit_verdict};
+use super::{WorkerJob, WorkerJobKind, Workflow, audit_detects_issues, audit_feedback, find_node};
+use crate::session_store::ReviewQuorumFileEntry;
+
+/// One reviewer's verdict in a quorum audit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewerVote {
+    pub top_task_id: u64,
+    pub auditor_task_id: String,
+    pub pass: u8,
+    pub reviewer: u8,
+    pub reviewers: u8,
+    pub model_profile: Option<String>,
+    pub passed: bool,
+    /// Feedback the reviewer would have sent back on its own; empty for a passing vote.
+    pub feedback: String,
+    pub(crate) blocking_findings: Vec<AuditFinding>,
+}
+
+pub(crate) enum QuorumOutcome {
+    Pending,
+    Passed,
+    Failed(String),
+}
+
+impl Workflow {
+    pub(crate) fn review_quorum(&self, auditor_id: u64) -> Option<ReviewQuorumFileEntry> {
+        find_node(&self.tasks, auditor_id).and_then(|node| node.review_quorum.clone())
+    }
+
+    pub(super) fn reviewer_profile_for_job(&self, job: &WorkerJob) -> Option<String> {
+        let WorkerJobKind::Auditor {
+            auditor_id,
+            reviewer,
+            ..
+        } = &job.kind
+        else {
+            return None;
+        };
+        let quorum = self.review_quorum(*auditor_id)?;
+        reviewer_profile(&quorum, *reviewer)
+    }
+
+    pub fn drain_review_votes(&mut self) -> Vec<ReviewerVote> {
+        std::mem::take(&mut self.new_review_votes)
+    }
+
+    /// Records a reviewer's verdict and reports whether the quorum is decided. Voting stops as
+    /// soon as enough reviewers passed or too many failed for the quorum to still be reached.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn record_review_vote(
+        &mut self,
+        top_task_id: u64,
+        auditor_id: u64,
+        quorum: &ReviewQuorumFileEntry,
+        pass: u8,
+        reviewer: u8,
+        transcript: &[String],
+        success: bool,
+        code: i32,
+        messages: &mut Vec<String>,
+    ) -> QuorumOutcome {
+        let passed = success && !audit_detects_issues(transcript);
+        let vote = ReviewerVote {
+            top_task_id,
+            auditor_task_id: find_node(&self.tasks, auditor_id)
+                .and_then(|node| node.external_id.clone())
+                .unwrap_or_else(|| format!("internal-{auditor_id}")),
+            pass,
+            reviewer,
+            reviewers: quorum.reviewers,
+            model_profile: reviewer_profile(quorum, reviewer),
+            passed,
+            feedback: if passed {
+                String::new()
+            } else {
+                audit_feedback(transcript, code, success)
+            },
+            blocking_findings: match parse_audit_verdict(transcript) {
+                Some(verdict) if success => verdict.blocking_findings().cloned().collect(),
+                _ => Vec::new(),
+            },
+        };
+        messages.push(format!(
+            "System: Task #{} reviewer {} of {} {} (audit pass {}).",
+            top_task_id,
+            reviewer,
+            quorum.reviewers,
+            if passed { "passed" } else { "requested fixes" },
+            pass
+        ));
+        self.new_review_votes.push(vote.clone());
+        let votes = self.review_votes.entry(auditor_id).or_default();
+        votes.push(vote);
+
+        let pass_count = votes.iter().filter(|vote| vote.passed).count();
+        let fail_count = votes.len() - pass_count;
+        let required = usize::from(quorum.required);
+        let outcome = if pass_count >= required {
+            QuorumOutcome::Passed
+        } else if fail_count > usize::from(quorum.reviewers) - required {
+            QuorumOutcome::Failed(merged_feedback(quorum, votes))
+        } else {
+            return QuorumOutcome::Pending;
+        };
+        messages.push(format!(
+            "System: Task #{} review quorum {}: {} of {} reviewers passed, {} required.",
+            top_task_id,
+            if matches!(outcome, QuorumOutcome::Passed) {
+                "met"
+            } else {
+                "not met"
+            },
+            pass_count,
+            quorum.reviewers,
+            quorum.required
+        ));
+        self.review_votes.remove(&auditor_id);
+        outcome
+    }
+}
+
+fn reviewer_profile(quorum: &ReviewQuorumFileEntry, reviewer: u8) -> Option<String> {
+    if quorum.profiles.is_empty() {
+        return None;
+    }
+    let index = usize::from(reviewer.saturating_sub(1)) % quorum.profiles.len();
+    Some(quorum.profiles[index].clone())
+}
+
+/// Combines the failing reviewers' blocking findings into one retry prompt, listing a finding
+/// reported by several reviewers once.
+fn merged_feedback(quorum: &ReviewQuorumFileEntry, votes: &[ReviewerVote]) -> String {
+    let pass_count = votes.iter().filter(|vote| vote.passed).count();
+    let mut merged: Vec<(AuditFinding, Vec<u8>)> = Vec::new();
+    let mut notes = Vec::new();
+    for vote in votes.iter().filter(|vote| !vote.passed) {
+        if vote.blocking_findings.is_empty() {
+            notes.push(format!("Reviewer {}: {}", vote.reviewer, vote.feedback));
+            continue;
+        }
+        for finding in &vote.blocking_findings {
+            match merged
+                .iter_mut()
+                .find(|(existing, _)| same_finding(existing, finding))
+            {
+                Some((existing, reviewers)) => {
+                    if finding.severity == AuditSeverity::Critical {
+                        existing.severity = AuditSeverity::Critical;
+                    }
+                    if !reviewers.contains(&vote.reviewer) {
+                        reviewers.push(vote.reviewer);
+                    }
+                }
+                None => merged.push((finding.clone(), vec![vote.reviewer])),
+            }
+        }
+    }
+
+    let mut lines = vec![format!(
+        "Review quorum not met: {} of {} reviewers passed, {} required.",
+        pass_count, quorum.reviewers, quorum.required
+    )];
+    if !merged.is_empty() {
+        lines.push("Unresolved blocking audit findings (merged across reviewers):".to_string());
+        for (finding, reviewers) in &merged {
+            let reviewers = reviewers
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let label = if reviewers.contains(',') {
+                "reviewers"
+            } else {
+                "reviewer"
+            };
+            lines.push(format!("{} ({label} {reviewers})", finding.render()));
+        }
+    }
+    lines.extend(notes);
+    lines.join("\n")
+}
+
+fn same_finding(left: &AuditFinding, right: &AuditFinding) -> bool {
+    let file = |finding: &AuditFinding| {
+        finding
+            .file
+            .as_deref()
+            .map(|file| file.trim().trim_start_matches("./").to_string())
+    };
+    file(left) == file(right)
+        && left.line == right.line
+        && normalized_message(&left.message) == normalized_message(&right.message)
+}
+
+fn normalized_message(message: &str) -> String {
+    message
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches('.')
+        .to_lowercase()
+}
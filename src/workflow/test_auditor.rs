@@ -1,6 +1,8 @@
 use super::WorkerJob;
 use super::Workflow;
+use super::audit_verdict::AUDIT_VERDICT_PROTOCOL;
 use super::{TaskStatus, audit_detects_issues, audit_feedback};
+use crate::prompt_templates::{self, PromptTemplate};
 
 pub(crate) fn build_prompt(
     workflow: &Workflow,
@@ -15,37 +17,42 @@ pub(crate) fn build_prompt(
     } else {
         "Tests mode policy (OFF): do not request new tests or test edits; only report that test auditing is disabled by policy."
     };
-    format!(
-        "You are an audit sub-agent reviewing test-writing output.\n\
-         Top-level task: {}\n\
-         Parent test-writer task: {}\n\
-         Parent test-writer details:\n{}\n\
-         Audit subtask details:\n{}\n\
-         Audit pass: {} of {}\n\
-         Rolling task context:\n{}\n\
-         Test-writer output to audit:\n{}\n\
-         {}\n\
-         Execution guardrail: do not run tests and do not execute/check shell commands. Command/test execution is handled by a subsequent dedicated agent.\n\
-         Strictness policy for this audit pass:\n{}\n\
-         Response protocol (required):\n\
-         - First line must be exactly one of:\n\
-           PASS\n\
-           FAIL\n\
-         - PASS: no additional text after the token.\n\
-         - FAIL: include one or more lines of findings and rationale after the token.\n\
-         - On pass 4, only FAIL for truly critical blockers that would prevent the broader plan from running.",
-        workflow.task_title(top_task_id),
-        workflow.node_title(test_writer_id, "Test Writing"),
-        workflow.node_details(test_writer_id),
-        workflow.node_details(auditor_id),
-        pass,
-        super::MAX_AUDIT_RETRIES,
-        workflow.context_block(),
-        test_report
-            .as_deref()
-            .unwrap_or("(no test-writer output captured)"),
-        tests_policy,
-        audit_strictness_policy(pass),
+    prompt_templates::render(
+        PromptTemplate::TestAuditor,
+        &[
+            ("top_task_title", &workflow.task_title(top_task_id)),
+            (
+                "test_writer_title",
+                &workflow.node_title(test_writer_id, "Test Writing"),
+            ),
+            (
+                "test_writer_details",
+                &workflow.node_details(test_writer_id),
+            ),
+            ("audit_details", &workflow.node_details(auditor_id)),
+            ("pass", &pass.to_string()),
+            (
+                "max_passes",
+                &workflow
+                    .retry_budget(auditor_id, super::RetryLoop::Audit)
+                    .limit
+                    .to_string(),
+            ),
+            ("rolling_context", &workflow.context_block()),
+            (
+                "observed_changes",
+                &workflow.observed_changes_block(test_writer_id, None),
+            ),
+            (
+                "test_writer_output",
+                test_report
+                    .as_deref()
+                    .unwrap_or("(no test-writer output captured)"),
+            ),
+            ("tests_policy", tests_policy),
+            ("strictness_policy", audit_strictness_policy(pass)),
+            ("verdict_protocol", AUDIT_VERDICT_PROTOCOL),
+        ],
     )
 }
 
@@ -74,7 +81,8 @@ pub(crate) fn on_completion(
             return;
         }
         workflow.set_status(test_writer_id, TaskStatus::NeedsChanges);
-        if pass >= super::MAX_AUDIT_RETRIES {
+        let budget = workflow.retry_budget(auditor_id, super::RetryLoop::Audit);
+        if pass >= budget.limit {
             workflow.set_status(auditor_id, TaskStatus::Done);
             workflow.recent_failures.push(super::WorkflowFailure {
                 kind: super::WorkflowFailureKind::Audit,
@@ -85,6 +93,8 @@ pub(crate) fn on_completion(
                 action_taken:
                     "Test-writer audit retries exhausted; continued to deterministic test run."
                         .to_string(),
+                retry_budget: Some(budget.clone()),
+                checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
             });
             workflow.queue_test_writer_next_step(
                 top_task_id,
@@ -96,7 +106,7 @@ pub(crate) fn on_completion(
             );
             messages.push(format!(
                 "System: Task #{} test-writer audit still found critical blockers at pass {}. Max retries ({}) reached; proceeding to deterministic tests.",
-                top_task_id, pass, super::MAX_AUDIT_RETRIES
+                top_task_id, pass, budget.limit
             ));
         } else {
             workflow.set_status(auditor_id, TaskStatus::NeedsChanges);
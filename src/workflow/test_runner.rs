@@ -1,5 +1,6 @@
 use super::Workflow;
-use super::{TaskStatus, test_runner_feedback};
+use super::{RetryLoop, TaskStatus, test_runner_feedback};
+use crate::test_report::TestRunSummary;
 
 pub(crate) fn on_writer_completion(
     workflow: &mut Workflow,
@@ -7,6 +8,7 @@ pub(crate) fn on_writer_completion(
     test_writer_id: u64,
     test_runner_id: u64,
     pass: u8,
+    report: Option<&TestRunSummary>,
     transcript: &[String],
     success: bool,
     code: i32,
@@ -15,11 +17,26 @@ pub(crate) fn on_writer_completion(
     workflow.set_status(test_runner_id, TaskStatus::Done);
 
     if success {
-        workflow.set_status(test_writer_id, TaskStatus::Done);
         messages.push(format!(
             "System: Task #{} deterministic tests passed on run {}.",
             top_task_id, pass
         ));
+        if workflow.coverage_threshold().is_some() {
+            workflow.queue.push_back(super::WorkerJob {
+                top_task_id,
+                kind: super::WorkerJobKind::CoverageRunner {
+                    test_writer_id,
+                    test_runner_id,
+                    pass,
+                },
+            });
+            messages.push(format!(
+                "System: Task #{} coverage check queued.",
+                top_task_id
+            ));
+            return;
+        }
+        workflow.set_status(test_writer_id, TaskStatus::Done);
         workflow.try_mark_top_done(top_task_id, messages);
     } else {
         if super::ENFORCE_TESTS_MODE_RUNTIME_GATING && !workflow.tests_mode_enabled() {
@@ -33,8 +50,9 @@ pub(crate) fn on_writer_completion(
             return;
         }
         workflow.set_status(test_writer_id, TaskStatus::NeedsChanges);
-        if pass >= super::MAX_TEST_RETRIES {
-            let failure_reason = test_runner_feedback(transcript, code);
+        let budget = workflow.retry_budget(test_runner_id, RetryLoop::Test);
+        if pass >= budget.limit {
+            let failure_reason = test_runner_feedback(transcript, code, report);
             workflow.recent_failures.push(super::WorkflowFailure {
                 kind: super::WorkflowFailureKind::Test,
                 top_task_id,
@@ -43,6 +61,8 @@ pub(crate) fn on_writer_completion(
                 reason: failure_reason.clone(),
                 action_taken: "Requested test cleanup (remove failing tests) and continued."
                     .to_string(),
+                retry_budget: Some(budget.clone()),
+                checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
             });
             workflow.queue.push_back(super::WorkerJob {
                 top_task_id,
@@ -64,7 +84,7 @@ pub(crate) fn on_writer_completion(
             });
             messages.push(format!(
                 "System: Task #{} tests still failing at pass {}. Max retries ({}) reached; queued cleanup removal pass.",
-                top_task_id, pass, super::MAX_TEST_RETRIES
+                top_task_id, pass, budget.limit
             ));
         } else {
             workflow.queue.push_back(super::WorkerJob {
@@ -72,7 +92,7 @@ pub(crate) fn on_writer_completion(
                 kind: super::WorkerJobKind::TestWriter {
                     test_writer_id,
                     pass: pass.saturating_add(1),
-                    feedback: Some(test_runner_feedback(transcript, code)),
+                    feedback: Some(test_runner_feedback(transcript, code, report)),
                     skip_test_runner_on_success: false,
                     resume_auditor_id: None,
                     resume_audit_pass: None,
@@ -93,6 +113,7 @@ pub(crate) fn on_implementor_completion(
     implementor_id: u64,
     test_runner_id: u64,
     pass: u8,
+    report: Option<&TestRunSummary>,
     transcript: &[String],
     success: bool,
     code: i32,
@@ -118,22 +139,25 @@ pub(crate) fn on_implementor_completion(
             return;
         }
         workflow.set_status(test_runner_id, TaskStatus::NeedsChanges);
-        if pass >= super::MAX_TEST_RETRIES {
+        let budget = workflow.retry_budget(test_runner_id, RetryLoop::Test);
+        if pass >= budget.limit {
             workflow.set_status(test_runner_id, TaskStatus::Done);
             workflow.recent_failures.push(super::WorkflowFailure {
                 kind: super::WorkflowFailureKind::Test,
                 top_task_id,
                 top_task_title: workflow.task_title(top_task_id),
                 attempts: pass,
-                reason: test_runner_feedback(transcript, code),
+                reason: test_runner_feedback(transcript, code, report),
                 action_taken: "Existing-tests runner retries exhausted; continued to next step."
                     .to_string(),
+                retry_budget: Some(budget.clone()),
+                checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
             });
             workflow.set_status(implementor_id, TaskStatus::Done);
             workflow.try_mark_top_done(top_task_id, messages);
             messages.push(format!(
                 "System: Task #{} existing tests still failing at pass {}. Max retries ({}) reached; proceeding to next step.",
-                top_task_id, pass, super::MAX_TEST_RETRIES
+                top_task_id, pass, budget.limit
             ));
         } else {
             workflow.set_status(implementor_id, TaskStatus::NeedsChanges);
@@ -142,7 +166,7 @@ pub(crate) fn on_implementor_completion(
                 kind: super::WorkerJobKind::Implementor {
                     implementor_id,
                     pass: pass.saturating_add(1),
-                    feedback: Some(test_runner_feedback(transcript, code)),
+                    feedback: Some(test_runner_feedback(transcript, code, report)),
                     resume_auditor_id: None,
                     resume_audit_pass: None,
                 },
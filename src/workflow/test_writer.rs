@@ -1,4 +1,7 @@
-use super::{MAX_TEST_RETRIES, TaskStatus, WorkerJob, Workflow, make_context_summary};
+use super::{
+    RetryLoop, TaskStatus, WorkerJob, Workflow, failed_run_feedback, make_context_summary,
+};
+use crate::prompt_templates::{self, PromptTemplate};
 
 pub(crate) fn build_prompt(
     workflow: &Workflow,
@@ -17,34 +20,35 @@ pub(crate) fn build_prompt(
     } else {
         "Keep output concise and explicitly state that test writing is disabled by tests mode."
     };
-    format!(
-        "You are a test-writer sub-agent.\n\
-         Top-level task: {}\n\
-         Test-writer subtask: {}\n\
-         Test-writing details:\n{}\n\
-         Rolling task context:\n{}\n\
-         {}\n\
-         {}\n\
-         {}\n\
-         {}",
-        workflow.task_title(top_task_id),
-        workflow.node_title(test_writer_id, "Test Writing"),
-        workflow.node_details(test_writer_id),
-        workflow.context_block(),
-        feedback
-            .as_ref()
-            .map(|f| format!("Feedback to address before re-running deterministic tests:\n{f}"))
-            .unwrap_or_else(|| {
-                "No test feedback yet; infer tests from task and implementation branch progress."
-                    .to_string()
-            }),
-        tests_policy,
-        if skip_test_runner_on_success {
-            "Special instruction: this is a cleanup pass after exhausted deterministic test retries. Remove failing tests and do not add replacements."
-        } else {
-            ""
-        },
-        output_instruction
+    let feedback = feedback
+        .map(|f| format!("Feedback to address before re-running deterministic tests:\n{f}"))
+        .unwrap_or_else(|| {
+            "No test feedback yet; infer tests from task and implementation branch progress."
+                .to_string()
+        });
+    let cleanup_instruction = if skip_test_runner_on_success {
+        "Special instruction: this is a cleanup pass after exhausted deterministic test retries. Remove failing tests and do not add replacements."
+    } else {
+        ""
+    };
+    prompt_templates::render(
+        PromptTemplate::TestWriter,
+        &[
+            ("top_task_title", &workflow.task_title(top_task_id)),
+            (
+                "test_writer_title",
+                &workflow.node_title(test_writer_id, "Test Writing"),
+            ),
+            (
+                "test_writer_details",
+                &workflow.node_details(test_writer_id),
+            ),
+            ("rolling_context", &workflow.context_block()),
+            ("feedback", &feedback),
+            ("tests_policy", tests_policy),
+            ("cleanup_instruction", cleanup_instruction),
+            ("output_instruction", output_instruction),
+        ],
     )
 }
 
@@ -113,7 +117,8 @@ pub(crate) fn on_completion(
             return;
         }
         workflow.set_status(test_writer_id, TaskStatus::NeedsChanges);
-        if pass >= MAX_TEST_RETRIES {
+        let budget = workflow.retry_budget(test_writer_id, RetryLoop::Test);
+        if pass >= budget.limit {
             workflow.set_status(test_writer_id, TaskStatus::Done);
             workflow.recent_failures.push(super::WorkflowFailure {
                 kind: super::WorkflowFailureKind::Test,
@@ -123,10 +128,12 @@ pub(crate) fn on_completion(
                 reason: format!("Test-writer failed repeatedly; latest exit code {code}."),
                 action_taken: "Test-writer retries exhausted; proceeded without adding tests."
                     .to_string(),
+                retry_budget: Some(budget.clone()),
+                checkpoint_refs: workflow.task_checkpoint_refs(top_task_id),
             });
             messages.push(format!(
                 "System: Task #{} test-writer still failing at pass {}. Max retries ({}) reached; proceeding to next step.",
-                top_task_id, pass, MAX_TEST_RETRIES
+                top_task_id, pass, budget.limit
             ));
             workflow.try_mark_top_done(top_task_id, messages);
         } else {
@@ -135,7 +142,7 @@ pub(crate) fn on_completion(
                 kind: super::WorkerJobKind::TestWriter {
                     test_writer_id,
                     pass: pass.saturating_add(1),
-                    feedback: Some(format!("Previous test-writer run failed with code {code}.")),
+                    feedback: Some(failed_run_feedback("test-writer", code, transcript)),
                     skip_test_runner_on_success: false,
                     resume_auditor_id: None,
                     resume_audit_pass: None,
@@ -0,0 +1,153 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const BRANCH_PREFIX: &str = "bob/";
+const WORKTREES_DIR_NAME: &str = "worktrees";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Merged,
+    NothingToMerge,
+    Conflict { files: Vec<String> },
+    DirtyWorkspace { files: Vec<String> },
+}
+
+pub fn repo_root(cwd: &Path) -> io::Result<PathBuf> {
+    let output = run_git(cwd, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(output.trim()))
+}
+
+pub fn task_branch_name(task_key: &str) -> String {
+    format!("{BRANCH_PREFIX}{}", sanitize_task_key(task_key))
+}
+
+pub fn task_worktree_dir(session_dir: &Path, task_key: &str) -> PathBuf {
+    session_dir
+        .join(WORKTREES_DIR_NAME)
+        .join(sanitize_task_key(task_key))
+}
+
+pub fn ensure_task_worktree(
+    repo_root: &Path,
+    session_dir: &Path,
+    task_key: &str,
+) -> io::Result<PathBuf> {
+    let worktree_dir = task_worktree_dir(session_dir, task_key);
+    if worktree_dir.join(".git").exists() {
+        return Ok(worktree_dir);
+    }
+    if let Some(parent) = worktree_dir.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let branch = task_branch_name(task_key);
+    let worktree_arg = worktree_dir.to_string_lossy().to_string();
+    run_git(
+        repo_root,
+        &["worktree", "add", "-B", &branch, &worktree_arg, "HEAD"],
+    )?;
+    Ok(worktree_dir)
+}
+
+pub fn merge_task_worktree(
+    repo_root: &Path,
+    worktree_dir: &Path,
+    task_key: &str,
+    task_title: &str,
+) -> io::Result<MergeOutcome> {
+    let status = run_git(worktree_dir, &["status", "--porcelain"])?;
+    if !status.trim().is_empty() {
+        run_git(worktree_dir, &["add", "-A"])?;
+        run_git(worktree_dir, &["commit", "-m", task_title])?;
+    }
+
+    let branch = task_branch_name(task_key);
+    let ahead = run_git(
+        repo_root,
+        &["rev-list", "--count", &format!("HEAD..{branch}")],
+    )?;
+    if ahead.trim() == "0" {
+        remove_task_worktree(repo_root, worktree_dir, task_key)?;
+        return Ok(MergeOutcome::NothingToMerge);
+    }
+
+    let main_status = run_git(repo_root, &["status", "--porcelain", "--untracked-files=no"])?;
+    let dirty = main_status
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    if !dirty.is_empty() {
+        return Ok(MergeOutcome::DirtyWorkspace { files: dirty });
+    }
+
+    let message = format!("Merge task \"{task_title}\"");
+    if run_git(repo_root, &["merge", "--no-ff", "-m", &message, &branch]).is_ok() {
+        remove_task_worktree(repo_root, worktree_dir, task_key)?;
+        return Ok(MergeOutcome::Merged);
+    }
+
+    let conflicted =
+        run_git(repo_root, &["diff", "--name-only", "--diff-filter=U"]).unwrap_or_default();
+    let files = conflicted
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let _ = run_git(repo_root, &["merge", "--abort"]);
+    Ok(MergeOutcome::Conflict { files })
+}
+
+pub fn remove_task_worktree(
+    repo_root: &Path,
+    worktree_dir: &Path,
+    task_key: &str,
+) -> io::Result<()> {
+    let worktree_arg = worktree_dir.to_string_lossy().to_string();
+    run_git(repo_root, &["worktree", "remove", "--force", &worktree_arg])?;
+    run_git(repo_root, &["branch", "-D", &task_branch_name(task_key)])?;
+    Ok(())
+}
+
+pub(crate) fn sanitize_task_key(task_key: &str) -> String {
+    let sanitized = task_key
+        .trim()
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    if sanitized.is_empty() {
+        "task".to_string()
+    } else {
+        sanitized
+    }
+}
+
+pub(crate) fn run_git(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(io::Error::other(format!(
+        "git {} failed: {}",
+        args.join(" "),
+        if stderr.is_empty() {
+            format!("exit status {}", output.status)
+        } else {
+            stderr
+        }
+    )))
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/worktree_tests.rs"]
+mod tests;
@@ -116,6 +116,269 @@ fn backend_override_precedence_and_fallback_use_embedded_defaults() {
     );
 }
 
+#[test]
+fn agent_profiles_entries_may_name_a_backend_per_role() {
+    let routing = CodexAgentModelRouting::from_toml_str(
+        r#"
+        [backend.claude]
+        program = "/opt/bin/claude"
+
+        [codex.agent_profiles]
+        worker_implementor = "large-genius"
+        worker_auditor = { profile = "small-smart", backend = "Claude" }
+        worker_final_audit = { backend = "claude" }
+        task_check = "large-dumb"
+        "#,
+    )
+    .expect("parse should succeed");
+
+    assert_eq!(routing.selected_backend(), BackendKind::Codex);
+    assert_eq!(routing.backend_for(CodexAgentKind::WorkerImplementor), None);
+    assert_eq!(
+        routing.backend_for(CodexAgentKind::WorkerAuditor),
+        Some(BackendKind::Claude)
+    );
+    assert_eq!(routing.backend_for(CodexAgentKind::TaskCheck), None);
+
+    let implementor = routing
+        .command_config_for(CodexAgentKind::WorkerImplementor, BackendKind::Codex)
+        .expect("backend should be configured");
+    assert_eq!(implementor.program, "codex");
+    let auditor = routing
+        .command_config_for(CodexAgentKind::WorkerAuditor, BackendKind::Codex)
+        .expect("backend should be configured");
+    assert_eq!(auditor.program, "/opt/bin/claude");
+    assert_eq!(auditor.backend_kind(), BackendKind::Claude);
+    let final_audit = routing
+        .command_config_for(CodexAgentKind::WorkerFinalAudit, BackendKind::Codex)
+        .expect("backend should be configured");
+    assert_eq!(final_audit.backend_kind(), BackendKind::Claude);
+
+    assert_eq!(
+        routing
+            .profile_for(CodexAgentKind::WorkerAuditor)
+            .thinking_effort
+            .as_deref(),
+        Some("medium")
+    );
+    assert_eq!(
+        routing.profile_for(CodexAgentKind::WorkerFinalAudit),
+        routing.profile_for(CodexAgentKind::Master)
+    );
+    assert_eq!(
+        routing
+            .profile_for(CodexAgentKind::TaskCheck)
+            .thinking_effort
+            .as_deref(),
+        Some("low")
+    );
+}
+
+#[test]
+fn agent_profiles_entries_may_set_permissions_per_role() {
+    let routing = CodexAgentModelRouting::from_toml_str(
+        r#"
+        [backend.codex.permissions]
+        workspace_write = ["--sandbox", "workspace-write"]
+
+        [codex.agent_profiles]
+        worker_test_writer = { permissions = "full" }
+        master = { profile = "large-smart", permissions = "read_only" }
+        "#,
+    )
+    .expect("parse should succeed");
+
+    assert_eq!(
+        routing.permissions_for(CodexAgentKind::WorkerAuditor),
+        PermissionProfile::ReadOnly
+    );
+    assert_eq!(
+        routing.permissions_for(CodexAgentKind::TaskCheck),
+        PermissionProfile::ReadOnly
+    );
+    assert_eq!(
+        routing.permissions_for(CodexAgentKind::WorkerTestWriter),
+        PermissionProfile::Full
+    );
+    assert_eq!(
+        routing.permissions_for(CodexAgentKind::Master),
+        PermissionProfile::ReadOnly
+    );
+
+    let implementor = routing
+        .command_config_for(CodexAgentKind::WorkerImplementor, BackendKind::Codex)
+        .expect("backend should be configured");
+    assert_eq!(implementor.permissions, PermissionProfile::WorkspaceWrite);
+    assert_eq!(
+        implementor.permission_args.workspace_write,
+        vec!["--sandbox".to_string(), "workspace-write".to_string()]
+    );
+    assert_eq!(
+        implementor.permission_args.read_only,
+        PermissionArgs::default_for_backend(BackendKind::Codex).read_only
+    );
+    assert!(implementor.guard_workspace);
+    let report = routing
+        .command_config_for(CodexAgentKind::MasterReport, BackendKind::Codex)
+        .expect("backend should be configured");
+    assert_eq!(report.permissions, PermissionProfile::ReadOnly);
+    assert!(!report.guard_workspace);
+
+    let err = CodexAgentModelRouting::from_toml_str(
+        r#"
+        [codex.agent_profiles]
+        worker_auditor = { permissions = "root" }
+        "#,
+    )
+    .expect_err("unknown permissions should be rejected");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("worker_auditor"));
+}
+
+#[test]
+fn custom_backend_is_built_from_backend_custom_table() {
+    let routing = CodexAgentModelRouting::from_toml_str(
+        r#"
+        [backend]
+        selected = "custom"
+
+        [backend.custom]
+        program = "opencode"
+        args_prefix = ["run"]
+        new_session_args = ["--format", "json"]
+        resume_args = ["--format", "json", "--session", "{session_id}"]
+        model_args = ["--model", "{model}"]
+        prompt_input = "Stdin"
+        output_mode = "json"
+        assistant_text_path = "part.text"
+        session_id_regex = "\"sessionID\":\"([^\"]+)\""
+
+        [codex.agent_profiles]
+        worker_auditor = { backend = "codex" }
+        "#,
+    )
+    .expect("parse should succeed");
+
+    assert!(routing.available_backends().contains(&BackendKind::Custom));
+    assert_eq!(routing.selected_backend(), BackendKind::Custom);
+    let config = routing
+        .command_config_for(CodexAgentKind::Master, BackendKind::Custom)
+        .expect("backend should be configured");
+    assert_eq!(config.backend_kind(), BackendKind::Custom);
+    assert_eq!(config.program, "opencode");
+    assert_eq!(config.args_prefix, vec!["run".to_string()]);
+    assert!(config.accepts_model_profiles());
+    let spec = config.custom.expect("custom spec should be set");
+    assert_eq!(spec.prompt_input, PromptInput::Stdin);
+    assert_eq!(spec.output_mode, AdapterOutputMode::JsonAssistantOnly);
+    assert_eq!(
+        spec.assistant_text.json_path,
+        Some(vec!["part".to_string(), "text".to_string()])
+    );
+    assert!(spec.session_id.regex.is_some());
+
+    let auditor = routing
+        .command_config_for(CodexAgentKind::WorkerAuditor, BackendKind::Custom)
+        .expect("backend should be configured");
+    assert_eq!(auditor.backend_kind(), BackendKind::Codex);
+}
+
+#[test]
+fn custom_backend_selection_falls_back_to_codex_without_a_program() {
+    let routing = CodexAgentModelRouting::from_toml_str("[backend]\nselected = \"custom\"\n")
+        .expect("parse should succeed");
+
+    assert!(!routing.available_backends().contains(&BackendKind::Custom));
+    assert_eq!(routing.selected_backend(), BackendKind::Codex);
+    let master = routing
+        .command_config_for(CodexAgentKind::Master, routing.selected_backend())
+        .expect("backend should be configured");
+    assert_eq!(master.backend_kind(), BackendKind::Codex);
+
+    let err = routing
+        .command_config_for(CodexAgentKind::Master, BackendKind::Custom)
+        .expect_err("an unconfigured backend should not fall back to codex");
+    assert!(err.to_string().contains("[backend.custom]"));
+}
+
+#[test]
+fn agent_profiles_backends_must_be_known_and_configured() {
+    let err = CodexAgentModelRouting::from_toml_str(
+        "[codex.agent_profiles]\ntask_check = { profile = \"large-dumb\", backend = \"unknown\" }\n",
+    )
+    .expect_err("unknown backend should be rejected");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("task_check"));
+    assert!(err.to_string().contains("\"unknown\""));
+
+    let err = CodexAgentModelRouting::from_toml_str(
+        "[codex.agent_profiles]\nworker_auditor = { backend = \"http\" }\n",
+    )
+    .expect_err("unconfigured backend should be rejected");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("worker_auditor"));
+    assert!(err.to_string().contains("[backend.http]"));
+}
+
+#[test]
+fn custom_backend_rejects_unknown_modes_and_bad_regexes() {
+    let err = CodexAgentModelRouting::from_toml_str(
+        "[backend.custom]\nprogram = \"aider\"\nprompt_input = \"pipe\"\n",
+    )
+    .expect_err("unknown prompt_input should fail");
+    assert!(err.to_string().contains("prompt_input"));
+
+    let err = CodexAgentModelRouting::from_toml_str(
+        "[backend.custom]\nprogram = \"aider\"\nsession_id_regex = \"(unclosed\"\n",
+    )
+    .expect_err("invalid regex should fail");
+    assert!(err.to_string().contains("session_id_regex"));
+}
+
+#[test]
+fn http_backend_is_built_from_backend_http_table() {
+    let routing = CodexAgentModelRouting::from_toml_str(
+        r#"
+        [backend.http]
+        base_url = " http://127.0.0.1:8000/v1 "
+        model = "qwen2.5-coder"
+        api_key_env = "LOCAL_LLM_KEY"
+
+        [codex.agent_profiles]
+        master_report = { backend = "http" }
+        worker_auditor = { profile = "small-smart", backend = "HTTP" }
+        "#,
+    )
+    .expect("parse should succeed");
+
+    assert!(routing.available_backends().contains(&BackendKind::Http));
+    assert_eq!(routing.selected_backend(), BackendKind::Codex);
+    let auditor = routing
+        .command_config_for(CodexAgentKind::WorkerAuditor, BackendKind::Codex)
+        .expect("backend should be configured");
+    assert_eq!(auditor.backend_kind(), BackendKind::Http);
+    assert!(!auditor.accepts_model_profiles());
+    assert_eq!(auditor.model.as_deref(), Some("qwen2.5-coder"));
+    let spec = auditor.http.expect("http spec should be set");
+    assert_eq!(spec.base_url, "http://127.0.0.1:8000/v1");
+    assert_eq!(spec.api_key_env.as_deref(), Some("LOCAL_LLM_KEY"));
+    assert_eq!(spec.read_timeout_secs, 600);
+
+    let implementor = routing
+        .command_config_for(CodexAgentKind::WorkerImplementor, BackendKind::Codex)
+        .expect("backend should be configured");
+    assert_eq!(implementor.backend_kind(), BackendKind::Codex);
+
+    let unconfigured = CodexAgentModelRouting::from_toml_str("[backend]\nselected = \"http\"\n")
+        .expect("parse should succeed");
+    assert_eq!(unconfigured.selected_backend(), BackendKind::Codex);
+    assert!(
+        !unconfigured
+            .available_backends()
+            .contains(&BackendKind::Http)
+    );
+}
+
 #[test]
 fn load_from_metaagent_config_creates_default_config_when_missing() {
     let _guard = crate::artifact_io::home_env_test_lock()
@@ -250,6 +250,352 @@ fn claude_resume_prompt_args_use_resume_flag() {
     );
 }
 
+#[test]
+fn restricted_permissions_replace_the_bypass_flag() {
+    let config = CodexCommandConfig {
+        permissions: PermissionProfile::ReadOnly,
+        ..CodexCommandConfig::default()
+    };
+    let args = build_new_session_args(&config);
+    assert_eq!(
+        args,
+        vec![
+            "exec".to_string(),
+            "--color".to_string(),
+            "never".to_string(),
+            "-c".to_string(),
+            "sandbox_mode=\"read-only\"".to_string(),
+        ]
+    );
+    let args = build_resume_prompt_args(&config, "session-123");
+    assert!(
+        !args
+            .iter()
+            .any(|arg| arg == "--dangerously-bypass-approvals-and-sandbox")
+    );
+    assert!(args.iter().any(|arg| arg == "sandbox_mode=\"read-only\""));
+
+    let mut config = CodexCommandConfig::default_for_backend(BackendKind::Claude);
+    config.permissions = PermissionProfile::WorkspaceWrite;
+    assert_eq!(
+        build_new_session_args(&config),
+        vec![
+            "--permission-mode".to_string(),
+            "acceptEdits".to_string(),
+            "--allowedTools".to_string(),
+            "Bash".to_string(),
+            "-p".to_string(),
+        ]
+    );
+
+    config.permissions = PermissionProfile::Full;
+    config.args_prefix.clear();
+    assert_eq!(
+        build_new_session_args(&config),
+        vec![
+            "--dangerously-skip-permissions".to_string(),
+            "-p".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn permission_profiles_parse_config_labels() {
+    assert_eq!(
+        PermissionProfile::from_label(" Read_Only "),
+        Some(PermissionProfile::ReadOnly)
+    );
+    assert_eq!(
+        PermissionProfile::from_label("workspace-write"),
+        Some(PermissionProfile::WorkspaceWrite)
+    );
+    assert_eq!(
+        PermissionProfile::from_label("full"),
+        Some(PermissionProfile::Full)
+    );
+    assert_eq!(PermissionProfile::from_label("sudo"), None);
+}
+
+fn custom_config(spec: CustomBackendSpec) -> CodexCommandConfig {
+    let mut config = CodexCommandConfig::default_for_backend(BackendKind::Custom);
+    config.program = "agent-cli".to_string();
+    config.args_prefix = vec!["--yes".to_string()];
+    config.output_mode = spec.output_mode;
+    config.custom = Some(spec);
+    config
+}
+
+#[test]
+fn custom_backend_is_detected_from_its_spec_not_the_program_name() {
+    let mut config = custom_config(CustomBackendSpec::default());
+    config.program = "/usr/local/bin/claude-wrapper".to_string();
+    assert_eq!(config.backend_kind(), BackendKind::Custom);
+    assert!(!config.accepts_model_profiles());
+
+    config.custom = Some(CustomBackendSpec {
+        model_args: vec!["--model".to_string(), "{model}".to_string()],
+        ..CustomBackendSpec::default()
+    });
+    assert!(config.accepts_model_profiles());
+}
+
+#[test]
+fn custom_args_expand_templates_and_append_the_prompt() {
+    let spec = CustomBackendSpec {
+        new_session_args: vec!["run".to_string()],
+        resume_args: vec!["run".to_string(), "--session={session_id}".to_string()],
+        model_args: vec!["--model".to_string(), "{model}".to_string()],
+        effort_args: vec!["--effort".to_string(), "{effort}".to_string()],
+        ..CustomBackendSpec::default()
+    };
+    let mut config = custom_config(spec.clone());
+    config.model = Some("local-7b".to_string());
+
+    assert_eq!(
+        build_custom_args(&config, &spec, None, "fix {model}", None),
+        vec!["--yes", "run", "--model", "local-7b", "fix {model}"]
+    );
+
+    config.model_reasoning_effort = Some("high".to_string());
+    assert_eq!(
+        build_custom_args(&config, &spec, Some("sess-12345678"), "go", None),
+        vec![
+            "--yes",
+            "run",
+            "--session=sess-12345678",
+            "--model",
+            "local-7b",
+            "--effort",
+            "high",
+            "go"
+        ]
+    );
+}
+
+#[test]
+fn custom_args_start_a_new_session_when_resume_is_not_configured() {
+    let spec = CustomBackendSpec {
+        new_session_args: vec![
+            "-p".to_string(),
+            "{prompt}".to_string(),
+            "--quiet".to_string(),
+        ],
+        ..CustomBackendSpec::default()
+    };
+    let config = custom_config(spec.clone());
+    assert_eq!(
+        build_custom_args(&config, &spec, Some("sess-12345678"), "hello", None),
+        vec!["--yes", "-p", "hello", "--quiet"]
+    );
+
+    let stdin_spec = CustomBackendSpec {
+        prompt_input: PromptInput::Stdin,
+        ..spec.clone()
+    };
+    let config = custom_config(stdin_spec.clone());
+    assert_eq!(
+        build_custom_args(&config, &stdin_spec, None, "hello", None),
+        vec!["--yes", "-p", "", "--quiet"]
+    );
+
+    let file_spec = CustomBackendSpec {
+        prompt_input: PromptInput::File,
+        new_session_args: vec!["--message-file".to_string()],
+        ..CustomBackendSpec::default()
+    };
+    let config = custom_config(file_spec.clone());
+    assert_eq!(
+        build_custom_args(
+            &config,
+            &file_spec,
+            None,
+            "hello",
+            Some(Path::new("/tmp/prompt.txt"))
+        ),
+        vec!["--yes", "--message-file", "/tmp/prompt.txt"]
+    );
+}
+
+#[test]
+fn output_extractor_prefers_json_path_then_regex() {
+    let extractor = OutputExtractor {
+        json_path: Some(vec![
+            "parts".to_string(),
+            "0".to_string(),
+            "text".to_string(),
+        ]),
+        regex: Some(Regex::new(r"^session: (\S+)$").expect("regex should compile")),
+    };
+    assert_eq!(
+        extractor.extract(r#"{"parts":[{"text":"hi there"}]}"#),
+        Some("hi there".to_string())
+    );
+    assert_eq!(
+        extractor.extract("session: abc-12345"),
+        Some("abc-12345".to_string())
+    );
+    assert_eq!(extractor.extract(r#"{"parts":[]}"#), None);
+    assert!(!OutputExtractor::default().is_configured());
+}
+
+#[test]
+fn custom_backend_reads_prompt_from_stdin_and_extracts_text_and_session() {
+    let adapter = CodexAdapter::with_config(CodexCommandConfig {
+        program: "sh".to_string(),
+        args_prefix: vec![
+            "-c".to_string(),
+            "read line; printf '{\"reply\":{\"text\":\"got %s\"}}\\n' \"$line\"; printf 'session: abcd-1234\\n'"
+                .to_string(),
+        ],
+        output_mode: AdapterOutputMode::PlainText,
+        persistent_session: true,
+        skip_reader_join_after_wait: false,
+        model: None,
+        model_reasoning_effort: None,
+        working_dir: None,
+        custom: Some(CustomBackendSpec {
+            prompt_input: PromptInput::Stdin,
+            output_mode: AdapterOutputMode::JsonAssistantOnly,
+            assistant_text: OutputExtractor {
+                json_path: Some(vec!["reply".to_string(), "text".to_string()]),
+                regex: None,
+            },
+            session_id: OutputExtractor {
+                json_path: None,
+                regex: Some(Regex::new(r"^session: (\S+)$").expect("regex should compile")),
+            },
+            ..CustomBackendSpec::default()
+        }),
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
+    });
+    adapter.send_prompt("hello".to_string());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut outputs = Vec::new();
+    let mut completed = None;
+    while Instant::now() < deadline && completed.is_none() {
+        for event in adapter.drain_events() {
+            match event {
+                AgentEvent::Output(line) => outputs.push(line),
+                AgentEvent::Completed { success, .. } => completed = Some(success),
+                AgentEvent::System(_) => {}
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert_eq!(completed, Some(true));
+    assert!(outputs.iter().any(|line| line == "got hello"));
+    assert_eq!(adapter.saved_session_id().as_deref(), Some("abcd-1234"));
+}
+
+fn run_read_only_script(script: &str, working_dir: &Path) -> (Option<bool>, Vec<String>) {
+    let mut config = custom_config(CustomBackendSpec::default());
+    config.program = "sh".to_string();
+    config.args_prefix = vec!["-c".to_string(), script.to_string()];
+    config.working_dir = Some(working_dir.to_path_buf());
+    config.permissions = PermissionProfile::ReadOnly;
+    let adapter = CodexAdapter::with_config(config);
+    adapter.send_prompt("check".to_string());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut system = Vec::new();
+    let mut completed = None;
+    while Instant::now() < deadline && completed.is_none() {
+        for event in adapter.drain_events() {
+            match event {
+                AgentEvent::System(line) => system.push(line),
+                AgentEvent::Completed { success, .. } => completed = Some(success),
+                AgentEvent::Output(_) => {}
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    (completed, system)
+}
+
+#[test]
+fn read_only_run_that_changes_the_workspace_fails() {
+    let dir = std::env::temp_dir().join(format!(
+        "agentbob-read-only-guard-{}-{}",
+        std::process::id(),
+        PROMPT_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).expect("temp dir should be created");
+    std::fs::write(dir.join("kept.txt"), "kept").expect("seed file");
+
+    let (completed, system) = run_read_only_script("cat kept.txt", &dir);
+    assert_eq!(completed, Some(true));
+    assert!(system.is_empty());
+
+    let (completed, system) = run_read_only_script("printf x > written.txt", &dir);
+    assert_eq!(completed, Some(false));
+    assert!(
+        system
+            .iter()
+            .any(|line| line.contains("Read-only run changed the workspace")
+                && line.contains("A written.txt"))
+    );
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn custom_backend_passes_prompt_through_a_file() {
+    let adapter = CodexAdapter::with_config(CodexCommandConfig {
+        program: "sh".to_string(),
+        args_prefix: vec![
+            "-c".to_string(),
+            "cat \"$1\"; printf '\\npath:%s\\n' \"$1\"".to_string(),
+            "sh".to_string(),
+        ],
+        output_mode: AdapterOutputMode::PlainText,
+        persistent_session: false,
+        skip_reader_join_after_wait: false,
+        model: None,
+        model_reasoning_effort: None,
+        working_dir: None,
+        custom: Some(CustomBackendSpec {
+            prompt_input: PromptInput::File,
+            ..CustomBackendSpec::default()
+        }),
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
+    });
+    adapter.send_prompt("from a file".to_string());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut outputs = Vec::new();
+    let mut completed = false;
+    while Instant::now() < deadline && !completed {
+        for event in adapter.drain_events() {
+            match event {
+                AgentEvent::Output(line) => outputs.push(line),
+                AgentEvent::Completed { .. } => completed = true,
+                AgentEvent::System(_) => {}
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert!(completed);
+    assert!(outputs.iter().any(|line| line == "from a file"));
+    let path = outputs
+        .iter()
+        .find_map(|line| line.strip_prefix("path:"))
+        .expect("script should echo the prompt file path");
+    assert!(!Path::new(path).exists());
+}
+
 #[test]
 fn adapter_streams_stdout_and_stderr() {
     let adapter = CodexAdapter::with_config(CodexCommandConfig {
@@ -264,6 +610,14 @@ fn adapter_streams_stdout_and_stderr() {
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("hello".to_string());
 
@@ -297,6 +651,14 @@ fn adapter_emits_completed_event() {
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("ignored".to_string());
 
@@ -332,6 +694,14 @@ fn adapter_emits_completed_after_output_is_drained() {
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("ignored".to_string());
 
@@ -392,6 +762,14 @@ fn adapter_reports_spawn_errors() {
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("hello".to_string());
 
@@ -427,6 +805,14 @@ fn adapter_spawn_error_still_emits_completed_event() {
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("hello".to_string());
 
@@ -468,6 +854,14 @@ fn claude_backend_spawn_error_still_emits_completed_event() {
         skip_reader_join_after_wait: false,
         model: Some("claude-sonnet-4.5".to_string()),
         model_reasoning_effort: Some("high".to_string()),
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("hello".to_string());
 
@@ -512,6 +906,14 @@ fn persistent_plain_text_completes_without_waiting_for_background_descendants()
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     let started = Instant::now();
     adapter.send_prompt("ignored".to_string());
@@ -551,6 +953,14 @@ fn persistent_json_can_complete_without_waiting_for_background_descendants() {
         skip_reader_join_after_wait: true,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     let started = Instant::now();
     adapter.send_prompt("ignored".to_string());
@@ -637,6 +1047,14 @@ fn json_assistant_mode_keeps_plaintext_stdout_lines() {
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("ignored".to_string());
 
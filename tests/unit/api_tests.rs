@@ -68,6 +68,8 @@ fn api_response_serialization_uses_domain_and_kind_tags() {
             attempts: 2,
             reason: "missing coverage".to_string(),
             action_taken: "requeue test writer".to_string(),
+            retry_budget: Some("5 ([workflow.retries] test)".to_string()),
+            checkpoint_refs: vec!["refs/bob/checkpoints/top/001-implementor-pass-1".to_string()],
         }],
     });
     let value = serde_json::to_value(response).expect("response should serialize");
@@ -84,7 +86,9 @@ fn api_response_serialization_uses_domain_and_kind_tags() {
                         "top_task_title": "Write regression test",
                         "attempts": 2,
                         "reason": "missing coverage",
-                        "action_taken": "requeue test writer"
+                        "action_taken": "requeue test writer",
+                        "retry_budget": "5 ([workflow.retries] test)",
+                        "checkpoint_refs": ["refs/bob/checkpoints/top/001-implementor-pass-1"]
                     }
                 ]
             }
@@ -256,6 +260,11 @@ fn capability_matrix_definitions_cover_expected_workflow_and_session_ids() {
     assert!(ids.contains(&CapabilityId::SessionPlannerStorage));
     assert!(ids.contains(&CapabilityId::SessionFailureStorage));
     assert!(ids.contains(&CapabilityId::SessionProjectContextStorage));
+    assert!(ids.contains(&CapabilityId::SessionExecutionControl));
+    assert!(ids.contains(&CapabilityId::SessionReviewDecision));
+    assert!(ids.contains(&CapabilityId::WorkflowTaskControl));
+    assert!(ids.contains(&CapabilityId::WorkflowDryRun));
+    assert!(ids.contains(&CapabilityId::SubagentPromptTemplates));
 
     let workflow_task_graph = capability_definition(CapabilityId::WorkflowTaskGraphSync)
         .expect("workflow task graph capability should exist");
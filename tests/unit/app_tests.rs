@@ -29,6 +29,7 @@ fn load_default_plan(app: &mut App, top_title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -39,6 +40,7 @@ fn load_default_plan(app: &mut App, top_title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -49,6 +51,7 @@ fn load_default_plan(app: &mut App, top_title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -59,6 +62,7 @@ fn load_default_plan(app: &mut App, top_title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -69,6 +73,7 @@ fn load_default_plan(app: &mut App, top_title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("seed plan should sync");
@@ -528,6 +533,36 @@ fn start_command_detection_handles_aliases() {
     assert!(App::is_remove_final_audit_command("/remove-final-audit"));
     assert!(!App::is_add_final_audit_command("/start"));
     assert!(!App::is_remove_final_audit_command("/start"));
+    assert!(App::is_rollback_command("/rollback top1"));
+    assert!(!App::is_rollback_command("/start"));
+    assert_eq!(App::rollback_command_target("/rollback top1"), Some("top1"));
+    assert_eq!(App::rollback_command_target("/rollback"), None);
+    assert!(App::is_pause_command("/pause"));
+    assert!(App::is_stop_command("/stop"));
+    assert!(App::is_resume_execution_command("/resume-execution"));
+    assert!(!App::is_resume_execution_command("/resume"));
+    assert!(!App::is_stop_command("/stop now"));
+    assert!(App::is_approve_command("/approve"));
+    assert_eq!(App::approve_command_target("/approve"), None);
+    assert_eq!(App::approve_command_target("/approve top1"), Some("top1"));
+    assert!(App::is_reject_command("/reject"));
+    assert_eq!(
+        App::reject_command_args("/reject top1  keep the old API  "),
+        Some(("top1", "keep the old API"))
+    );
+    assert_eq!(App::reject_command_args("/reject keep"), None);
+    assert_eq!(App::reject_command_args("/reject"), None);
+    assert_eq!(App::reject_command_args("/rejected top1 no"), None);
+    assert_eq!(
+        App::retry_command_args("/retry top1 use the new API"),
+        Some(("top1", Some("use the new API")))
+    );
+    assert_eq!(App::retry_command_args("/retry top1"), Some(("top1", None)));
+    assert_eq!(App::retry_command_args("/retry"), None);
+    assert_eq!(App::skip_command_target("/skip top1"), Some("top1"));
+    assert!(!App::is_skip_command("/skip-plan"));
+    assert!(App::is_dry_run_command(" /DRY-RUN "));
+    assert!(!App::is_dry_run_command("/dry-run now"));
 }
 
 #[test]
@@ -666,6 +701,7 @@ fn task_details_toggle_changes_block_rendering() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-a".to_string(),
@@ -680,6 +716,7 @@ fn task_details_toggle_changes_block_rendering() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("task-a".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-a-audit".to_string(),
@@ -690,6 +727,7 @@ fn task_details_toggle_changes_block_rendering() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl-a".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -790,8 +828,11 @@ fn command_index_matches_resume_prefix() {
     app.input_char('e');
     app.input_char('s');
     let suggestions = app.command_suggestions();
-    assert_eq!(suggestions.len(), 1);
-    assert_eq!(suggestions[0].command, "/resume");
+    let commands = suggestions
+        .iter()
+        .map(|entry| entry.command)
+        .collect::<Vec<_>>();
+    assert_eq!(commands, vec!["/resume-execution", "/resume"]);
 }
 
 #[test]
@@ -0,0 +1,157 @@
+use super::*;
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn init_repo(prefix: &str) -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let repo = std::env::temp_dir().join(format!("{prefix}-{now}"));
+    std::fs::create_dir_all(&repo).expect("create temp dir");
+    run_git(&repo, &["init", "-q"]).expect("git init");
+    run_git(&repo, &["config", "user.email", "bob@example.com"]).expect("git config email");
+    run_git(&repo, &["config", "user.name", "Bob"]).expect("git config name");
+    std::fs::write(repo.join("shared.txt"), "base\n").expect("write base file");
+    run_git(&repo, &["add", "-A"]).expect("git add");
+    run_git(&repo, &["commit", "-q", "-m", "base"]).expect("git commit");
+    repo
+}
+
+#[test]
+fn create_checkpoint_records_sequenced_refs_without_touching_workspace() {
+    let repo = init_repo("bob-checkpoint-create");
+    std::fs::write(repo.join("draft.txt"), "draft\n").expect("write draft");
+
+    let first =
+        create_checkpoint(&repo, "session-1", "task a", "implementor").expect("first checkpoint");
+    let second =
+        create_checkpoint(&repo, "session-1", "task a", "test_writer").expect("second checkpoint");
+
+    assert_eq!(
+        first,
+        "refs/bob/checkpoints/session-1/task-a/001-implementor"
+    );
+    assert_eq!(
+        second,
+        "refs/bob/checkpoints/session-1/task-a/002-test_writer"
+    );
+    assert_eq!(
+        list_checkpoints(&repo, "session-1", "task a").expect("list"),
+        vec![first.clone(), second]
+    );
+    let status = run_git(&repo, &["status", "--porcelain"]).expect("status");
+    assert_eq!(status.trim(), "?? draft.txt");
+    let snapshot = run_git(&repo, &["show", &format!("{first}:draft.txt")]).expect("show");
+    assert_eq!(snapshot, "draft\n");
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn rollback_task_restores_first_checkpoint_and_keeps_backup() {
+    let repo = init_repo("bob-checkpoint-rollback");
+    let checkpoint =
+        create_checkpoint(&repo, "session-1", "task-a", "implementor").expect("checkpoint");
+    std::fs::write(repo.join("shared.txt"), "broken\n").expect("modify tracked");
+    std::fs::write(repo.join("extra.txt"), "extra\n").expect("add untracked");
+
+    let outcome = rollback_task(&repo, "session-1", "task-a").expect("rollback");
+
+    assert_eq!(outcome.checkpoint_ref, checkpoint);
+    assert_eq!(
+        outcome.backup_ref,
+        "refs/bob/checkpoints/session-1/task-a/002-pre-rollback"
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.join("shared.txt")).expect("restored"),
+        "base\n"
+    );
+    assert!(!repo.join("extra.txt").exists());
+    let backup = run_git(
+        &repo,
+        &["show", &format!("{}:extra.txt", outcome.backup_ref)],
+    )
+    .expect("backup");
+    assert_eq!(backup, "extra\n");
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn rollback_task_without_checkpoints_reports_not_found() {
+    let repo = init_repo("bob-checkpoint-missing");
+
+    let err = rollback_task(&repo, "session-1", "task-a").expect_err("no checkpoints");
+
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn task_diff_covers_tracked_and_untracked_changes_since_first_checkpoint() {
+    let repo = init_repo("bob-checkpoint-diff");
+    std::fs::write(repo.join("shared.txt"), "before task\n").expect("modify before task");
+    create_checkpoint(&repo, "session-1", "task-a", "implementor").expect("checkpoint");
+    std::fs::write(repo.join("shared.txt"), "after task\n").expect("modify tracked");
+    std::fs::write(repo.join("new.txt"), "new\n").expect("add untracked");
+
+    let diff = task_diff(&repo, "session-1", "task-a").expect("diff");
+
+    assert!(diff.contains("-before task"));
+    assert!(diff.contains("+after task"));
+    assert!(diff.contains("new.txt"));
+    assert!(!diff.contains("-base"));
+    let status = run_git(&repo, &["status", "--porcelain"]).expect("status");
+    assert!(status.contains("?? new.txt"));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn rollback_task_ignores_checkpoints_from_other_sessions() {
+    let repo = init_repo("bob-checkpoint-sessions");
+    std::fs::write(repo.join("shared.txt"), "old session\n").expect("modify for old session");
+    create_checkpoint(&repo, "session-1", "task-a", "implementor").expect("old checkpoint");
+    std::fs::write(repo.join("shared.txt"), "new session\n").expect("modify for new session");
+    let current =
+        create_checkpoint(&repo, "session-2", "task-a", "implementor").expect("new checkpoint");
+    std::fs::write(repo.join("shared.txt"), "broken\n").expect("break workspace");
+
+    let outcome = rollback_task(&repo, "session-2", "task-a").expect("rollback");
+
+    assert_eq!(outcome.checkpoint_ref, current);
+    assert_eq!(
+        std::fs::read_to_string(repo.join("shared.txt")).expect("restored"),
+        "new session\n"
+    );
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn clear_checkpoints_removes_only_the_task_refs() {
+    let repo = init_repo("bob-checkpoint-clear");
+    create_checkpoint(&repo, "session-1", "task-a", "implementor").expect("task a");
+    create_checkpoint(&repo, "session-1", "task-a", "test_writer").expect("task a again");
+    let other = create_checkpoint(&repo, "session-1", "task-b", "implementor").expect("task b");
+
+    assert_eq!(
+        clear_checkpoints(&repo, "session-1", "task-a").expect("clear"),
+        2
+    );
+
+    assert!(
+        list_checkpoints(&repo, "session-1", "task-a")
+            .expect("list a")
+            .is_empty()
+    );
+    assert_eq!(
+        list_checkpoints(&repo, "session-1", "task-b").expect("list b"),
+        vec![other]
+    );
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
@@ -0,0 +1,180 @@
+use super::*;
+
+use std::collections::BTreeSet;
+
+fn lines(text: &str) -> Vec<String> {
+    text.lines().map(ToString::to_string).collect()
+}
+
+fn file_totals(text: &str) -> Vec<FileCoverage> {
+    match parse_coverage_report(&lines(text)) {
+        CoverageReport::FileTotals(files) => files,
+        other => panic!("expected per-file totals, got {other:?}"),
+    }
+}
+
+fn line_report(text: &str) -> Vec<LineCoverage> {
+    match parse_coverage_report(&lines(text)) {
+        CoverageReport::Lines(files) => files,
+        other => panic!("expected per-line data, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_llvm_cov_table_line_counts() {
+    let report = file_totals(
+        "Filename                      Regions    Missed Regions     Cover   Functions  Missed Functions  Executed       Lines      Missed Lines     Cover    Branches   Missed Branches     Cover\n\
+         ----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------\n\
+         src/lib.rs                         10                 2    80.00%           3                 0   100.00%          20                 5    75.00%           0                 0         -\n\
+         TOTAL                              10                 2    80.00%           3                 0   100.00%          20                 5    75.00%           0                 0         -",
+    );
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].path, "src/lib.rs");
+    assert_eq!(report[0].lines, Some((15, 20)));
+    assert_eq!(report[0].percent, 75.0);
+}
+
+#[test]
+fn parses_coverage_py_report_with_missing_lines() {
+    let report = file_totals(
+        "Name             Stmts   Miss  Cover   Missing\n\
+         ----------------------------------------------\n\
+         pkg/core.py         10      4    60%   3-5, 9\n\
+         pkg/util.py          5      0   100%\n\
+         ----------------------------------------------\n\
+         TOTAL               15      4    73%",
+    );
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0].lines, Some((6, 10)));
+    assert_eq!(report[0].missing.as_deref(), Some("3-5, 9"));
+    assert_eq!(report[1].missing, None);
+}
+
+#[test]
+fn parses_istanbul_text_table_with_directory_rows() {
+    let report = file_totals(concat!(
+        "----------|---------|----------|---------|---------|-------------------\n",
+        "File      | % Stmts | % Branch | % Funcs | % Lines | Uncovered Line #s\n",
+        "----------|---------|----------|---------|---------|-------------------\n",
+        "All files |   70.00 |      100 |     100 |   70.00 |\n",
+        " src      |   70.00 |      100 |     100 |   70.00 |\n",
+        "  app.js  |   62.50 |      100 |     100 |   62.50 | 4-6\n",
+        "----------|---------|----------|---------|---------|-------------------",
+    ));
+    let app = report
+        .iter()
+        .find(|file| file.path == "src/app.js")
+        .expect("nested file path");
+    assert_eq!(app.percent, 62.5);
+    assert_eq!(app.lines, None);
+    assert_eq!(app.missing.as_deref(), Some("4-6"));
+}
+
+#[test]
+fn parses_lcov_records_and_sums_hits_across_records() {
+    let report = line_report(
+        "running 3 tests\n\
+         TN:\n\
+         SF:/repo/src/lib.rs\n\
+         DA:1,1\n\
+         DA:2,0\n\
+         LF:2\n\
+         LH:1\n\
+         end_of_record\n\
+         SF:/repo/src/lib.rs\n\
+         DA:2,3\n\
+         end_of_record",
+    );
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].path, "/repo/src/lib.rs");
+    assert_eq!(report[0].hits, BTreeMap::from([(1, 1), (2, 3)]));
+}
+
+#[test]
+fn parses_cobertura_xml_line_hits() {
+    let report = line_report(concat!(
+        "<?xml version=\"1.0\" ?>\n",
+        "<coverage version=\"7.4\" line-rate=\"0.5\">\n",
+        "  <packages><package name=\"pkg\"><classes>\n",
+        "    <class name=\"core.py\"\n",
+        "           filename=\"pkg/core.py\" line-rate=\"0.5\">\n",
+        "      <lines>\n",
+        "        <line number=\"3\" hits=\"1\"/>\n",
+        "        <line number=\"4\" hits=\"0\"/>\n",
+        "      </lines>\n",
+        "    </class>\n",
+        "  </classes></package></packages>\n",
+        "</coverage>",
+    ));
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].path, "pkg/core.py");
+    assert_eq!(report[0].hits, BTreeMap::from([(3, 1), (4, 0)]));
+}
+
+#[test]
+fn output_without_coverage_data_is_unrecognized() {
+    assert_eq!(
+        parse_coverage_report(&lines("test result: ok. 3 passed")),
+        CoverageReport::Unrecognized
+    );
+}
+
+#[test]
+fn changed_line_check_counts_only_changed_non_test_lines() {
+    let report = vec![
+        LineCoverage {
+            path: "/repo/src/a.rs".to_string(),
+            hits: BTreeMap::from([(1, 0), (2, 1), (3, 0), (4, 0), (5, 1), (6, 0)]),
+        },
+        LineCoverage {
+            path: "tests/a_tests.rs".to_string(),
+            hits: BTreeMap::from([(1, 0)]),
+        },
+    ];
+    let changed = ChangedLines::from([
+        // Line 1 and 6 are untouched, and line 7 is not instrumented.
+        ("src/a.rs".to_string(), BTreeSet::from([2, 3, 4, 5, 7])),
+        ("tests/a_tests.rs".to_string(), BTreeSet::from([1])),
+    ]);
+
+    let check = check_changed_line_coverage(&report, &changed).expect("instrumented lines");
+    assert_eq!(check.percent, 50.0);
+    assert_eq!(
+        check.files,
+        vec![ChangedFileCoverage {
+            path: "src/a.rs".to_string(),
+            covered: 2,
+            uncovered: vec![3, 4],
+        }]
+    );
+    let feedback = check.feedback(80.0);
+    assert!(feedback.contains("lines changed in this task is 50.0%, below the required 80.0%"));
+    assert!(feedback.contains("- src/a.rs: 2 of 4 changed lines covered, uncovered: 3-4"));
+
+    let docs_only = ChangedLines::from([("README.md".to_string(), BTreeSet::from([1]))]);
+    assert!(check_changed_line_coverage(&report, &docs_only).is_none());
+}
+
+#[test]
+fn test_paths_follow_common_runner_conventions() {
+    for path in [
+        "tests/integration.rs",
+        "src/__tests__/app.js",
+        "pkg/test_core.py",
+        "pkg/core_test.py",
+        "server/handler_test.go",
+        "tests/unit/coverage_tests.rs",
+        "web/app.test.ts",
+        "web/app.spec.js",
+    ] {
+        assert!(is_test_path(path), "{path}");
+    }
+    for path in [
+        "src/lib.rs",
+        "src/testing.rs",
+        "pkg/contest.py",
+        "web/app.ts",
+    ] {
+        assert!(!is_test_path(path), "{path}");
+    }
+}
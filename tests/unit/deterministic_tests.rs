@@ -196,13 +196,32 @@ fn deterministic_runner_fails_when_command_missing() {
     assert!(saw_completed);
 }
 
+#[test]
+fn deterministic_lint_runner_passes_when_command_missing() {
+    let runner = TestRunnerAdapter::new();
+    runner.run_lint_with_command(Some("   "));
+
+    let events = runner.drain_events();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::System(line) if line.contains("no lint command configured")
+    )));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::Completed {
+            success: true,
+            code: 0
+        }
+    )));
+}
+
 #[test]
 fn drain_events_limited_respects_max_and_preserves_queue() {
     let runner = TestRunnerAdapter::new();
     for idx in 0..4 {
         runner
             .event_tx
-            .send(AgentEvent::Output(format!("line-{idx}")))
+            .send((None, AgentEvent::Output(format!("line-{idx}"))))
             .expect("send should succeed");
     }
 
@@ -214,3 +233,252 @@ fn drain_events_limited_respects_max_and_preserves_queue() {
     assert_eq!(second.len(), 3);
     assert!(second.iter().all(|e| matches!(e, AgentEvent::Output(_))));
 }
+
+#[test]
+fn deterministic_runner_parses_configured_junit_report_before_completing() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let report = std::env::temp_dir().join(format!("bob-junit-report-{now}.xml"));
+    std::fs::write(&report, "<testsuite><testcase name=\"stale\"/></testsuite>")
+        .expect("write stale report");
+    let runner = TestRunnerAdapter::new();
+    let command = format!(
+        "printf '%s' '<testsuite><testcase name=\"ok\"/><testcase classname=\"suite\" name=\"bad\"><failure message=\"boom\"/></testcase></testsuite>' > {}; exit 1",
+        report.display()
+    );
+    runner.run_tests_with_report(Some(&command), &report);
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let mut saw_completed = false;
+    while Instant::now() < deadline && !saw_completed {
+        saw_completed = runner
+            .drain_events()
+            .iter()
+            .any(|event| matches!(event, AgentEvent::Completed { success: false, .. }));
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert!(saw_completed);
+    let summary = runner.take_report().expect("parsed junit report");
+    assert_eq!(summary.counts.label(), "1 passed, 1 failed");
+    assert_eq!(summary.failures[0].name, "suite::bad");
+    assert_eq!(summary.failures[0].message, "boom");
+    assert!(runner.take_report().is_none());
+
+    let _ = std::fs::remove_file(&report);
+}
+
+#[test]
+fn deterministic_runner_reruns_failing_tests_and_classifies_flaky_ones() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("bob-flaky-{now}"));
+    std::fs::create_dir_all(&dir).expect("create fake cargo dir");
+    let runs = dir.join("runs");
+    let args_log = dir.join("args");
+    // Run 1 fails both tests, rerun 1 still fails one and exits non-zero, rerun 2 passes.
+    let script = format!(
+        "#!/bin/sh\n\
+         echo \"$*\" > {args}\n\
+         echo run >> {runs}\n\
+         case $(wc -l < {runs}) in\n\
+         *1) printf 'test a::flaky ... FAILED\\ntest a::solid ... FAILED\\ntest result: FAILED. 3 passed; 2 failed\\n'; exit 101;;\n\
+         *2) printf 'test a::flaky ... ok\\ntest a::solid ... FAILED\\ntest result: FAILED. 1 passed; 1 failed\\n'; exit 101;;\n\
+         *) printf 'test a::flaky ... ok\\ntest a::solid ... ok\\ntest result: ok. 2 passed; 0 failed\\n';;\n\
+         esac\n",
+        args = args_log.display(),
+        runs = runs.display()
+    );
+    let cargo = dir.join("cargo");
+    std::fs::write(&cargo, script).expect("write fake cargo");
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755))
+            .expect("make fake cargo executable");
+    }
+    let mut runner = TestRunnerAdapter::with_config(TestRunnerConfig {
+        program: cargo.display().to_string(),
+        args: vec!["test".to_string()],
+    });
+    runner.set_flaky_test_reruns(2);
+    runner.run_tests();
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let mut completed = None;
+    let mut rerun_notes = 0;
+    let mut kept_after_failed_exit = false;
+    while Instant::now() < deadline && completed.is_none() {
+        for event in runner.drain_events() {
+            match event {
+                AgentEvent::System(line) if line.contains("re-running only the failing tests") => {
+                    rerun_notes += 1;
+                }
+                AgentEvent::System(line) if line.contains("rerun exited with status code 101") => {
+                    kept_after_failed_exit = true;
+                }
+                AgentEvent::Completed { success, code } => completed = Some((success, code)),
+                _ => {}
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert_eq!(completed, Some((true, 0)));
+    assert_eq!(rerun_notes, 2);
+    assert!(kept_after_failed_exit);
+    assert_eq!(
+        std::fs::read_to_string(&args_log).expect("args log").trim(),
+        "test -- --exact a::flaky a::solid"
+    );
+    let summary = runner.take_report().expect("classified report");
+    assert!(summary.failures.is_empty());
+    let flaky = summary
+        .flaky
+        .iter()
+        .map(|failure| failure.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(flaky, vec!["a::flaky", "a::solid"]);
+    assert_eq!(summary.counts.label(), "3 passed, 0 failed, 2 flaky");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn filtered_rerun_config_narrows_supported_frameworks_only() {
+    let shell = TestRunnerConfig {
+        program: "bash".to_string(),
+        args: vec!["-lc".to_string(), "cargo test --workspace".to_string()],
+    };
+    let libtest = filtered_rerun_config(&shell, Some(TestFramework::Libtest), &["a::b"])
+        .expect("libtest filter");
+    assert_eq!(
+        libtest.args[1],
+        "cargo test --workspace '--' '--exact' 'a::b'"
+    );
+
+    let go = TestRunnerConfig {
+        program: "go".to_string(),
+        args: vec!["test".to_string(), "./...".to_string()],
+    };
+    let go = filtered_rerun_config(
+        &go,
+        Some(TestFramework::GoTest),
+        &["TestA/case_1", "TestA/case_2", "TestB"],
+    )
+    .expect("go filter");
+    assert_eq!(go.args[2..], ["-v", "-run", "^(TestA|TestB)$"]);
+
+    assert!(filtered_rerun_config(&shell, Some(TestFramework::JUnit), &["x"]).is_none());
+    assert!(filtered_rerun_config(&shell, None, &["x"]).is_none());
+}
+
+#[test]
+fn filtered_rerun_config_only_narrows_a_single_test_invocation() {
+    let shell = |line: &str| TestRunnerConfig {
+        program: "bash".to_string(),
+        args: vec!["-lc".to_string(), line.to_string()],
+    };
+    let narrowed = |line: &str, framework| {
+        filtered_rerun_config(&shell(line), Some(framework), &["a"]).is_some()
+    };
+
+    assert!(narrowed(
+        "RUST_BACKTRACE=1 cargo test -p core 2>&1",
+        TestFramework::Libtest
+    ));
+    assert!(narrowed("python3 -m pytest tests", TestFramework::Pytest));
+    assert!(!narrowed(
+        "cargo build && cargo test",
+        TestFramework::Libtest
+    ));
+    assert!(!narrowed(
+        "cargo test | tee test.log",
+        TestFramework::Libtest
+    ));
+    assert!(!narrowed(
+        "cargo test; cargo clippy",
+        TestFramework::Libtest
+    ));
+    assert!(!narrowed("make test", TestFramework::Libtest));
+    assert!(!narrowed("pytest tests &", TestFramework::Pytest));
+    assert!(!narrowed("cargo test", TestFramework::Pytest));
+
+    let script = TestRunnerConfig {
+        program: "./run-tests.sh".to_string(),
+        args: Vec::new(),
+    };
+    assert!(filtered_rerun_config(&script, Some(TestFramework::Libtest), &["a"]).is_none());
+}
+
+#[test]
+fn deterministic_runner_replays_fixture_stages_and_records_runs() {
+    let dir = std::env::temp_dir().join(format!(
+        "deterministic-replay-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default()
+    ));
+    let recorder = SessionRecorder::create(&dir).expect("recorder");
+    let mut runner = TestRunnerAdapter::new();
+    runner.set_recorder(Some(recorder.clone()));
+    runner.set_replay_player(Some(Arc::new(ReplayPlayer::new(
+        crate::replay::ReplayFixture {
+            steps: vec![crate::replay::ReplayStep {
+                role: "test_runner".to_string(),
+                task: Some(5),
+                output: vec!["test result: FAILED".to_string()],
+                exit_code: 101,
+                ..Default::default()
+            }],
+        },
+    ))));
+
+    // The fixture has no lint steps, so the lint stage behaves as it would live.
+    runner.run_lint_for_task(5, None, Some(&dir));
+    runner.run_tests_for_task(5, Some("exit 0"), Some(&dir), None);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut events = Vec::new();
+    while Instant::now() < deadline
+        && events
+            .iter()
+            .filter(|(_, event)| matches!(event, AgentEvent::Completed { .. }))
+            .count()
+            < 2
+    {
+        events.extend(runner.drain_task_events_limited(usize::MAX));
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert!(events.contains(&(
+        Some(5),
+        AgentEvent::Completed {
+            success: true,
+            code: 0
+        }
+    )));
+    assert!(events.contains(&(
+        Some(5),
+        AgentEvent::Completed {
+            success: false,
+            code: 101
+        }
+    )));
+    let fixture: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(recorder.fixture_path()).expect("fixture"))
+            .expect("fixture json");
+    let roles = fixture["steps"]
+        .as_array()
+        .expect("steps")
+        .iter()
+        .map(|step| (step["role"].clone(), step["prompt"].clone()))
+        .collect::<Vec<_>>();
+    assert!(roles.contains(&("lint_runner".into(), "".into())));
+    assert!(roles.contains(&("test_runner".into(), "exit 0".into())));
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,273 @@
+use super::*;
+use crate::agent::{AgentEvent, BackendKind, CodexAdapter, CodexCommandConfig};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Serves one canned response per connection and reports each request body.
+fn spawn_stub_server(responses: Vec<String>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("stub server should bind");
+    let base_url = format!(
+        "http://{}/v1",
+        listener.local_addr().expect("stub server address")
+    );
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for response in responses {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0_u8; 4096];
+            let body = loop {
+                let read = stream.read(&mut buf).unwrap_or(0);
+                if read == 0 {
+                    break String::new();
+                }
+                request.extend_from_slice(&buf[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                let Some(split) = text.find("\r\n\r\n") else {
+                    continue;
+                };
+                let length = text[..split]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if request.len() >= split + 4 + length {
+                    break String::from_utf8_lossy(&request[split + 4..split + 4 + length])
+                        .to_string();
+                }
+            };
+            let _ = tx.send(body);
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    (base_url, rx)
+}
+
+fn sse_response(deltas: &[&str]) -> String {
+    let mut body = String::new();
+    for delta in deltas {
+        let chunk = serde_json::json!({ "choices": [{ "delta": { "content": delta } }] });
+        body.push_str(&format!("data: {chunk}\n\n"));
+    }
+    body.push_str("data: [DONE]\n\n");
+    format!("HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n{body}")
+}
+
+fn json_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn spec_for(base_url: &str) -> HttpBackendSpec {
+    HttpBackendSpec {
+        base_url: base_url.to_string(),
+        model: "stub-model".to_string(),
+        connect_timeout_secs: 2,
+        read_timeout_secs: 5,
+        ..HttpBackendSpec::default()
+    }
+}
+
+fn http_adapter(base_url: &str, persistent_session: bool) -> CodexAdapter {
+    let mut config = CodexCommandConfig::default_for_backend(BackendKind::Http);
+    config.program = base_url.to_string();
+    config.http = Some(spec_for(base_url));
+    config.model = None;
+    config.persistent_session = persistent_session;
+    CodexAdapter::with_config(config)
+}
+
+fn collect_until_completed(adapter: &CodexAdapter) -> Vec<AgentEvent> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut events = Vec::new();
+    while Instant::now() < deadline {
+        events.extend(adapter.drain_events());
+        if events
+            .iter()
+            .any(|event| matches!(event, AgentEvent::Completed { .. }))
+        {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    events
+}
+
+#[test]
+fn completions_url_joins_base_url_without_double_slashes() {
+    assert_eq!(
+        completions_url("http://127.0.0.1:8080/v1/"),
+        "http://127.0.0.1:8080/v1/chat/completions"
+    );
+}
+
+#[test]
+fn line_buffer_emits_whole_lines_and_flushes_the_rest() {
+    let mut buffer = LineBuffer::default();
+    assert!(buffer.push("Hel").is_empty());
+    assert_eq!(buffer.push("lo\r\nwor"), vec!["Hello".to_string()]);
+    assert_eq!(
+        buffer.push("ld\n\n"),
+        vec!["world".to_string(), String::new()]
+    );
+    assert_eq!(buffer.push("tail"), Vec::<String>::new());
+    assert_eq!(buffer.finish(), Some("tail".to_string()));
+    assert_eq!(buffer.finish(), None);
+}
+
+#[test]
+fn stream_chat_completion_feeds_deltas_and_returns_full_text() {
+    let (base_url, requests) = spawn_stub_server(vec![sse_response(&["Hel", "lo", " there"])]);
+    let mut deltas = Vec::new();
+    let outcome = stream_chat_completion(
+        &spec_for(&base_url),
+        &[ChatMessage::user("hi".to_string())],
+        |delta| {
+            deltas.push(delta.to_string());
+            true
+        },
+    );
+
+    assert_eq!(
+        outcome,
+        Ok(ChatOutcome::Completed("Hello there".to_string()))
+    );
+    assert_eq!(deltas, vec!["Hel", "lo", " there"]);
+    let body: serde_json::Value =
+        serde_json::from_str(&requests.recv().expect("request body")).expect("json body");
+    assert_eq!(body["model"], "stub-model");
+    assert_eq!(body["stream"], true);
+    assert_eq!(body["messages"][0]["role"], "user");
+    assert_eq!(body["messages"][0]["content"], "hi");
+}
+
+#[test]
+fn stream_chat_completion_accepts_non_streaming_json_replies() {
+    let (base_url, _requests) = spawn_stub_server(vec![json_response(
+        "200 OK",
+        r#"{"choices":[{"message":{"role":"assistant","content":"whole reply"}}]}"#,
+    )]);
+    let outcome = stream_chat_completion(
+        &spec_for(&base_url),
+        &[ChatMessage::user("hi".to_string())],
+        |_| true,
+    );
+    assert_eq!(
+        outcome,
+        Ok(ChatOutcome::Completed("whole reply".to_string()))
+    );
+}
+
+#[test]
+fn stream_chat_completion_reports_status_errors_with_server_message() {
+    let (base_url, _requests) = spawn_stub_server(vec![json_response(
+        "400 Bad Request",
+        r#"{"error":{"message":"context length exceeded"}}"#,
+    )]);
+    let err = stream_chat_completion(
+        &spec_for(&base_url),
+        &[ChatMessage::user("hi".to_string())],
+        |_| true,
+    )
+    .expect_err("status 400 should fail");
+    assert!(err.contains("status 400"));
+    assert!(err.contains("context length exceeded"));
+}
+
+#[test]
+fn stream_chat_completion_stops_when_the_caller_cancels() {
+    let (base_url, _requests) = spawn_stub_server(vec![sse_response(&["one", "two"])]);
+    let outcome = stream_chat_completion(
+        &spec_for(&base_url),
+        &[ChatMessage::user("hi".to_string())],
+        |_| false,
+    );
+    assert_eq!(outcome, Ok(ChatOutcome::Cancelled));
+}
+
+#[test]
+fn http_adapter_streams_lines_and_replays_history_for_persistent_sessions() {
+    let (base_url, requests) = spawn_stub_server(vec![
+        sse_response(&["first ", "line\nsecond", " line"]),
+        sse_response(&["ok"]),
+    ]);
+    let adapter = http_adapter(&base_url, true);
+
+    adapter.send_prompt("question one".to_string());
+    let events = collect_until_completed(&adapter);
+    assert_eq!(
+        events,
+        vec![
+            AgentEvent::Output("first line".to_string()),
+            AgentEvent::Output("second line".to_string()),
+            AgentEvent::Completed {
+                success: true,
+                code: 0
+            },
+        ]
+    );
+    let _ = requests.recv().expect("first request body");
+
+    adapter.send_prompt("question two".to_string());
+    let events = collect_until_completed(&adapter);
+    assert!(events.contains(&AgentEvent::Output("ok".to_string())));
+    let body: serde_json::Value =
+        serde_json::from_str(&requests.recv().expect("second request body")).expect("json");
+    let messages = body["messages"].as_array().expect("messages array");
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0]["content"], "question one");
+    assert_eq!(messages[1]["role"], "assistant");
+    assert_eq!(messages[1]["content"], "first line\nsecond line");
+    assert_eq!(messages[2]["content"], "question two");
+}
+
+#[test]
+fn http_adapter_reset_session_forgets_history() {
+    let (base_url, requests) = spawn_stub_server(vec![sse_response(&["a"]), sse_response(&["b"])]);
+    let adapter = http_adapter(&base_url, true);
+
+    adapter.send_prompt("one".to_string());
+    let _ = collect_until_completed(&adapter);
+    let _ = requests.recv();
+    adapter.reset_session();
+    adapter.send_prompt("two".to_string());
+    let _ = collect_until_completed(&adapter);
+
+    let body: serde_json::Value =
+        serde_json::from_str(&requests.recv().expect("request body")).expect("json");
+    assert_eq!(body["messages"].as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn http_adapter_reports_connection_errors_as_system_events() {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("free port")
+        .port();
+    let adapter = http_adapter(&format!("http://127.0.0.1:{port}/v1"), false);
+
+    adapter.send_prompt("hello".to_string());
+    let events = collect_until_completed(&adapter);
+
+    assert!(events.iter().any(|event| matches!(
+        event,
+        AgentEvent::System(message) if message.contains("request failed")
+    )));
+    assert_eq!(
+        events.last(),
+        Some(&AgentEvent::Completed {
+            success: false,
+            code: -1
+        })
+    );
+}
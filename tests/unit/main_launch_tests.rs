@@ -1,4 +1,5 @@
 use super::*;
+use crate::agent::{PermissionArgs, PermissionProfile};
 
 fn open_temp_store(prefix: &str) -> (SessionStore, std::path::PathBuf) {
     let now = SystemTime::now()
@@ -73,6 +74,7 @@ fn integration_plan_with_final() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -83,6 +85,7 @@ fn integration_plan_with_final() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -93,6 +96,7 @@ fn integration_plan_with_final() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -103,6 +107,7 @@ fn integration_plan_with_final() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-audit".to_string(),
@@ -113,6 +118,7 @@ fn integration_plan_with_final() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -123,6 +129,7 @@ fn integration_plan_with_final() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "final".to_string(),
@@ -133,6 +140,7 @@ fn integration_plan_with_final() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ]
 }
@@ -148,6 +156,7 @@ fn simple_execution_plan() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -158,6 +167,7 @@ fn simple_execution_plan() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -168,6 +178,7 @@ fn simple_execution_plan() -> Vec<PlannerTaskFileEntry> {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ]
 }
@@ -185,6 +196,17 @@ fn parse_launch_options_accepts_send_file() {
     );
 }
 
+#[test]
+fn parse_launch_options_accepts_record_dir() {
+    let options = parse_launch_options(vec!["--record".to_string(), "/tmp/rec".to_string()])
+        .expect("options should parse");
+    assert_eq!(
+        options.record.as_deref(),
+        Some(std::path::Path::new("/tmp/rec"))
+    );
+    assert!(options.command.is_none());
+}
+
 #[test]
 fn parse_launch_options_accepts_verbose_flag() {
     let options =
@@ -283,6 +305,7 @@ fn resumed_right_pane_mode_uses_task_list_when_tasks_exist() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
     assert_eq!(resumed_right_pane_mode(&tasks), RightPaneMode::TaskList);
 }
@@ -355,17 +378,48 @@ fn backend_command_recognition_is_trimmed_and_case_insensitive() {
 
 #[test]
 fn backend_picker_options_prioritize_current_backend() {
-    let codex_first = backend_picker_options(BackendKind::Codex);
+    let codex_first = backend_picker_options(
+        BackendKind::Codex,
+        &[BackendKind::Codex, BackendKind::Claude],
+    );
     assert_eq!(codex_first.len(), 2);
     assert_eq!(codex_first[0].kind, BackendKind::Codex);
     assert_eq!(codex_first[1].kind, BackendKind::Claude);
 
-    let claude_first = backend_picker_options(BackendKind::Claude);
+    let claude_first = backend_picker_options(
+        BackendKind::Claude,
+        &[BackendKind::Codex, BackendKind::Claude],
+    );
     assert_eq!(claude_first.len(), 2);
     assert_eq!(claude_first[0].kind, BackendKind::Claude);
     assert_eq!(claude_first[1].kind, BackendKind::Codex);
 }
 
+#[test]
+fn backend_picker_options_list_configured_optional_backends() {
+    let available = [
+        BackendKind::Codex,
+        BackendKind::Claude,
+        BackendKind::Custom,
+        BackendKind::Http,
+    ];
+    let options = backend_picker_options(BackendKind::Custom, &available);
+    assert_eq!(
+        options.iter().map(|option| option.kind).collect::<Vec<_>>(),
+        vec![
+            BackendKind::Custom,
+            BackendKind::Codex,
+            BackendKind::Claude,
+            BackendKind::Http
+        ]
+    );
+
+    let options = backend_picker_options(BackendKind::Claude, &available[..3]);
+    assert_eq!(options.len(), 3);
+    assert_eq!(options[0].kind, BackendKind::Claude);
+    assert_eq!(options[2].kind, BackendKind::Custom);
+}
+
 #[test]
 fn update_backend_selected_in_toml_preserves_existing_sections() {
     let updated = update_backend_selected_in_toml(
@@ -445,6 +499,18 @@ fn execution_busy_blocks_master_and_task_editing_commands() {
         submit_block_reason(false, false, false, true, "/start"),
         None
     );
+    for command in [
+        "/pause",
+        "/stop",
+        "/resume-execution",
+        "/approve",
+        "/reject top1 needs a migration",
+    ] {
+        assert_eq!(
+            submit_block_reason(false, false, false, true, command),
+            None
+        );
+    }
 }
 
 #[test]
@@ -460,6 +526,14 @@ fn post_completion_tail_drain_collects_late_worker_output() {
         skip_reader_join_after_wait: false,
         model: None,
         model_reasoning_effort: None,
+        working_dir: None,
+        custom: None,
+        http: None,
+        replay: None,
+        recording: None,
+        permissions: PermissionProfile::Full,
+        permission_args: PermissionArgs::default(),
+        guard_workspace: true,
     });
     adapter.send_prompt("ignored".to_string());
 
@@ -703,6 +777,14 @@ fn planner_edit_before_session_initializes_and_notifies_adapters() {
             skip_reader_join_after_wait: false,
             model: None,
             model_reasoning_effort: None,
+            working_dir: None,
+            custom: None,
+            http: None,
+            replay: None,
+            recording: None,
+            permissions: PermissionProfile::Full,
+            permission_args: PermissionArgs::default(),
+            guard_workspace: true,
         });
         let project_info_adapter = CodexAdapter::with_config(CodexCommandConfig {
             program: "bash".to_string(),
@@ -716,6 +798,14 @@ fn planner_edit_before_session_initializes_and_notifies_adapters() {
             skip_reader_join_after_wait: false,
             model: None,
             model_reasoning_effort: None,
+            working_dir: None,
+            custom: None,
+            http: None,
+            replay: None,
+            recording: None,
+            permissions: PermissionProfile::Full,
+            permission_args: PermissionArgs::default(),
+            guard_workspace: true,
         });
 
         let mut app = App::default();
@@ -760,6 +850,14 @@ fn planner_edit_before_session_saves_via_debounce_after_initialization() {
             skip_reader_join_after_wait: false,
             model: None,
             model_reasoning_effort: None,
+            working_dir: None,
+            custom: None,
+            http: None,
+            replay: None,
+            recording: None,
+            permissions: PermissionProfile::Full,
+            permission_args: PermissionArgs::default(),
+            guard_workspace: true,
         });
         let project_info_adapter = CodexAdapter::with_config(CodexCommandConfig {
             program: "bash".to_string(),
@@ -773,6 +871,14 @@ fn planner_edit_before_session_saves_via_debounce_after_initialization() {
             skip_reader_join_after_wait: false,
             model: None,
             model_reasoning_effort: None,
+            working_dir: None,
+            custom: None,
+            http: None,
+            replay: None,
+            recording: None,
+            permissions: PermissionProfile::Full,
+            permission_args: PermissionArgs::default(),
+            guard_workspace: true,
         });
 
         let mut app = App::default();
@@ -884,6 +990,7 @@ fn prepare_resumed_session_rejects_invalid_task_hierarchy() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -894,6 +1001,7 @@ fn prepare_resumed_session_rejects_invalid_task_hierarchy() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ];
     std::fs::write(
@@ -931,6 +1039,7 @@ fn resume_session_does_not_switch_when_target_tasks_are_invalid() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
     std::fs::write(
         current_store.tasks_file(),
@@ -952,7 +1061,7 @@ fn resume_session_does_not_switch_when_target_tasks_are_invalid() {
     let master_report_adapter = CodexAdapter::new();
     let project_info_adapter = CodexAdapter::new();
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let mut pending_task_write_baseline = None;
     let mut docs_attach_in_flight = false;
     let mut master_session_intro_needed = false;
@@ -985,7 +1094,7 @@ fn resume_session_does_not_switch_when_target_tasks_are_invalid() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &mut pending_task_write_baseline,
         &mut docs_attach_in_flight,
         &mut master_session_intro_needed,
@@ -1037,6 +1146,7 @@ fn resume_session_resets_master_report_and_task_check_runtime_state_on_success()
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
     std::fs::write(
         target_store.tasks_file(),
@@ -1051,7 +1161,7 @@ fn resume_session_resets_master_report_and_task_check_runtime_state_on_success()
     let master_report_adapter = CodexAdapter::new();
     let project_info_adapter = CodexAdapter::new();
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let mut pending_task_write_baseline = Some(PendingTaskWriteBaseline {
         tasks_json: "[]".to_string(),
     });
@@ -1086,7 +1196,7 @@ fn resume_session_resets_master_report_and_task_check_runtime_state_on_success()
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &mut pending_task_write_baseline,
         &mut docs_attach_in_flight,
         &mut master_session_intro_needed,
@@ -1127,7 +1237,7 @@ fn newmaster_resets_master_report_and_task_check_runtime_state() {
     let model_routing = CodexAgentModelRouting::default();
 
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let mut session_store: Option<SessionStore> = None;
     let cwd = std::env::current_dir().expect("cwd");
     let mut pending_task_write_baseline = Some(PendingTaskWriteBaseline {
@@ -1160,7 +1270,7 @@ fn newmaster_resets_master_report_and_task_check_runtime_state() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -1205,6 +1315,7 @@ fn cli_task_contract_round_trip_preserves_all_fields() {
         status: PlannerTaskStatusFile::NeedsChanges,
         parent_id: Some("top".to_string()),
         order: Some(2),
+        ..Default::default()
     };
 
     let contract = file_task_to_contract_task(file_task.clone());
@@ -1258,6 +1369,12 @@ fn execute_core_workflow_validation_is_transport_agnostic() {
         status: api::PlannerTaskStatusContract::Pending,
         parent_id: None,
         order: Some(0),
+        depends_on: Vec::new(),
+        max_retries: None,
+        command: None,
+        working_dir: None,
+        review_quorum: None,
+        role: None,
     }];
 
     let request_with_cli_transport = api::RequestEnvelope {
@@ -1301,6 +1418,227 @@ fn execute_core_workflow_validation_is_transport_agnostic() {
     assert_eq!(data_a, data_b);
 }
 
+#[test]
+fn execute_core_workflow_retry_and_skip_rewrite_the_tasks_file() {
+    let (store, session_dir) = open_temp_store("metaagent-workflow-task-control");
+    let entry = |id: &str, kind, parent_id: Option<&str>| PlannerTaskFileEntry {
+        status: PlannerTaskStatusFile::Done,
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, 0)
+    };
+    let tasks = vec![
+        entry("top", PlannerTaskKindFile::Task, None),
+        entry("impl", PlannerTaskKindFile::Implementor, Some("top")),
+        entry("audit", PlannerTaskKindFile::Auditor, Some("impl")),
+    ];
+    std::fs::write(
+        store.tasks_file(),
+        serde_json::to_string_pretty(&tasks).expect("serialize tasks"),
+    )
+    .expect("write tasks");
+    let tasks_file = store.tasks_file().display().to_string();
+    let request = |payload| api::RequestEnvelope {
+        request_id: Some("task-control".to_string()),
+        capability: api::CapabilityId::WorkflowTaskControl,
+        metadata: api::RequestMetadata {
+            transport: Some("cli".to_string()),
+            actor: None,
+        },
+        payload: api::ApiRequestContract::Workflow(payload),
+    };
+    let status_of = |id: &str| {
+        store
+            .read_tasks()
+            .expect("read tasks")
+            .into_iter()
+            .find(|task| task.id == id)
+            .map(|task| task.status)
+    };
+
+    execute_core_api_contract(request(api::WorkflowRequest::RetryTask {
+        tasks_file: tasks_file.clone(),
+        task_id: "impl".to_string(),
+        guidance: Some("Handle empty input".to_string()),
+    }))
+    .expect("retry should succeed");
+    assert_eq!(status_of("top"), Some(PlannerTaskStatusFile::Pending));
+    assert_eq!(status_of("audit"), Some(PlannerTaskStatusFile::Pending));
+
+    execute_core_api_contract(request(api::WorkflowRequest::SkipTask {
+        tasks_file: tasks_file.clone(),
+        task_id: "impl".to_string(),
+    }))
+    .expect("skip should succeed");
+    assert_eq!(status_of("impl"), Some(PlannerTaskStatusFile::Skipped));
+    assert_eq!(status_of("top"), Some(PlannerTaskStatusFile::Done));
+
+    let err = execute_core_api_contract(request(api::WorkflowRequest::SkipTask {
+        tasks_file,
+        task_id: "missing".to_string(),
+    }))
+    .expect_err("unknown task should fail");
+    assert_eq!(err.code, api::ApiErrorCode::NotFound);
+
+    drop(store);
+    std::fs::remove_dir_all(session_dir).ok();
+}
+
+#[test]
+fn execute_core_workflow_dry_run_writes_prompt_files_in_dispatch_order() {
+    let (store, session_dir) = open_temp_store("metaagent-workflow-dry-run");
+    let entry = |id: &str, kind, parent_id: Option<&str>| {
+        PlannerTaskFileEntry::test_entry(id, kind, parent_id, 0)
+    };
+    let tasks = vec![
+        entry("top", PlannerTaskKindFile::Task, None),
+        entry("impl", PlannerTaskKindFile::Implementor, Some("top")),
+        entry("audit", PlannerTaskKindFile::Auditor, Some("impl")),
+    ];
+    std::fs::write(
+        store.tasks_file(),
+        serde_json::to_string_pretty(&tasks).expect("serialize tasks"),
+    )
+    .expect("write tasks");
+    let output_dir = session_dir.join("dry-run");
+    std::fs::create_dir_all(&output_dir).expect("create output dir");
+    std::fs::write(output_dir.join("notes.md"), "keep me").expect("write notes");
+    std::fs::write(output_dir.join("007-auditor-stale.md"), "old").expect("write stale prompt");
+
+    let response = execute_core_api_contract(api::RequestEnvelope {
+        request_id: Some("dry-run".to_string()),
+        capability: api::CapabilityId::WorkflowDryRun,
+        metadata: api::RequestMetadata {
+            transport: Some("cli".to_string()),
+            actor: None,
+        },
+        payload: api::ApiRequestContract::Workflow(api::WorkflowRequest::DryRun {
+            tasks_file: store.tasks_file().display().to_string(),
+            output_dir: Some(output_dir.display().to_string()),
+        }),
+    })
+    .expect("dry run should succeed");
+    let prompts = match response.result {
+        api::ApiResultEnvelope::Ok {
+            data: api::ApiResponseContract::Workflow(api::WorkflowResponse::DryRun { prompts, .. }),
+        } => prompts,
+        other => panic!("unexpected dry-run response: {other:?}"),
+    };
+    let steps = prompts
+        .iter()
+        .map(|prompt| {
+            (
+                prompt.sequence,
+                prompt.role.clone(),
+                prompt.task_id.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        steps,
+        vec![
+            (1, api::WorkerRoleContract::Implementor, "impl"),
+            (2, api::WorkerRoleContract::Auditor, "audit"),
+        ]
+    );
+    assert!(prompts.iter().all(|prompt| !prompt.model.is_empty()));
+    let written = std::fs::read_to_string(output_dir.join("001-implementor-impl.md"))
+        .expect("implementor prompt file");
+    assert!(written.contains("impl details"));
+    assert!(output_dir.join("002-auditor-audit.md").exists());
+    assert!(!output_dir.join("007-auditor-stale.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(output_dir.join("notes.md")).expect("notes survive"),
+        "keep me"
+    );
+    assert_eq!(
+        store.read_tasks().expect("read tasks")[1].status,
+        PlannerTaskStatusFile::Pending
+    );
+
+    drop(store);
+    std::fs::remove_dir_all(session_dir).ok();
+}
+
+#[test]
+fn execute_core_prompt_templates_render_workspace_overrides() {
+    let (_store, workspace) = open_temp_store("metaagent-prompt-templates");
+    let prompts_dir = workspace.join(".agentbob").join("prompts");
+    std::fs::create_dir_all(&prompts_dir).expect("create prompts dir");
+    std::fs::write(
+        prompts_dir.join("implementor.md"),
+        "House rules: log with tracing.\nTask: {top_task_title}\n{subtask_details}\n",
+    )
+    .expect("write template");
+    let request = |payload| api::RequestEnvelope {
+        request_id: None,
+        capability: api::CapabilityId::SubagentPromptTemplates,
+        metadata: api::RequestMetadata {
+            transport: Some("cli".to_string()),
+            actor: None,
+        },
+        payload: api::ApiRequestContract::Subagent(payload),
+    };
+    let cwd = workspace.display().to_string();
+
+    let listed = execute_core_api_contract(request(api::SubagentRequest::ListPromptTemplates {
+        cwd: cwd.clone(),
+    }))
+    .expect("list should succeed");
+    let templates = match listed.result {
+        api::ApiResultEnvelope::Ok {
+            data:
+                api::ApiResponseContract::Subagent(api::SubagentResponse::PromptTemplates {
+                    templates,
+                    ..
+                }),
+        } => templates,
+        other => panic!("unexpected list response: {other:?}"),
+    };
+    assert_eq!(templates.len(), PromptTemplate::ALL.len());
+    let overridden = templates
+        .iter()
+        .filter(|template| template.source.is_some())
+        .map(|template| template.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(overridden, vec!["implementor"]);
+
+    let rendered = execute_core_api_contract(request(api::SubagentRequest::RenderPromptTemplate {
+        cwd: cwd.clone(),
+        name: "implementor".to_string(),
+        values: parse_prompt_placeholder_values(&["top_task_title=Login page".to_string()])
+            .expect("values should parse"),
+    }))
+    .expect("render should succeed");
+    match rendered.result {
+        api::ApiResultEnvelope::Ok {
+            data:
+                api::ApiResponseContract::Subagent(api::SubagentResponse::RenderedPromptTemplate {
+                    text,
+                    unfilled,
+                    ..
+                }),
+        } => {
+            assert_eq!(
+                text,
+                "House rules: log with tracing.\nTask: Login page\n{subtask_details}"
+            );
+            assert_eq!(unfilled, vec!["subtask_details".to_string()]);
+        }
+        other => panic!("unexpected render response: {other:?}"),
+    }
+
+    let err = execute_core_api_contract(request(api::SubagentRequest::RenderPromptTemplate {
+        cwd,
+        name: "implementor".to_string(),
+        values: parse_prompt_placeholder_values(&["tasks_file=x".to_string()])
+            .expect("values should parse"),
+    }))
+    .expect_err("unknown placeholder should fail");
+    assert_eq!(err.code, api::ApiErrorCode::InvalidRequest);
+    assert!(parse_prompt_placeholder_values(&["no-separator".to_string()]).is_err());
+
+    std::fs::remove_dir_all(workspace).ok();
+}
+
 #[test]
 fn execute_core_unsupported_domain_is_transport_agnostic() {
     let request_cli = api::RequestEnvelope {
@@ -1407,6 +1745,8 @@ fn execute_core_session_task_fails_append_then_read_round_trips() {
                 attempts: 2,
                 reason: "missing test path".to_string(),
                 action_taken: "requeued".to_string(),
+                retry_budget: None,
+                checkpoint_refs: Vec::new(),
             }],
         }),
     };
@@ -1489,6 +1829,202 @@ fn execute_core_session_read_session_meta_maps_file_fields() {
     std::fs::remove_dir_all(session_dir).ok();
 }
 
+#[test]
+fn execute_core_session_control_execution_persists_requested_state() {
+    let (store, session_dir) = open_temp_store("metaagent-session-control-execution");
+    let cwd = std::env::current_dir().expect("cwd");
+    let actor = format!(
+        "{{\"cwd\":\"{}\",\"session_dir\":\"{}\"}}",
+        cwd.display(),
+        session_dir.display()
+    );
+    let control_request = |control| api::RequestEnvelope {
+        request_id: Some("control-execution".to_string()),
+        capability: api::CapabilityId::SessionExecutionControl,
+        metadata: api::RequestMetadata {
+            transport: Some("cli".to_string()),
+            actor: Some(actor.clone()),
+        },
+        payload: api::ApiRequestContract::Session(api::SessionRequest::ControlExecution {
+            control,
+        }),
+    };
+
+    let response = execute_core_api_contract(control_request(
+        api::ExecutionControlActionContract::Stop,
+    ))
+    .expect("stop request should succeed");
+    match response.result {
+        api::ApiResultEnvelope::Ok {
+            data:
+                api::ApiResponseContract::Session(api::SessionResponse::ExecutionControl {
+                    paused,
+                    stop_requested,
+                }),
+        } => {
+            assert!(paused);
+            assert!(stop_requested);
+        }
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert_eq!(
+        store.read_execution_control(),
+        ExecutionControl {
+            paused: true,
+            stop_requested: true,
+        }
+    );
+
+    execute_core_api_contract(control_request(api::ExecutionControlActionContract::Resume))
+        .expect("resume request should succeed");
+    assert_eq!(store.read_execution_control(), ExecutionControl::default());
+
+    drop(store);
+    std::fs::remove_dir_all(session_dir).ok();
+}
+
+#[test]
+fn execute_core_session_submit_review_records_decision_and_requires_reject_feedback() {
+    let (store, session_dir) = open_temp_store("metaagent-session-submit-review");
+    let cwd = std::env::current_dir().expect("cwd");
+    let actor = format!(
+        "{{\"cwd\":\"{}\",\"session_dir\":\"{}\"}}",
+        cwd.display(),
+        session_dir.display()
+    );
+    let review_request = |decision, feedback: Option<&str>| api::RequestEnvelope {
+        request_id: Some("submit-review".to_string()),
+        capability: api::CapabilityId::SessionReviewDecision,
+        metadata: api::RequestMetadata {
+            transport: Some("cli".to_string()),
+            actor: Some(actor.clone()),
+        },
+        payload: api::ApiRequestContract::Session(api::SessionRequest::SubmitReview {
+            decision,
+            task_id: Some(" top1 ".to_string()),
+            feedback: feedback.map(ToString::to_string),
+        }),
+    };
+
+    let err = execute_core_api_contract(review_request(
+        api::ReviewDecisionContract::Reject,
+        Some("   "),
+    ))
+    .expect_err("reject without feedback should fail");
+    assert_eq!(err.code, api::ApiErrorCode::InvalidRequest);
+    assert_eq!(store.take_review_decision().expect("take"), None);
+
+    execute_core_api_contract(review_request(
+        api::ReviewDecisionContract::Reject,
+        Some(" Keep the old endpoint "),
+    ))
+    .expect("reject request should succeed");
+    assert_eq!(
+        store.take_review_decision().expect("take"),
+        Some(ReviewDecision::Reject {
+            task_id: Some("top1".to_string()),
+            feedback: "Keep the old endpoint".to_string(),
+        })
+    );
+
+    execute_core_api_contract(review_request(api::ReviewDecisionContract::Approve, None))
+        .expect("approve request should succeed");
+    assert_eq!(
+        store.take_review_decision().expect("take"),
+        Some(ReviewDecision::Approve {
+            task_id: Some("top1".to_string()),
+        })
+    );
+
+    drop(store);
+    std::fs::remove_dir_all(session_dir).ok();
+}
+
+#[test]
+fn external_execution_control_only_acts_on_state_changes() {
+    let mut app = App::default();
+    assert_eq!(
+        external_execution_control_command(&app, ExecutionControl::default()),
+        None
+    );
+    assert_eq!(
+        external_execution_control_command(
+            &app,
+            ExecutionControl {
+                paused: true,
+                stop_requested: false,
+            }
+        ),
+        Some(ExecutionControlCommand::Pause)
+    );
+    assert_eq!(
+        external_execution_control_command(
+            &app,
+            ExecutionControl {
+                paused: true,
+                stop_requested: true,
+            }
+        ),
+        Some(ExecutionControlCommand::Stop)
+    );
+
+    app.set_execution_paused(true);
+    assert_eq!(
+        external_execution_control_command(
+            &app,
+            ExecutionControl {
+                paused: true,
+                stop_requested: false,
+            }
+        ),
+        None
+    );
+    assert_eq!(
+        external_execution_control_command(&app, ExecutionControl::default()),
+        Some(ExecutionControlCommand::Resume)
+    );
+}
+
+#[test]
+fn session_metadata_is_only_reread_after_it_changes() {
+    let (store, session_dir) = open_temp_store("metaagent-metadata-poll");
+    let mut seen = None;
+
+    assert!(session_metadata_changed(&store, &mut seen));
+    assert!(!session_metadata_changed(&store, &mut seen));
+
+    std::thread::sleep(Duration::from_millis(20));
+    store
+        .write_execution_control(ExecutionControl {
+            paused: true,
+            stop_requested: false,
+        })
+        .expect("write control");
+    assert!(session_metadata_changed(&store, &mut seen));
+    assert!(!session_metadata_changed(&store, &mut seen));
+
+    // A rewrite of the same length within the mtime granularity must still be noticed.
+    let metadata_path = session_dir.join("metadata.json");
+    let modified = std::fs::metadata(&metadata_path)
+        .and_then(|metadata| metadata.modified())
+        .expect("metadata mtime");
+    store
+        .write_execution_control(ExecutionControl {
+            paused: false,
+            stop_requested: true,
+        })
+        .expect("write control");
+    std::fs::File::options()
+        .write(true)
+        .open(&metadata_path)
+        .and_then(|file| file.set_modified(modified))
+        .expect("restore mtime");
+    assert!(session_metadata_changed(&store, &mut seen));
+    assert!(!session_metadata_changed(&store, &mut seen));
+
+    let _ = std::fs::remove_dir_all(session_dir);
+}
+
 #[test]
 fn convert_submit_uses_prompt_service_and_captures_tasks_baseline() {
     let mut app = App::default();
@@ -1500,7 +2036,7 @@ fn convert_submit_uses_prompt_service_and_captures_tasks_baseline() {
     let model_routing = CodexAgentModelRouting::default();
 
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let cwd = std::env::current_dir().expect("cwd");
     let (store, session_dir) = open_temp_store("metaagent-convert-submit-service");
     let mut session_store = Some(store);
@@ -1532,7 +2068,7 @@ fn convert_submit_uses_prompt_service_and_captures_tasks_baseline() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -1579,7 +2115,7 @@ fn convert_submit_persists_latest_planner_markdown_before_conversion() {
     let model_routing = CodexAgentModelRouting::default();
 
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let cwd = std::env::current_dir().expect("cwd");
     let (store, session_dir) = open_temp_store("metaagent-convert-persist-planner");
     store
@@ -1620,7 +2156,7 @@ fn convert_submit_persists_latest_planner_markdown_before_conversion() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -1667,7 +2203,7 @@ fn normal_submit_with_project_context_uses_prompt_service_path() {
     let model_routing = CodexAgentModelRouting::default();
 
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let cwd = std::env::current_dir().expect("cwd");
     let (store, session_dir) = open_temp_store("metaagent-message-submit-service");
     let mut session_store = Some(store);
@@ -1699,7 +2235,7 @@ fn normal_submit_with_project_context_uses_prompt_service_path() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -1749,7 +2285,7 @@ fn start_submit_claims_first_job_and_persists_snapshot_via_service() {
     let model_routing = CodexAgentModelRouting::default();
 
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let cwd = std::env::current_dir().expect("cwd");
     let (store, session_dir) = open_temp_store("metaagent-start-submit-service");
     let mut session_store = Some(store);
@@ -1778,7 +2314,7 @@ fn start_submit_claims_first_job_and_persists_snapshot_via_service() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -1831,6 +2367,7 @@ fn persist_runtime_tasks_snapshot_writes_updated_subtask_statuses() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -1841,6 +2378,7 @@ fn persist_runtime_tasks_snapshot_writes_updated_subtask_statuses() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -1851,6 +2389,7 @@ fn persist_runtime_tasks_snapshot_writes_updated_subtask_statuses() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -1861,6 +2400,7 @@ fn persist_runtime_tasks_snapshot_writes_updated_subtask_statuses() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -1871,6 +2411,7 @@ fn persist_runtime_tasks_snapshot_writes_updated_subtask_statuses() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -1919,6 +2460,7 @@ fn start_execution_claim_persists_first_job_status_immediately() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -1929,6 +2471,7 @@ fn start_execution_claim_persists_first_job_status_immediately() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -1939,6 +2482,7 @@ fn start_execution_claim_persists_first_job_status_immediately() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -1983,7 +2527,9 @@ fn integration_happy_path_persists_and_reloads_cleanly() {
                 }
                 let _ = app.on_worker_completed(true, 0);
             }
-            JobRun::DeterministicTestRun => {
+            JobRun::DeterministicTestRun
+            | JobRun::DeterministicLintRun
+            | JobRun::DeterministicCoverageRun => {
                 app.on_worker_output("all passed".to_string());
                 let _ = app.on_worker_completed(true, 0);
             }
@@ -2054,7 +2600,9 @@ fn integration_retry_paths_recover_without_exhausted_failures() {
                 }
                 let _ = app.on_worker_completed(true, 0);
             }
-            JobRun::DeterministicTestRun => {
+            JobRun::DeterministicTestRun
+            | JobRun::DeterministicLintRun
+            | JobRun::DeterministicCoverageRun => {
                 if runner_failures_left > 0 {
                     runner_failures_left = runner_failures_left.saturating_sub(1);
                     app.on_worker_output("tests failing".to_string());
@@ -2084,6 +2632,274 @@ fn integration_retry_paths_recover_without_exhausted_failures() {
     let _ = std::fs::remove_dir_all(&session_dir);
 }
 
+/// Runs a plan to completion against a `[backend.replay]` fixture, driving real adapters and the
+/// deterministic runner the way the worker loop in `run_app` does. Without a `plan`, the
+/// fixture's master step writes tasks.json first. Returns the failure report prompts the run
+/// produced.
+fn run_replay_workflow(
+    plan: Option<Vec<PlannerTaskFileEntry>>,
+    fixture: serde_json::Value,
+    store: &SessionStore,
+    fixture_dir: &std::path::Path,
+) -> (App, Vec<String>) {
+    std::fs::create_dir_all(fixture_dir).expect("create fixture dir");
+    let fixture_path = fixture_dir.join("replay.json");
+    std::fs::write(&fixture_path, fixture.to_string()).expect("write fixture");
+    let model_routing = CodexAgentModelRouting::from_toml_str(&format!(
+        "[backend]\nselected = \"replay\"\n\n[backend.replay]\nfixture = {:?}\n",
+        fixture_path.display().to_string()
+    ))
+    .expect("replay routing should load");
+    assert_eq!(model_routing.selected_backend(), BackendKind::Replay);
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    let plan = plan.unwrap_or_else(|| {
+        let master = build_json_persistent_adapter(
+            &model_routing,
+            BackendKind::Replay,
+            CodexAgentKind::Master,
+        )
+        .expect("master adapter");
+        master.send_prompt("Plan the feature.".to_string());
+        while !master
+            .drain_events()
+            .iter()
+            .any(|event| matches!(event, AgentEvent::Completed { success: true, .. }))
+        {
+            assert!(Instant::now() < deadline, "master step should finish");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        store.read_tasks().expect("master should write tasks.json")
+    });
+    let mut app = App::default();
+    app.sync_planner_tasks_from_file(plan)
+        .expect("sync should succeed");
+    let orchestration_service = DefaultCoreOrchestrationService;
+    let mut test_runner_adapter = TestRunnerAdapter::new();
+    test_runner_adapter.set_replay_player(model_routing.replay_player());
+    let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
+    let mut active_worker_context_keys = HashMap::new();
+    let mut master_report_session_intro_needed = false;
+    let mut failure_reports = Vec::new();
+
+    app.start_execution();
+    orchestration_service
+        .start_ready_worker_jobs(
+            &mut app,
+            &mut worker_agent_adapters,
+            &mut active_worker_context_keys,
+            &test_runner_adapter,
+            store,
+            &model_routing,
+        )
+        .expect("first jobs should start");
+    while app.is_execution_busy() && Instant::now() < deadline {
+        let mut events = Vec::new();
+        for (top_task_id, key) in &active_worker_context_keys {
+            if let Some(adapter) = worker_agent_adapters.get(key) {
+                events.extend(
+                    adapter
+                        .drain_events()
+                        .into_iter()
+                        .map(|event| (Some(*top_task_id), event)),
+                );
+            }
+        }
+        events.extend(test_runner_adapter.drain_task_events_limited(usize::MAX));
+        let mut completed = Vec::new();
+        for (top_task_id, event) in events {
+            let Some(top_task_id) = top_task_id else {
+                continue;
+            };
+            match event {
+                AgentEvent::Output(line) => app.on_worker_output_for_task(top_task_id, line),
+                AgentEvent::System(line) => app.on_worker_system_output(line),
+                AgentEvent::Completed { success, code } => {
+                    completed.push((top_task_id, success, code));
+                }
+            }
+        }
+        for (top_task_id, success, code) in completed {
+            active_worker_context_keys.remove(&top_task_id);
+            let outcome = orchestration_service.complete_worker_cycle_and_start_next(
+                &mut app,
+                top_task_id,
+                success,
+                code,
+                &mut worker_agent_adapters,
+                &mut active_worker_context_keys,
+                &test_runner_adapter,
+                store,
+                &model_routing,
+                &mut master_report_session_intro_needed,
+                None,
+            );
+            failure_reports.extend(outcome.failure_report_prompt);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(
+        !app.is_execution_busy(),
+        "replay workflow should finish before the deadline"
+    );
+    (app, failure_reports)
+}
+
+fn replay_plan_without_tests(max_retries: Option<u8>) -> Vec<PlannerTaskFileEntry> {
+    integration_plan_with_final()
+        .into_iter()
+        .filter(|entry| !entry.id.starts_with("tw"))
+        .map(|mut entry| {
+            if entry.id == "top" {
+                entry.max_retries = max_retries;
+            }
+            entry
+        })
+        .collect()
+}
+
+fn persisted_status(store: &SessionStore, id: &str) -> Option<PlannerTaskStatusFile> {
+    store
+        .read_tasks()
+        .expect("read persisted tasks")
+        .iter()
+        .find(|entry| entry.id == id)
+        .map(|entry| entry.status)
+}
+
+#[test]
+fn integration_replay_backend_runs_audit_retry_and_final_audit_offline() {
+    let (store, session_dir) = open_temp_store("metaagent-integration-replay");
+    let marker = session_dir.join("workspace").join("feature.txt");
+    let fixture = serde_json::json!({
+        "steps": [
+            {
+                "role": "worker_implementor",
+                "output": ["implemented"],
+                "edits": [{ "path": marker.display().to_string(), "content": "done" }]
+            },
+            { "role": "worker_auditor", "pass": 1, "output": ["FAIL", "- Missing edge case"] },
+            { "role": "worker_auditor", "output": ["PASS"] },
+            { "role": "worker_final_audit", "output": ["PASS"] }
+        ]
+    });
+
+    let (mut app, failure_reports) = run_replay_workflow(
+        Some(replay_plan_without_tests(None)),
+        fixture,
+        &store,
+        &session_dir.join("fixture"),
+    );
+
+    assert_eq!(
+        persisted_status(&store, "top"),
+        Some(PlannerTaskStatusFile::Done)
+    );
+    assert_eq!(
+        persisted_status(&store, "final"),
+        Some(PlannerTaskStatusFile::Done)
+    );
+    assert!(failure_reports.is_empty());
+    assert!(app.drain_worker_failures().is_empty());
+    assert_eq!(
+        std::fs::read_to_string(&marker).expect("implementor edit should land"),
+        "done"
+    );
+
+    let _ = std::fs::remove_dir_all(&session_dir);
+}
+
+#[test]
+fn integration_replay_backend_reproduces_audit_retry_exhaustion() {
+    let (store, session_dir) = open_temp_store("metaagent-integration-replay-exhausted");
+    let fixture = serde_json::json!({
+        "steps": [
+            { "role": "worker_implementor", "output": ["implemented"] },
+            { "role": "worker_auditor", "output": ["FAIL", "- Still broken"] },
+            { "role": "worker_final_audit", "output": ["PASS"] }
+        ]
+    });
+
+    let (_app, failure_reports) = run_replay_workflow(
+        Some(replay_plan_without_tests(Some(2))),
+        fixture,
+        &store,
+        &session_dir.join("fixture"),
+    );
+
+    assert_eq!(failure_reports.len(), 1);
+    let failures = store.read_task_fails().expect("read task fails");
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].kind, "audit");
+    assert_eq!(failures[0].attempts, 2);
+
+    let _ = std::fs::remove_dir_all(&session_dir);
+}
+
+#[test]
+fn integration_replay_backend_plans_writes_tests_and_runs_them_offline() {
+    let (store, session_dir) = open_temp_store("metaagent-integration-replay-plan");
+    let workspace = session_dir.join("workspace");
+    let feature = workspace.join("src/answer.rs");
+    let test_file = workspace.join("tests/answer_tests.rs");
+    let plan = serde_json::to_string_pretty(&integration_plan_with_final()).expect("plan json");
+    let fixture = serde_json::json!({
+        "steps": [
+            {
+                "role": "master",
+                "output": ["Plan written to tasks.json."],
+                "edits": [{ "path": store.tasks_file().display().to_string(), "content": plan }]
+            },
+            {
+                "role": "worker_implementor",
+                "output": ["implemented"],
+                "edits": [{ "path": feature.display().to_string(), "content": "pub fn answer() -> u32 { 42 }\n" }]
+            },
+            { "role": "worker_auditor", "output": ["PASS"] },
+            {
+                "role": "worker_test_writer",
+                "pass": 1,
+                "output": ["wrote a test"],
+                "edits": [{ "path": test_file.display().to_string(), "content": "assert_eq!(answer(), 41);\n" }]
+            },
+            {
+                "role": "worker_test_writer",
+                "output": ["fixed the expected value"],
+                "edits": [{ "path": test_file.display().to_string(), "content": "assert_eq!(answer(), 42);\n" }]
+            },
+            {
+                "role": "test_runner",
+                "pass": 1,
+                "output": ["test answer_is_42 ... FAILED", "test result: FAILED. 0 passed; 1 failed"],
+                "exit_code": 101
+            },
+            { "role": "test_runner", "output": ["test result: ok. 1 passed; 0 failed"] },
+            { "role": "worker_final_audit", "output": ["PASS"] }
+        ]
+    });
+
+    let (mut app, failure_reports) =
+        run_replay_workflow(None, fixture, &store, &session_dir.join("fixture"));
+
+    for id in ["top", "impl", "tw", "tw-runner", "final"] {
+        assert_eq!(
+            persisted_status(&store, id),
+            Some(PlannerTaskStatusFile::Done),
+            "{id} should finish"
+        );
+    }
+    assert!(failure_reports.is_empty());
+    assert!(app.drain_worker_failures().is_empty());
+    assert!(feature.exists(), "implementor edit should land");
+    assert_eq!(
+        std::fs::read_to_string(&test_file).expect("test writer edit should land"),
+        "assert_eq!(answer(), 42);\n",
+        "the failing fixture run should send the test writer back once"
+    );
+
+    let _ = std::fs::remove_dir_all(&session_dir);
+}
+
 #[test]
 fn integration_snapshot_does_not_write_illegal_children_for_done_or_final_roots() {
     let mut app = App::default();
@@ -2097,6 +2913,7 @@ fn integration_snapshot_does_not_write_illegal_children_for_done_or_final_roots(
             status: PlannerTaskStatusFile::Done,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "final".to_string(),
@@ -2107,6 +2924,7 @@ fn integration_snapshot_does_not_write_illegal_children_for_done_or_final_roots(
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2153,6 +2971,7 @@ fn integration_persisted_legacy_in_progress_impl_resumes_at_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -2163,6 +2982,7 @@ fn integration_persisted_legacy_in_progress_impl_resumes_at_auditor() {
             status: PlannerTaskStatusFile::InProgress,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -2173,6 +2993,7 @@ fn integration_persisted_legacy_in_progress_impl_resumes_at_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2193,7 +3014,11 @@ fn integration_persisted_legacy_in_progress_impl_resumes_at_auditor() {
         JobRun::AgentPrompt(prompt) => {
             assert!(prompt.contains("reviewing implementation output"));
         }
-        JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
     }
 
     let _ = std::fs::remove_dir_all(&session_dir);
@@ -2379,6 +3204,7 @@ fn sanitize_master_docs_fields_clears_docs_for_new_tasks() {
         status: session_store::PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
 
     let changed = sanitize_master_docs_fields(&mut tasks, Some("[]"));
@@ -2411,6 +3237,7 @@ fn sanitize_master_docs_fields_preserves_baseline_docs_for_existing_tasks() {
         status: session_store::PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
 
     let changed = sanitize_master_docs_fields(&mut tasks, Some(baseline));
@@ -2434,6 +3261,7 @@ fn sanitize_master_docs_fields_ignores_missing_baseline() {
         status: session_store::PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
 
     let changed = sanitize_master_docs_fields(&mut tasks, None);
@@ -2453,7 +3281,7 @@ fn docs_attach_blocks_session_switch_commands() {
     let model_routing = CodexAgentModelRouting::default();
 
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let mut session_store: Option<SessionStore> = None;
     let cwd = std::env::current_dir().expect("cwd");
     let mut pending_task_write_baseline = None;
@@ -2480,7 +3308,7 @@ fn docs_attach_blocks_session_switch_commands() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -2516,7 +3344,7 @@ fn docs_attach_blocks_session_switch_commands() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -2567,7 +3395,7 @@ fn backend_command_opens_picker_and_closes_resume_picker() {
     let model_routing = CodexAgentModelRouting::default();
 
     let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-    let mut active_worker_context_key = None;
+    let mut active_worker_context_keys = HashMap::new();
     let mut session_store: Option<SessionStore> = None;
     let cwd = std::env::current_dir().expect("cwd");
     let mut pending_task_write_baseline = None;
@@ -2594,7 +3422,7 @@ fn backend_command_opens_picker_and_closes_resume_picker() {
         &master_report_adapter,
         &project_info_adapter,
         &mut worker_agent_adapters,
-        &mut active_worker_context_key,
+        &mut active_worker_context_keys,
         &docs_attach_adapter,
         &test_runner_adapter,
         &mut master_report_in_flight,
@@ -2702,6 +3530,7 @@ fn mouse_left_click_scroll_button_scrolls_active_pane_down_by_half_page_in_narro
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(idx as u32),
+                ..Default::default()
             })
             .collect(),
     )
@@ -2767,30 +3596,36 @@ fn apply_backend_selection_persists_and_reports_agentbob_default_success_path()
         let mut selected_backend = BackendKind::Codex;
         let mut model_routing = CodexAgentModelRouting::default();
         let mut master_adapter =
-            build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master);
+            build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master)
+                .expect("adapter should build");
         let mut master_report_adapter = build_json_persistent_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::MasterReport,
-        );
+        )
+        .expect("adapter should build");
         let mut project_info_adapter = build_json_persistent_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::ProjectInfo,
-        );
+        )
+        .expect("adapter should build");
         let mut docs_attach_adapter = build_plain_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::DocsAttach,
             false,
-        );
+        )
+        .expect("adapter should build");
         let mut task_check_adapter = build_plain_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::TaskCheck,
             false,
-        );
-        let mut active_worker_context_key = Some("top:42".to_string());
+        )
+        .expect("adapter should build");
+        let mut active_worker_context_keys: HashMap<u64, String> =
+            [(42, "top:42".to_string())].into_iter().collect();
         let mut worker_agent_adapters: HashMap<String, CodexAdapter> =
             [("top:42".to_string(), CodexAdapter::new())]
                 .into_iter()
@@ -2805,7 +3640,7 @@ fn apply_backend_selection_persists_and_reports_agentbob_default_success_path()
             },
             &mut selected_backend,
             &mut model_routing,
-            &mut active_worker_context_key,
+            &mut active_worker_context_keys,
             &mut worker_agent_adapters,
             &mut master_adapter,
             &mut master_report_adapter,
@@ -2825,7 +3660,7 @@ fn apply_backend_selection_persists_and_reports_agentbob_default_success_path()
         assert_eq!(project_info_adapter.program(), expected_program);
         assert_eq!(docs_attach_adapter.program(), expected_program);
         assert_eq!(task_check_adapter.program(), expected_program);
-        assert!(active_worker_context_key.is_none());
+        assert!(active_worker_context_keys.is_empty());
         assert!(worker_agent_adapters.is_empty());
         let last = app.left_bottom_lines().last().expect("status message");
         assert!(last.contains("Backend set to Claude. Saved to"));
@@ -2846,7 +3681,7 @@ fn toggle_tests_command_persists_and_reports_success() {
         let model_routing = CodexAgentModelRouting::default();
 
         let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-        let mut active_worker_context_key = None;
+        let mut active_worker_context_keys = HashMap::new();
         let mut session_store: Option<SessionStore> = None;
         let cwd = std::env::current_dir().expect("cwd");
         let mut pending_task_write_baseline = None;
@@ -2874,7 +3709,7 @@ fn toggle_tests_command_persists_and_reports_success() {
             &master_report_adapter,
             &project_info_adapter,
             &mut worker_agent_adapters,
-            &mut active_worker_context_key,
+            &mut active_worker_context_keys,
             &docs_attach_adapter,
             &test_runner_adapter,
             &mut master_report_in_flight,
@@ -2925,7 +3760,7 @@ fn toggle_tests_command_persist_failure_keeps_runtime_state_and_reports_error()
         let model_routing = CodexAgentModelRouting::default();
 
         let mut worker_agent_adapters: HashMap<String, CodexAdapter> = HashMap::new();
-        let mut active_worker_context_key = None;
+        let mut active_worker_context_keys = HashMap::new();
         let mut session_store: Option<SessionStore> = None;
         let cwd = std::env::current_dir().expect("cwd");
         let mut pending_task_write_baseline = None;
@@ -2953,7 +3788,7 @@ fn toggle_tests_command_persist_failure_keeps_runtime_state_and_reports_error()
             &master_report_adapter,
             &project_info_adapter,
             &mut worker_agent_adapters,
-            &mut active_worker_context_key,
+            &mut active_worker_context_keys,
             &docs_attach_adapter,
             &test_runner_adapter,
             &mut master_report_in_flight,
@@ -3005,30 +3840,36 @@ fn apply_backend_selection_persists_and_reports_metaagent_fallback_success_path(
         let mut selected_backend = BackendKind::Codex;
         let mut model_routing = CodexAgentModelRouting::default();
         let mut master_adapter =
-            build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master);
+            build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master)
+                .expect("adapter should build");
         let mut master_report_adapter = build_json_persistent_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::MasterReport,
-        );
+        )
+        .expect("adapter should build");
         let mut project_info_adapter = build_json_persistent_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::ProjectInfo,
-        );
+        )
+        .expect("adapter should build");
         let mut docs_attach_adapter = build_plain_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::DocsAttach,
             false,
-        );
+        )
+        .expect("adapter should build");
         let mut task_check_adapter = build_plain_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::TaskCheck,
             false,
-        );
-        let mut active_worker_context_key = Some("top:42".to_string());
+        )
+        .expect("adapter should build");
+        let mut active_worker_context_keys: HashMap<u64, String> =
+            [(42, "top:42".to_string())].into_iter().collect();
         let mut worker_agent_adapters: HashMap<String, CodexAdapter> =
             [("top:42".to_string(), CodexAdapter::new())]
                 .into_iter()
@@ -3043,7 +3884,7 @@ fn apply_backend_selection_persists_and_reports_metaagent_fallback_success_path(
             },
             &mut selected_backend,
             &mut model_routing,
-            &mut active_worker_context_key,
+            &mut active_worker_context_keys,
             &mut worker_agent_adapters,
             &mut master_adapter,
             &mut master_report_adapter,
@@ -3070,30 +3911,36 @@ fn apply_backend_selection_persist_failure_keeps_in_memory_backend_and_reports_f
         let mut selected_backend = BackendKind::Codex;
         let mut model_routing = CodexAgentModelRouting::default();
         let mut master_adapter =
-            build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master);
+            build_json_persistent_adapter(&model_routing, selected_backend, CodexAgentKind::Master)
+                .expect("adapter should build");
         let mut master_report_adapter = build_json_persistent_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::MasterReport,
-        );
+        )
+        .expect("adapter should build");
         let mut project_info_adapter = build_json_persistent_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::ProjectInfo,
-        );
+        )
+        .expect("adapter should build");
         let mut docs_attach_adapter = build_plain_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::DocsAttach,
             false,
-        );
+        )
+        .expect("adapter should build");
         let mut task_check_adapter = build_plain_adapter(
             &model_routing,
             selected_backend,
             CodexAgentKind::TaskCheck,
             false,
-        );
-        let mut active_worker_context_key = Some("top:42".to_string());
+        )
+        .expect("adapter should build");
+        let mut active_worker_context_keys: HashMap<u64, String> =
+            [(42, "top:42".to_string())].into_iter().collect();
         let mut worker_agent_adapters: HashMap<String, CodexAdapter> =
             [("top:42".to_string(), CodexAdapter::new())]
                 .into_iter()
@@ -3108,7 +3955,7 @@ fn apply_backend_selection_persist_failure_keeps_in_memory_backend_and_reports_f
             },
             &mut selected_backend,
             &mut model_routing,
-            &mut active_worker_context_key,
+            &mut active_worker_context_keys,
             &mut worker_agent_adapters,
             &mut master_adapter,
             &mut master_report_adapter,
@@ -3128,7 +3975,7 @@ fn apply_backend_selection_persist_failure_keeps_in_memory_backend_and_reports_f
         assert_eq!(project_info_adapter.program(), expected_program);
         assert_eq!(docs_attach_adapter.program(), expected_program);
         assert_eq!(task_check_adapter.program(), expected_program);
-        assert!(active_worker_context_key.is_none());
+        assert!(active_worker_context_keys.is_empty());
         assert!(worker_agent_adapters.is_empty());
         let last = app.left_bottom_lines().last().expect("status message");
         assert!(last.contains("Backend set to Claude for this run"));
@@ -3152,6 +3999,8 @@ fn failure_report_prompt_includes_entries_and_todo_question_for_tests() {
             attempts: 5,
             reason: "tests kept failing".to_string(),
             action_taken: "removed failing tests".to_string(),
+            retry_budget: None,
+            checkpoint_refs: Vec::new(),
             created_at_epoch_secs: 123,
         }],
         true,
@@ -3243,8 +4092,11 @@ fn task_check_prompt_includes_tasks_path_and_guardrails() {
         "/tmp/session/tasks.json",
         "/tmp/session/project-info.md",
         "/tmp/session/meta.json",
+        "- Configured roles:\n  - security_reviewer (Security review)",
     );
     assert!(prompt.contains("/tmp/session/tasks.json"));
+    assert!(prompt.contains("Validate `role`: only `role` tasks may set it"));
+    assert!(prompt.contains("  - security_reviewer (Security review)"));
     assert!(prompt.contains("/tmp/session/project-info.md"));
     assert!(prompt.contains("/tmp/session/meta.json"));
     assert!(prompt.contains("edit this tasks.json directly to fix them"));
@@ -3315,6 +4167,7 @@ fn ensure_final_audit_task_adds_or_resets_final_task() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
     ensure_final_audit_task(&mut tasks);
     assert!(
@@ -3348,6 +4201,7 @@ fn normalize_root_orders_with_final_last_places_final_at_end() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "a".to_string(),
@@ -3358,6 +4212,7 @@ fn normalize_root_orders_with_final_last_places_final_at_end() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ];
     normalize_root_orders_with_final_last(&mut tasks);
@@ -3435,6 +4290,7 @@ fn remove_final_audit_reports_not_present_when_only_non_final_tasks_exist() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
     let tasks_json = serde_json::to_string_pretty(&tasks).expect("serialize tasks");
     std::fs::write(store.tasks_file(), tasks_json).expect("write tasks");
@@ -3500,6 +4356,7 @@ fn add_final_audit_reports_write_failure_without_returning_error() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }];
     let tasks_json = serde_json::to_string_pretty(&tasks).expect("serialize tasks");
     std::fs::write(store.tasks_file(), tasks_json).expect("write tasks");
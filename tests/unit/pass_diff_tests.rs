@@ -0,0 +1,169 @@
+use super::*;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{prefix}-{now}"));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn init_repo(prefix: &str) -> PathBuf {
+    let repo = temp_dir(prefix);
+    run_git(&repo, &["init", "-q"]).expect("git init");
+    run_git(&repo, &["config", "user.email", "bob@example.com"]).expect("git config email");
+    run_git(&repo, &["config", "user.name", "Bob"]).expect("git config name");
+    std::fs::write(repo.join("shared.txt"), "base\n").expect("write base file");
+    std::fs::write(repo.join("stale.txt"), "stale\n").expect("write stale file");
+    run_git(&repo, &["add", "-A"]).expect("git add");
+    run_git(&repo, &["commit", "-q", "-m", "base"]).expect("git commit");
+    repo
+}
+
+#[test]
+fn git_pass_diff_lists_files_and_patch_changed_since_snapshot() {
+    let repo = init_repo("bob-pass-diff-git");
+    std::fs::write(repo.join("shared.txt"), "dirty before pass\n").expect("pre-pass edit");
+    let before = capture_snapshot(&repo).expect("snapshot");
+    assert!(matches!(before, WorkspaceSnapshot::GitTree(_)));
+
+    std::fs::write(repo.join("shared.txt"), "changed in pass\n").expect("modify");
+    std::fs::write(repo.join("added.txt"), "added\n").expect("add");
+    std::fs::remove_file(repo.join("stale.txt")).expect("delete");
+    let diff = diff_since(&repo, &before).expect("diff");
+
+    assert_eq!(diff.file_list(), "A added.txt\nM shared.txt\nD stale.txt");
+    assert!(diff.patch.contains("-dirty before pass\n+changed in pass"));
+    let status = run_git(&repo, &["status", "--porcelain"]).expect("status");
+    assert!(status.contains("?? added.txt"));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn manifest_pass_diff_covers_non_git_workspaces() {
+    let dir = temp_dir("bob-pass-diff-manifest");
+    std::fs::create_dir_all(dir.join("src")).expect("create src");
+    std::fs::create_dir_all(dir.join("target")).expect("create target");
+    std::fs::write(dir.join("src/lib.rs"), "old\n").expect("write lib");
+    std::fs::write(dir.join("notes.md"), "notes\n").expect("write notes");
+    let before = capture_snapshot(&dir).expect("snapshot");
+    assert!(matches!(before, WorkspaceSnapshot::Manifest(_)));
+
+    std::fs::write(dir.join("src/lib.rs"), "new\n").expect("modify lib");
+    std::fs::write(dir.join("src/new.rs"), "new\n").expect("add file");
+    std::fs::write(dir.join("target/out.bin"), "build\n").expect("build output");
+    let diff = diff_since(&dir, &before).expect("diff");
+
+    assert_eq!(diff.file_list(), "M src/lib.rs\nA src/new.rs");
+    assert!(diff.patch.is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn discrepancies_compare_observed_files_with_files_changed_block() {
+    let diff = PassDiff {
+        files: vec![
+            PassDiffFile {
+                status: 'M',
+                path: "crates/core/src/app.rs".to_string(),
+            },
+            PassDiffFile {
+                status: 'A',
+                path: "src/hidden.rs".to_string(),
+            },
+        ],
+        ..PassDiff::default()
+    };
+
+    let discrepancies = diff.discrepancies(
+        "- `src/app.rs`: relative to the crate\n- README.md: docs\n- No other files: none",
+    );
+
+    assert_eq!(
+        discrepancies,
+        vec![
+            "src/hidden.rs changed in the workspace but is missing from FILES_CHANGED.".to_string(),
+            "README.md is listed in FILES_CHANGED but was not changed in the workspace."
+                .to_string(),
+        ]
+    );
+}
+
+#[test]
+fn write_pass_diff_stores_file_list_and_patch_under_session_dir() {
+    let session_dir = temp_dir("bob-pass-diff-write");
+    let diff = PassDiff {
+        files: vec![PassDiffFile {
+            status: 'M',
+            path: "src/app.rs".to_string(),
+        }],
+        patch: "+line\n".to_string(),
+        diff_file: None,
+    };
+
+    let path = write_pass_diff(&session_dir, "task a", "implementor-pass-2", &diff).expect("write");
+
+    assert_eq!(
+        path,
+        session_dir.join("diffs/task-a/implementor-pass-2.diff")
+    );
+    assert_eq!(
+        std::fs::read_to_string(&path).expect("read"),
+        "# implementor-pass-2\n# Changed files:\n#   M src/app.rs\n\n+line\n"
+    );
+
+    let _ = std::fs::remove_dir_all(&session_dir);
+}
+
+#[test]
+fn changed_lines_follow_later_passes_through_their_hunks() {
+    let repo = init_repo("bob-pass-diff-lines");
+    let numbered = |lines: &[&str]| {
+        lines
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect::<String>()
+    };
+    let mut lib = vec!["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"];
+    std::fs::write(repo.join("lib.txt"), numbered(&lib)).expect("write lib");
+    let mut changed = ChangedLines::new();
+
+    let before = capture_snapshot(&repo).expect("first snapshot");
+    lib[2] = "three";
+    lib[7] = "eight";
+    std::fs::write(repo.join("lib.txt"), numbered(&lib)).expect("first pass lib");
+    std::fs::write(repo.join("new.txt"), "a\nb\n").expect("first pass new file");
+    let first = diff_since(&repo, &before).expect("first diff");
+    assert!(first.apply_to_changed_lines(&mut changed));
+    assert_eq!(changed["lib.txt"], BTreeSet::from([3, 8]));
+    assert_eq!(changed["new.txt"], BTreeSet::from([1, 2]));
+
+    let before = capture_snapshot(&repo).expect("second snapshot");
+    lib.remove(7);
+    lib.splice(0..0, ["top", "header"]);
+    std::fs::write(repo.join("lib.txt"), numbered(&lib)).expect("second pass lib");
+    std::fs::remove_file(repo.join("new.txt")).expect("second pass delete");
+    let second = diff_since(&repo, &before).expect("second diff");
+    assert!(second.apply_to_changed_lines(&mut changed));
+    assert_eq!(
+        changed,
+        ChangedLines::from([("lib.txt".to_string(), BTreeSet::from([1, 2, 5]))])
+    );
+
+    let manifest_only = PassDiff {
+        files: vec![PassDiffFile {
+            status: 'M',
+            path: "lib.txt".to_string(),
+        }],
+        ..PassDiff::default()
+    };
+    assert!(!manifest_only.apply_to_changed_lines(&mut changed));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
@@ -0,0 +1,57 @@
+use super::*;
+use std::time::{Duration, Instant};
+
+#[test]
+fn kill_without_attached_process_only_bumps_generation() {
+    let control = RunControl::default();
+    let generation = control.generation();
+
+    assert!(!control.kill());
+    assert_eq!(control.generation(), generation + 1);
+}
+
+#[test]
+fn send_if_current_drops_sends_from_superseded_runs() {
+    let control = RunControl::default();
+    let stale = control.generation();
+    control.kill();
+    let current = control.generation();
+
+    let mut sent = Vec::new();
+    control.send_if_current(stale, || sent.push("stale"));
+    control.send_if_current(current, || sent.push("current"));
+
+    assert_eq!(sent, vec!["current"]);
+}
+
+#[test]
+fn detach_clears_the_attached_process() {
+    let control = RunControl::default();
+    let generation = control.generation();
+    control.attach(generation, u32::MAX);
+    control.detach(generation);
+
+    assert!(!control.kill());
+}
+
+#[cfg(unix)]
+#[test]
+fn kill_terminates_the_attached_process_group() {
+    let mut command = Command::new("sleep");
+    command.arg("30");
+    isolate_process_group(&mut command);
+    let mut child = command.spawn().expect("spawn sleep");
+
+    let control = RunControl::default();
+    control.attach(control.generation(), child.id());
+    assert!(control.kill());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        if child.try_wait().expect("poll child").is_some() {
+            break;
+        }
+        assert!(Instant::now() < deadline, "child should exit after kill");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
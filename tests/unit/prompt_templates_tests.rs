@@ -0,0 +1,130 @@
+use super::*;
+use std::collections::BTreeSet;
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "{prefix}-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default()
+    ));
+    std::fs::create_dir_all(&dir).expect("temp dir should be created");
+    dir
+}
+
+fn referenced_names(text: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    fill_placeholders(text, |name| {
+        names.insert(name.to_string());
+        None
+    });
+    names
+}
+
+#[test]
+fn builtin_templates_use_exactly_their_declared_placeholders() {
+    for template in PromptTemplate::ALL {
+        let declared = template
+            .placeholders()
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            referenced_names(template.builtin()),
+            declared,
+            "placeholders of `{}`",
+            template.name()
+        );
+        assert_eq!(PromptTemplate::from_name(template.name()), Some(template));
+    }
+    assert_eq!(
+        PromptTemplate::from_name(" Final-Audit "),
+        Some(PromptTemplate::FinalAudit)
+    );
+    assert_eq!(PromptTemplate::from_name("reviewer"), None);
+}
+
+#[test]
+fn fill_placeholders_substitutes_known_names_once_and_keeps_other_braces() {
+    let rendered = fill_placeholders("{\"title\":\"...\"} {name} {missing} {} {name", |name| {
+        (name == "name").then_some("{name}")
+    });
+
+    assert_eq!(rendered, "{\"title\":\"...\"} {name} {missing} {} {name");
+    assert_eq!(
+        fill_placeholders("a {x}{x} b", |_| Some("1")),
+        "a 11 b".to_string()
+    );
+}
+
+#[test]
+fn resolve_prefers_the_first_override_and_falls_back_to_builtin() {
+    let root = temp_dir("prompt-templates-resolve");
+    let workspace = root.join("workspace");
+    let config = root.join("config");
+    std::fs::create_dir_all(&workspace).expect("workspace dir");
+    std::fs::create_dir_all(&config).expect("config dir");
+    std::fs::write(
+        workspace.join("implementor.md"),
+        "Workspace rules for {top_task_title}\n\n",
+    )
+    .expect("workspace template");
+    std::fs::write(config.join("implementor.md"), "Config rules").expect("config template");
+    std::fs::write(config.join("task_check.md"), "Check {tasks_file}").expect("config template");
+    let dirs = PromptTemplateDirs::new(vec![workspace.clone(), config.clone()]);
+
+    let implementor = dirs.resolve(PromptTemplate::Implementor);
+    assert_eq!(implementor.source, Some(workspace.join("implementor.md")));
+    assert_eq!(
+        implementor.render(&[("top_task_title", "Login")]),
+        "Workspace rules for Login"
+    );
+    assert_eq!(implementor.used_placeholders(), vec!["top_task_title"]);
+
+    let task_check = dirs.resolve(PromptTemplate::TaskCheck);
+    assert_eq!(task_check.source, Some(config.join("task_check.md")));
+    assert_eq!(
+        task_check.render(&[("tasks_file", "/s/tasks.json")]),
+        "Check /s/tasks.json"
+    );
+
+    let master = dirs.resolve(PromptTemplate::Master);
+    assert_eq!(master.source, None);
+    assert_eq!(master.text, PromptTemplate::Master.builtin());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn discover_searches_the_workspace_before_the_config_dir() {
+    let _guard = crate::artifact_io::home_env_test_lock()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let old_home = std::env::var_os("HOME");
+    let home = temp_dir("prompt-templates-home");
+    std::fs::create_dir_all(home.join(".agentbob")).expect("config dir");
+    unsafe { std::env::set_var("HOME", &home) };
+
+    let workspace = home.join("project");
+    let dirs = PromptTemplateDirs::discover(&workspace);
+    let home_only = PromptTemplateDirs::discover(&home);
+
+    match old_home {
+        Some(value) => unsafe { std::env::set_var("HOME", value) },
+        None => unsafe { std::env::remove_var("HOME") },
+    }
+    assert_eq!(
+        dirs.dirs(),
+        &[
+            workspace.join(".agentbob").join(PROMPTS_DIR_NAME),
+            home.join(".agentbob").join(PROMPTS_DIR_NAME),
+        ]
+    );
+    assert_eq!(
+        home_only.dirs(),
+        &[home.join(".agentbob").join(PROMPTS_DIR_NAME)]
+    );
+    let _ = std::fs::remove_dir_all(home);
+}
@@ -0,0 +1,236 @@
+use super::*;
+use crate::agent::{BackendKind, CodexAdapter, CodexCommandConfig};
+use crate::replay::{ReplayBackendSpec, ReplayFixture, ReplayPlayer};
+use crate::session_store::SessionStore;
+use std::time::Duration;
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "{prefix}-{}-{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default()
+    ));
+    std::fs::create_dir_all(&dir).expect("temp dir should be created");
+    dir
+}
+
+fn spec(recorder: &SessionRecorder, role: &str, task: Option<u64>) -> RecordingSpec {
+    RecordingSpec {
+        recorder: recorder.clone(),
+        role: role.to_string(),
+        task,
+        context_key: format!("{role}:{}", task.unwrap_or_default()),
+    }
+}
+
+fn read_fixture(recorder: &SessionRecorder) -> serde_json::Value {
+    let text = std::fs::read_to_string(recorder.fixture_path()).expect("fixture should exist");
+    serde_json::from_str(&text).expect("fixture should be json")
+}
+
+#[test]
+fn recorded_runs_replay_with_their_output_exit_code_and_edits() {
+    let workspace = temp_dir("recorder-workspace");
+    std::fs::write(workspace.join("old.txt"), "old").expect("seed file");
+    std::fs::write(workspace.join("keep.txt"), "same").expect("seed file");
+    let recorder = SessionRecorder::create(&workspace.join("recording")).expect("recorder");
+
+    let run = spec(&recorder, "worker_implementor", Some(3)).begin(
+        "implement it",
+        Some("gpt-5.3-codex"),
+        Some("medium"),
+        &workspace,
+    );
+    std::fs::write(workspace.join("new.txt"), "fresh\n").expect("agent edit");
+    std::fs::remove_file(workspace.join("old.txt")).expect("agent delete");
+    run.record(&AgentEvent::Output("implemented".to_string()))
+        .expect("output should record");
+    run.record(&AgentEvent::Completed {
+        success: false,
+        code: 2,
+    })
+    .expect("completion should flush");
+
+    let fixture = read_fixture(&recorder);
+    let step = &fixture["steps"][0];
+    assert_eq!(step["role"], "worker_implementor");
+    assert_eq!(step["task"], 3);
+    assert_eq!(step["pass"], 1);
+    assert_eq!(step["context_key"], "worker_implementor:3");
+    assert_eq!(step["model"], "gpt-5.3-codex");
+    assert_eq!(step["thinking_effort"], "medium");
+    assert_eq!(step["prompt"], "implement it");
+    assert_eq!(step["events"][0]["kind"], "output");
+    assert_eq!(step["events"][1]["kind"], "completed");
+    assert!(step["events"][1]["at_ms"].is_u64());
+
+    let player = ReplayPlayer::load(&recorder.fixture_path()).expect("fixture should replay");
+    let replayed = player
+        .next_step("worker_implementor", Some(3))
+        .expect("recorded step");
+    assert_eq!(replayed.output, vec!["implemented".to_string()]);
+    assert_eq!(replayed.exit_code, 2);
+    let mut edits = replayed
+        .edits
+        .iter()
+        .map(|edit| (edit.path.as_str(), edit.content.as_deref(), edit.delete))
+        .collect::<Vec<_>>();
+    edits.sort();
+    assert_eq!(
+        edits,
+        vec![("new.txt", Some("fresh\n"), false), ("old.txt", None, true)]
+    );
+
+    let _ = std::fs::remove_dir_all(workspace);
+}
+
+#[test]
+fn runs_without_a_task_do_not_claim_workspace_edits() {
+    let workspace = temp_dir("recorder-overlap");
+    let recorder = SessionRecorder::create(&workspace.join("recording")).expect("recorder");
+
+    let report = spec(&recorder, "master_report", None).begin("report", None, None, &workspace);
+    let worker =
+        spec(&recorder, "worker_implementor", Some(4)).begin("build", None, None, &workspace);
+    std::fs::write(workspace.join("worker.txt"), "done\n").expect("worker edit");
+    for run in [&report, &worker] {
+        run.record(&AgentEvent::Completed {
+            success: true,
+            code: 0,
+        })
+        .expect("completion should flush");
+    }
+
+    let fixture = read_fixture(&recorder);
+    assert_eq!(fixture["steps"][0]["role"], "master_report");
+    assert_eq!(fixture["steps"][0]["edits"], serde_json::json!([]));
+    assert_eq!(fixture["steps"][1]["edits"][0]["path"], "worker.txt");
+
+    let _ = std::fs::remove_dir_all(workspace);
+}
+
+#[test]
+fn session_files_written_by_the_master_replay_into_an_empty_session() {
+    let workspace = temp_dir("recorder-session");
+    let session_dir = workspace.join("session");
+    let store = SessionStore::open_existing(&workspace, &session_dir).expect("empty session");
+    let recorder = SessionRecorder::create(&workspace.join("recording")).expect("recorder");
+    recorder.set_session_dir(Some(store.session_dir()));
+    let tasks = r#"[{"id":"top","title":"Add answer","kind":"task","status":"pending","order":0}]"#;
+    let meta = r#"{"title":"Answer","created_at":"now","test_command":"cargo test"}"#;
+
+    let run = spec(&recorder, "master", None).begin("plan it", None, None, &workspace);
+    std::fs::write(store.tasks_file(), tasks).expect("master writes tasks.json");
+    std::fs::write(session_dir.join("meta.json"), meta).expect("master writes meta.json");
+    run.record(&AgentEvent::Output("planned".to_string()))
+        .expect("output should record");
+    run.record(&AgentEvent::Completed {
+        success: true,
+        code: 0,
+    })
+    .expect("completion should flush");
+
+    std::fs::remove_dir_all(&session_dir).expect("drop the recorded session");
+    let store = SessionStore::open_existing(&workspace, &session_dir).expect("fresh session");
+    assert!(store.read_tasks().expect("empty tasks").is_empty());
+    let player = ReplayPlayer::load(&recorder.fixture_path()).expect("fixture should replay");
+    let mut events = Vec::new();
+    crate::replay::play_next_step(&player, "master", None, &workspace, "Replay", |event| {
+        events.push(event)
+    });
+
+    assert!(matches!(
+        events.last(),
+        Some(AgentEvent::Completed { success: true, .. })
+    ));
+    let replayed = store.read_tasks().expect("replayed tasks");
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].title, "Add answer");
+    assert_eq!(
+        store
+            .read_session_meta()
+            .expect("replayed meta")
+            .test_command,
+        Some("cargo test".to_string())
+    );
+    assert!(!workspace.join("tasks.json").exists());
+
+    let _ = std::fs::remove_dir_all(workspace);
+}
+
+#[test]
+fn passes_count_per_role_and_task_and_unfinished_runs_are_left_out() {
+    let workspace = temp_dir("recorder-passes");
+    let recorder = SessionRecorder::create(&workspace.join("recording")).expect("recorder");
+
+    let first = spec(&recorder, "worker_auditor", Some(1)).begin("a", None, None, &workspace);
+    let killed = spec(&recorder, "worker_auditor", Some(1)).begin("b", None, None, &workspace);
+    let other = spec(&recorder, "worker_auditor", Some(2)).begin("c", None, None, &workspace);
+    for run in [&first, &other] {
+        run.record(&AgentEvent::Completed {
+            success: true,
+            code: 0,
+        })
+        .expect("completion should flush");
+    }
+    killed
+        .record(&AgentEvent::Output("partial".to_string()))
+        .expect("output should record");
+
+    let fixture = read_fixture(&recorder);
+    let steps = fixture["steps"].as_array().expect("steps");
+    assert_eq!(steps.len(), 2);
+    assert_eq!(
+        (steps[0]["task"].clone(), steps[0]["pass"].clone()),
+        (1.into(), 1.into())
+    );
+    assert_eq!(
+        (steps[1]["task"].clone(), steps[1]["pass"].clone()),
+        (2.into(), 1.into())
+    );
+
+    let _ = std::fs::remove_dir_all(workspace);
+}
+
+#[test]
+fn replay_adapter_sessions_can_be_recorded_again() {
+    let workspace = temp_dir("recorder-adapter");
+    let recorder = SessionRecorder::create(&workspace.join("recording")).expect("recorder");
+    let player = ReplayPlayer::new(ReplayFixture {
+        steps: vec![crate::replay::ReplayStep {
+            role: "master".to_string(),
+            output: vec!["planned".to_string()],
+            ..Default::default()
+        }],
+    });
+    let mut config = CodexCommandConfig::default_for_backend(BackendKind::Replay);
+    config.working_dir = Some(workspace.clone());
+    config.replay = Some(ReplayBackendSpec {
+        player: Arc::new(player),
+        role: "master".to_string(),
+        task: None,
+    });
+    config.recording = Some(spec(&recorder, "master", None));
+    let adapter = CodexAdapter::with_config(config);
+
+    adapter.send_prompt("plan it".to_string());
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline
+        && !adapter
+            .drain_events()
+            .iter()
+            .any(|event| matches!(event, AgentEvent::Completed { .. }))
+    {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let fixture = read_fixture(&recorder);
+    assert_eq!(fixture["steps"][0]["prompt"], "plan it");
+    assert_eq!(fixture["steps"][0]["output"][0], "planned");
+    assert_eq!(fixture["steps"][0]["edits"], serde_json::json!([]));
+
+    let _ = std::fs::remove_dir_all(workspace);
+}
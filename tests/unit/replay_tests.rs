@@ -0,0 +1,194 @@
+use super::*;
+use crate::agent::{AgentEvent, BackendKind, CodexAdapter, CodexCommandConfig};
+use std::time::{Duration, Instant};
+
+fn step(role: &str, task: Option<u64>, pass: Option<u32>, line: &str) -> ReplayStep {
+    ReplayStep {
+        role: role.to_string(),
+        task,
+        pass,
+        output: vec![line.to_string()],
+        ..ReplayStep::default()
+    }
+}
+
+fn first_line(player: &ReplayPlayer, role: &str, task: Option<u64>) -> String {
+    player
+        .next_step(role, task)
+        .expect("fixture should have a step")
+        .output
+        .remove(0)
+}
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "{prefix}-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default()
+    ));
+    std::fs::create_dir_all(&dir).expect("temp dir should be created");
+    dir
+}
+
+#[test]
+fn next_step_prefers_task_and_pass_specific_steps() {
+    let player = ReplayPlayer::new(ReplayFixture {
+        steps: vec![
+            step("worker_auditor", None, None, "any"),
+            step("worker_auditor", None, Some(1), "first pass"),
+            step("worker_auditor", Some(7), None, "task seven"),
+            step("worker_auditor", Some(7), Some(2), "task seven pass two"),
+        ],
+    });
+
+    assert_eq!(first_line(&player, "worker_auditor", Some(3)), "first pass");
+    assert_eq!(first_line(&player, "worker_auditor", Some(3)), "any");
+    assert_eq!(first_line(&player, "worker_auditor", Some(7)), "task seven");
+    assert_eq!(
+        first_line(&player, "worker_auditor", Some(7)),
+        "task seven pass two"
+    );
+    assert_eq!(first_line(&player, "worker_auditor", Some(7)), "task seven");
+}
+
+#[test]
+fn next_step_counts_passes_per_role_and_task() {
+    let player = ReplayPlayer::new(ReplayFixture {
+        steps: vec![
+            step("worker_implementor", None, Some(1), "one"),
+            step("worker_implementor", None, Some(2), "two"),
+        ],
+    });
+
+    assert_eq!(first_line(&player, "worker_implementor", Some(1)), "one");
+    assert_eq!(first_line(&player, "worker_implementor", Some(2)), "one");
+    assert_eq!(first_line(&player, "worker_implementor", Some(1)), "two");
+    let err = player
+        .next_step("worker_implementor", Some(1))
+        .expect_err("third pass is not scripted");
+    assert_eq!(
+        err,
+        "Replay fixture has no step for role worker_implementor, task 1, pass 3."
+    );
+}
+
+#[test]
+fn load_reads_json_fixture_with_defaults() {
+    let dir = temp_dir("replay-load");
+    let path = dir.join("fixture.json");
+    std::fs::write(
+        &path,
+        r#"{"steps":[{"role":"master","output":["planned"]},{"role":"worker_auditor","task":2,"exit_code":3}]}"#,
+    )
+    .expect("fixture should be written");
+
+    let player = ReplayPlayer::load(&path).expect("fixture should load");
+    let master = player.next_step("master", None).expect("master step");
+    assert_eq!(master.output, vec!["planned".to_string()]);
+    assert_eq!(master.exit_code, 0);
+    assert_eq!(
+        player
+            .next_step("worker_auditor", Some(2))
+            .expect("auditor step")
+            .exit_code,
+        3
+    );
+
+    std::fs::write(&path, "{not json").expect("fixture should be written");
+    let err = ReplayPlayer::load(&path).expect_err("malformed fixture should fail");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn apply_edits_writes_and_deletes_files_under_root() {
+    let dir = temp_dir("replay-edits");
+    std::fs::write(dir.join("stale.txt"), "old").expect("seed file");
+
+    apply_edits(
+        &[
+            ReplayEdit {
+                path: "src/new.rs".to_string(),
+                content: Some("fn main() {}\n".to_string()),
+                delete: false,
+            },
+            ReplayEdit {
+                path: "stale.txt".to_string(),
+                delete: true,
+                ..ReplayEdit::default()
+            },
+            ReplayEdit {
+                path: "missing.txt".to_string(),
+                delete: true,
+                ..ReplayEdit::default()
+            },
+        ],
+        &dir,
+    )
+    .expect("edits should apply");
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join("src/new.rs")).expect("new file"),
+        "fn main() {}\n"
+    );
+    assert!(!dir.join("stale.txt").exists());
+    assert!(
+        apply_edits(&[ReplayEdit::default()], &dir).is_err(),
+        "an edit without a path should fail"
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn replay_adapter_applies_edits_then_emits_output_and_exit_code() {
+    let dir = temp_dir("replay-adapter");
+    let player = Arc::new(ReplayPlayer::new(ReplayFixture {
+        steps: vec![ReplayStep {
+            role: "worker_test_writer".to_string(),
+            output: vec!["wrote tests".to_string()],
+            exit_code: 2,
+            edits: vec![ReplayEdit {
+                path: "tests/replay.txt".to_string(),
+                content: Some("ok".to_string()),
+                delete: false,
+            }],
+            ..ReplayStep::default()
+        }],
+    }));
+    let mut config = CodexCommandConfig::default_for_backend(BackendKind::Replay);
+    config.working_dir = Some(dir.clone());
+    config.replay = Some(ReplayBackendSpec {
+        player,
+        role: "worker_test_writer".to_string(),
+        task: None,
+    });
+    let adapter = CodexAdapter::with_config(config).with_worker_context(4, "test_writer:4");
+
+    adapter.send_prompt("write tests".to_string());
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut events = Vec::new();
+    while Instant::now() < deadline && events.len() < 3 {
+        events.extend(adapter.drain_events());
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    assert_eq!(
+        events,
+        vec![
+            AgentEvent::Output("wrote tests".to_string()),
+            AgentEvent::Completed {
+                success: false,
+                code: 2
+            },
+            AgentEvent::System("Adapter (replay) exited with status code 2".to_string()),
+        ]
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("tests/replay.txt")).expect("edited file"),
+        "ok"
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -30,6 +30,7 @@ fn seed_simple_plan(app: &mut App) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -40,6 +41,7 @@ fn seed_simple_plan(app: &mut App) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -50,6 +52,7 @@ fn seed_simple_plan(app: &mut App) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -104,7 +107,7 @@ fn start_next_worker_job_if_any_none_still_persists_snapshot() {
         .start_next_worker_job_if_any(
             &mut app,
             &mut std::collections::HashMap::new(),
-            &mut None,
+            &mut std::collections::HashMap::new(),
             &TestRunnerAdapter::new(),
             &store,
             &CodexAgentModelRouting::default(),
@@ -129,7 +132,7 @@ fn dispatch_agent_prompt_preserves_prior_worker_session_id() {
     old_adapter.set_saved_session_id(Some("session-123".to_string()));
     adapters.insert("implementor:1".to_string(), old_adapter);
 
-    let mut active_key = None;
+    let mut active_keys = std::collections::HashMap::new();
     let test_runner = TestRunnerAdapter::new();
     let routing = CodexAgentModelRouting::default();
     let job = StartedJob {
@@ -137,18 +140,30 @@ fn dispatch_agent_prompt_preserves_prior_worker_session_id() {
         role: WorkerRole::Implementor,
         top_task_id: 1,
         parent_context_key: Some("implementor:1".to_string()),
+        workspace_dir: None,
+        checkpoint_label: None,
+        pass_label: None,
+        test_command: None,
+        test_working_dir: None,
+        model_profile: None,
+        permissions: None,
     };
 
-    service.dispatch_worker_job(
-        &job,
-        &mut adapters,
-        &mut active_key,
-        &test_runner,
-        &store,
-        &routing,
-    );
+    service
+        .dispatch_worker_job(
+            &job,
+            &mut adapters,
+            &mut active_keys,
+            &test_runner,
+            &store,
+            &routing,
+        )
+        .expect("worker job should dispatch");
 
-    assert_eq!(active_key.as_deref(), Some("implementor:1"));
+    assert_eq!(
+        active_keys.get(&1).map(String::as_str),
+        Some("implementor:1")
+    );
     let saved = adapters
         .get("implementor:1")
         .expect("adapter should exist")
@@ -167,7 +182,7 @@ fn dispatch_agent_prompt_keeps_existing_context_and_creates_new_context_for_futu
     existing_adapter.set_saved_session_id(Some("session-123".to_string()));
     adapters.insert("implementor:1".to_string(), existing_adapter);
 
-    let mut active_key = None;
+    let mut active_keys = std::collections::HashMap::new();
     let test_runner = TestRunnerAdapter::new();
     let mut routing = CodexAgentModelRouting::default();
 
@@ -176,16 +191,28 @@ fn dispatch_agent_prompt_keeps_existing_context_and_creates_new_context_for_futu
         role: WorkerRole::Implementor,
         top_task_id: 1,
         parent_context_key: Some("implementor:1".to_string()),
+        workspace_dir: None,
+        checkpoint_label: None,
+        pass_label: None,
+        test_command: None,
+        test_working_dir: None,
+        model_profile: None,
+        permissions: None,
     };
-    service.dispatch_worker_job(
-        &first_job,
-        &mut adapters,
-        &mut active_key,
-        &test_runner,
-        &store,
-        &routing,
+    service
+        .dispatch_worker_job(
+            &first_job,
+            &mut adapters,
+            &mut active_keys,
+            &test_runner,
+            &store,
+            &routing,
+        )
+        .expect("worker job should dispatch");
+    assert_eq!(
+        active_keys.get(&1).map(String::as_str),
+        Some("implementor:1")
     );
-    assert_eq!(active_key.as_deref(), Some("implementor:1"));
 
     routing = CodexAgentModelRouting::from_toml_str("[backend]\nselected = \"claude\"\n")
         .unwrap_or_default();
@@ -194,17 +221,29 @@ fn dispatch_agent_prompt_keeps_existing_context_and_creates_new_context_for_futu
         role: WorkerRole::Implementor,
         top_task_id: 2,
         parent_context_key: Some("implementor:2".to_string()),
+        workspace_dir: None,
+        checkpoint_label: None,
+        pass_label: None,
+        test_command: None,
+        test_working_dir: None,
+        model_profile: None,
+        permissions: None,
     };
-    service.dispatch_worker_job(
-        &second_job,
-        &mut adapters,
-        &mut active_key,
-        &test_runner,
-        &store,
-        &routing,
-    );
+    service
+        .dispatch_worker_job(
+            &second_job,
+            &mut adapters,
+            &mut active_keys,
+            &test_runner,
+            &store,
+            &routing,
+        )
+        .expect("worker job should dispatch");
 
-    assert_eq!(active_key.as_deref(), Some("implementor:2"));
+    assert_eq!(
+        active_keys.get(&2).map(String::as_str),
+        Some("implementor:2")
+    );
     assert_eq!(adapters.len(), 2);
     assert_eq!(
         adapters
@@ -234,7 +273,7 @@ fn dispatch_agent_prompt_does_not_replace_in_flight_context_adapter_after_backen
     existing_adapter.set_saved_session_id(Some("session-123".to_string()));
     adapters.insert("implementor:1".to_string(), existing_adapter);
 
-    let mut active_key = None;
+    let mut active_keys = std::collections::HashMap::new();
     let test_runner = TestRunnerAdapter::new();
     let routing = CodexAgentModelRouting::from_toml_str("[backend]\nselected = \"claude\"\n")
         .unwrap_or_default();
@@ -244,17 +283,29 @@ fn dispatch_agent_prompt_does_not_replace_in_flight_context_adapter_after_backen
         role: WorkerRole::Implementor,
         top_task_id: 1,
         parent_context_key: Some("implementor:1".to_string()),
+        workspace_dir: None,
+        checkpoint_label: None,
+        pass_label: None,
+        test_command: None,
+        test_working_dir: None,
+        model_profile: None,
+        permissions: None,
     };
-    service.dispatch_worker_job(
-        &job,
-        &mut adapters,
-        &mut active_key,
-        &test_runner,
-        &store,
-        &routing,
-    );
+    service
+        .dispatch_worker_job(
+            &job,
+            &mut adapters,
+            &mut active_keys,
+            &test_runner,
+            &store,
+            &routing,
+        )
+        .expect("worker job should dispatch");
 
-    assert_eq!(active_key.as_deref(), Some("implementor:1"));
+    assert_eq!(
+        active_keys.get(&1).map(String::as_str),
+        Some("implementor:1")
+    );
     assert_eq!(adapters.len(), 1);
     assert_eq!(
         adapters
@@ -268,10 +319,32 @@ fn dispatch_agent_prompt_does_not_replace_in_flight_context_adapter_after_backen
     let _ = std::fs::remove_dir_all(&session_dir);
 }
 
+#[test]
+fn build_worker_adapter_routes_each_role_to_its_own_backend() {
+    let routing = CodexAgentModelRouting::from_toml_str(
+        "[codex.agent_profiles]\nworker_auditor = { profile = \"large-smart\", backend = \"claude\" }\n",
+    )
+    .expect("routing should parse");
+
+    let implementor = build_worker_adapter(&routing, WorkerRole::Implementor, None, None, None)
+        .expect("backend should be configured");
+    let implementor = implementor.config_snapshot();
+    assert_eq!(implementor.backend_kind(), BackendKind::Codex);
+    assert_eq!(implementor.output_mode, AdapterOutputMode::PlainText);
+
+    let auditor = build_worker_adapter(&routing, WorkerRole::Auditor, None, None, None)
+        .expect("backend should be configured");
+    let auditor = auditor.config_snapshot();
+    assert_eq!(auditor.backend_kind(), BackendKind::Claude);
+    assert_eq!(auditor.output_mode, AdapterOutputMode::JsonAssistantOnly);
+    assert_eq!(auditor.model, None);
+}
+
 #[test]
 fn build_worker_adapter_for_codex_keeps_plain_text_persistent_behavior() {
     let routing = CodexAgentModelRouting::default();
-    let adapter = build_worker_adapter(&routing, WorkerRole::Implementor);
+    let adapter = build_worker_adapter(&routing, WorkerRole::Implementor, None, None, None)
+        .expect("backend should be configured");
     let config = adapter.config_snapshot();
 
     assert_eq!(config.backend_kind(), BackendKind::Codex);
@@ -280,11 +353,33 @@ fn build_worker_adapter_for_codex_keeps_plain_text_persistent_behavior() {
     assert!(config.skip_reader_join_after_wait);
 }
 
+#[test]
+fn build_worker_adapter_uses_reviewer_profile_when_known() {
+    let routing = CodexAgentModelRouting::from_toml_str(
+        "[codex.model_profiles.review-b]\nmodel = \"other-model\"\nthinking_effort = \"high\"\n",
+    )
+    .expect("routing should parse");
+
+    let adapter = build_worker_adapter(&routing, WorkerRole::Auditor, Some("Review-B"), None, None)
+        .expect("backend should be configured");
+    let config = adapter.config_snapshot();
+    assert_eq!(config.model.as_deref(), Some("other-model"));
+    assert_eq!(config.model_reasoning_effort.as_deref(), Some("high"));
+
+    let fallback = build_worker_adapter(&routing, WorkerRole::Auditor, Some("missing"), None, None)
+        .expect("backend should be configured");
+    assert_eq!(
+        fallback.config_snapshot().model,
+        Some(routing.profile_for(CodexAgentKind::WorkerAuditor).model)
+    );
+}
+
 #[test]
 fn build_worker_adapter_for_claude_uses_json_persistent_mode_for_resumption() {
     let routing = CodexAgentModelRouting::from_toml_str("[backend]\nselected = \"claude\"\n")
         .unwrap_or_default();
-    let adapter = build_worker_adapter(&routing, WorkerRole::Implementor);
+    let adapter = build_worker_adapter(&routing, WorkerRole::Implementor, None, None, None)
+        .expect("backend should be configured");
     let config = adapter.config_snapshot();
 
     assert_eq!(config.backend_kind(), BackendKind::Claude);
@@ -303,7 +398,8 @@ fn dispatch_deterministic_test_run_uses_trimmed_meta_test_command() {
     )
     .expect("write meta");
 
-    let mut active_key = Some("placeholder".to_string());
+    let mut active_keys: std::collections::HashMap<u64, String> =
+        [(1, "placeholder".to_string())].into_iter().collect();
     let test_runner = TestRunnerAdapter::new();
     let routing = CodexAgentModelRouting::default();
     let mut adapters = std::collections::HashMap::new();
@@ -312,16 +408,25 @@ fn dispatch_deterministic_test_run_uses_trimmed_meta_test_command() {
         role: WorkerRole::TestRunner,
         top_task_id: 1,
         parent_context_key: Some("test_writer:1".to_string()),
+        workspace_dir: None,
+        checkpoint_label: None,
+        pass_label: None,
+        test_command: None,
+        test_working_dir: None,
+        model_profile: None,
+        permissions: None,
     };
 
-    service.dispatch_worker_job(
-        &job,
-        &mut adapters,
-        &mut active_key,
-        &test_runner,
-        &store,
-        &routing,
-    );
+    service
+        .dispatch_worker_job(
+            &job,
+            &mut adapters,
+            &mut active_keys,
+            &test_runner,
+            &store,
+            &routing,
+        )
+        .expect("worker job should dispatch");
 
     let events = wait_for_runner_events(&test_runner);
     assert!(events.iter().any(|event| {
@@ -339,7 +444,7 @@ fn dispatch_deterministic_test_run_uses_trimmed_meta_test_command() {
             }
         )
     }));
-    assert!(active_key.is_none());
+    assert!(active_keys.is_empty());
 
     let _ = std::fs::remove_dir_all(&session_dir);
 }
@@ -380,6 +485,8 @@ fn build_exhausted_loop_failures_prompt_appends_entries_and_intro_once() {
                     attempts: 4,
                     reason: "audit failed".to_string(),
                     action_taken: "stopped".to_string(),
+                    retry_budget: None,
+                    checkpoint_refs: Vec::new(),
                 },
                 WorkflowFailure {
                     kind: WorkflowFailureKind::Test,
@@ -388,6 +495,8 @@ fn build_exhausted_loop_failures_prompt_appends_entries_and_intro_once() {
                     attempts: 5,
                     reason: "tests failed".to_string(),
                     action_taken: "stopped".to_string(),
+                    retry_budget: None,
+                    checkpoint_refs: Vec::new(),
                 },
             ],
         )
@@ -412,6 +521,8 @@ fn build_exhausted_loop_failures_prompt_appends_entries_and_intro_once() {
                 attempts: 4,
                 reason: "still failing".to_string(),
                 action_taken: "stopped".to_string(),
+                retry_budget: None,
+                checkpoint_refs: Vec::new(),
             }],
         )
         .expect("second prompt generation should succeed")
@@ -429,6 +540,44 @@ fn build_exhausted_loop_failures_prompt_appends_entries_and_intro_once() {
     let _ = std::fs::remove_dir_all(&session_dir);
 }
 
+#[test]
+fn exhausted_tasks_with_checkpoints_are_offered_a_rollback() {
+    let mut app = App::default();
+    seed_simple_plan(&mut app);
+    let top_id = app.resolve_top_task_id("top").expect("top task");
+    let failure = |kind, checkpoint_refs: Vec<String>| WorkflowFailure {
+        kind,
+        top_task_id: top_id,
+        top_task_title: "Task".to_string(),
+        attempts: 4,
+        reason: "audit failed".to_string(),
+        action_taken: "stopped".to_string(),
+        retry_budget: None,
+        checkpoint_refs,
+    };
+
+    offer_rollback_for_exhausted_tasks(
+        &mut app,
+        &[
+            failure(WorkflowFailureKind::Audit, Vec::new()),
+            failure(WorkflowFailureKind::Merge, vec!["refs/merge".to_string()]),
+        ],
+    );
+    assert!(app.left_bottom_lines().is_empty());
+
+    offer_rollback_for_exhausted_tasks(
+        &mut app,
+        &[
+            failure(WorkflowFailureKind::Audit, vec!["refs/first".to_string()]),
+            failure(WorkflowFailureKind::Test, vec!["refs/first".to_string()]),
+        ],
+    );
+    let offers = app.left_bottom_lines();
+    assert_eq!(offers.len(), 1);
+    assert!(offers[0].contains("Run /rollback top"));
+    assert!(offers[0].contains("refs/first"));
+}
+
 #[test]
 fn capture_tasks_baseline_reads_tasks_json() {
     let service = DefaultCoreOrchestrationService;
@@ -84,6 +84,159 @@ fn metaagent_config_storage_falls_back_when_storage_section_missing() {
     assert_eq!(parsed.storage.root_dir, "~/.agentbob/sessions");
 }
 
+#[test]
+fn metaagent_config_reads_workflow_retry_budgets_with_defaults() {
+    let parsed: MetaAgentConfig = toml::from_str(crate::default_config::DEFAULT_CONFIG_TOML)
+        .expect("embedded default config should parse");
+    assert_eq!(
+        parsed.workflow.retries.to_budgets(),
+        Ok(RetryBudgets::default())
+    );
+
+    let parsed: MetaAgentConfig = toml::from_str(
+        r#"
+        [workflow.retries]
+        test = 8
+        "#,
+    )
+    .expect("partial retries config should parse");
+    let budgets = parsed.workflow.retries.to_budgets().expect("valid retries");
+    assert_eq!(budgets.test, 8);
+    assert_eq!(budgets.audit, RetryBudgets::default().audit);
+    assert_eq!(budgets.final_audit, RetryBudgets::default().final_audit);
+    assert_eq!(parsed.workflow.flaky_test_reruns, 2);
+}
+
+#[test]
+fn metaagent_config_rejects_a_zero_retry_budget() {
+    let parsed: MetaAgentConfig = toml::from_str(
+        r#"
+        [workflow.retries]
+        final_audit = 0
+        "#,
+    )
+    .expect("retries config should parse");
+    assert_eq!(
+        parsed.workflow.retries.to_budgets(),
+        Err("[workflow.retries] final_audit must be at least 1".to_string())
+    );
+}
+
+#[test]
+fn metaagent_config_reads_workflow_timeouts_with_zero_disabling_a_limit() {
+    let parsed: MetaAgentConfig = toml::from_str(crate::default_config::DEFAULT_CONFIG_TOML)
+        .expect("embedded default config should parse");
+    assert_eq!(parsed.workflow.timeouts.to_timeouts(), JobTimeouts::default());
+
+    let parsed: MetaAgentConfig = toml::from_str(
+        r#"
+        [workflow.timeouts]
+        test_runner = { max_minutes = 5, stall_minutes = 0 }
+        "#,
+    )
+    .expect("partial timeouts config should parse");
+    let timeouts = parsed.workflow.timeouts.to_timeouts();
+    assert_eq!(
+        timeouts.test_runner,
+        JobTimeout {
+            max_runtime: Some(std::time::Duration::from_secs(300)),
+            stall_after: None,
+        }
+    );
+    assert_eq!(timeouts.implementor, JobTimeouts::default().implementor);
+}
+
+#[test]
+fn metaagent_config_reads_custom_worker_roles() {
+    let parsed: MetaAgentConfig = toml::from_str(crate::default_config::DEFAULT_CONFIG_TOML)
+        .expect("embedded default config should parse");
+    assert_eq!(parsed.workflow.custom_roles(), Ok(Vec::new()));
+
+    let parsed: MetaAgentConfig = toml::from_str(
+        r#"
+        [workflow.roles.security_reviewer]
+        instructions = "Look for injection."
+        verdict = "pass_fail"
+        stage = "before_audit"
+        on_fail = "continue"
+        max_retries = 2
+        profile = "small-smart"
+
+        [workflow.roles.docs_writer]
+        title = "Docs writer"
+        prompt = "Update docs for {implementor_title}."
+        permissions = "workspace-write"
+        auto = false
+        "#,
+    )
+    .expect("roles config should parse");
+    let roles = parsed.workflow.custom_roles().expect("valid roles");
+    assert_eq!(roles.len(), 2);
+    assert_eq!(roles[0].name, "docs_writer");
+    assert_eq!(roles[0].title, "Docs writer");
+    assert_eq!(roles[0].stage, RoleStage::AfterAudit);
+    assert_eq!(roles[0].verdict, RoleVerdict::Json);
+    assert!(!roles[0].auto);
+    assert_eq!(
+        roles[0].permissions,
+        Some(crate::agent::PermissionProfile::WorkspaceWrite)
+    );
+    assert_eq!(roles[1].permissions, None);
+    assert_eq!(roles[1].title, "security reviewer");
+    assert_eq!(roles[1].stage, RoleStage::BeforeAudit);
+    assert_eq!(roles[1].verdict, RoleVerdict::PassFail);
+    assert_eq!(roles[1].on_fail, RoleFailurePolicy::Continue);
+    assert_eq!(roles[1].max_retries, Some(2));
+    assert_eq!(roles[1].profile.as_deref(), Some("small-smart"));
+    assert!(roles[1].auto);
+
+    let parsed: MetaAgentConfig = toml::from_str(
+        r#"
+        [workflow.roles.Security]
+        instructions = "Look for injection."
+        "#,
+    )
+    .expect("roles config should parse");
+    assert!(
+        parsed
+            .workflow
+            .custom_roles()
+            .expect_err("uppercase name should be rejected")
+            .contains("lowercase")
+    );
+
+    let parsed: MetaAgentConfig = toml::from_str(
+        r#"
+        [workflow.roles.empty]
+        stage = "after_audit"
+        "#,
+    )
+    .expect("roles config should parse");
+    assert!(
+        parsed
+            .workflow
+            .custom_roles()
+            .expect_err("role without instructions should be rejected")
+            .contains("instructions or prompt")
+    );
+
+    let parsed: MetaAgentConfig = toml::from_str(
+        r#"
+        [workflow.roles.migrations]
+        instructions = "Check migrations."
+        permissions = "root"
+        "#,
+    )
+    .expect("roles config should parse");
+    assert!(
+        parsed
+            .workflow
+            .custom_roles()
+            .expect_err("unknown permissions should be rejected")
+            .contains("permissions must be")
+    );
+}
+
 #[test]
 fn initialize_uses_agentbob_sessions_by_default_in_fresh_home() {
     with_temp_home("session-store-agentbob-default", |home| {
@@ -205,6 +358,20 @@ fn planner_task_accepts_numeric_or_string_ids() {
     assert_eq!(stringy.parent_id.as_deref(), Some("b"));
 }
 
+#[test]
+fn planner_task_depends_on_defaults_empty_and_accepts_numeric_ids() {
+    let missing: PlannerTaskFileEntry =
+        serde_json::from_str("{\"id\":\"a\",\"title\":\"Task\",\"order\":0}")
+            .expect("missing depends_on should parse");
+    assert!(missing.depends_on.is_empty());
+
+    let mixed: PlannerTaskFileEntry = serde_json::from_str(
+        "{\"id\":\"a\",\"title\":\"Task\",\"order\":0,\"depends_on\":[1,\"setup\"]}",
+    )
+    .expect("mixed dependency ids should parse");
+    assert_eq!(mixed.depends_on, vec!["1".to_string(), "setup".to_string()]);
+}
+
 #[test]
 fn planner_task_parses_details_field() {
     let parsed: PlannerTaskFileEntry = serde_json::from_str(
@@ -265,6 +432,7 @@ fn list_sessions_in_root_orders_by_last_used_desc() {
             workspace: "/tmp/w1".to_string(),
             created_at_epoch_secs: 10,
             last_used_epoch_secs: 20,
+            ..SessionMetadata::default()
         })
         .expect("serialize"),
     )
@@ -275,6 +443,7 @@ fn list_sessions_in_root_orders_by_last_used_desc() {
             workspace: "/tmp/w2".to_string(),
             created_at_epoch_secs: 15,
             last_used_epoch_secs: 30,
+            ..SessionMetadata::default()
         })
         .expect("serialize"),
     )
@@ -286,6 +455,10 @@ fn list_sessions_in_root_orders_by_last_used_desc() {
             created_at: "2026-02-16T12:00:00Z".to_string(),
             stack_description: "Rust + Ratatui terminal UI app".to_string(),
             test_command: Some("cargo test".to_string()),
+            lint_command: None,
+            test_report_path: None,
+            coverage_command: None,
+            coverage_threshold: None,
         })
         .expect("serialize"),
     )
@@ -400,6 +573,74 @@ fn create_unique_session_dir_avoids_same_second_workspace_collision() {
     let _ = fs::remove_dir_all(&base);
 }
 
+#[test]
+fn execution_control_persists_across_reopen() {
+    let base = std::env::temp_dir().join(format!(
+        "metaagent-session-paused-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should work")
+            .as_nanos()
+    ));
+    let session_dir = base.join("session-a");
+    fs::create_dir_all(&session_dir).expect("session dir");
+    let cwd = std::env::current_dir().expect("cwd");
+    let store = SessionStore::open_existing(&cwd, &session_dir).expect("open existing");
+    assert_eq!(store.read_execution_control(), ExecutionControl::default());
+
+    store
+        .write_execution_control(ExecutionControl {
+            paused: true,
+            stop_requested: true,
+        })
+        .expect("write control");
+    let reopened = SessionStore::open_existing(&cwd, &session_dir).expect("reopen");
+    let control = reopened.read_execution_control();
+    assert!(control.paused);
+    assert!(control.stop_requested);
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn review_decision_is_taken_once_and_keeps_execution_control() {
+    let base = std::env::temp_dir().join(format!(
+        "metaagent-session-review-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should work")
+            .as_nanos()
+    ));
+    let session_dir = base.join("session-a");
+    fs::create_dir_all(&session_dir).expect("session dir");
+    let cwd = std::env::current_dir().expect("cwd");
+    let store = SessionStore::open_existing(&cwd, &session_dir).expect("open existing");
+    assert_eq!(store.take_review_decision().expect("take empty"), None);
+
+    store
+        .write_execution_control(ExecutionControl {
+            paused: true,
+            stop_requested: false,
+        })
+        .expect("write control");
+    store
+        .write_review_decision(ReviewDecision::Reject {
+            task_id: Some("top1".to_string()),
+            feedback: "Keep the old endpoint".to_string(),
+        })
+        .expect("write decision");
+
+    assert_eq!(
+        store.take_review_decision().expect("take decision"),
+        Some(ReviewDecision::Reject {
+            task_id: Some("top1".to_string()),
+            feedback: "Keep the old endpoint".to_string(),
+        })
+    );
+    assert_eq!(store.take_review_decision().expect("take again"), None);
+    assert!(store.read_execution_control().paused);
+    let _ = fs::remove_dir_all(base);
+}
+
 #[test]
 fn task_fails_round_trip_append() {
     let base = std::env::temp_dir().join(format!(
@@ -422,6 +663,8 @@ fn task_fails_round_trip_append() {
             attempts: 4,
             reason: "Critical blocker".to_string(),
             action_taken: "Continued".to_string(),
+            retry_budget: None,
+            checkpoint_refs: Vec::new(),
             created_at_epoch_secs: 123,
         }])
         .expect("append fails");
@@ -433,6 +676,84 @@ fn task_fails_round_trip_append() {
     let _ = fs::remove_dir_all(&base);
 }
 
+#[test]
+fn flaky_tests_round_trip_append() {
+    let base = std::env::temp_dir().join(format!(
+        "metaagent-session-flaky-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should work")
+            .as_nanos()
+    ));
+    let session_dir = base.join("session-a");
+    fs::create_dir_all(&session_dir).expect("session dir");
+    let cwd = std::env::current_dir().expect("cwd");
+    let store = SessionStore::open_existing(&cwd, &session_dir).expect("open existing");
+    assert!(store.read_flaky_tests().expect("bootstrapped").is_empty());
+
+    for name in ["a::flaky", "b::flaky"] {
+        store
+            .append_flaky_tests(&[FlakyTestFileEntry {
+                top_task_id: 2,
+                test_name: name.to_string(),
+                file: None,
+                message: String::new(),
+                created_at_epoch_secs: 123,
+            }])
+            .expect("append flaky tests");
+    }
+    let read_back = store.read_flaky_tests().expect("read flaky tests");
+    assert_eq!(read_back.len(), 2);
+    assert_eq!(read_back[1].test_name, "b::flaky");
+    assert!(store.flaky_tests_file().ends_with("flaky-tests.json"));
+
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn review_votes_round_trip_append() {
+    let base = std::env::temp_dir().join(format!(
+        "metaagent-session-review-votes-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should work")
+            .as_nanos()
+    ));
+    let session_dir = base.join("session-a");
+    fs::create_dir_all(&session_dir).expect("session dir");
+    let cwd = std::env::current_dir().expect("cwd");
+    let store = SessionStore::open_existing(&cwd, &session_dir).expect("open existing");
+    assert!(store.read_review_votes().expect("bootstrapped").is_empty());
+
+    let vote = |reviewer: u8, passed: bool| ReviewVoteFileEntry {
+        top_task_id: 1,
+        auditor_task_id: "impl-audit".to_string(),
+        audit_pass: 1,
+        reviewer,
+        reviewers: 3,
+        model_profile: Some("large-smart".to_string()),
+        passed,
+        feedback: String::new(),
+        created_at_epoch_secs: 123,
+    };
+    store
+        .append_review_votes(&[vote(1, false), vote(2, true)])
+        .expect("append votes");
+    store
+        .append_review_votes(&[vote(3, true)])
+        .expect("append more votes");
+    let read_back = store.read_review_votes().expect("read votes");
+    assert_eq!(
+        read_back
+            .iter()
+            .map(|entry| (entry.reviewer, entry.passed))
+            .collect::<Vec<_>>(),
+        vec![(1, false), (2, true), (3, true)]
+    );
+
+    let _ = fs::remove_dir_all(&base);
+}
+
 #[test]
 fn read_rolling_context_reports_invalid_json() {
     let base = std::env::temp_dir().join(format!(
@@ -480,6 +801,8 @@ fn append_task_fails_recovers_from_malformed_existing_file() {
             attempts: 1,
             reason: "reason".to_string(),
             action_taken: "action".to_string(),
+            retry_budget: None,
+            checkpoint_refs: Vec::new(),
             created_at_epoch_secs: 456,
         }])
         .expect("append should recover from malformed file");
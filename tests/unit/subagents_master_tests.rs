@@ -16,7 +16,7 @@ fn assert_prompt_omits_removed_test_decision_questions(prompt: &str) {
 
 #[test]
 fn build_master_prompt_in_tests_mode_off_uses_off_policy_without_test_decision_questions() {
-    let prompt = build_master_prompt("/tmp/tasks.json", "Workflow context", false);
+    let prompt = build_master_prompt("/tmp/tasks.json", "Workflow context", false, "");
 
     assert!(
         prompt.contains("Tests mode is OFF. Testing is globally disabled for this planning run.")
@@ -32,7 +32,7 @@ fn build_master_prompt_in_tests_mode_off_uses_off_policy_without_test_decision_q
 
 #[test]
 fn build_master_prompt_in_tests_mode_on_includes_test_writer_and_test_runner_guidance() {
-    let prompt = build_master_prompt("/tmp/tasks.json", "Workflow context", true);
+    let prompt = build_master_prompt("/tmp/tasks.json", "Workflow context", true, "");
 
     assert!(prompt.contains("Tests mode is ON. Test requirements are enabled for planning."));
     assert!(prompt.contains(
@@ -61,7 +61,7 @@ fn build_master_prompt_in_tests_mode_on_includes_test_writer_and_test_runner_gui
 
 #[test]
 fn build_master_prompt_requires_self_contained_isolated_context_details() {
-    let prompt = build_master_prompt("/tmp/tasks.json", "Workflow context", true);
+    let prompt = build_master_prompt("/tmp/tasks.json", "Workflow context", true, "");
     assert!(prompt.contains(
         "Every details field must be self-contained for isolated-context execution"
     ));
@@ -0,0 +1,160 @@
+use super::*;
+
+fn lines(text: &str) -> Vec<String> {
+    text.lines().map(ToString::to_string).collect()
+}
+
+#[test]
+fn parses_libtest_failures_with_panic_location_and_summed_counts() {
+    let output = lines(
+        "running 3 tests\n\
+         test app::tests::ok_case ... ok\n\
+         test app::tests::bad_case ... FAILED\n\
+         test app::tests::silent_case ... FAILED\n\
+         \n\
+         failures:\n\
+         \n\
+         ---- app::tests::bad_case stdout ----\n\
+         thread 'app::tests::bad_case' (42) panicked at src/app.rs:10:5:\n\
+         assertion `left == right` failed\n\
+         \x20 left: 1\n\
+         \x20right: 2\n\
+         note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n\
+         \n\
+         \n\
+         failures:\n\
+         \x20   app::tests::bad_case\n\
+         \x20   app::tests::silent_case\n\
+         \n\
+         test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out\n\
+         running 2 tests\n\
+         test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out",
+    );
+
+    let summary = parse_test_output(&output).expect("libtest summary");
+
+    assert_eq!(
+        summary.counts,
+        TestCounts {
+            passed: 3,
+            failed: 2,
+            flaky: 0
+        }
+    );
+    assert_eq!(summary.failures.len(), 2);
+    let bad = &summary.failures[0];
+    assert_eq!(bad.name, "app::tests::bad_case");
+    assert_eq!(bad.file.as_deref(), Some("src/app.rs:10:5"));
+    assert_eq!(bad.message, "assertion `left == right` failed");
+    assert_eq!(bad.snippet.len(), 4);
+    assert_eq!(summary.failures[1].name, "app::tests::silent_case");
+    assert!(summary.failures[1].file.is_none());
+}
+
+#[test]
+fn parses_pytest_short_summary_and_failure_sections() {
+    let output = lines(
+        "============================= test session starts ==============================\n\
+         collected 3 items\n\
+         \n\
+         tests/test_math.py .F.                                                   [100%]\n\
+         \n\
+         =================================== FAILURES ===================================\n\
+         _________________________________ test_divide __________________________________\n\
+         \n\
+         \x20   def test_divide():\n\
+         >       assert divide(4, 2) == 3\n\
+         E       assert 2 == 3\n\
+         \n\
+         tests/test_math.py:9: AssertionError\n\
+         =========================== short test summary info ============================\n\
+         FAILED tests/test_math.py::test_divide - assert 2 == 3\n\
+         ========================= 1 failed, 2 passed in 0.03s ==========================",
+    );
+
+    let summary = parse_test_output(&output).expect("pytest summary");
+
+    assert_eq!(summary.counts.label(), "2 passed, 1 failed");
+    assert_eq!(
+        summary.failures,
+        vec![TestFailure {
+            name: "tests/test_math.py::test_divide".to_string(),
+            file: Some("tests/test_math.py".to_string()),
+            message: "assert 2 == 3".to_string(),
+            snippet: vec![
+                "E       assert 2 == 3".to_string(),
+                "tests/test_math.py:9: AssertionError".to_string(),
+            ],
+        }]
+    );
+}
+
+#[test]
+fn parses_go_test_failures() {
+    let output = lines(
+        "=== RUN   TestAdd\n\
+         --- PASS: TestAdd (0.00s)\n\
+         === RUN   TestSub\n\
+         \x20   math_test.go:14: Sub(3, 1) = 1; want 2\n\
+         --- FAIL: TestSub (0.00s)\n\
+         FAIL\n\
+         FAIL\texample.com/math\t0.002s",
+    );
+
+    let summary = parse_test_output(&output).expect("go summary");
+
+    assert_eq!(summary.counts.label(), "1 passed, 1 failed");
+    assert_eq!(summary.failures.len(), 1);
+    assert_eq!(summary.failures[0].name, "TestSub");
+}
+
+#[test]
+fn go_test_failure_details_after_the_fail_line_are_captured() {
+    let output = lines(
+        "--- FAIL: TestSub (0.00s)\n\
+         \x20   math_test.go:14: Sub(3, 1) = 1; want 2\n\
+         FAIL\texample.com/math\t0.002s",
+    );
+
+    let summary = parse_test_output(&output).expect("go summary");
+
+    assert_eq!(summary.failures[0].file.as_deref(), Some("math_test.go:14"));
+    assert_eq!(summary.failures[0].message, "Sub(3, 1) = 1; want 2");
+}
+
+#[test]
+fn unrecognized_output_is_not_parsed() {
+    assert!(parse_test_output(&lines("error: could not compile `bob`\nok then")).is_none());
+}
+
+#[test]
+fn parses_junit_xml_failures_errors_and_skips() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="suite" tests="4">
+    <testcase classname="pkg.Mod" name="passes" time="0.1"/>
+    <testcase classname="pkg.Mod" name="fails" file="pkg/mod.py">
+      <failure message="expected &quot;a&quot;">Traceback
+  line &lt;1&gt;</failure>
+    </testcase>
+    <testcase classname="pkg.Mod" name="errors"><error message="boom"><![CDATA[stack]]></error></testcase>
+    <testcase classname="pkg.Mod" name="skipped"><skipped/></testcase>
+  </testsuite>
+</testsuites>"#;
+
+    let summary = parse_junit_xml(xml).expect("junit summary");
+
+    assert_eq!(summary.counts.label(), "1 passed, 2 failed");
+    assert_eq!(
+        summary.failures[0],
+        TestFailure {
+            name: "pkg.Mod::fails".to_string(),
+            file: Some("pkg/mod.py".to_string()),
+            message: "expected \"a\"".to_string(),
+            snippet: vec!["Traceback".to_string(), "  line <1>".to_string()],
+        }
+    );
+    assert_eq!(summary.failures[1].name, "pkg.Mod::errors");
+    assert_eq!(summary.failures[1].snippet, vec!["stack".to_string()]);
+    assert!(parse_junit_xml("<testsuite/>").is_none());
+}
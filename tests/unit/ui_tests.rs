@@ -42,6 +42,7 @@ fn seed_execution_plan(app: &mut App) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1".to_string(),
@@ -52,6 +53,7 @@ fn seed_execution_plan(app: &mut App) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1-audit".to_string(),
@@ -62,6 +64,7 @@ fn seed_execution_plan(app: &mut App) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("seed plan should sync");
@@ -599,6 +602,7 @@ fn right_pane_toggle_hit_test_returns_task_key_on_marker_click() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1".to_string(),
@@ -609,6 +613,7 @@ fn right_pane_toggle_hit_test_returns_task_key_on_marker_click() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("task-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1-audit".to_string(),
@@ -619,6 +624,7 @@ fn right_pane_toggle_hit_test_returns_task_key_on_marker_click() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -661,6 +667,7 @@ fn right_pane_toggle_hit_test_returns_docs_toggle_key_on_docs_marker_click() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1".to_string(),
@@ -675,6 +682,7 @@ fn right_pane_toggle_hit_test_returns_docs_toggle_key_on_docs_marker_click() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("task-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1-audit".to_string(),
@@ -685,6 +693,7 @@ fn right_pane_toggle_hit_test_returns_docs_toggle_key_on_docs_marker_click() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -1,4 +1,6 @@
 use super::*;
+use super::audit_verdict::{AuditSeverity, AuditStatus};
+use crate::pass_diff::PassDiffFile;
 
 const REMOVED_TEST_DECISION_QUESTIONS: [&str; 5] = [
     "Testing-decision flow before initial planning in a session:",
@@ -25,6 +27,7 @@ fn seed_single_default_task(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -35,6 +38,7 @@ fn seed_single_default_task(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -45,6 +49,7 @@ fn seed_single_default_task(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -55,6 +60,7 @@ fn seed_single_default_task(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -65,13 +71,19 @@ fn seed_single_default_task(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("seed plan should sync");
 }
 
 fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &str) {
-    wf.sync_planner_tasks_from_file(vec![
+    wf.sync_planner_tasks_from_file(two_default_task_entries(first_title, second_title))
+        .expect("seed plan should sync");
+}
+
+fn two_default_task_entries(first_title: &str, second_title: &str) -> Vec<PlannerTaskFileEntry> {
+    vec![
         PlannerTaskFileEntry {
             id: "top1".to_string(),
             title: first_title.to_string(),
@@ -81,6 +93,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl1".to_string(),
@@ -91,6 +104,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl1-audit".to_string(),
@@ -101,6 +115,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw1".to_string(),
@@ -111,6 +126,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top1".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw1-runner".to_string(),
@@ -121,6 +137,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top2".to_string(),
@@ -131,6 +148,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl2".to_string(),
@@ -141,6 +159,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top2".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl2-audit".to_string(),
@@ -151,6 +170,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl2".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw2".to_string(),
@@ -161,6 +181,7 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top2".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw2-runner".to_string(),
@@ -171,9 +192,9 @@ fn seed_two_default_tasks(wf: &mut Workflow, first_title: &str, second_title: &s
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw2".to_string()),
             order: Some(0),
+            ..Default::default()
         },
-    ])
-    .expect("seed plan should sync");
+    ]
 }
 
 fn seed_single_default_task_with_final_audit(wf: &mut Workflow, title: &str) {
@@ -187,6 +208,7 @@ fn seed_single_default_task_with_final_audit(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -197,6 +219,7 @@ fn seed_single_default_task_with_final_audit(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -207,6 +230,7 @@ fn seed_single_default_task_with_final_audit(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -217,6 +241,7 @@ fn seed_single_default_task_with_final_audit(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -227,6 +252,7 @@ fn seed_single_default_task_with_final_audit(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "fa".to_string(),
@@ -237,6 +263,7 @@ fn seed_single_default_task_with_final_audit(wf: &mut Workflow, title: &str) {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("seed plan should sync");
@@ -284,6 +311,7 @@ fn syncs_planner_tasks_from_file_entries() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "child".to_string(),
@@ -294,6 +322,7 @@ fn syncs_planner_tasks_from_file_entries() {
                 status: PlannerTaskStatusFile::InProgress,
                 parent_id: Some("parent".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "child-audit".to_string(),
@@ -304,6 +333,7 @@ fn syncs_planner_tasks_from_file_entries() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("child".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
         ])
         .expect("sync should succeed");
@@ -326,6 +356,7 @@ fn renders_nested_task_blocks_with_local_wrapping() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "c".to_string(),
@@ -336,6 +367,7 @@ fn renders_nested_task_blocks_with_local_wrapping() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("p".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "c-audit".to_string(),
@@ -346,6 +378,7 @@ fn renders_nested_task_blocks_with_local_wrapping() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("c".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -416,6 +449,7 @@ fn top_task_does_not_complete_until_all_implementor_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1".to_string(),
@@ -426,6 +460,7 @@ fn top_task_does_not_complete_until_all_implementor_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-1-audit".to_string(),
@@ -436,6 +471,7 @@ fn top_task_does_not_complete_until_all_implementor_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-2".to_string(),
@@ -446,6 +482,7 @@ fn top_task_does_not_complete_until_all_implementor_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-2-audit".to_string(),
@@ -456,6 +493,7 @@ fn top_task_does_not_complete_until_all_implementor_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl-2".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -504,6 +542,7 @@ fn top_task_does_not_complete_until_all_test_writer_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -514,6 +553,7 @@ fn top_task_does_not_complete_until_all_test_writer_branches_are_done() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -524,6 +564,7 @@ fn top_task_does_not_complete_until_all_test_writer_branches_are_done() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-1".to_string(),
@@ -534,6 +575,7 @@ fn top_task_does_not_complete_until_all_test_writer_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-1-runner".to_string(),
@@ -544,6 +586,7 @@ fn top_task_does_not_complete_until_all_test_writer_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw-1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-2".to_string(),
@@ -554,6 +597,7 @@ fn top_task_does_not_complete_until_all_test_writer_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(2),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-2-runner".to_string(),
@@ -564,6 +608,7 @@ fn top_task_does_not_complete_until_all_test_writer_branches_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw-2".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -627,10 +672,12 @@ fn top_level_tasks_run_sequentially_in_order() {
         match job.role {
             WorkerRole::Implementor => wf.append_active_output("implemented".to_string()),
             WorkerRole::TestWriter => wf.append_active_output("wrote tests".to_string()),
-            WorkerRole::Auditor => {
+            WorkerRole::Auditor | WorkerRole::Custom => {
                 wf.append_active_output("PASS".to_string());
             }
-            WorkerRole::TestRunner => wf.append_active_output("all passed".to_string()),
+            WorkerRole::TestRunner | WorkerRole::LintRunner | WorkerRole::CoverageRunner => {
+                wf.append_active_output("all passed".to_string())
+            }
             WorkerRole::FinalAudit => wf.append_active_output("PASS".to_string()),
         }
         let messages = wf.finish_active_job(true, 0);
@@ -682,7 +729,61 @@ fn deterministic_test_runner_loops_back_to_test_writer_on_failure() {
             assert!(prompt.contains("Deterministic test run failed"));
             assert!(prompt.contains("test failure output"));
         }
-        JobRun::DeterministicTestRun => panic!("expected agent prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected agent prompt")
+        }
+    }
+}
+
+#[test]
+fn parsed_test_failures_replace_raw_output_in_retry_feedback_and_right_pane() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+
+    let _ = wf.start_next_job().expect("implementor");
+    wf.finish_active_job(true, 0);
+    let _ = wf.start_next_job().expect("auditor");
+    wf.append_active_output("PASS".to_string());
+    wf.finish_active_job(true, 0);
+    let _ = wf.start_next_job().expect("test writer");
+    wf.finish_active_job(true, 0);
+
+    let runner = wf.start_next_job().expect("test runner");
+    assert_eq!(runner.role, WorkerRole::TestRunner);
+    for line in [
+        "   Compiling bob v0.1.0",
+        "test tests::passes ... ok",
+        "test tests::breaks ... FAILED",
+        "---- tests::breaks stdout ----",
+        "thread 'tests::breaks' (7) panicked at src/lib.rs:3:9:",
+        "explicit panic",
+        "failures:",
+        "test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out",
+    ] {
+        wf.append_active_output(line.to_string());
+    }
+    wf.finish_active_job(false, 101);
+
+    let view = wf.right_pane_block_view(80, &HashSet::new());
+    assert!(view.lines.iter().any(|line| line.contains("(1 passed, 1 failed)")));
+    let retry = wf.start_next_job().expect("test writer retry");
+    match retry.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains(
+                "Deterministic test run failed with code 101 (1 passed, 1 failed). Failing tests:\n\
+                 1. tests::breaks (src/lib.rs:3:9)\n   explicit panic"
+            ));
+            assert!(!prompt.contains("Compiling bob"));
+            assert!(!prompt.contains("tests::passes"));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected agent prompt")
+        }
     }
 }
 
@@ -700,6 +801,7 @@ fn tests_mode_off_sync_keeps_top_task_pending_until_runtime_children_exist() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }])
     .expect("sync should succeed");
 
@@ -761,6 +863,7 @@ fn test_writer_child_auditor_runs_before_test_runner_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -771,6 +874,7 @@ fn test_writer_child_auditor_runs_before_test_runner_when_present() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -781,6 +885,7 @@ fn test_writer_child_auditor_runs_before_test_runner_when_present() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -791,6 +896,7 @@ fn test_writer_child_auditor_runs_before_test_runner_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-audit".to_string(),
@@ -801,6 +907,7 @@ fn test_writer_child_auditor_runs_before_test_runner_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -811,6 +918,7 @@ fn test_writer_child_auditor_runs_before_test_runner_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -836,7 +944,11 @@ fn test_writer_child_auditor_runs_before_test_runner_when_present() {
             assert!(prompt.contains("do not run tests"));
             assert!(prompt.contains("do not execute/check shell commands"));
         }
-        JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
     }
     wf.append_active_output("PASS".to_string());
     wf.finish_active_job(true, 0);
@@ -858,6 +970,7 @@ fn failing_test_writer_child_auditor_queues_test_writer_retry() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -868,6 +981,7 @@ fn failing_test_writer_child_auditor_queues_test_writer_retry() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -878,6 +992,7 @@ fn failing_test_writer_child_auditor_queues_test_writer_retry() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -888,6 +1003,7 @@ fn failing_test_writer_child_auditor_queues_test_writer_retry() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-audit".to_string(),
@@ -898,6 +1014,7 @@ fn failing_test_writer_child_auditor_queues_test_writer_retry() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -908,6 +1025,7 @@ fn failing_test_writer_child_auditor_queues_test_writer_retry() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -934,7 +1052,11 @@ fn failing_test_writer_child_auditor_queues_test_writer_retry() {
             assert!(prompt.contains("Audit feedback"));
             assert!(prompt.contains("Missing edge-case assertions"));
         }
-        JobRun::DeterministicTestRun => panic!("expected test writer prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected test writer prompt")
+        }
     }
 }
 
@@ -951,6 +1073,7 @@ fn multiple_implementor_audits_run_sequentially() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -961,6 +1084,7 @@ fn multiple_implementor_audits_run_sequentially() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "audit-1".to_string(),
@@ -971,6 +1095,7 @@ fn multiple_implementor_audits_run_sequentially() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "audit-2".to_string(),
@@ -981,6 +1106,7 @@ fn multiple_implementor_audits_run_sequentially() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -1013,6 +1139,7 @@ fn exhausted_implementor_audit_moves_to_next_audit() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -1023,6 +1150,7 @@ fn exhausted_implementor_audit_moves_to_next_audit() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "audit-1".to_string(),
@@ -1033,6 +1161,7 @@ fn exhausted_implementor_audit_moves_to_next_audit() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "audit-2".to_string(),
@@ -1043,6 +1172,7 @@ fn exhausted_implementor_audit_moves_to_next_audit() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -1128,14 +1258,14 @@ fn test_fix_loop_stops_after_five_failed_test_runs() {
     wf.append_active_output("implemented".to_string());
     wf.finish_active_job(true, 0);
 
-    let _ = wf.start_next_job().expect("test writer");
-    wf.append_active_output("wrote tests".to_string());
-    wf.finish_active_job(true, 0);
-
     let _ = wf.start_next_job().expect("auditor");
     wf.append_active_output("PASS".to_string());
     wf.finish_active_job(true, 0);
 
+    let _ = wf.start_next_job().expect("test writer");
+    wf.append_active_output("wrote tests".to_string());
+    wf.finish_active_job(true, 0);
+
     let mut last_messages = Vec::new();
     for pass in 1..=5 {
         let runner = wf.start_next_job().expect("test runner");
@@ -1167,7 +1297,11 @@ fn test_fix_loop_stops_after_five_failed_test_runs() {
         JobRun::AgentPrompt(prompt) => {
             assert!(prompt.contains("Remove the failing tests completely"));
         }
-        JobRun::DeterministicTestRun => panic!("expected cleanup writer prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected cleanup writer prompt")
+        }
     }
     wf.append_active_output("Removed failing tests".to_string());
     wf.finish_active_job(true, 0);
@@ -1203,7 +1337,11 @@ fn auditor_output_is_forwarded_to_implementor_retry_prompt() {
             assert!(prompt.contains("Audit feedback"));
             assert!(prompt.contains("Issue: missing edge-case handling"));
         }
-        JobRun::DeterministicTestRun => panic!("expected implementor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected implementor prompt")
+        }
     }
 }
 
@@ -1230,12 +1368,74 @@ fn implementor_changed_files_summary_is_forwarded_to_auditor_prompt() {
     match auditor.run {
         JobRun::AgentPrompt(prompt) => {
             assert!(prompt.contains("Implementor changed-files summary"));
-            assert!(prompt.contains("PASS"));
-            assert!(prompt.contains("FAIL"));
+            assert!(prompt.contains("```json"));
+            assert!(prompt.contains("\"severity\""));
             assert!(prompt.contains("- src/app.rs: added state transition for command handling"));
             assert!(prompt.contains("- src/ui.rs: updated rendering path for task block layout"));
         }
-        JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
+    }
+}
+
+#[test]
+fn observed_pass_diff_and_self_report_discrepancies_reach_auditor_prompt() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+
+    let implementor = wf.start_next_job().expect("implementor");
+    assert_eq!(
+        implementor.pass_label.as_deref(),
+        Some("implementor-pass-1")
+    );
+    wf.append_active_output("FILES_CHANGED_BEGIN".to_string());
+    wf.append_active_output("- src/app.rs: added command handling".to_string());
+    wf.append_active_output("- src/ui.rs: updated rendering".to_string());
+    wf.append_active_output("FILES_CHANGED_END".to_string());
+    wf.record_pass_diff(
+        implementor.top_task_id,
+        PassDiff {
+            files: vec![
+                PassDiffFile {
+                    status: 'M',
+                    path: "src/app.rs".to_string(),
+                },
+                PassDiffFile {
+                    status: 'A',
+                    path: "src/secret.rs".to_string(),
+                },
+            ],
+            patch: "+fn handle() {}\n".to_string(),
+            diff_file: Some(PathBuf::from("/session/diffs/1/implementor-pass-1.diff")),
+        },
+    );
+    wf.finish_active_job(true, 0);
+
+    let auditor = wf.start_next_job().expect("auditor");
+    assert_eq!(auditor.pass_label, None);
+    match auditor.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains("Files:\nM src/app.rs\nA src/secret.rs"));
+            assert!(
+                prompt.contains("Full diff saved at: /session/diffs/1/implementor-pass-1.diff")
+            );
+            assert!(prompt.contains("```diff\n+fn handle() {}\n```"));
+            assert!(prompt.contains(
+                "- src/secret.rs changed in the workspace but is missing from FILES_CHANGED."
+            ));
+            assert!(prompt.contains(
+                "- src/ui.rs is listed in FILES_CHANGED but was not changed in the workspace."
+            ));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
     }
 }
 
@@ -1292,11 +1492,86 @@ fn audit_result_token_is_rejected_when_not_first_line() {
 }
 
 #[test]
-fn missing_audit_result_token_falls_back_to_heuristics() {
+fn missing_audit_verdict_is_not_treated_as_a_pass() {
     assert!(audit_detects_issues(&[
-        "Issue: edge case missing".to_string()
+        "Reviewed the error handling paths; looks good.".to_string()
     ]));
-    assert!(!audit_detects_issues(&["No issues found".to_string()]));
+    assert!(audit_detects_issues(&["No issues found".to_string()]));
+}
+
+#[test]
+fn audit_verdict_only_blocks_on_blocking_severities() {
+    let advisory = vec![
+        "Reviewed error handling.".to_string(),
+        "```json".to_string(),
+        r#"{"status": "pass", "findings": [{"severity": "low", "file": "src/app.rs", "line": 10, "message": "Rename helper", "suggested_fix": null}]}"#.to_string(),
+        "```".to_string(),
+    ];
+    assert!(!audit_detects_issues(&advisory));
+
+    let blocking = vec![
+        "```json".to_string(),
+        r#"{"status": "fail", "findings": [{"severity": "high", "file": "src/app.rs", "line": 42, "message": "Panics on empty input", "suggested_fix": "Return early when input is empty"}, {"severity": "low", "message": "Typo in comment"}]}"#.to_string(),
+        "```".to_string(),
+    ];
+    assert!(audit_detects_issues(&blocking));
+
+    let verdict = parse_audit_verdict(&blocking).expect("verdict should parse");
+    assert_eq!(verdict.status, AuditStatus::Fail);
+    assert_eq!(verdict.findings.len(), 2);
+    assert_eq!(verdict.findings[0].severity, AuditSeverity::High);
+    assert_eq!(verdict.findings[0].line, Some(42));
+}
+
+#[test]
+fn audit_feedback_lists_only_unresolved_blocking_findings() {
+    let transcript = vec![
+        "Long narrative that should not be forwarded.".to_string(),
+        "```json".to_string(),
+        r#"{"status": "fail", "findings": [{"severity": "critical", "file": "src/app.rs", "line": 7, "message": "Data loss on save", "suggested_fix": "Write to a temp file first"}, {"severity": "medium", "message": "Consider caching"}]}"#.to_string(),
+        "```".to_string(),
+    ];
+    let feedback = audit_feedback(&transcript, 0, true);
+
+    assert!(feedback.contains("[critical] src/app.rs:7 Data loss on save"));
+    assert!(feedback.contains("Suggested fix: Write to a temp file first"));
+    assert!(!feedback.contains("Consider caching"));
+    assert!(!feedback.contains("Long narrative"));
+}
+
+#[test]
+fn blocking_audit_verdict_feeds_findings_into_implementor_retry() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+
+    let _ = wf.start_next_job().expect("implementor pass 1");
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+
+    let _ = wf.start_next_job().expect("auditor");
+    wf.append_active_output("Audit notes mention error handling.".to_string());
+    wf.append_active_output("```json".to_string());
+    wf.append_active_output(
+        r#"{"status": "fail", "findings": [{"severity": "high", "file": "src/lib.rs", "line": 3, "message": "Missing bounds check"}]}"#
+            .to_string(),
+    );
+    wf.append_active_output("```".to_string());
+    wf.finish_active_job(true, 0);
+
+    let retry = wf.start_next_job().expect("implementor retry");
+    assert_eq!(retry.role, WorkerRole::Implementor);
+    match retry.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains("[high] src/lib.rs:3 Missing bounds check"));
+            assert!(!prompt.contains("Audit notes mention error handling."));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected implementor prompt")
+        }
+    }
 }
 
 #[test]
@@ -1329,7 +1604,11 @@ fn audit_retry_limit_stops_after_four_failed_audits() {
                     assert!(prompt.contains("truly critical blockers"));
                 }
             }
-            JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+            JobRun::DeterministicTestRun
+            | JobRun::DeterministicLintRun
+            | JobRun::DeterministicCoverageRun => {
+                panic!("expected auditor prompt")
+            }
         }
         wf.append_active_output("FAIL".to_string());
         wf.append_active_output("- Critical blocker still present".to_string());
@@ -1372,6 +1651,180 @@ fn audit_retry_limit_stops_after_four_failed_audits() {
     assert_eq!(failures[0].attempts, 4);
 }
 
+#[test]
+fn configured_audit_retry_budget_applies_and_is_reported() {
+    let mut wf = Workflow::default();
+    wf.set_retry_budgets(RetryBudgets {
+        audit: 2,
+        ..RetryBudgets::default()
+    });
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+
+    let _ = wf.start_next_job().expect("implementor pass 1");
+    wf.append_active_output("implemented v1".to_string());
+    wf.finish_active_job(true, 0);
+
+    let mut last_messages = Vec::new();
+    for audit_pass in 1..=2 {
+        let auditor = wf.start_next_job().expect("auditor");
+        assert_eq!(auditor.role, WorkerRole::Auditor);
+        match auditor.run {
+            JobRun::AgentPrompt(prompt) => {
+                assert!(prompt.contains(&format!("Audit pass: {audit_pass} of 2")));
+            }
+            JobRun::DeterministicTestRun
+            | JobRun::DeterministicLintRun
+            | JobRun::DeterministicCoverageRun => {
+                panic!("expected auditor prompt")
+            }
+        }
+        wf.append_active_output("FAIL".to_string());
+        wf.append_active_output("- Critical blocker still present".to_string());
+        last_messages = wf.finish_active_job(true, 0);
+
+        if audit_pass < 2 {
+            let implementor = wf.start_next_job().expect("implementor retry");
+            assert_eq!(implementor.role, WorkerRole::Implementor);
+            wf.append_active_output(format!("implemented retry {audit_pass}"));
+            wf.finish_active_job(true, 0);
+        }
+    }
+
+    assert!(
+        last_messages
+            .iter()
+            .any(|m| m.contains("Max retries (2) reached"))
+    );
+    let failures = wf.drain_recent_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].attempts, 2);
+    assert_eq!(
+        failures[0].retry_budget,
+        Some(RetryBudget {
+            limit: 2,
+            source: "[workflow.retries] audit".to_string(),
+        })
+    );
+}
+
+#[test]
+fn checkpoint_labels_are_assigned_only_when_enabled() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+    let implementor = wf.start_next_job().expect("implementor pass 1");
+    assert_eq!(implementor.checkpoint_label, None);
+
+    let mut wf = Workflow::default();
+    wf.set_checkpoints_enabled(true);
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+    let implementor = wf.start_next_job().expect("implementor pass 1");
+    assert_eq!(
+        implementor.checkpoint_label.as_deref(),
+        Some("implementor-pass-1")
+    );
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+    let auditor = wf.start_next_job().expect("auditor");
+    assert_eq!(auditor.checkpoint_label, None);
+}
+
+#[test]
+fn exhausted_failures_carry_recorded_checkpoint_refs() {
+    let mut wf = Workflow::default();
+    wf.set_retry_budgets(RetryBudgets {
+        audit: 1,
+        ..RetryBudgets::default()
+    });
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+
+    let implementor = wf.start_next_job().expect("implementor pass 1");
+    let checkpoint = "refs/bob/checkpoints/top/001-implementor-pass-1".to_string();
+    wf.record_task_checkpoint(implementor.top_task_id, checkpoint.clone());
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+    let _ = wf.start_next_job().expect("auditor");
+    wf.append_active_output("FAIL".to_string());
+    wf.append_active_output("- Still broken".to_string());
+    wf.finish_active_job(true, 0);
+
+    let failures = wf.drain_recent_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].checkpoint_refs, vec![checkpoint]);
+}
+
+#[test]
+fn resolve_top_task_id_accepts_file_ids_and_numbers() {
+    let mut wf = Workflow::default();
+    wf.sync_planner_tasks_from_file(two_default_task_entries("Task One", "Task Two"))
+        .expect("seed plan should sync");
+    let top_id = wf.resolve_top_task_id("top1").expect("file id resolves");
+
+    assert_eq!(wf.resolve_top_task_id(&format!("#{top_id}")), Some(top_id));
+    assert_eq!(wf.resolve_top_task_id(&top_id.to_string()), Some(top_id));
+    assert_eq!(wf.resolve_top_task_id("missing"), None);
+}
+
+#[test]
+fn task_max_retries_overrides_configured_budget() {
+    let mut wf = Workflow::default();
+    let mut entries = two_default_task_entries("Docs tweak", "Other");
+    entries
+        .iter_mut()
+        .find(|entry| entry.id == "top1")
+        .expect("top1 entry")
+        .max_retries = Some(1);
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("seed plan should sync");
+    wf.start_execution();
+
+    let implementor = wf.start_next_job().expect("implementor pass 1");
+    wf.append_output_for_task(implementor.top_task_id, "implemented".to_string());
+    wf.finish_job_for_task(implementor.top_task_id, true, 0);
+
+    let auditor = wf.start_next_job().expect("auditor");
+    match auditor.run {
+        JobRun::AgentPrompt(prompt) => assert!(prompt.contains("Audit pass: 1 of 1")),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
+    }
+    wf.append_output_for_task(auditor.top_task_id, "FAIL".to_string());
+    wf.append_output_for_task(auditor.top_task_id, "- Still broken".to_string());
+    let messages = wf.finish_job_for_task(auditor.top_task_id, true, 0);
+
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("Max retries (1) reached"))
+    );
+    let failures = wf.drain_recent_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(
+        failures[0].retry_budget,
+        Some(RetryBudget {
+            limit: 1,
+            source: "max_retries on task top1".to_string(),
+        })
+    );
+}
+
+#[test]
+fn sync_rejects_zero_max_retries() {
+    let mut wf = Workflow::default();
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    entries[0].max_retries = Some(0);
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("zero retry budget should fail");
+    assert_eq!(err, "Planner task top1 max_retries must be at least 1");
+}
+
 #[test]
 fn implementor_owned_test_runner_runs_after_audits_when_present() {
     let mut wf = Workflow::default();
@@ -1385,6 +1838,7 @@ fn implementor_owned_test_runner_runs_after_audits_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl1".to_string(),
@@ -1395,6 +1849,7 @@ fn implementor_owned_test_runner_runs_after_audits_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("t1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "runner1".to_string(),
@@ -1405,6 +1860,7 @@ fn implementor_owned_test_runner_runs_after_audits_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl1".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "audit1".to_string(),
@@ -1415,6 +1871,7 @@ fn implementor_owned_test_runner_runs_after_audits_when_present() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -1453,6 +1910,7 @@ fn implementor_owned_test_runner_exhaustion_records_failure_and_continues_to_nex
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl1".to_string(),
@@ -1463,6 +1921,7 @@ fn implementor_owned_test_runner_exhaustion_records_failure_and_continues_to_nex
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("t1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "runner1".to_string(),
@@ -1473,6 +1932,7 @@ fn implementor_owned_test_runner_exhaustion_records_failure_and_continues_to_nex
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl1".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "audit1".to_string(),
@@ -1483,6 +1943,7 @@ fn implementor_owned_test_runner_exhaustion_records_failure_and_continues_to_nex
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl1".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -1535,14 +1996,14 @@ fn top_task_done_requires_impl_and_test_branches() {
     wf.append_active_output("implemented".to_string());
     wf.finish_active_job(true, 0);
 
-    let _ = wf.start_next_job().expect("test writer");
-    wf.append_active_output("wrote tests".to_string());
-    wf.finish_active_job(true, 0);
-
     let _ = wf.start_next_job().expect("auditor");
     wf.append_active_output("PASS".to_string());
     wf.finish_active_job(true, 0);
 
+    let _ = wf.start_next_job().expect("test writer");
+    wf.append_active_output("wrote tests".to_string());
+    wf.finish_active_job(true, 0);
+
     let tree = wf.right_pane_lines().join("\n");
     assert!(!tree.contains("[x] Task: Do work"));
 
@@ -1567,6 +2028,7 @@ fn sync_rejects_tasks_missing_details() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         }])
         .expect_err("missing details should fail");
     assert!(err.contains("non-empty details"));
@@ -1589,6 +2051,7 @@ fn sync_rejects_reload_while_execution_has_active_or_queued_work() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         }])
         .expect_err("reload should be blocked while execution is busy");
     assert!(err.contains("Cannot reload planner tasks while execution is enabled"));
@@ -1611,6 +2074,7 @@ fn sync_allows_reload_when_execution_is_enabled_but_idle() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         }])
         .expect("reload should succeed when execution is idle");
     assert_eq!(count, 1);
@@ -1629,6 +2093,7 @@ fn auto_generated_subtasks_include_non_empty_details_for_reload() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }])
     .expect("seed plan should sync");
 
@@ -1666,6 +2131,7 @@ fn mid_run_snapshot_with_auto_generated_branches_is_reloadable() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }])
     .expect("seed plan should sync");
 
@@ -1704,6 +2170,7 @@ fn sync_rejects_implementor_without_auditor() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -1714,6 +2181,7 @@ fn sync_rejects_implementor_without_auditor() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
         ])
         .expect_err("should reject missing auditor");
@@ -1734,6 +2202,7 @@ fn sync_rejects_implementor_test_runner_before_audit() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -1744,6 +2213,7 @@ fn sync_rejects_implementor_test_runner_before_audit() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-runner".to_string(),
@@ -1754,6 +2224,7 @@ fn sync_rejects_implementor_test_runner_before_audit() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -1764,6 +2235,7 @@ fn sync_rejects_implementor_test_runner_before_audit() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
         ])
         .expect_err("should reject runner before audit");
@@ -1784,6 +2256,7 @@ fn sync_rejects_test_writer_without_test_runner() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -1794,6 +2267,7 @@ fn sync_rejects_test_writer_without_test_runner() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -1804,6 +2278,7 @@ fn sync_rejects_test_writer_without_test_runner() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tw".to_string(),
@@ -1814,6 +2289,7 @@ fn sync_rejects_test_writer_without_test_runner() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
         ])
         .expect_err("should reject missing test runner");
@@ -1834,6 +2310,7 @@ fn sync_rejects_nested_test_writer_groups() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -1844,6 +2321,7 @@ fn sync_rejects_nested_test_writer_groups() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -1854,6 +2332,7 @@ fn sync_rejects_nested_test_writer_groups() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tests-parent".to_string(),
@@ -1864,6 +2343,7 @@ fn sync_rejects_nested_test_writer_groups() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tests-parent-runner".to_string(),
@@ -1874,6 +2354,7 @@ fn sync_rejects_nested_test_writer_groups() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("tests-parent".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tests-child".to_string(),
@@ -1884,6 +2365,7 @@ fn sync_rejects_nested_test_writer_groups() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("tests-parent".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tests-child-runner".to_string(),
@@ -1894,6 +2376,7 @@ fn sync_rejects_nested_test_writer_groups() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("tests-child".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
         ])
         .expect_err("should reject nested test writer grouping");
@@ -1914,6 +2397,7 @@ fn sync_rejects_nested_implementor_branches() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-root".to_string(),
@@ -1924,6 +2408,7 @@ fn sync_rejects_nested_implementor_branches() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-root-audit".to_string(),
@@ -1934,6 +2419,7 @@ fn sync_rejects_nested_implementor_branches() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl-root".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-nested".to_string(),
@@ -1944,6 +2430,7 @@ fn sync_rejects_nested_implementor_branches() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl-root".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-nested-audit".to_string(),
@@ -1954,6 +2441,7 @@ fn sync_rejects_nested_implementor_branches() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl-nested".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
         ])
         .expect_err("should reject nested implementor branch");
@@ -1975,6 +2463,7 @@ fn sync_rejects_nested_final_audit_task() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -1985,6 +2474,7 @@ fn sync_rejects_nested_final_audit_task() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -1995,6 +2485,7 @@ fn sync_rejects_nested_final_audit_task() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "nested-final".to_string(),
@@ -2005,6 +2496,7 @@ fn sync_rejects_nested_final_audit_task() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
         ])
         .expect_err("should reject nested final audit task");
@@ -2026,6 +2518,7 @@ fn sync_rejects_auditor_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -2036,6 +2529,7 @@ fn sync_rejects_auditor_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -2046,6 +2540,7 @@ fn sync_rejects_auditor_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "orphan-audit".to_string(),
@@ -2056,6 +2551,7 @@ fn sync_rejects_auditor_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
         ])
         .expect_err("should reject auditor parent kind");
@@ -2077,6 +2573,7 @@ fn sync_rejects_test_runner_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -2087,6 +2584,7 @@ fn sync_rejects_test_runner_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -2097,6 +2595,7 @@ fn sync_rejects_test_runner_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "orphan-runner".to_string(),
@@ -2107,6 +2606,7 @@ fn sync_rejects_test_runner_with_invalid_parent_kind() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
         ])
         .expect_err("should reject test-runner parent kind");
@@ -2128,6 +2628,7 @@ fn sync_rejects_multiple_test_runners_under_implementor() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -2138,6 +2639,7 @@ fn sync_rejects_multiple_test_runners_under_implementor() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -2148,6 +2650,7 @@ fn sync_rejects_multiple_test_runners_under_implementor() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-runner-1".to_string(),
@@ -2158,6 +2661,7 @@ fn sync_rejects_multiple_test_runners_under_implementor() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-runner-2".to_string(),
@@ -2168,6 +2672,7 @@ fn sync_rejects_multiple_test_runners_under_implementor() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(2),
+                ..Default::default()
             },
         ])
         .expect_err("should reject multiple implementor test runners");
@@ -2189,6 +2694,7 @@ fn sync_rejects_multiple_test_runners_under_test_writer() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: None,
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl".to_string(),
@@ -2199,6 +2705,7 @@ fn sync_rejects_multiple_test_runners_under_test_writer() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "impl-audit".to_string(),
@@ -2209,6 +2716,7 @@ fn sync_rejects_multiple_test_runners_under_test_writer() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("impl".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tw".to_string(),
@@ -2219,6 +2727,7 @@ fn sync_rejects_multiple_test_runners_under_test_writer() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("top".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tw-runner-1".to_string(),
@@ -2229,6 +2738,7 @@ fn sync_rejects_multiple_test_runners_under_test_writer() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("tw".to_string()),
                 order: Some(0),
+                ..Default::default()
             },
             PlannerTaskFileEntry {
                 id: "tw-runner-2".to_string(),
@@ -2239,6 +2749,7 @@ fn sync_rejects_multiple_test_runners_under_test_writer() {
                 status: PlannerTaskStatusFile::Pending,
                 parent_id: Some("tw".to_string()),
                 order: Some(1),
+                ..Default::default()
             },
         ])
         .expect_err("should reject multiple test-writer test runners");
@@ -2258,7 +2769,11 @@ fn implementor_and_auditor_prompts_include_test_guardrails() {
             assert!(prompt.contains("Implementation details:"));
             assert!(prompt.contains("implementor details"));
         }
-        JobRun::DeterministicTestRun => panic!("expected implementor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected implementor prompt")
+        }
     }
 
     wf.append_active_output("implemented".to_string());
@@ -2273,7 +2788,11 @@ fn implementor_and_auditor_prompts_include_test_guardrails() {
             assert!(prompt.contains("do not run tests"));
             assert!(prompt.contains("do not execute/check shell commands"));
         }
-        JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
     }
 }
 
@@ -2290,6 +2809,7 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -2304,6 +2824,7 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -2318,6 +2839,7 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -2332,6 +2854,7 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-audit".to_string(),
@@ -2346,6 +2869,7 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -2356,6 +2880,7 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "final".to_string(),
@@ -2370,6 +2895,7 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2408,7 +2934,9 @@ fn worker_prompts_prepend_task_docs_and_web_read_instruction() {
                     panic!("unexpected prompt variant: {prompt}");
                 }
             }
-            JobRun::DeterministicTestRun => {}
+            JobRun::DeterministicTestRun
+            | JobRun::DeterministicLintRun
+            | JobRun::DeterministicCoverageRun => {}
         }
 
         match job.role {
@@ -2449,6 +2977,7 @@ fn start_execution_picks_unfinished_task_when_some_are_done() {
             status: PlannerTaskStatusFile::Done,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "todo-task".to_string(),
@@ -2459,6 +2988,7 @@ fn start_execution_picks_unfinished_task_when_some_are_done() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2468,7 +2998,11 @@ fn start_execution_picks_unfinished_task_when_some_are_done() {
     assert_eq!(first.role, WorkerRole::Implementor);
     match first.run {
         JobRun::AgentPrompt(prompt) => assert!(prompt.contains("Pending task")),
-        JobRun::DeterministicTestRun => panic!("expected implementor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected implementor prompt")
+        }
     }
 }
 
@@ -2485,6 +3019,7 @@ fn start_execution_resumes_with_pending_implementor_auditor_subtask() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -2495,6 +3030,7 @@ fn start_execution_resumes_with_pending_implementor_auditor_subtask() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -2505,6 +3041,7 @@ fn start_execution_resumes_with_pending_implementor_auditor_subtask() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -2515,6 +3052,7 @@ fn start_execution_resumes_with_pending_implementor_auditor_subtask() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -2525,6 +3063,7 @@ fn start_execution_resumes_with_pending_implementor_auditor_subtask() {
             status: PlannerTaskStatusFile::Done,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2536,7 +3075,11 @@ fn start_execution_resumes_with_pending_implementor_auditor_subtask() {
         JobRun::AgentPrompt(prompt) => {
             assert!(prompt.contains("reviewing implementation output"));
         }
-        JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
     }
 }
 
@@ -2553,6 +3096,7 @@ fn start_execution_resumes_with_in_progress_implementor_and_pending_auditor_subt
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -2563,6 +3107,7 @@ fn start_execution_resumes_with_in_progress_implementor_and_pending_auditor_subt
             status: PlannerTaskStatusFile::InProgress,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -2573,6 +3118,7 @@ fn start_execution_resumes_with_in_progress_implementor_and_pending_auditor_subt
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2584,7 +3130,11 @@ fn start_execution_resumes_with_in_progress_implementor_and_pending_auditor_subt
         JobRun::AgentPrompt(prompt) => {
             assert!(prompt.contains("reviewing implementation output"));
         }
-        JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
     }
 }
 
@@ -2601,6 +3151,7 @@ fn first_implementor_pass_enqueues_implementor_auditor_before_test_writer() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -2611,6 +3162,7 @@ fn first_implementor_pass_enqueues_implementor_auditor_before_test_writer() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -2621,6 +3173,7 @@ fn first_implementor_pass_enqueues_implementor_auditor_before_test_writer() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-runner".to_string(),
@@ -2631,6 +3184,7 @@ fn first_implementor_pass_enqueues_implementor_auditor_before_test_writer() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw".to_string(),
@@ -2641,6 +3195,7 @@ fn first_implementor_pass_enqueues_implementor_auditor_before_test_writer() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "tw-runner".to_string(),
@@ -2651,6 +3206,7 @@ fn first_implementor_pass_enqueues_implementor_auditor_before_test_writer() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2683,6 +3239,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top-a-impl".to_string(),
@@ -2693,6 +3250,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top-a".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top-a-audit".to_string(),
@@ -2703,6 +3261,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top-a-impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top-a-tw".to_string(),
@@ -2713,6 +3272,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top-a".to_string()),
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top-a-tw-runner".to_string(),
@@ -2723,6 +3283,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top-a-tw".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top-b".to_string(),
@@ -2733,6 +3294,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top-b-impl".to_string(),
@@ -2743,6 +3305,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top-b".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "top-b-audit".to_string(),
@@ -2753,6 +3316,7 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("top-b-impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2782,7 +3346,11 @@ fn resume_does_not_mark_task_done_with_pending_implementor_auditor() {
         JobRun::AgentPrompt(prompt) => {
             assert!(prompt.contains("reviewing implementation output"));
         }
-        JobRun::DeterministicTestRun => panic!("expected auditor prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected auditor prompt")
+        }
     }
 }
 
@@ -2799,6 +3367,7 @@ fn enqueue_ready_does_not_mutate_done_or_final_audit_roots() {
             status: PlannerTaskStatusFile::Done,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "fa".to_string(),
@@ -2809,6 +3378,7 @@ fn enqueue_ready_does_not_mutate_done_or_final_audit_roots() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2867,6 +3437,7 @@ fn final_audit_runs_after_non_final_tasks_complete() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "fa".to_string(),
@@ -2877,6 +3448,7 @@ fn final_audit_runs_after_non_final_tasks_complete() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -2886,14 +3458,14 @@ fn final_audit_runs_after_non_final_tasks_complete() {
     wf.append_active_output("implemented".to_string());
     wf.finish_active_job(true, 0);
 
-    let _ = wf.start_next_job().expect("test writer");
-    wf.append_active_output("tests".to_string());
-    wf.finish_active_job(true, 0);
-
     let _ = wf.start_next_job().expect("auditor");
     wf.append_active_output("PASS".to_string());
     wf.finish_active_job(true, 0);
 
+    let _ = wf.start_next_job().expect("test writer");
+    wf.append_active_output("tests".to_string());
+    wf.finish_active_job(true, 0);
+
     let _ = wf.start_next_job().expect("test runner");
     wf.append_active_output("all passed".to_string());
     wf.finish_active_job(true, 0);
@@ -2933,8 +3505,8 @@ fn final_audit_requires_explicit_pass_token_to_complete() {
     let first_final_audit = complete_non_final_branches_and_start_final_audit(&mut wf);
     match first_final_audit.run {
         JobRun::AgentPrompt(prompt) => {
-            assert!(prompt.contains("PASS"));
-            assert!(prompt.contains("FAIL"));
+            assert!(prompt.contains("```json"));
+            assert!(prompt.contains("\"severity\""));
             assert!(prompt.contains(
                 "Tests mode policy (ON): include cross-task test adequacy in holistic risk assessment when relevant."
             ));
@@ -2942,7 +3514,11 @@ fn final_audit_requires_explicit_pass_token_to_complete() {
                 !prompt.contains("If tests exist, ask whether to write new tests as part of this work.")
             );
         }
-        JobRun::DeterministicTestRun => panic!("expected final audit prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected final audit prompt")
+        }
     }
 
     wf.append_active_output("PASS".to_string());
@@ -2971,7 +3547,11 @@ fn final_audit_prompt_in_tests_mode_off_uses_off_policy_language() {
             );
             assert_prompt_omits_removed_test_decision_questions(&prompt);
         }
-        JobRun::DeterministicTestRun => panic!("expected final audit prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected final audit prompt")
+        }
     }
 }
 
@@ -2991,7 +3571,11 @@ fn final_audit_prompt_in_tests_mode_on_uses_on_policy_and_omits_master_decision_
             );
             assert_prompt_omits_removed_test_decision_questions(&prompt);
         }
-        JobRun::DeterministicTestRun => panic!("expected final audit prompt"),
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected final audit prompt")
+        }
     }
 }
 
@@ -3069,14 +3653,14 @@ fn rolling_context_records_progress_and_why() {
     wf.append_active_output("implemented".to_string());
     wf.finish_active_job(true, 0);
 
-    let _ = wf.start_next_job().expect("test writer");
-    wf.append_active_output("wrote tests".to_string());
-    wf.finish_active_job(true, 0);
-
     let _ = wf.start_next_job().expect("auditor");
     wf.append_active_output("PASS".to_string());
     wf.finish_active_job(true, 0);
 
+    let _ = wf.start_next_job().expect("test writer");
+    wf.append_active_output("wrote tests".to_string());
+    wf.finish_active_job(true, 0);
+
     let _ = wf.start_next_job().expect("test runner");
     wf.append_active_output("all passed".to_string());
     wf.finish_active_job(true, 0);
@@ -3173,6 +3757,7 @@ fn right_pane_shows_docs_badge_for_non_test_runner_only() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: None,
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl".to_string(),
@@ -3183,6 +3768,7 @@ fn right_pane_shows_docs_badge_for_non_test_runner_only() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("task".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "impl-audit".to_string(),
@@ -3193,6 +3779,7 @@ fn right_pane_shows_docs_badge_for_non_test_runner_only() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(0),
+            ..Default::default()
         },
         PlannerTaskFileEntry {
             id: "runner".to_string(),
@@ -3207,6 +3794,7 @@ fn right_pane_shows_docs_badge_for_non_test_runner_only() {
             status: PlannerTaskStatusFile::Pending,
             parent_id: Some("impl".to_string()),
             order: Some(1),
+            ..Default::default()
         },
     ])
     .expect("sync should succeed");
@@ -3234,6 +3822,7 @@ fn right_pane_docs_toggle_expands_full_docs_without_collapsed_preview() {
         status: PlannerTaskStatusFile::Pending,
         parent_id: None,
         order: Some(0),
+        ..Default::default()
     }])
     .expect("sync should succeed");
 
@@ -3254,3 +3843,1741 @@ fn right_pane_docs_toggle_expands_full_docs_without_collapsed_preview() {
     assert!(expanded_text.contains("https://example.com/doc"));
     assert!(expanded_text.contains("Doc summary"));
 }
+
+#[test]
+fn parallel_limit_starts_independent_top_level_tasks_concurrently() {
+    let mut wf = Workflow::default();
+    wf.set_max_parallel_tasks(2);
+    seed_two_default_tasks(&mut wf, "Task One", "Task Two");
+    wf.start_execution();
+
+    let first = wf.start_next_job().expect("first top-level job");
+    let second = wf.start_next_job().expect("second top-level job");
+    assert_ne!(first.top_task_id, second.top_task_id);
+    assert!(wf.start_next_job().is_none());
+    assert_eq!(wf.active_jobs_meta().len(), 2);
+}
+
+fn drive_top_task_to_completion(wf: &mut Workflow, top_id: u64, pending: &mut Vec<StartedJob>) {
+    for _ in 0..24 {
+        let job = match pending.iter().position(|job| job.top_task_id == top_id) {
+            Some(idx) => pending.remove(idx),
+            None => wf.start_next_job().expect("queued job for top-level task"),
+        };
+        if job.top_task_id != top_id {
+            pending.push(job);
+            continue;
+        }
+        let line = match job.role {
+            WorkerRole::Auditor | WorkerRole::FinalAudit => "PASS",
+            _ => "done",
+        };
+        wf.append_output_for_task(top_id, line.to_string());
+        let messages = wf.finish_job_for_task(top_id, true, 0);
+        if messages
+            .iter()
+            .any(|m| m.contains(&format!("Task #{} completed", top_id)))
+        {
+            return;
+        }
+    }
+    panic!("top-level task #{top_id} never completed");
+}
+
+#[test]
+fn parallel_tasks_merge_back_as_they_finish() {
+    let mut wf = Workflow::default();
+    wf.set_max_parallel_tasks(2);
+    seed_two_default_tasks(&mut wf, "Task One", "Task Two");
+    wf.start_execution();
+    let mut pending = Vec::new();
+    while let Some(job) = wf.start_next_job() {
+        wf.assign_task_workspace(
+            job.top_task_id,
+            PathBuf::from(format!("/tmp/{}", job.top_task_id)),
+        );
+        pending.push(job);
+    }
+    let first_top_id = pending[0].top_task_id;
+    let second_top_id = pending[1].top_task_id;
+
+    drive_top_task_to_completion(&mut wf, second_top_id, &mut pending);
+    let merged_ids: Vec<u64> = wf
+        .take_ready_merges()
+        .iter()
+        .map(|m| m.top_task_id)
+        .collect();
+    assert_eq!(merged_ids, vec![second_top_id]);
+    wf.mark_task_merged(second_top_id);
+
+    drive_top_task_to_completion(&mut wf, first_top_id, &mut pending);
+    let merged_ids: Vec<u64> = wf
+        .take_ready_merges()
+        .iter()
+        .map(|m| m.top_task_id)
+        .collect();
+    assert_eq!(merged_ids, vec![first_top_id]);
+}
+
+#[test]
+fn dependent_task_starts_only_after_dependency_merges_back() {
+    let mut wf = Workflow::default();
+    wf.set_max_parallel_tasks(2);
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    let top2 = entries
+        .iter_mut()
+        .find(|entry| entry.id == "top2")
+        .expect("top2 entry");
+    top2.depends_on = vec!["top1".to_string()];
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("seed plan should sync");
+    wf.start_execution();
+
+    let first = wf.start_next_job().expect("dependency job");
+    assert!(wf.start_next_job().is_none());
+    let first_top_id = first.top_task_id;
+    wf.assign_task_workspace(first_top_id, PathBuf::from("/tmp/top1"));
+    let mut pending = vec![first];
+
+    drive_top_task_to_completion(&mut wf, first_top_id, &mut pending);
+    assert!(wf.start_next_job().is_none());
+
+    let merges = wf.take_ready_merges();
+    assert_eq!(merges.len(), 1);
+    assert!(wf.start_next_job().is_none());
+    wf.mark_task_merged(first_top_id);
+    let next = wf.start_next_job().expect("dependent job after merge-back");
+    assert_ne!(next.top_task_id, first_top_id);
+}
+
+#[test]
+fn ready_merges_follow_plan_order_when_a_later_sweep_releases_an_earlier_task() {
+    let entry = |id: &str, kind, parent_id: Option<&str>, order| PlannerTaskFileEntry {
+        status: PlannerTaskStatusFile::Done,
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, order)
+    };
+    let mut entries = Vec::new();
+    for (order, top) in ["a", "c", "x"].into_iter().enumerate() {
+        let implementor = format!("{top}-impl");
+        entries.push(entry(top, PlannerTaskKindFile::Task, None, order as u32));
+        entries.push(entry(
+            &implementor,
+            PlannerTaskKindFile::Implementor,
+            Some(top),
+            0,
+        ));
+        entries.push(entry(
+            &format!("{top}-audit"),
+            PlannerTaskKindFile::Auditor,
+            Some(&implementor),
+            0,
+        ));
+    }
+    entries[0].depends_on = vec!["x".to_string()];
+    let mut wf = Workflow::default();
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("seed plan should sync");
+    let id = |wf: &Workflow, key: &str| wf.resolve_task_id(key).expect("task id");
+    let (a, c) = (id(&wf, "a"), id(&wf, "c"));
+    wf.assign_task_workspace(a, PathBuf::from("/tmp/a"));
+    wf.assign_task_workspace(c, PathBuf::from("/tmp/c"));
+
+    // "x" ran in the main workspace, so "a" is only released on the second sweep.
+    let merges = wf
+        .take_ready_merges()
+        .iter()
+        .map(|merge| merge.top_task_id)
+        .collect::<Vec<_>>();
+    assert_eq!(merges, vec![a, c]);
+}
+
+#[test]
+fn failed_merge_keeps_the_worktree_and_blocks_dependents() {
+    let mut wf = Workflow::default();
+    wf.set_max_parallel_tasks(2);
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    entries
+        .iter_mut()
+        .find(|entry| entry.id == "top2")
+        .expect("top2 entry")
+        .depends_on = vec!["top1".to_string()];
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("seed plan should sync");
+    wf.start_execution();
+
+    let first = wf.start_next_job().expect("dependency job");
+    let first_top_id = first.top_task_id;
+    wf.assign_task_workspace(first_top_id, PathBuf::from("/tmp/top1"));
+    let mut pending = vec![first];
+    drive_top_task_to_completion(&mut wf, first_top_id, &mut pending);
+
+    assert_eq!(wf.take_ready_merges().len(), 1);
+    wf.record_merge_failure(
+        first_top_id,
+        "Merge conflict in: shared.txt".to_string(),
+        "Merge aborted.".to_string(),
+    );
+    let failures = wf.drain_recent_failures();
+    assert!(failures[0].action_taken.contains("stay blocked"));
+    assert_eq!(
+        wf.task_workspace(first_top_id),
+        Some(PathBuf::from("/tmp/top1"))
+    );
+    assert!(wf.take_ready_merges().is_empty());
+    assert!(wf.start_next_job().is_none());
+}
+
+#[test]
+fn skipped_dependency_does_not_hold_back_merge_of_dependents() {
+    let mut wf = Workflow::default();
+    wf.set_max_parallel_tasks(2);
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    entries
+        .iter_mut()
+        .find(|entry| entry.id == "top2")
+        .expect("top2 entry")
+        .depends_on = vec!["top1".to_string()];
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("seed plan should sync");
+    let skipped_id = wf.resolve_top_task_id("top1").expect("top1 id");
+    wf.skip_task(skipped_id).expect("skip dependency");
+    wf.start_execution();
+
+    let job = wf.start_next_job().expect("dependent job");
+    let top_id = job.top_task_id;
+    assert_ne!(top_id, skipped_id);
+    wf.assign_task_workspace(top_id, PathBuf::from("/tmp/top2"));
+    let mut pending = vec![job];
+    drive_top_task_to_completion(&mut wf, top_id, &mut pending);
+
+    let merged_ids: Vec<u64> = wf
+        .take_ready_merges()
+        .iter()
+        .map(|m| m.top_task_id)
+        .collect();
+    assert_eq!(merged_ids, vec![top_id]);
+}
+
+#[test]
+fn retrying_a_merged_task_merges_it_back_again() {
+    let mut wf = Workflow::default();
+    wf.set_max_parallel_tasks(2);
+    seed_two_default_tasks(&mut wf, "Task One", "Task Two");
+    wf.start_execution();
+    let first = wf.start_next_job().expect("first job");
+    let top_id = first.top_task_id;
+    wf.assign_task_workspace(top_id, PathBuf::from("/tmp/top1"));
+    let mut pending = vec![first];
+    drive_top_task_to_completion(&mut wf, top_id, &mut pending);
+    assert_eq!(wf.take_ready_merges().len(), 1);
+    wf.mark_task_merged(top_id);
+
+    wf.retry_task(top_id, None).expect("retry merged task");
+    assert_eq!(wf.drain_reset_merges(), vec![(top_id, None)]);
+    assert!(wf.task_checkpoint_refs(top_id).is_empty());
+    assert_eq!(wf.task_changed_lines(top_id), Some(ChangedLines::new()));
+
+    drive_top_task_to_completion(&mut wf, top_id, &mut pending);
+    assert!(wf.task_workspace(top_id).is_none());
+    wf.assign_task_workspace(top_id, PathBuf::from("/tmp/top1-retry"));
+    let merges = wf.take_ready_merges();
+    assert_eq!(merges.len(), 1);
+    assert_eq!(merges[0].workspace_dir, PathBuf::from("/tmp/top1-retry"));
+}
+
+#[test]
+fn retrying_after_a_failed_merge_hands_back_the_old_worktree() {
+    let mut wf = Workflow::default();
+    wf.set_max_parallel_tasks(2);
+    seed_two_default_tasks(&mut wf, "Task One", "Task Two");
+    wf.start_execution();
+    let first = wf.start_next_job().expect("first job");
+    let top_id = first.top_task_id;
+    wf.assign_task_workspace(top_id, PathBuf::from("/tmp/top1"));
+    let mut pending = vec![first];
+    drive_top_task_to_completion(&mut wf, top_id, &mut pending);
+    assert_eq!(wf.take_ready_merges().len(), 1);
+    wf.record_merge_failure(
+        top_id,
+        "Merge conflict in: shared.txt".to_string(),
+        "Merge aborted.".to_string(),
+    );
+
+    wf.retry_task(top_id, None).expect("retry failed merge");
+    assert_eq!(
+        wf.drain_reset_merges(),
+        vec![(top_id, Some(PathBuf::from("/tmp/top1")))]
+    );
+    assert!(wf.task_workspace(top_id).is_none());
+
+    drive_top_task_to_completion(&mut wf, top_id, &mut pending);
+    wf.assign_task_workspace(top_id, PathBuf::from("/tmp/top1-retry"));
+    let merges = wf.take_ready_merges();
+    assert_eq!(merges.len(), 1);
+    assert_eq!(merges[0].workspace_dir, PathBuf::from("/tmp/top1-retry"));
+}
+
+#[test]
+fn dependencies_override_positional_order() {
+    let mut wf = Workflow::default();
+    let mut entries = two_default_task_entries("Feature", "Testing setup");
+    let top1 = entries
+        .iter_mut()
+        .find(|entry| entry.id == "top1")
+        .expect("top1 entry");
+    top1.depends_on = vec!["top2".to_string()];
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("seed plan should sync");
+    wf.start_execution();
+
+    let first = wf.start_next_job().expect("first job");
+    assert_eq!(wf.task_title(first.top_task_id), "Testing setup");
+}
+
+#[test]
+fn sync_rejects_missing_and_cyclic_dependencies() {
+    let mut wf = Workflow::default();
+
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    entries[0].depends_on = vec!["nope".to_string()];
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("dangling dependency should fail");
+    assert_eq!(err, "Planner task top1 depends_on missing task nope");
+
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    for entry in &mut entries {
+        match entry.id.as_str() {
+            "top1" => entry.depends_on = vec!["top2".to_string()],
+            "top2" => entry.depends_on = vec!["top1".to_string()],
+            _ => {}
+        }
+    }
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("cycle should fail");
+    assert_eq!(err, "Planner task dependency cycle: top1 -> top2 -> top1");
+
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    let impl2 = entries
+        .iter_mut()
+        .find(|entry| entry.id == "impl2")
+        .expect("impl2 entry");
+    impl2.depends_on = vec!["top1".to_string()];
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("subtask dependency should fail");
+    assert!(err.contains("only top-level \"task\" entries may have dependencies"));
+
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    entries[0].depends_on = vec!["impl2".to_string()];
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("dependency on subtask should fail");
+    assert_eq!(
+        err,
+        "Planner task top1 depends_on impl2, which is not a top-level \"task\" entry"
+    );
+}
+
+#[test]
+fn planner_tasks_for_file_round_trips_dependencies() {
+    let mut wf = Workflow::default();
+    let mut entries = two_default_task_entries("Task One", "Task Two");
+    entries
+        .iter_mut()
+        .find(|entry| entry.id == "top2")
+        .expect("top2 entry")
+        .depends_on = vec!["top1".to_string()];
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("seed plan should sync");
+
+    let out = wf.planner_tasks_for_file();
+    let top2 = out
+        .iter()
+        .find(|entry| entry.id == "top2")
+        .expect("top2 entry");
+    assert_eq!(top2.depends_on, vec!["top1".to_string()]);
+    assert!(
+        out.iter()
+            .filter(|entry| entry.id != "top2")
+            .all(|entry| entry.depends_on.is_empty())
+    );
+}
+
+#[test]
+fn merge_failures_are_reported_with_merge_kind() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Task One");
+    wf.start_execution();
+    let top_id = wf.start_next_job().expect("first job").top_task_id;
+    wf.record_merge_failure(
+        top_id,
+        "Merge conflict in: src/lib.rs".to_string(),
+        "Merge aborted.".to_string(),
+    );
+    let failures = wf.drain_recent_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].kind, WorkflowFailureKind::Merge);
+    assert_eq!(failures[0].top_task_title, "Task One");
+}
+
+#[test]
+fn paused_execution_lets_running_jobs_finish_but_starts_no_new_ones() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+    let _ = wf.start_next_job().expect("implementor");
+
+    let messages = wf.pause_execution();
+    assert!(messages[0].contains("1 running job(s) will finish"));
+    assert!(wf.execution_busy());
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+    assert!(wf.start_next_job().is_none());
+    assert!(!wf.execution_busy());
+    assert_eq!(
+        wf.start_execution(),
+        vec!["System: Execution is paused. Use /resume-execution to continue.".to_string()]
+    );
+
+    let messages = wf.resume_execution();
+    assert_eq!(messages[0], "System: Execution resumed.");
+    let auditor = wf.start_next_job().expect("auditor after resume");
+    assert_eq!(auditor.role, WorkerRole::Auditor);
+}
+
+#[test]
+fn stopped_job_is_marked_needs_changes_and_reruns_after_resume() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    assert!(wf.stop_active_jobs().is_empty());
+    wf.start_execution();
+    let first = wf.start_next_job().expect("implementor");
+
+    assert_eq!(wf.stop_active_jobs(), vec![first.top_task_id]);
+    assert!(wf.execution_paused());
+    let snapshot = wf.planner_tasks_for_file();
+    let status_of = |id: &str| {
+        snapshot
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.status)
+    };
+    assert_eq!(status_of("top"), Some(PlannerTaskStatusFile::NeedsChanges));
+    assert_eq!(status_of("impl"), Some(PlannerTaskStatusFile::NeedsChanges));
+    assert!(wf.start_next_job().is_none());
+
+    wf.resume_execution();
+    let rerun = wf.start_next_job().expect("implementor rerun");
+    assert_eq!(rerun.role, WorkerRole::Implementor);
+    assert_eq!(rerun.top_task_id, first.top_task_id);
+    assert!(wf.start_next_job().is_none());
+}
+
+#[test]
+fn stalled_job_times_out_and_retries_with_the_kill_reason() {
+    let mut wf = Workflow::default();
+    wf.set_job_timeouts(JobTimeouts {
+        implementor: JobTimeout::minutes(60, 1),
+        ..JobTimeouts::default()
+    });
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+    let first = wf.start_next_job().expect("implementor");
+    let now = Instant::now();
+    assert!(wf.timed_out_jobs(now).is_empty());
+
+    let timed_out = wf.timed_out_jobs(now + Duration::from_secs(61));
+    assert_eq!(timed_out.len(), 1);
+    assert_eq!(timed_out[0].top_task_id, first.top_task_id);
+    assert_eq!(timed_out[0].role, WorkerRole::Implementor);
+    assert!(timed_out[0].reason.contains("no output for 1m"));
+
+    wf.record_job_timeout(&timed_out[0]);
+    wf.finish_job_for_task(first.top_task_id, false, JOB_TIMEOUT_EXIT_CODE);
+    assert!(wf.drain_recent_failures().is_empty());
+
+    let retry = wf.start_next_job().expect("implementor retry");
+    let JobRun::AgentPrompt(prompt) = retry.run else {
+        panic!("implementor retry should be an agent prompt");
+    };
+    assert!(prompt.contains(
+        "Previous implementor run was killed: Implementor job produced no output for 1m"
+    ));
+}
+
+#[test]
+fn timeout_that_exhausts_the_audit_budget_is_reported_as_a_timeout() {
+    let mut wf = Workflow::default();
+    wf.set_retry_budgets(RetryBudgets {
+        audit: 1,
+        ..RetryBudgets::default()
+    });
+    wf.set_job_timeouts(JobTimeouts {
+        auditor: JobTimeout::minutes(60, 1),
+        ..JobTimeouts::default()
+    });
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+    let implementor = wf.start_next_job().expect("implementor");
+    wf.append_active_output("implemented".to_string());
+    wf.finish_job_for_task(implementor.top_task_id, true, 0);
+    let audit = wf.start_next_job().expect("auditor");
+    assert_eq!(audit.role, WorkerRole::Auditor);
+
+    let timed_out = wf.timed_out_jobs(Instant::now() + Duration::from_secs(61));
+    assert_eq!(timed_out.len(), 1);
+    wf.record_job_timeout(&timed_out[0]);
+    assert!(wf.drain_recent_failures().is_empty());
+    wf.finish_job_for_task(audit.top_task_id, false, JOB_TIMEOUT_EXIT_CODE);
+
+    let failures = wf.drain_recent_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].kind, WorkflowFailureKind::Timeout);
+    assert!(failures[0].reason.contains("no output for 1m"));
+}
+
+#[test]
+fn wall_clock_limit_applies_even_while_output_keeps_arriving() {
+    let mut wf = Workflow::default();
+    wf.set_job_timeouts(JobTimeouts {
+        implementor: JobTimeout::minutes(2, 0),
+        ..JobTimeouts::default()
+    });
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+    let _ = wf.start_next_job().expect("implementor");
+    wf.append_active_output("still working".to_string());
+
+    let later = Instant::now() + Duration::from_secs(121);
+    let timed_out = wf.timed_out_jobs(later);
+    assert_eq!(timed_out.len(), 1);
+    assert!(
+        timed_out[0]
+            .reason
+            .contains("exceeded its 2m wall-clock limit")
+    );
+
+    wf.set_job_timeouts(JobTimeouts {
+        implementor: JobTimeout::minutes(0, 0),
+        ..JobTimeouts::default()
+    });
+    assert!(wf.timed_out_jobs(later).is_empty());
+}
+
+#[test]
+fn timed_out_test_run_feedback_names_the_kill_reason() {
+    let transcript = vec![
+        "running 3 tests".to_string(),
+        format!(
+            "{JOB_TIMEOUT_MARKER} TestRunner job produced no output for 10m; its process tree was killed."
+        ),
+    ];
+    let feedback = test_runner_feedback(&transcript, JOB_TIMEOUT_EXIT_CODE, None);
+    assert!(feedback.starts_with(
+        "Deterministic test run was killed: TestRunner job produced no output for 10m"
+    ));
+    assert!(feedback.ends_with("Output before the kill:\nrunning 3 tests"));
+}
+
+fn seed_task_with_review(wf: &mut Workflow) {
+    seed_task_with_review_and_retries(wf, None);
+}
+
+fn seed_task_with_review_and_retries(wf: &mut Workflow, max_retries: Option<u8>) {
+    let entry = PlannerTaskFileEntry::test_entry;
+    wf.sync_planner_tasks_from_file(vec![
+        PlannerTaskFileEntry {
+            max_retries,
+            ..entry("top", PlannerTaskKindFile::Task, None, 0)
+        },
+        entry("impl", PlannerTaskKindFile::Implementor, Some("top"), 0),
+        entry("impl-audit", PlannerTaskKindFile::Auditor, Some("impl"), 0),
+        entry("impl-review", PlannerTaskKindFile::Review, Some("impl"), 1),
+    ])
+    .expect("sync review tasks");
+}
+
+fn run_implementor_and_passing_audit(wf: &mut Workflow) -> Vec<String> {
+    let implementor = wf.start_next_job().expect("implementor");
+    assert_eq!(implementor.role, WorkerRole::Implementor);
+    wf.append_active_output("FILES_CHANGED_BEGIN".to_string());
+    wf.append_active_output("- src/db.rs: added migration".to_string());
+    wf.append_active_output("FILES_CHANGED_END".to_string());
+    wf.finish_active_job(true, 0);
+    let auditor = wf.start_next_job().expect("auditor");
+    assert_eq!(auditor.role, WorkerRole::Auditor);
+    wf.append_active_output("PASS".to_string());
+    wf.finish_active_job(true, 0)
+}
+
+#[test]
+fn review_task_holds_the_queue_until_approved() {
+    let mut wf = Workflow::default();
+    seed_task_with_review(&mut wf);
+    wf.start_execution();
+
+    let messages = run_implementor_and_passing_audit(&mut wf);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("waiting for human review"))
+    );
+    assert!(wf.start_next_job().is_none());
+    assert!(wf.execution_busy());
+    let reviews = wf.drain_new_reviews();
+    assert_eq!(reviews.len(), 1);
+    assert!(
+        reviews[0]
+            .changed_files_summary
+            .as_deref()
+            .is_some_and(|summary| summary.contains("src/db.rs"))
+    );
+    assert!(wf.drain_new_reviews().is_empty());
+
+    let messages = wf.approve_review(None);
+    assert!(messages[0].contains("review approved"));
+    assert!(messages.iter().any(|m| m.contains("passed all audits")));
+    assert!(!wf.execution_busy());
+    let snapshot = wf.planner_tasks_for_file();
+    assert!(
+        snapshot
+            .iter()
+            .all(|entry| entry.status == PlannerTaskStatusFile::Done)
+    );
+    assert_eq!(
+        wf.approve_review(None),
+        vec!["System: No task is waiting for review.".to_string()]
+    );
+}
+
+#[test]
+fn rejected_review_reruns_implementor_with_feedback_and_gates_again() {
+    let mut wf = Workflow::default();
+    seed_task_with_review(&mut wf);
+    wf.start_execution();
+    run_implementor_and_passing_audit(&mut wf);
+    wf.drain_new_reviews();
+
+    let messages = wf.reject_review(Some("top"), "Split the migration into two steps");
+    assert!(messages[0].contains("review rejected"));
+    let retry = wf.start_next_job().expect("implementor retry");
+    assert_eq!(retry.role, WorkerRole::Implementor);
+    match retry.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains("Human review rejected the changes"));
+            assert!(prompt.contains("Split the migration into two steps"));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected implementor prompt")
+        }
+    }
+
+    wf.append_active_output("reworked".to_string());
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("waiting for human review"))
+    );
+    assert!(wf.start_next_job().is_none());
+    assert_eq!(wf.drain_new_reviews().len(), 1);
+}
+
+#[test]
+fn review_decisions_only_apply_to_the_named_task() {
+    let mut wf = Workflow::default();
+    seed_task_with_review(&mut wf);
+    wf.start_execution();
+    run_implementor_and_passing_audit(&mut wf);
+    wf.drain_new_reviews();
+
+    assert_eq!(
+        wf.reject_review(Some("missing"), "nope"),
+        vec!["System: Unknown task \"missing\".".to_string()]
+    );
+    assert!(wf.start_next_job().is_none());
+
+    let messages = wf.approve_review(Some("top"));
+    assert!(messages[0].contains("Task #1 review approved"));
+    assert!(!wf.execution_busy());
+}
+
+#[test]
+fn rejected_review_holds_the_task_once_the_audit_budget_is_spent() {
+    let mut wf = Workflow::default();
+    seed_task_with_review_and_retries(&mut wf, Some(1));
+    wf.start_execution();
+    run_implementor_and_passing_audit(&mut wf);
+    wf.drain_new_reviews();
+
+    let messages = wf.reject_review(Some("top"), "Still missing the down migration");
+    assert!(messages[0].contains("Max retries (1) reached"));
+    assert!(messages[0].contains("held until /retry or /skip"));
+    assert!(!messages.iter().any(|m| m.contains("passed all audits")));
+    assert!(wf.start_next_job().is_none());
+    let statuses = wf
+        .planner_tasks_for_file()
+        .into_iter()
+        .map(|entry| (entry.id, entry.status))
+        .collect::<HashMap<_, _>>();
+    assert_eq!(statuses["impl"], PlannerTaskStatusFile::NeedsChanges);
+    assert_eq!(statuses["impl-review"], PlannerTaskStatusFile::NeedsChanges);
+    assert_ne!(statuses["top"], PlannerTaskStatusFile::Done);
+    let failures = wf.drain_recent_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].kind, WorkflowFailureKind::Audit);
+    assert!(
+        failures[0]
+            .reason
+            .contains("Still missing the down migration")
+    );
+    assert_eq!(
+        failures[0].retry_budget.as_ref().map(|budget| budget.limit),
+        Some(1)
+    );
+
+    let top_id = wf.resolve_top_task_id("top").expect("top task");
+    wf.retry_task(top_id, None).expect("retry should succeed");
+    let implementor = wf.start_next_job().expect("implementor after retry");
+    assert_eq!(implementor.role, WorkerRole::Implementor);
+}
+
+#[test]
+fn review_task_must_be_a_child_of_an_implementor() {
+    let mut wf = Workflow::default();
+    let err = wf
+        .sync_planner_tasks_from_file(vec![
+            PlannerTaskFileEntry {
+                id: "top".to_string(),
+                title: "Top".to_string(),
+                details: "top details".to_string(),
+                docs: Vec::new(),
+                kind: PlannerTaskKindFile::Task,
+                status: PlannerTaskStatusFile::Pending,
+                parent_id: None,
+                order: Some(0),
+                ..Default::default()
+            },
+            PlannerTaskFileEntry {
+                id: "review".to_string(),
+                title: "Review".to_string(),
+                details: "review details".to_string(),
+                docs: Vec::new(),
+                kind: PlannerTaskKindFile::Review,
+                status: PlannerTaskStatusFile::Pending,
+                parent_id: Some("top".to_string()),
+                order: Some(0),
+                ..Default::default()
+            },
+        ])
+        .expect_err("review under a top-level task should be rejected");
+    assert!(err.contains("must be a child of implementor"));
+}
+
+fn security_role(stage: RoleStage, on_fail: RoleFailurePolicy, auto: bool) -> CustomRole {
+    CustomRole {
+        name: "security_reviewer".to_string(),
+        title: "Security reviewer".to_string(),
+        instructions: "Look for injection and auth bypasses.".to_string(),
+        prompt: None,
+        profile: None,
+        permissions: None,
+        verdict: RoleVerdict::PassFail,
+        stage,
+        on_fail,
+        max_retries: None,
+        auto,
+    }
+}
+
+fn seed_task_with_role(wf: &mut Workflow) {
+    let entry = |id: &str, kind, parent_id: Option<&str>, order| PlannerTaskFileEntry {
+        role: (kind == PlannerTaskKindFile::Role).then(|| "security_reviewer".to_string()),
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, order)
+    };
+    wf.sync_planner_tasks_from_file(vec![
+        entry("top", PlannerTaskKindFile::Task, None, 0),
+        entry("impl", PlannerTaskKindFile::Implementor, Some("top"), 0),
+        entry("impl-security", PlannerTaskKindFile::Role, Some("impl"), 0),
+        entry("impl-audit", PlannerTaskKindFile::Auditor, Some("impl"), 1),
+    ])
+    .expect("sync role tasks");
+}
+
+#[test]
+fn failing_role_returns_to_implementor_and_resumes_at_the_role() {
+    let mut wf = Workflow::default();
+    wf.set_custom_roles(vec![security_role(
+        RoleStage::AfterAudit,
+        RoleFailurePolicy::Implementor,
+        false,
+    )]);
+    seed_task_with_role(&mut wf);
+    wf.start_execution();
+
+    run_implementor_and_passing_audit(&mut wf);
+    let role = wf.start_next_job().expect("role after audits");
+    assert_eq!(role.role, WorkerRole::Custom);
+    match role.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains("Security reviewer"));
+            assert!(prompt.contains("Look for injection and auth bypasses."));
+            assert!(prompt.contains("impl-security details"));
+            assert!(prompt.contains("exactly PASS or FAIL"));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => panic!("expected role prompt"),
+    }
+    wf.append_active_output("FAIL".to_string());
+    wf.append_active_output("src/db.rs: query built with format!".to_string());
+    wf.finish_active_job(true, 0);
+
+    let retry = wf.start_next_job().expect("implementor retry");
+    assert_eq!(retry.role, WorkerRole::Implementor);
+    match retry.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains("Role `security_reviewer`"));
+            assert!(prompt.contains("query built with format!"));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => panic!("expected implementor prompt"),
+    }
+    wf.append_active_output("parameterized the query".to_string());
+    wf.finish_active_job(true, 0);
+
+    let role = wf.start_next_job().expect("role rerun");
+    assert_eq!(role.role, WorkerRole::Custom);
+    wf.append_active_output("PASS".to_string());
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("role `security_reviewer` passed on pass 2"))
+    );
+    let snapshot = wf.planner_tasks_for_file();
+    assert!(
+        snapshot
+            .iter()
+            .filter(|entry| entry.parent_id.as_deref() == Some("impl"))
+            .all(|entry| entry.status == PlannerTaskStatusFile::Done)
+    );
+}
+
+#[test]
+fn auto_roles_attach_to_every_implementor_at_their_stage() {
+    let mut wf = Workflow::default();
+    let mut role = security_role(RoleStage::BeforeAudit, RoleFailurePolicy::Continue, true);
+    role.permissions = Some(PermissionProfile::WorkspaceWrite);
+    wf.set_custom_roles(vec![role]);
+    seed_single_default_task(&mut wf, "Do work");
+    wf.start_execution();
+
+    let implementor = wf.start_next_job().expect("implementor");
+    assert_eq!(implementor.role, WorkerRole::Implementor);
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+
+    let role = wf.start_next_job().expect("auto role before audit");
+    assert_eq!(role.role, WorkerRole::Custom);
+    assert_eq!(role.permissions, Some(PermissionProfile::WorkspaceWrite));
+    wf.append_active_output("FAIL".to_string());
+    wf.append_active_output("src/auth.rs: token compared with ==".to_string());
+    let messages = wf.finish_active_job(true, 0);
+    assert!(messages.iter().any(|m| m.contains("advisory findings")));
+
+    let audit = wf.start_next_job().expect("audit after advisory role");
+    assert_eq!(audit.role, WorkerRole::Auditor);
+    let roles = wf
+        .planner_tasks_for_file()
+        .into_iter()
+        .filter(|entry| entry.kind == PlannerTaskKindFile::Role)
+        .collect::<Vec<_>>();
+    assert_eq!(roles.len(), 1);
+    assert_eq!(roles[0].role.as_deref(), Some("security_reviewer"));
+    assert_eq!(roles[0].parent_id.as_deref(), Some("impl"));
+    assert_eq!(roles[0].status, PlannerTaskStatusFile::Done);
+}
+
+#[test]
+fn before_audit_roles_rerun_after_an_audit_sends_work_back() {
+    let mut wf = Workflow::default();
+    wf.set_custom_roles(vec![security_role(
+        RoleStage::BeforeAudit,
+        RoleFailurePolicy::Implementor,
+        false,
+    )]);
+    seed_task_with_role(&mut wf);
+    wf.start_execution();
+
+    let implementor = wf.start_next_job().expect("implementor");
+    assert_eq!(implementor.role, WorkerRole::Implementor);
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+    let role = wf.start_next_job().expect("role before audit");
+    assert_eq!(role.role, WorkerRole::Custom);
+    wf.append_active_output("PASS".to_string());
+    wf.finish_active_job(true, 0);
+    let audit = wf.start_next_job().expect("audit");
+    assert_eq!(audit.role, WorkerRole::Auditor);
+    wf.append_active_output("FAIL".to_string());
+    wf.append_active_output("- critical issue".to_string());
+    wf.finish_active_job(true, 0);
+
+    let retry = wf.start_next_job().expect("implementor retry");
+    assert_eq!(retry.role, WorkerRole::Implementor);
+    wf.append_active_output("fixed".to_string());
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("queued before the resumed audit (pass 1)"))
+    );
+
+    let role = wf.start_next_job().expect("role rerun on the fix");
+    assert_eq!(role.role, WorkerRole::Custom);
+    wf.append_active_output("FAIL".to_string());
+    wf.append_active_output("src/auth.rs: token compared with ==".to_string());
+    wf.finish_active_job(true, 0);
+    let retry = wf.start_next_job().expect("implementor fix");
+    assert_eq!(retry.role, WorkerRole::Implementor);
+    wf.append_active_output("constant-time compare".to_string());
+    wf.finish_active_job(true, 0);
+    let role = wf.start_next_job().expect("role rerun");
+    assert_eq!(role.role, WorkerRole::Custom);
+    wf.append_active_output("PASS".to_string());
+    wf.finish_active_job(true, 0);
+
+    let audit = wf.start_next_job().expect("resumed audit");
+    assert_eq!(audit.role, WorkerRole::Auditor);
+    wf.append_active_output("PASS".to_string());
+    let messages = wf.finish_active_job(true, 0);
+    assert!(messages.iter().any(|m| m.contains("passed all audits")));
+}
+
+#[test]
+fn before_audit_role_reruns_count_their_own_passes_after_repeated_audit_failures() {
+    let mut wf = Workflow::default();
+    wf.set_retry_budgets(RetryBudgets {
+        audit: 4,
+        ..RetryBudgets::default()
+    });
+    wf.set_custom_roles(vec![security_role(
+        RoleStage::BeforeAudit,
+        RoleFailurePolicy::Implementor,
+        false,
+    )]);
+    seed_task_with_role(&mut wf);
+    wf.start_execution();
+
+    let finish = |wf: &mut Workflow, role: WorkerRole, output: &[&str]| {
+        let job = wf.start_next_job().expect("next job");
+        assert_eq!(job.role, role);
+        for line in output {
+            wf.append_active_output(line.to_string());
+        }
+        wf.finish_active_job(true, 0)
+    };
+    finish(&mut wf, WorkerRole::Implementor, &["implemented"]);
+    for _ in 0..3 {
+        finish(&mut wf, WorkerRole::Custom, &["PASS"]);
+        finish(&mut wf, WorkerRole::Auditor, &["FAIL", "- critical issue"]);
+        let messages = finish(&mut wf, WorkerRole::Implementor, &["fixed"]);
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.contains("queued before the resumed audit (pass 1)"))
+        );
+    }
+
+    // The audit is on its fourth pass, but this is the role's first failure.
+    let findings = ["FAIL", "src/auth.rs: weak hash"];
+    let messages = finish(&mut wf, WorkerRole::Custom, &findings);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("requested fixes; implementor pass 2 queued"))
+    );
+    assert!(wf.recent_failures.is_empty());
+    let messages = finish(&mut wf, WorkerRole::Implementor, &["switched to argon2"]);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("queued before the resumed audit (pass 2)"))
+    );
+    let messages = finish(&mut wf, WorkerRole::Custom, &["PASS"]);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("role `security_reviewer` passed on pass 2"))
+    );
+    let messages = finish(&mut wf, WorkerRole::Auditor, &["PASS"]);
+    assert!(messages.iter().any(|m| m.contains("passed all audits")));
+}
+
+#[test]
+fn unconfigured_role_tasks_are_skipped() {
+    let mut wf = Workflow::default();
+    seed_task_with_role(&mut wf);
+    wf.start_execution();
+
+    let implementor = wf.start_next_job().expect("implementor");
+    assert_eq!(implementor.role, WorkerRole::Implementor);
+    wf.append_active_output("implemented".to_string());
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("role `security_reviewer` is not configured"))
+    );
+    let audit = wf.start_next_job().expect("audit");
+    assert_eq!(audit.role, WorkerRole::Auditor);
+}
+
+#[test]
+fn role_tasks_need_a_role_name_and_an_implementor_parent() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    let role_entry = |parent_id: &str, role: Option<&str>| PlannerTaskFileEntry {
+        title: "Security".to_string(),
+        role: role.map(ToString::to_string),
+        ..PlannerTaskFileEntry::test_entry(
+            "stray-role",
+            PlannerTaskKindFile::Role,
+            Some(parent_id),
+            5,
+        )
+    };
+
+    let mut entries = wf.planner_tasks_for_file();
+    entries.push(role_entry("top", Some("security_reviewer")));
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("role under top should fail validation");
+    assert!(err.contains("must be a child of implementor"));
+
+    let mut entries = wf.planner_tasks_for_file();
+    entries.push(role_entry("impl", None));
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("role without a name should fail validation");
+    assert!(err.contains("role"));
+
+    let mut entries = wf.planner_tasks_for_file();
+    if let Some(audit) = entries.iter_mut().find(|entry| entry.id == "impl-audit") {
+        audit.role = Some("security_reviewer".to_string());
+    }
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("role name on an auditor should fail validation");
+    assert!(err.contains("role"));
+}
+
+#[test]
+fn retry_task_reopens_subtree_and_ancestors_with_guidance() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    let mut entries = wf.planner_tasks_for_file();
+    entries.retain(|entry| entry.id != "tw" && entry.id != "tw-runner");
+    for entry in &mut entries {
+        entry.status = PlannerTaskStatusFile::Done;
+    }
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("sync finished plan");
+    let audit_id = wf.resolve_task_id("impl-audit").expect("audit id");
+
+    let message = wf
+        .retry_task(audit_id, Some("Check the error paths"))
+        .expect("retry");
+    assert!(message.contains("reset to Pending"));
+    let snapshot = wf.planner_tasks_for_file();
+    let entry = |id: &str| {
+        snapshot
+            .iter()
+            .find(|entry| entry.id == id)
+            .cloned()
+            .expect("entry")
+    };
+    assert_eq!(entry("top").status, PlannerTaskStatusFile::Pending);
+    assert_eq!(entry("impl").status, PlannerTaskStatusFile::Pending);
+    assert_eq!(entry("impl-audit").status, PlannerTaskStatusFile::Pending);
+    assert!(
+        entry("impl-audit")
+            .details
+            .ends_with("Retry guidance: Check the error paths")
+    );
+
+    wf.start_execution();
+    let rerun = wf.start_next_job().expect("implementor rerun");
+    assert_eq!(rerun.role, WorkerRole::Implementor);
+}
+
+#[test]
+fn skip_task_lets_dependent_tasks_proceed_but_not_while_running() {
+    let mut wf = Workflow::default();
+    let mut entries = two_default_task_entries("First", "Second");
+    for entry in &mut entries {
+        if entry.id == "top2" {
+            entry.depends_on = vec!["top1".to_string()];
+        }
+    }
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("sync dependent plan");
+    let first_id = wf.resolve_task_id("top1").expect("first id");
+    assert_eq!(wf.resolve_task_id(&format!("#{first_id}")), Some(first_id));
+    wf.start_execution();
+    let running = wf.start_next_job().expect("first task job");
+    assert_eq!(running.top_task_id, first_id);
+
+    let err = wf
+        .skip_task(first_id)
+        .expect_err("running task cannot be skipped");
+    assert!(err.contains("running job"));
+    wf.stop_active_jobs();
+    wf.resume_execution();
+
+    let messages = wf.skip_task(first_id).expect("skip");
+    assert!(messages[0].contains("skipped"));
+    let snapshot = wf.planner_tasks_for_file();
+    assert!(
+        snapshot
+            .iter()
+            .filter(|entry| entry.id.ends_with('1'))
+            .all(|entry| entry.status == PlannerTaskStatusFile::Skipped)
+    );
+    let next = wf.start_next_job().expect("dependent task job");
+    assert_eq!(wf.resolve_task_id("top2"), Some(next.top_task_id));
+}
+
+#[test]
+fn dry_run_prompts_walk_the_plan_without_touching_the_workflow() {
+    let mut wf = Workflow::default();
+    seed_single_default_task_with_final_audit(&mut wf, "Do work");
+    let prompts = wf.dry_run_prompts().expect("dry run");
+    let steps = prompts
+        .iter()
+        .map(|prompt| (prompt.role, prompt.task_id.as_str(), prompt.pass))
+        .collect::<Vec<_>>();
+    assert_eq!(steps.first(), Some(&(WorkerRole::Implementor, "impl", 1)));
+    assert_eq!(steps.get(1), Some(&(WorkerRole::Auditor, "impl-audit", 1)));
+    assert_eq!(
+        steps.last().map(|step| step.0),
+        Some(WorkerRole::FinalAudit)
+    );
+    assert!(
+        steps
+            .iter()
+            .all(|(role, _, pass)| *role != WorkerRole::TestRunner && *pass == 1)
+    );
+    assert!(prompts[0].prompt.contains("implementor details"));
+
+    assert!(
+        wf.planner_tasks_for_file()
+            .iter()
+            .all(|entry| entry.status == PlannerTaskStatusFile::Pending)
+    );
+    assert!(wf.start_next_job().is_none());
+}
+
+#[test]
+fn lint_runner_failures_return_to_implementor_before_audit() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    let mut entries = wf.planner_tasks_for_file();
+    entries.retain(|entry| entry.id != "tw" && entry.id != "tw-runner");
+    entries.push(PlannerTaskFileEntry {
+        id: "impl-lint".to_string(),
+        title: "Lint".to_string(),
+        details: "lint details".to_string(),
+        docs: Vec::new(),
+        kind: PlannerTaskKindFile::LintRunner,
+        status: PlannerTaskStatusFile::Pending,
+        parent_id: Some("impl".to_string()),
+        order: Some(1),
+        ..Default::default()
+    });
+    wf.sync_planner_tasks_from_file(entries)
+        .expect("sync plan with lint runner");
+    wf.start_execution();
+
+    let implementor = wf.start_next_job().expect("implementor job");
+    assert_eq!(implementor.role, WorkerRole::Implementor);
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+
+    let lint = wf.start_next_job().expect("lint job");
+    assert_eq!(lint.role, WorkerRole::LintRunner);
+    assert!(matches!(lint.run, JobRun::DeterministicLintRun));
+    wf.append_active_output("warning: unused variable `x`".to_string());
+    wf.finish_active_job(false, 1);
+
+    let retry = wf.start_next_job().expect("implementor retry");
+    assert_eq!(retry.role, WorkerRole::Implementor);
+    match retry.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains("Lint run failed with code 1"));
+            assert!(prompt.contains("unused variable `x`"));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => {
+            panic!("expected implementor prompt")
+        }
+    }
+    wf.append_active_output("fixed lint".to_string());
+    wf.finish_active_job(true, 0);
+
+    let lint = wf.start_next_job().expect("lint rerun");
+    assert_eq!(lint.role, WorkerRole::LintRunner);
+    wf.finish_active_job(true, 0);
+
+    let audit = wf.start_next_job().expect("audit after clean lint");
+    assert_eq!(audit.role, WorkerRole::Auditor);
+}
+
+#[test]
+fn lint_runner_must_be_a_child_of_implementor() {
+    let mut wf = Workflow::default();
+    seed_single_default_task(&mut wf, "Do work");
+    let mut entries = wf.planner_tasks_for_file();
+    entries.push(PlannerTaskFileEntry {
+        id: "stray-lint".to_string(),
+        title: "Lint".to_string(),
+        details: "lint details".to_string(),
+        docs: Vec::new(),
+        kind: PlannerTaskKindFile::LintRunner,
+        status: PlannerTaskStatusFile::Pending,
+        parent_id: Some("top".to_string()),
+        order: Some(5),
+        ..Default::default()
+    });
+    let err = wf
+        .sync_planner_tasks_from_file(entries)
+        .expect_err("lint runner under top should fail validation");
+    assert!(err.contains("must be a child of implementor"));
+}
+
+#[test]
+fn test_runner_command_override_reaches_started_job_and_round_trips() {
+    let entry = |id: &str, kind, parent_id: Option<&str>, status| PlannerTaskFileEntry {
+        status,
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, 0)
+    };
+    let mut runner = entry(
+        "tw-runner",
+        PlannerTaskKindFile::TestRunner,
+        Some("tw"),
+        PlannerTaskStatusFile::Pending,
+    );
+    runner.command = Some("  cargo test -p storage ".to_string());
+    runner.working_dir = Some("crates/storage".to_string());
+    let mut tasks = vec![
+        entry("top", PlannerTaskKindFile::Task, None, PlannerTaskStatusFile::Pending),
+        entry(
+            "impl",
+            PlannerTaskKindFile::Implementor,
+            Some("top"),
+            PlannerTaskStatusFile::Done,
+        ),
+        entry(
+            "impl-audit",
+            PlannerTaskKindFile::Auditor,
+            Some("impl"),
+            PlannerTaskStatusFile::Done,
+        ),
+        entry(
+            "tw",
+            PlannerTaskKindFile::TestWriter,
+            Some("top"),
+            PlannerTaskStatusFile::Pending,
+        ),
+        runner,
+    ];
+    tasks[3].order = Some(1);
+
+    let mut invalid = tasks.clone();
+    invalid[1].command = Some("make check".to_string());
+    let err = Workflow::default()
+        .sync_planner_tasks_from_file(invalid)
+        .expect_err("command on an implementor is rejected");
+    assert!(err.contains("only test_runner tasks"));
+
+    let mut wf = Workflow::default();
+    wf.sync_planner_tasks_from_file(tasks)
+        .expect("sync should succeed");
+    wf.start_execution();
+
+    let test_writer = wf.start_next_job().expect("test writer");
+    assert_eq!(test_writer.test_command, None);
+    wf.append_active_output("wrote tests".to_string());
+    wf.finish_active_job(true, 0);
+
+    let test_runner = wf.start_next_job().expect("test runner");
+    assert_eq!(test_runner.role, WorkerRole::TestRunner);
+    assert_eq!(
+        test_runner.test_command.as_deref(),
+        Some("cargo test -p storage")
+    );
+    assert_eq!(
+        test_runner.test_working_dir,
+        Some(PathBuf::from("crates/storage"))
+    );
+
+    let snapshot = wf.planner_tasks_for_file();
+    let runner = snapshot
+        .iter()
+        .find(|entry| entry.id == "tw-runner")
+        .expect("runner entry");
+    assert_eq!(runner.command.as_deref(), Some("cargo test -p storage"));
+    assert_eq!(runner.working_dir.as_deref(), Some("crates/storage"));
+}
+
+#[test]
+fn coverage_gate_sends_test_writer_back_until_changed_lines_reach_threshold() {
+    let entry = |id: &str, kind, parent_id: Option<&str>, order, status| PlannerTaskFileEntry {
+        status,
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, order)
+    };
+    let mut wf = Workflow::default();
+    wf.set_coverage_threshold(Some(80.0));
+    wf.sync_planner_tasks_from_file(vec![
+        entry(
+            "top",
+            PlannerTaskKindFile::Task,
+            None,
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+        entry(
+            "impl",
+            PlannerTaskKindFile::Implementor,
+            Some("top"),
+            0,
+            PlannerTaskStatusFile::Done,
+        ),
+        entry(
+            "impl-audit",
+            PlannerTaskKindFile::Auditor,
+            Some("impl"),
+            0,
+            PlannerTaskStatusFile::Done,
+        ),
+        entry(
+            "tw",
+            PlannerTaskKindFile::TestWriter,
+            Some("top"),
+            1,
+            PlannerTaskStatusFile::Pending,
+        ),
+        entry(
+            "tw-runner",
+            PlannerTaskKindFile::TestRunner,
+            Some("tw"),
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+    ])
+    .expect("sync should succeed");
+    wf.start_execution();
+    // Lines 2-6 of src/lib.py changed; line 1 is untouched and uncovered.
+    let lcov = |uncovered: &[usize]| {
+        let mut lines = vec!["SF:src/lib.py".to_string()];
+        for line in 1..=6 {
+            let hits = usize::from(line != 1 && !uncovered.contains(&line));
+            lines.push(format!("DA:{line},{hits}"));
+        }
+        lines.push("end_of_record".to_string());
+        lines
+    };
+
+    let test_writer = wf.start_next_job().expect("test writer");
+    wf.record_pass_diff(
+        test_writer.top_task_id,
+        PassDiff {
+            files: vec![
+                PassDiffFile {
+                    status: 'M',
+                    path: "src/lib.py".to_string(),
+                },
+                PassDiffFile {
+                    status: 'A',
+                    path: "tests/test_lib.py".to_string(),
+                },
+            ],
+            patch: concat!(
+                "diff --git a/src/lib.py b/src/lib.py\n",
+                "--- a/src/lib.py\n",
+                "+++ b/src/lib.py\n",
+                "@@ -1,1 +1,6 @@\n",
+                " def f():\n",
+                "+    a = 1\n",
+                "+    b = 2\n",
+                "+    c = 3\n",
+                "+    d = 4\n",
+                "+    return a\n",
+                "diff --git a/tests/test_lib.py b/tests/test_lib.py\n",
+                "--- /dev/null\n",
+                "+++ b/tests/test_lib.py\n",
+                "@@ -0,0 +1,1 @@\n",
+                "+def test_f(): pass\n",
+            )
+            .to_string(),
+            diff_file: None,
+        },
+    );
+    wf.append_active_output("wrote a trivial test".to_string());
+    wf.finish_active_job(true, 0);
+    let runner = wf.start_next_job().expect("test runner");
+    assert_eq!(runner.role, WorkerRole::TestRunner);
+    let messages = wf.finish_active_job(true, 0);
+    assert!(messages.iter().any(|m| m.contains("coverage check queued")));
+
+    let coverage = wf.start_next_job().expect("coverage run");
+    assert_eq!(coverage.role, WorkerRole::CoverageRunner);
+    assert!(matches!(coverage.run, JobRun::DeterministicCoverageRun));
+    for line in lcov(&[3, 4, 5]) {
+        wf.append_active_output(line);
+    }
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("changed lines 40.0% is below 80.0%; test-writer pass 2 queued"))
+    );
+
+    let retry = wf.start_next_job().expect("test writer retry");
+    assert_eq!(retry.role, WorkerRole::TestWriter);
+    match retry.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert!(prompt.contains("below the required 80.0%"));
+            assert!(prompt.contains("- src/lib.py: 2 of 5 changed lines covered, uncovered: 3-5"));
+        }
+        JobRun::DeterministicTestRun
+        | JobRun::DeterministicLintRun
+        | JobRun::DeterministicCoverageRun => panic!("expected test writer prompt"),
+    }
+    wf.finish_active_job(true, 0);
+    wf.start_next_job().expect("test runner retry");
+    wf.finish_active_job(true, 0);
+    wf.start_next_job().expect("coverage retry");
+    for line in lcov(&[]) {
+        wf.append_active_output(line);
+    }
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("changed lines is 100.0% (threshold 80.0%)"))
+    );
+    assert!(wf.start_next_job().is_none());
+}
+
+#[test]
+fn coverage_gate_fails_loudly_when_the_report_has_only_file_totals() {
+    let entry = |id: &str, kind, parent_id: Option<&str>, order, status| PlannerTaskFileEntry {
+        status,
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, order)
+    };
+    let mut wf = Workflow::default();
+    wf.set_coverage_threshold(Some(80.0));
+    wf.sync_planner_tasks_from_file(vec![
+        entry(
+            "top",
+            PlannerTaskKindFile::Task,
+            None,
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+        entry(
+            "impl",
+            PlannerTaskKindFile::Implementor,
+            Some("top"),
+            0,
+            PlannerTaskStatusFile::Done,
+        ),
+        entry(
+            "impl-audit",
+            PlannerTaskKindFile::Auditor,
+            Some("impl"),
+            0,
+            PlannerTaskStatusFile::Done,
+        ),
+        entry(
+            "tw",
+            PlannerTaskKindFile::TestWriter,
+            Some("top"),
+            1,
+            PlannerTaskStatusFile::Pending,
+        ),
+        entry(
+            "tw-runner",
+            PlannerTaskKindFile::TestRunner,
+            Some("tw"),
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+    ])
+    .expect("sync should succeed");
+    wf.start_execution();
+    wf.start_next_job().expect("test writer");
+    wf.finish_active_job(true, 0);
+    wf.start_next_job().expect("test runner");
+    wf.finish_active_job(true, 0);
+    wf.start_next_job().expect("coverage run");
+    for line in [
+        "Name          Stmts   Miss  Cover   Missing",
+        "src/lib.py       10      9    10%   2-10",
+    ] {
+        wf.append_active_output(line.to_string());
+    }
+
+    let messages = wf.finish_active_job(true, 0);
+    assert!(messages.iter().any(|m| {
+        m.contains("coverage gate failed: the coverage command printed only per-file totals")
+    }));
+    let failure = wf.recent_failures.last().expect("failure recorded");
+    assert!(matches!(failure.kind, WorkflowFailureKind::Test));
+    assert!(failure.reason.contains("Coverage gate could not run"));
+    assert!(wf.start_next_job().is_none());
+}
+
+#[test]
+fn review_quorum_fans_out_reviewers_and_merges_failing_findings() {
+    let entry = |id: &str, kind, parent_id: Option<&str>, order, status| PlannerTaskFileEntry {
+        status,
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, order)
+    };
+    let mut auditor = entry(
+        "impl-audit",
+        PlannerTaskKindFile::Auditor,
+        Some("impl"),
+        0,
+        PlannerTaskStatusFile::Pending,
+    );
+    auditor.review_quorum = Some(ReviewQuorumFileEntry {
+        reviewers: 3,
+        required: 2,
+        profiles: vec!["large-smart".to_string(), " large-genius ".to_string()],
+    });
+    let tasks = vec![
+        entry(
+            "top",
+            PlannerTaskKindFile::Task,
+            None,
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+        entry(
+            "impl",
+            PlannerTaskKindFile::Implementor,
+            Some("top"),
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+        auditor,
+        entry(
+            "tw",
+            PlannerTaskKindFile::TestWriter,
+            Some("top"),
+            1,
+            PlannerTaskStatusFile::Done,
+        ),
+        entry(
+            "tw-runner",
+            PlannerTaskKindFile::TestRunner,
+            Some("tw"),
+            0,
+            PlannerTaskStatusFile::Done,
+        ),
+    ];
+
+    let mut invalid = tasks.clone();
+    invalid[1].review_quorum = invalid[2].review_quorum.clone();
+    let err = Workflow::default()
+        .sync_planner_tasks_from_file(invalid)
+        .expect_err("quorum on an implementor is rejected");
+    assert!(err.contains("only auditor tasks"));
+    let mut invalid = tasks.clone();
+    if let Some(quorum) = invalid[2].review_quorum.as_mut() {
+        quorum.required = 4;
+    }
+    let err = Workflow::default()
+        .sync_planner_tasks_from_file(invalid)
+        .expect_err("unreachable quorum is rejected");
+    assert!(err.contains("between 1 and reviewers (3)"));
+
+    let mut wf = Workflow::default();
+    wf.sync_planner_tasks_from_file(tasks)
+        .expect("sync should succeed");
+    wf.start_execution();
+    let verdict = |findings: &str| {
+        format!("```json\n{{\"status\": \"fail\", \"findings\": [{findings}]}}\n```")
+    };
+
+    let _ = wf.start_next_job().expect("implementor");
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+
+    let first = wf.start_next_job().expect("reviewer 1");
+    assert_eq!(first.role, WorkerRole::Auditor);
+    assert_eq!(first.model_profile.as_deref(), Some("large-smart"));
+    let first_key = first.parent_context_key.clone().expect("context key");
+    match &first.run {
+        JobRun::AgentPrompt(prompt) => assert!(prompt.contains("you are reviewer 1 of 3")),
+        _ => panic!("expected reviewer prompt"),
+    }
+    wf.append_active_output(verdict(
+        r#"{"severity": "high", "file": "src/lib.rs", "line": 3, "message": "Missing bounds check"}"#,
+    ));
+    wf.finish_active_job(true, 0);
+
+    let second = wf.start_next_job().expect("reviewer 2");
+    assert_eq!(second.role, WorkerRole::Auditor);
+    assert_eq!(second.model_profile.as_deref(), Some("large-genius"));
+    assert_eq!(
+        second.parent_context_key,
+        Some(format!("{first_key}:reviewer:2"))
+    );
+    wf.append_active_output(verdict(
+        r#"{"severity": "critical", "file": "./src/lib.rs", "line": 3, "message": "missing  bounds check."}, {"severity": "high", "file": "src/io.rs", "message": "Unchecked write"}"#,
+    ));
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("review quorum not met: 0 of 3 reviewers passed, 2 required"))
+    );
+
+    let retry = wf.start_next_job().expect("implementor retry");
+    assert_eq!(retry.role, WorkerRole::Implementor);
+    match &retry.run {
+        JobRun::AgentPrompt(prompt) => {
+            assert_eq!(prompt.matches("Missing bounds check").count(), 1);
+            assert!(
+                prompt.contains("[critical] src/lib.rs:3 Missing bounds check (reviewers 1, 2)")
+            );
+            assert!(prompt.contains("[high] src/io.rs Unchecked write (reviewer 2)"));
+        }
+        _ => panic!("expected implementor prompt"),
+    }
+    let votes = wf.drain_review_votes();
+    assert_eq!(
+        votes
+            .iter()
+            .map(|vote| (vote.reviewer, vote.passed, vote.model_profile.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, false, Some("large-smart")),
+            (2, false, Some("large-genius"))
+        ]
+    );
+    assert!(
+        votes
+            .iter()
+            .all(|vote| vote.auditor_task_id == "impl-audit")
+    );
+    wf.append_active_output("fixed".to_string());
+    wf.finish_active_job(true, 0);
+
+    for reviewer in 1..=2 {
+        let job = wf.start_next_job().expect("quorum reviewer");
+        assert_eq!(job.role, WorkerRole::Auditor);
+        match &job.run {
+            JobRun::AgentPrompt(prompt) => {
+                assert!(prompt.contains(&format!("you are reviewer {reviewer} of 3")))
+            }
+            _ => panic!("expected reviewer prompt"),
+        }
+        wf.append_active_output(r#"{"status": "pass", "findings": []}"#.to_string());
+        wf.finish_active_job(true, 0);
+    }
+    assert!(wf.start_next_job().is_none(), "reviewer 3 is not needed");
+    let votes = wf.drain_review_votes();
+    assert_eq!(votes.len(), 2);
+    assert!(votes.iter().all(|vote| vote.passed && vote.pass == 2));
+}
+
+#[test]
+fn retrying_a_task_mid_quorum_starts_the_review_round_over() {
+    let entry = |id: &str, kind, parent_id: Option<&str>, order, status| PlannerTaskFileEntry {
+        status,
+        ..PlannerTaskFileEntry::test_entry(id, kind, parent_id, order)
+    };
+    let mut auditor = entry(
+        "impl-audit",
+        PlannerTaskKindFile::Auditor,
+        Some("impl"),
+        0,
+        PlannerTaskStatusFile::Pending,
+    );
+    auditor.review_quorum = Some(ReviewQuorumFileEntry {
+        reviewers: 3,
+        required: 2,
+        profiles: Vec::new(),
+    });
+    let mut wf = Workflow::default();
+    wf.sync_planner_tasks_from_file(vec![
+        entry(
+            "top",
+            PlannerTaskKindFile::Task,
+            None,
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+        entry(
+            "impl",
+            PlannerTaskKindFile::Implementor,
+            Some("top"),
+            0,
+            PlannerTaskStatusFile::Pending,
+        ),
+        auditor,
+    ])
+    .expect("sync should succeed");
+    wf.start_execution();
+    let fail =
+        r#"{"status": "fail", "findings": [{"severity": "high", "message": "Missing check"}]}"#;
+
+    let implementor = wf.start_next_job().expect("implementor");
+    wf.append_active_output("implemented".to_string());
+    wf.finish_active_job(true, 0);
+    wf.start_next_job().expect("reviewer 1");
+    wf.append_active_output(fail.to_string());
+    wf.finish_active_job(true, 0);
+    wf.retry_task(implementor.top_task_id, None)
+        .expect("retry between reviewers");
+
+    wf.start_next_job().expect("implementor rerun");
+    wf.append_active_output("implemented again".to_string());
+    wf.finish_active_job(true, 0);
+    let first = wf.start_next_job().expect("reviewer 1 of the new round");
+    assert_eq!(first.role, WorkerRole::Auditor);
+    wf.append_active_output(fail.to_string());
+    let messages = wf.finish_active_job(true, 0);
+    assert!(
+        !messages.iter().any(|m| m.contains("review quorum not met")),
+        "the vote cast before the retry must not count: {messages:?}"
+    );
+    let mut messages = Vec::new();
+    for reviewer in 2..=3 {
+        let job = wf.start_next_job().expect("remaining reviewer");
+        match &job.run {
+            JobRun::AgentPrompt(prompt) => {
+                assert!(prompt.contains(&format!("you are reviewer {reviewer} of 3")))
+            }
+            _ => panic!("expected reviewer prompt"),
+        }
+        wf.append_active_output(r#"{"status": "pass", "findings": []}"#.to_string());
+        messages = wf.finish_active_job(true, 0);
+    }
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.contains("review quorum met: 2 of 3 reviewers passed"))
+    );
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{prefix}-{now}"));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn init_repo(prefix: &str) -> PathBuf {
+    let repo = temp_dir(prefix);
+    run_git(&repo, &["init", "-q"]).expect("git init");
+    run_git(&repo, &["config", "user.email", "bob@example.com"]).expect("git config email");
+    run_git(&repo, &["config", "user.name", "Bob"]).expect("git config name");
+    std::fs::write(repo.join("shared.txt"), "base\n").expect("write base file");
+    run_git(&repo, &["add", "-A"]).expect("git add");
+    run_git(&repo, &["commit", "-q", "-m", "base"]).expect("git commit");
+    repo
+}
+
+#[test]
+fn task_branch_name_sanitizes_task_key() {
+    assert_eq!(task_branch_name("api/auth task"), "bob/api-auth-task");
+    assert_eq!(task_branch_name("  "), "bob/task");
+}
+
+#[test]
+fn merge_task_worktree_commits_and_merges_changes_back() {
+    let repo = init_repo("bob-worktree-merge");
+    let session_dir = temp_dir("bob-worktree-merge-session");
+
+    let worktree_dir =
+        ensure_task_worktree(&repo, &session_dir, "task-a").expect("create worktree");
+    assert_eq!(worktree_dir, task_worktree_dir(&session_dir, "task-a"));
+    std::fs::write(worktree_dir.join("feature.txt"), "feature\n").expect("write feature");
+
+    let outcome =
+        merge_task_worktree(&repo, &worktree_dir, "task-a", "Task A").expect("merge back");
+
+    assert_eq!(outcome, MergeOutcome::Merged);
+    assert_eq!(
+        std::fs::read_to_string(repo.join("feature.txt")).expect("merged file"),
+        "feature\n"
+    );
+    assert!(!worktree_dir.exists());
+
+    let _ = std::fs::remove_dir_all(&repo);
+    let _ = std::fs::remove_dir_all(&session_dir);
+}
+
+#[test]
+fn merge_task_worktree_without_changes_reports_nothing_to_merge() {
+    let repo = init_repo("bob-worktree-noop");
+    let session_dir = temp_dir("bob-worktree-noop-session");
+
+    let worktree_dir =
+        ensure_task_worktree(&repo, &session_dir, "task-a").expect("create worktree");
+    let outcome =
+        merge_task_worktree(&repo, &worktree_dir, "task-a", "Task A").expect("merge back");
+
+    assert_eq!(outcome, MergeOutcome::NothingToMerge);
+
+    let _ = std::fs::remove_dir_all(&repo);
+    let _ = std::fs::remove_dir_all(&session_dir);
+}
+
+#[test]
+fn merge_task_worktree_reports_conflicting_files_and_keeps_branch() {
+    let repo = init_repo("bob-worktree-conflict");
+    let session_dir = temp_dir("bob-worktree-conflict-session");
+
+    let first = ensure_task_worktree(&repo, &session_dir, "task-a").expect("first worktree");
+    let second = ensure_task_worktree(&repo, &session_dir, "task-b").expect("second worktree");
+    std::fs::write(first.join("shared.txt"), "from a\n").expect("write a");
+    std::fs::write(second.join("shared.txt"), "from b\n").expect("write b");
+
+    assert_eq!(
+        merge_task_worktree(&repo, &first, "task-a", "Task A").expect("merge a"),
+        MergeOutcome::Merged
+    );
+    let outcome = merge_task_worktree(&repo, &second, "task-b", "Task B").expect("merge b");
+
+    assert_eq!(
+        outcome,
+        MergeOutcome::Conflict {
+            files: vec!["shared.txt".to_string()]
+        }
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.join("shared.txt")).expect("main file"),
+        "from a\n"
+    );
+    assert!(second.exists());
+    assert!(run_git(&repo, &["rev-parse", "--verify", "bob/task-b"]).is_ok());
+
+    let _ = std::fs::remove_dir_all(&repo);
+    let _ = std::fs::remove_dir_all(&session_dir);
+}
+
+#[test]
+fn merge_task_worktree_refuses_a_dirty_main_workspace() {
+    let repo = init_repo("bob-worktree-dirty");
+    let session_dir = temp_dir("bob-worktree-dirty-session");
+
+    let worktree_dir =
+        ensure_task_worktree(&repo, &session_dir, "task-a").expect("create worktree");
+    std::fs::write(worktree_dir.join("feature.txt"), "feature\n").expect("write feature");
+    std::fs::write(repo.join("shared.txt"), "local edit\n").expect("dirty main file");
+
+    let outcome =
+        merge_task_worktree(&repo, &worktree_dir, "task-a", "Task A").expect("merge back");
+
+    assert_eq!(
+        outcome,
+        MergeOutcome::DirtyWorkspace {
+            files: vec!["shared.txt".to_string()]
+        }
+    );
+    assert!(!repo.join("feature.txt").exists());
+    assert!(worktree_dir.exists());
+    assert_eq!(
+        std::fs::read_to_string(repo.join("shared.txt")).expect("main file"),
+        "local edit\n"
+    );
+
+    let _ = std::fs::remove_dir_all(&repo);
+    let _ = std::fs::remove_dir_all(&session_dir);
+}